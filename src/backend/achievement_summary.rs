@@ -0,0 +1,308 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Counting achievements and stats from the schema files Steam keeps on disk is much cheaper
+// than starting an app server per game. The counts are cached along with the schema's
+// modification time, so only new or updated schemas are parsed on later loads.
+//
+// Unlocked achievements come from the `UserGameStats_{account}_{app}.bin` files Steam keeps
+// next to the schemas. Each achievement group of the schema is a stat there, whose `data`
+// holds one bit per achievement. Searching achievements across the library reads the same
+// files.
+
+use crate::backend::{
+    key_value::KeyValue,
+    stat_definitions::{AchievementMatch, AchievementSummary},
+    types::UserStatType,
+};
+use crate::utils::app_paths::{get_app_cache_dir, get_user_game_stats_schema_path};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+const SUMMARY_CACHE_FILE_NAME: &str = "achievement_summaries.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CachedSummary {
+    /// Modification time of the schema file the summary was computed from, in seconds.
+    schema_modified: u64,
+    summary: AchievementSummary,
+    /// Bits used by achievements, by achievement group key in the schema.
+    #[serde(default)]
+    achievement_bits: HashMap<String, u32>,
+}
+
+/// Counts the achievements and stats defined in an app's stats schema.
+pub fn summarize_schema(schema: &KeyValue, app_id: u32) -> AchievementSummary {
+    let mut summary = AchievementSummary::default();
+    for (_, stat) in schema.get(&app_id.to_string()).get("stats").children.iter() {
+        if !stat.valid {
+            continue;
+        }
+        let raw_type = if stat.get("type_int").valid {
+            stat.get("type_int").as_i32(0)
+        } else {
+            stat.get("type").as_i32(0)
+        };
+        match UserStatType::try_from(raw_type as u8) {
+            Ok(UserStatType::Integer | UserStatType::Float | UserStatType::AverageRate) => {
+                summary.stats += 1;
+            }
+            Ok(UserStatType::Achievements | UserStatType::GroupAchievements) => {
+                let bits = stat.get("bits");
+                if bits.valid {
+                    summary.achievements += bits.children.len() as u32;
+                }
+            }
+            Ok(UserStatType::Invalid) => {}
+            // Shown read-only by the app manager
+            Err(_) => summary.stats += 1,
+        }
+    }
+    summary
+}
+
+/// Masks of the bits each achievement group of the schema uses, by group key.
+pub fn achievement_bit_masks(schema: &KeyValue, app_id: u32) -> HashMap<String, u32> {
+    let mut masks = HashMap::new();
+    for (key, stat) in schema.get(&app_id.to_string()).get("stats").children.iter() {
+        let raw_type = if stat.get("type_int").valid {
+            stat.get("type_int").as_i32(0)
+        } else {
+            stat.get("type").as_i32(0)
+        };
+        if !matches!(
+            UserStatType::try_from(raw_type as u8),
+            Ok(UserStatType::Achievements | UserStatType::GroupAchievements)
+        ) {
+            continue;
+        }
+        let mask = stat
+            .get("bits")
+            .children
+            .keys()
+            .filter_map(|bit| bit.parse::<u32>().ok())
+            .filter(|bit| *bit < 32)
+            .fold(0u32, |mask, bit| mask | (1 << bit));
+        if mask != 0 {
+            masks.insert(key.clone(), mask);
+        }
+    }
+    masks
+}
+
+/// Counts the unlocked achievements in a user's stats file, given the schema's bit masks.
+pub fn count_unlocked_achievements(user_stats: &KeyValue, masks: &HashMap<String, u32>) -> u32 {
+    let cache = user_stats.get("cache");
+    masks
+        .iter()
+        .map(|(key, mask)| (cache.get(key).get("data").as_i32(0) as u32 & mask).count_ones())
+        .sum()
+}
+
+/// Finds the achievements of the schema whose name or id contains `query`, which must be
+/// lowercase. Each one comes with the achievement group key and bit it uses in the user's
+/// stats, and `unlocked` left unknown.
+pub fn find_achievements(
+    schema: &KeyValue,
+    app_id: u32,
+    query: &str,
+    language: &str,
+) -> Vec<(AchievementMatch, String, u32)> {
+    let mut matches = Vec::new();
+    for (key, stat) in schema.get(&app_id.to_string()).get("stats").children.iter() {
+        let raw_type = if stat.get("type_int").valid {
+            stat.get("type_int").as_i32(0)
+        } else {
+            stat.get("type").as_i32(0)
+        };
+        if !matches!(
+            UserStatType::try_from(raw_type as u8),
+            Ok(UserStatType::Achievements | UserStatType::GroupAchievements)
+        ) {
+            continue;
+        }
+        for (bit, achievement) in stat.get("bits").children.iter() {
+            let Some(bit) = bit.parse::<u32>().ok().filter(|bit| *bit < 32) else {
+                continue;
+            };
+            let id = achievement.get("name").as_string("");
+            let name = achievement
+                .get("display")
+                .get("name")
+                .as_localized_string(language, &id);
+            if !name.to_lowercase().contains(query) && !id.to_lowercase().contains(query) {
+                continue;
+            }
+            let description = achievement
+                .get("display")
+                .get("desc")
+                .as_localized_string(language, "");
+            matches.push((
+                AchievementMatch {
+                    app_id,
+                    id,
+                    name,
+                    description,
+                    unlocked: None,
+                },
+                key.clone(),
+                bit,
+            ));
+        }
+    }
+    matches
+}
+
+/// The most recently written stats file of the app, in case several accounts used it.
+fn find_user_game_stats_path(schema_path: &str, app_id: u32) -> Option<PathBuf> {
+    let suffix = format!("_{app_id}.bin");
+    fs::read_dir(Path::new(schema_path).parent()?)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("UserGameStats_") && name.ends_with(&suffix)
+        })
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+fn get_summary_cache_path() -> Option<PathBuf> {
+    get_app_cache_dir()
+        .ok()
+        .map(|dir| PathBuf::from(dir).join(SUMMARY_CACHE_FILE_NAME))
+}
+
+fn load_summary_cache() -> HashMap<u32, CachedSummary> {
+    get_summary_cache_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_summary_cache(cache: &HashMap<u32, CachedSummary>) {
+    let Some(path) = get_summary_cache_path() else {
+        return;
+    };
+    match serde_json::to_string(cache) {
+        Ok(contents) => {
+            if let Err(e) = fs::write(&path, contents) {
+                eprintln!("[ORCHESTRATOR] Failed to write {}: {e}", path.display());
+            }
+        }
+        Err(e) => eprintln!("[ORCHESTRATOR] Failed to serialize achievement summaries: {e}"),
+    }
+}
+
+/// Summarizes each app from its local schema. Apps whose schema Steam has not downloaded
+/// yet get `None`, since they may well have achievements.
+pub fn get_achievement_summaries(app_ids: &[u32]) -> Vec<(u32, Option<AchievementSummary>)> {
+    let mut cache = load_summary_cache();
+    let mut cache_changed = false;
+
+    let summaries = app_ids
+        .iter()
+        .map(|&app_id| {
+            let Ok(schema_path) = get_user_game_stats_schema_path(&app_id) else {
+                return (app_id, None);
+            };
+            let Some(schema_modified) = fs::metadata(&schema_path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map(|modified| modified.as_secs())
+            else {
+                return (app_id, None);
+            };
+
+            // Caches written before the bit masks were kept have none
+            let cached = cache.get(&app_id).filter(|cached| {
+                cached.schema_modified == schema_modified
+                    && (cached.summary.achievements == 0 || !cached.achievement_bits.is_empty())
+            });
+            let (mut summary, achievement_bits) = match cached {
+                Some(cached) => (cached.summary, cached.achievement_bits.clone()),
+                None => {
+                    let Ok(schema) = KeyValue::load_as_binary(&schema_path) else {
+                        return (app_id, None);
+                    };
+                    let summary = summarize_schema(&schema, app_id);
+                    let achievement_bits = achievement_bit_masks(&schema, app_id);
+                    cache.insert(
+                        app_id,
+                        CachedSummary {
+                            schema_modified,
+                            summary,
+                            achievement_bits: achievement_bits.clone(),
+                        },
+                    );
+                    cache_changed = true;
+                    (summary, achievement_bits)
+                }
+            };
+
+            // Not cached, it changes whenever the user plays
+            summary.unlocked = find_user_game_stats_path(&schema_path, app_id)
+                .and_then(|path| KeyValue::load_as_binary(path).ok())
+                .map(|user_stats| count_unlocked_achievements(&user_stats, &achievement_bits));
+            (app_id, Some(summary))
+        })
+        .collect();
+
+    if cache_changed {
+        save_summary_cache(&cache);
+    }
+    summaries
+}
+
+/// Finds the achievements of each app whose name or id contains `query`, from the schemas
+/// on disk. Apps whose schema Steam has not downloaded yet are left out.
+pub fn search_achievements(app_ids: &[u32], query: &str, language: &str) -> Vec<AchievementMatch> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut found = Vec::new();
+    for &app_id in app_ids {
+        let Ok(schema_path) = get_user_game_stats_schema_path(&app_id) else {
+            continue;
+        };
+        let Ok(schema) = KeyValue::load_as_binary(&schema_path) else {
+            continue;
+        };
+        let matches = find_achievements(&schema, app_id, &query, language);
+        if matches.is_empty() {
+            continue;
+        }
+
+        let user_stats = find_user_game_stats_path(&schema_path, app_id)
+            .and_then(|path| KeyValue::load_as_binary(path).ok());
+        found.extend(matches.into_iter().map(|(mut achievement, key, bit)| {
+            achievement.unlocked = user_stats.as_ref().map(|user_stats| {
+                user_stats.get("cache").get(&key).get("data").as_i32(0) as u32 & (1 << bit) != 0
+            });
+            achievement
+        }));
+    }
+    found
+}
@@ -16,23 +16,38 @@
 use crate::{
     backend::{
         app_manager::AppManager,
-        stat_definitions::{AchievementInfo, StatInfo},
+        stat_definitions::{AchievementInfo, SchemaCheck, StatInfo, UserAchievement},
     },
     dev_println,
     steam_client::steamworks_types::AppId_t,
-    utils::ipc_types::{SamError, SamSerializable, SteamCommand, SteamResponse},
+    utils::{
+        ipc_types::{SamError, SamSerializable, SteamCommand, SteamResponse},
+        settings::load_settings,
+    },
 };
 use interprocess::unnamed_pipe::{Recver, Sender};
 use std::io::Write;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 use serde::Serialize;
 
 fn send_response<T: Serialize>(parent_tx: &mut Sender, response: SteamResponse<T>) {
     let response = response.sam_serialize();
-    if let Err(_e) = parent_tx.write_all(&response) {
+    if let Err(e) = parent_tx.write_all(&response) {
         dev_println!("[APP SERVER] Failed to send response: {e}");
     }
 }
 
+/// Answers a write, flagging it as simulated when nothing was sent to Steam.
+fn write_response<T>(app_manager: &AppManager, result: T) -> SteamResponse<T> {
+    if app_manager.is_dry_run() {
+        SteamResponse::Simulated(result)
+    } else {
+        SteamResponse::Success(result)
+    }
+}
+
 fn check_app_id(app_id_param: AppId_t, app_id: AppId_t, parent_tx: &mut Sender) -> bool {
     if app_id_param != app_id {
         dev_println!("[APP SERVER] App ID mismatch: {app_id_param} != {app_id}");
@@ -42,9 +57,49 @@ fn check_app_id(app_id_param: AppId_t, app_id: AppId_t, parent_tx: &mut Sender)
     true
 }
 
+/// Passes the commands from the pipe to the app server's loop, until the pipe closes or
+/// a shutdown is asked for.
+fn read_commands(parent_rx: &mut Recver, commands: mpsc::Sender<SteamCommand>) {
+    loop {
+        let command = match SteamCommand::from_recver(parent_rx) {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                dev_println!("[APP SERVER] No message from pipe: {e}");
+                return;
+            }
+        };
+        let is_shutdown = command == SteamCommand::Shutdown;
+        if commands.send(command).is_err() || is_shutdown {
+            return;
+        }
+    }
+}
+
 /// Entrypoint for the app process. Handles IPC and delegates to AppManager.
-pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> i32 {
+pub fn app(app_id: AppId_t, dry_run: bool, parent_tx: &mut Sender, parent_rx: &mut Recver) -> i32 {
+    let (commands_tx, commands) = mpsc::channel();
+    thread::scope(|scope| {
+        // Commands are read on their own thread, so that stats can be stored between them
+        scope.spawn(move || read_commands(parent_rx, commands_tx));
+        serve(app_id, dry_run, parent_tx, &commands)
+    })
+}
+
+fn serve(app_id: AppId_t, dry_run: bool, parent_tx: &mut Sender, commands: &Receiver<SteamCommand>) -> i32 {
+    let settings = load_settings();
     let mut app_manager = AppManager::new_connected(app_id);
+    if let Ok(app_manager) = app_manager.as_mut() {
+        app_manager.set_dry_run(dry_run);
+        app_manager.set_language_override(settings.steam_language);
+        app_manager.set_verify_writes(settings.verify_writes);
+        app_manager.set_write_interval(Duration::from_millis(settings.min_write_interval_ms));
+    }
+
+    // Off by default, writes are stored as they happen and this only retries failed stores
+    let auto_store_interval = Some(settings.auto_store_secs)
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs);
+    let mut next_auto_store = auto_store_interval.map(|interval| Instant::now() + interval);
 
     #[cfg(debug_assertions)]
     if app_manager.as_ref().is_err() {
@@ -54,12 +109,22 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> i
     loop {
         dev_println!("[APP SERVER] Main loop...");
 
-        let command = match SteamCommand::from_recver(parent_rx) {
+        let received = match next_auto_store {
+            Some(deadline) => commands.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => commands.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        let command = match received {
             Ok(cmd) => cmd,
-            Err(_e) => {
-                dev_println!("[APP SERVER] No message from pipe: {e}");
-                break;
+            Err(RecvTimeoutError::Timeout) => {
+                if let Ok(app_manager) = app_manager.as_ref()
+                    && let Err(e) = app_manager.store_pending_changes()
+                {
+                    eprintln!("[APP SERVER] Failed to store pending changes: {e}");
+                }
+                next_auto_store = auto_store_interval.map(|interval| Instant::now() + interval);
+                continue;
             }
+            Err(RecvTimeoutError::Disconnected) => break,
         };
 
         if app_manager.as_ref().is_err() {
@@ -69,7 +134,7 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> i
 
         let app_manager = match app_manager.as_mut() {
             Ok(am) => am,
-            Err(_e) => {
+            Err(e) => {
                 dev_println!("[APP SERVER] app_manager is None: {e}");
                 continue;
             }
@@ -86,8 +151,9 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> i
                 break;
             }
 
-            SteamCommand::GetAchievements(app_id_param) => {
+            SteamCommand::GetAchievements(app_id_param, language) => {
                 if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
+                app_manager.set_language_override(language);
                 let response = match app_manager.get_achievements() {
                     Ok(achievements) => SteamResponse::Success(achievements),
                     Err(e) => SteamResponse::Error::<Vec<AchievementInfo>>(e),
@@ -95,6 +161,25 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> i
                 send_response(parent_tx, response);
             }
 
+            SteamCommand::RefreshAchievements(app_id_param, language) => {
+                if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
+                app_manager.set_language_override(language);
+                let response = match app_manager.refresh_achievements() {
+                    Ok(achievements) => SteamResponse::Success(achievements),
+                    Err(e) => SteamResponse::Error::<Vec<AchievementInfo>>(e),
+                };
+                send_response(parent_tx, response);
+            }
+
+            SteamCommand::GetAchievement(app_id_param, achievement_id) => {
+                if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
+                let response = match app_manager.get_achievement(&achievement_id) {
+                    Ok(achievement) => SteamResponse::Success(achievement),
+                    Err(e) => SteamResponse::Error::<AchievementInfo>(e),
+                };
+                send_response(parent_tx, response);
+            }
+
             SteamCommand::GetStats(app_id_param) => {
                 if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
                 let response = match app_manager.get_statistics() {
@@ -104,10 +189,19 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> i
                 send_response(parent_tx, response);
             }
 
+            SteamCommand::GetUserAchievements(app_id_param, steam_id) => {
+                if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
+                let response = match app_manager.get_user_achievements(steam_id) {
+                    Ok(user_achievements) => SteamResponse::Success(user_achievements),
+                    Err(e) => SteamResponse::Error::<Vec<UserAchievement>>(e),
+                };
+                send_response(parent_tx, response);
+            }
+
             SteamCommand::SetAchievement(app_id_param, unlocked, achievement_id) => {
                 if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
                 let response = match app_manager.set_achievement(&achievement_id, unlocked) {
-                    Ok(_) => SteamResponse::Success(true),
+                    Ok(_) => write_response(app_manager, true),
                     Err(e) => {
                         dev_println!("[APP SERVER] Error setting achievement: {e}");
                         SteamResponse::Error::<bool>(e)
@@ -116,10 +210,34 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> i
                 send_response(parent_tx, response);
             }
 
+            SteamCommand::SetAchievements(app_id_param, unlocked, achievement_ids) => {
+                if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
+                let response = match app_manager.set_achievements(&achievement_ids, unlocked) {
+                    Ok(results) => write_response(app_manager, results),
+                    Err(e) => {
+                        dev_println!("[APP SERVER] Error setting achievements: {e}");
+                        SteamResponse::Error(e)
+                    }
+                };
+                send_response(parent_tx, response);
+            }
+
+            SteamCommand::UnlockAchievementViaStat(app_id_param, achievement_id) => {
+                if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
+                let response = match app_manager.unlock_achievement_via_stat(&achievement_id) {
+                    Ok(achieved) => write_response(app_manager, achieved),
+                    Err(e) => {
+                        dev_println!("[APP SERVER] Error unlocking achievement via stat: {e}");
+                        SteamResponse::Error::<bool>(e)
+                    }
+                };
+                send_response(parent_tx, response);
+            }
+
             SteamCommand::SetIntStat(app_id_param, stat_id, value) => {
                 if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
                 let response = match app_manager.set_stat_i32(&stat_id, value) {
-                    Ok(result) => SteamResponse::Success(result),
+                    Ok(result) => write_response(app_manager, result),
                     Err(e) => {
                         dev_println!("[APP SERVER] Error setting int stat: {e}");
                         SteamResponse::Error::<bool>(e)
@@ -131,7 +249,7 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> i
             SteamCommand::SetFloatStat(app_id_param, stat_id, value) => {
                 if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
                 let response = match app_manager.set_stat_f32(&stat_id, value) {
-                    Ok(result) => SteamResponse::Success(result),
+                    Ok(result) => write_response(app_manager, result),
                     Err(e) => {
                         dev_println!("[APP SERVER] Error setting float stat: {e}");
                         SteamResponse::Error::<bool>(e)
@@ -140,10 +258,25 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> i
                 send_response(parent_tx, response);
             }
 
+            SteamCommand::ResetStat(app_id_param, stat_id) => {
+                if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
+                let response = match app_manager.reset_stat(&stat_id) {
+                    Ok(value) => write_response(app_manager, value),
+                    Err(e) => {
+                        dev_println!("[APP SERVER] Error resetting stat: {e}");
+                        SteamResponse::Error::<f64>(e)
+                    }
+                };
+                send_response(parent_tx, response);
+            }
+
             SteamCommand::ResetStats(app_id_param, achievements_too) => {
                 if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
-                let response = match app_manager.reset_all_stats(achievements_too) {
-                    Ok(result) => SteamResponse::Success(result),
+                let response = match app_manager.reset_all_stats_with_progress(
+                    achievements_too,
+                    |done, total| send_response(parent_tx, SteamResponse::<bool>::Progress(done, total)),
+                ) {
+                    Ok(result) => write_response(app_manager, result),
                     Err(e) => {
                         dev_println!("[APP SERVER] Error resetting stats: {e}");
                         SteamResponse::Error::<bool>(e)
@@ -152,6 +285,26 @@ pub fn app(app_id: AppId_t, parent_tx: &mut Sender, parent_rx: &mut Recver) -> i
                 send_response(parent_tx, response);
             }
 
+            SteamCommand::GetLastStored(app_id_param) => {
+                if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
+                let response = SteamResponse::<Option<SystemTime>>::Success(app_manager.last_stored());
+                send_response(parent_tx, response);
+            }
+
+            SteamCommand::CheckSchema(app_id_param) => {
+                if !check_app_id(app_id_param, app_id, parent_tx) { continue; }
+                let response = match app_manager.check_schema() {
+                    Ok(check) => SteamResponse::Success(check),
+                    Err(e) => SteamResponse::Error::<SchemaCheck>(e),
+                };
+                send_response(parent_tx, response);
+            }
+
+            SteamCommand::SetDryRun(dry_run) => {
+                app_manager.set_dry_run(dry_run);
+                send_response(parent_tx, SteamResponse::<bool>::Success(true));
+            }
+
             _ => {
                 dev_println!("[APP SERVER] Received unknown command {command:?}");
                 send_response(parent_tx, SteamResponse::<()>::Error(SamError::UnknownError));
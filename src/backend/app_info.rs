@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Steam caches the metadata of every app it knows in `appcache/appinfo.vdf`, which lets the
+// app list name apps even when the live interfaces can't. It holds no achievement or stat
+// definitions, those come from the `UserGameStatsSchema_*.bin` files.
+//
+// The file starts with a magic number giving its version and the universe. Version 29 follows
+// with the offset of a table of key names, which the entries refer to by index instead of
+// spelling them out. Then come the entries, each an app id, the size of the rest of the entry,
+// a fixed header and a binary KeyValue tree. App id 0 ends the list.
+
+use crate::backend::key_value::{KeyValue, KeyValueError};
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{Cursor, Read},
+    ops::Range,
+    path::Path,
+};
+
+const MAGIC_V27: u32 = 0x0756_4427;
+const MAGIC_V28: u32 = 0x0756_4428;
+const MAGIC_V29: u32 = 0x0756_4429;
+
+/// `appinfo.vdf` loaded in memory, with the entries indexed by app id. An entry is only
+/// parsed when asked for.
+pub struct AppInfoFile {
+    data: Vec<u8>,
+    version: u32,
+    key_table: Option<Vec<String>>,
+    /// Where each app's entry lies in `data`, after its app id and size.
+    entries: HashMap<u32, Range<usize>>,
+}
+
+impl fmt::Debug for AppInfoFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppInfoFile")
+            .field("version", &self.version)
+            .field("entries", &self.entries.len())
+            .finish()
+    }
+}
+
+fn read_u32(input: &mut impl Read) -> Result<u32, KeyValueError> {
+    let mut buf = [0u8; 4];
+    input.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(input: &mut impl Read) -> Result<u64, KeyValueError> {
+    let mut buf = [0u8; 8];
+    input.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_key_table(bytes: &[u8]) -> Result<Vec<String>, KeyValueError> {
+    let mut input = Cursor::new(bytes);
+    let count = read_u32(&mut input)?;
+    (0..count)
+        .map(|_| KeyValue::read_string_unicode(&mut input))
+        .collect()
+}
+
+impl AppInfoFile {
+    /// Loads and indexes an `appinfo.vdf` file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, KeyValueError> {
+        Self::from_bytes(fs::read(path)?)
+    }
+
+    /// Indexes the entries of an `appinfo.vdf` file held in memory.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, KeyValueError> {
+        let mut input = Cursor::new(data.as_slice());
+        let version = match read_u32(&mut input)? {
+            MAGIC_V27 => 27,
+            MAGIC_V28 => 28,
+            MAGIC_V29 => 29,
+            magic => {
+                return Err(KeyValueError::Format(format!(
+                    "Unsupported appinfo.vdf magic {magic:#010x}"
+                )));
+            }
+        };
+        let _universe = read_u32(&mut input)?;
+
+        let key_table = if version >= 29 {
+            let offset = read_u64(&mut input)?;
+            let table = usize::try_from(offset)
+                .ok()
+                .and_then(|offset| data.get(offset..))
+                .ok_or_else(|| {
+                    KeyValueError::Format(format!("Key table offset {offset} is out of the file"))
+                })?;
+            Some(read_key_table(table)?)
+        } else {
+            None
+        };
+
+        let mut entries = HashMap::new();
+        loop {
+            let app_id = read_u32(&mut input)?;
+            if app_id == 0 {
+                break;
+            }
+            let size = read_u32(&mut input)? as usize;
+            let start = input.position() as usize;
+            let end = start
+                .checked_add(size)
+                .filter(|end| *end <= data.len())
+                .ok_or_else(|| {
+                    KeyValueError::Format(format!("Entry of app {app_id} is truncated"))
+                })?;
+            entries.insert(app_id, start..end);
+            input.set_position(end as u64);
+        }
+
+        Ok(Self {
+            data,
+            version,
+            key_table,
+            entries,
+        })
+    }
+
+    /// Parses the metadata of an app, `None` if Steam has none cached for it. The tree has
+    /// a single `appinfo` node holding `common`, `extended` and the other sections.
+    pub fn get(&self, app_id: u32) -> Result<Option<KeyValue>, KeyValueError> {
+        let Some(range) = self.entries.get(&app_id) else {
+            return Ok(None);
+        };
+        // Info state, last update, PICS token, text SHA-1, change number, and binary SHA-1
+        // since version 28
+        let header_size = 4 + 4 + 8 + 20 + 4 + if self.version >= 28 { 20 } else { 0 };
+        let body = self.data[range.clone()]
+            .get(header_size..)
+            .ok_or_else(|| KeyValueError::Format(format!("Entry of app {app_id} is too short")))?;
+
+        let mut app_info = KeyValue::root();
+        app_info.read_as_binary_with_keys(&mut Cursor::new(body), self.key_table.as_deref())?;
+        Ok(Some(app_info))
+    }
+}
+
+/// The app's name in metadata returned by `AppInfoFile::get`.
+pub fn app_info_name(app_info: &KeyValue) -> Option<String> {
+    let name = app_info.get("appinfo").get("common").get("name").as_string("");
+    Some(name).filter(|name| !name.trim().is_empty())
+}
+
+/// The app's developer in metadata returned by `AppInfoFile::get`. Older entries keep it in
+/// `extended`, newer ones list it among the `associations` of `common`.
+pub fn app_info_developer(app_info: &KeyValue) -> Option<String> {
+    let app_info = app_info.get("appinfo");
+    let developer = app_info.get("extended").get("developer").as_string("");
+    if !developer.trim().is_empty() {
+        return Some(developer);
+    }
+
+    let mut associations: Vec<(&String, &KeyValue)> = app_info
+        .get("common")
+        .get("associations")
+        .children
+        .iter()
+        .collect();
+    associations.sort_by_key(|(index, _)| index.parse::<u32>().unwrap_or(u32::MAX));
+    associations
+        .into_iter()
+        .map(|(_, association)| association)
+        .find(|association| association.get("type").as_string("") == "developer")
+        .map(|association| association.get("name").as_string(""))
+        .filter(|developer| !developer.trim().is_empty())
+}
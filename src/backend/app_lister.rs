@@ -14,21 +14,33 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+    backend::{
+        app_info::{AppInfoFile, app_info_developer, app_info_name},
+        key_value::KeyValue,
+    },
     dev_println,
     steam_client::{
+        steam_app_list_wrapper::SteamAppList,
         steam_apps_001_wrapper::{SteamApps001, SteamApps001AppDataKeys},
         steam_apps_wrapper::SteamApps,
         steamworks_types::AppId_t,
     },
-    utils::{app_paths::get_app_cache_dir, ipc_types::SamError},
+    utils::{
+        app_paths::{get_app_cache_dir, get_app_info_path},
+        ipc_types::SamError,
+    },
 };
 use log;
 use serde::{Deserialize, Serialize};
 use std::{
+    cell::OnceCell,
+    convert::Infallible,
     fmt::{self, Display},
-    fs::{self, File},
+    fs::{self, File, OpenOptions},
     io::BufReader,
+    process,
     str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
     time::{Duration, SystemTime},
 };
 
@@ -38,8 +50,13 @@ pub struct AppLister<'a> {
     app_list_url: String,
     app_list_local: String,
     current_language: String,
+    offline: bool,
     steam_apps_001: &'a SteamApps001,
     steam_apps: &'a SteamApps,
+    /// Steam's `appinfo.vdf`, loaded the first time live app data is missing.
+    app_info: OnceCell<Option<AppInfoFile>>,
+    /// Set from another thread to stop listing the owned apps.
+    cancelled: Option<&'a AtomicBool>,
 }
 
 /// Model for a Steam app.
@@ -48,18 +65,23 @@ pub struct AppModel {
     pub app_id: AppId_t,
     pub app_name: String,
     pub image_url: Option<String>,
+    /// The square icon, shown in the title bar while the app is open.
+    #[serde(default)]
+    pub icon_url: Option<String>,
     pub app_type: AppModelType,
     pub developer: String,
     pub metacritic_score: Option<u8>,
 }
 
 /// Enum for Steam app type.
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum AppModelType {
     App,
     Mod,
     Demo,
     Junk,
+    /// A type SamRewritten doesn't know, as `games.xml` spells it.
+    Other(String),
 }
 
 impl Display for AppModelType {
@@ -69,21 +91,23 @@ impl Display for AppModelType {
             Self::Mod => write!(f, "Mod"),
             Self::Demo => write!(f, "Demo"),
             Self::Junk => write!(f, "Junk"),
+            Self::Other(app_type) => write!(f, "{app_type}"),
         }
     }
 }
 
 impl FromStr for AppModelType {
-    type Err = String;
+    type Err = Infallible;
 
+    /// Never fails: types Valve adds later are kept as `Other`, and a missing type is an app.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "app" => Ok(Self::App),
-            "mod" => Ok(Self::Mod),
-            "demo" => Ok(Self::Demo),
-            "junk" => Ok(Self::Junk),
-            _ => Err(format!("'{}' is not a valid AppModelType", s)),
-        }
+        Ok(match s.trim().to_lowercase().as_str() {
+            "" | "app" => Self::App,
+            "mod" => Self::Mod,
+            "demo" => Self::Demo,
+            "junk" => Self::Junk,
+            _ => Self::Other(s.trim().to_owned()),
+        })
     }
 }
 
@@ -103,8 +127,15 @@ struct XmlGames {
     pub games: Vec<XmlGame>,
 }
 impl<'a> AppLister<'a> {
-    /// Create a new AppLister.
-    pub fn new(steam_apps_001: &'a SteamApps001, steam_apps: &'a SteamApps) -> Self {
+    /// Create a new AppLister. `language_override` replaces Steam's current language
+    /// when picking localized app data. When `offline`, the cached app list is used
+    /// however old it is and never downloaded.
+    pub fn new(
+        steam_apps_001: &'a SteamApps001,
+        steam_apps: &'a SteamApps,
+        language_override: Option<String>,
+        offline: bool,
+    ) -> Self {
         let cache_dir = match get_app_cache_dir() {
             Ok(dir) => dir,
             Err(e) => {
@@ -126,17 +157,44 @@ impl<'a> AppLister<'a> {
                 format!("{}{}", cache_dir, "/apps.xml")
             }
         };
-        let current_language = steam_apps.get_current_game_language();
+        let current_language = language_override
+            .filter(|language| !language.is_empty())
+            .unwrap_or_else(|| steam_apps.get_current_game_language());
 
         Self {
             app_list_url,
             app_list_local,
             current_language,
+            offline,
             steam_apps_001,
             steam_apps,
+            app_info: OnceCell::new(),
+            cancelled: None,
         }
     }
 
+    /// Makes `get_owned_apps` give up with `Cancelled` once `cancelled` is set.
+    pub fn with_cancel_flag(mut self, cancelled: &'a AtomicBool) -> Self {
+        self.cancelled = Some(cancelled);
+        self
+    }
+
+    /// Looks an app up in Steam's `appinfo.vdf`, for data the live interfaces did not give.
+    fn get_offline_app_info(&self, app_id: AppId_t) -> Option<KeyValue> {
+        let app_info = self.app_info.get_or_init(|| {
+            let path = get_app_info_path().ok()?;
+            AppInfoFile::load(&path)
+                .map_err(|e| log::warn!("Failed to load {}: {e}", path.display()))
+                .ok()
+        });
+        app_info
+            .as_ref()?
+            .get(app_id)
+            .map_err(|e| log::warn!("Failed to read the app info of {}: {e}", app_id))
+            .ok()
+            .flatten()
+    }
+
     /// Download the app list as a string from the remote URL.
     fn download_app_list_str(&self) -> Result<String, SamError> {
         dev_println!(
@@ -167,10 +225,42 @@ impl<'a> AppLister<'a> {
         Ok(xml_data)
     }
 
+    /// Waits until no other SamRewritten instance updates the cached app list, and keeps it
+    /// that way until the returned lock is dropped. Without a lock, the list is used unlocked.
+    fn lock_app_list(&self) -> Option<File> {
+        let path = format!("{}.lock", self.app_list_local);
+        let lock = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .inspect_err(|e| eprintln!("[ORCHESTRATOR] Failed to open {path}: {e}"))
+            .ok()?;
+        lock.lock()
+            .inspect_err(|e| eprintln!("[ORCHESTRATOR] Failed to lock {path}: {e}"))
+            .ok()?;
+        Some(lock)
+    }
+
+    /// Replaces the cached app list in one step, so it is never read half written.
+    fn save_app_list_str(&self, app_list_str: &str) -> Result<(), SamError> {
+        let temp_path = format!("{}.{}.tmp", self.app_list_local, process::id());
+        fs::write(&temp_path, app_list_str)
+            .and_then(|_| fs::rename(&temp_path, &self.app_list_local))
+            .map_err(|e| {
+                eprintln!("[ORCHESTRATOR] Failed to save {}: {e}", self.app_list_local);
+                let _ = fs::remove_file(&temp_path);
+                SamError::AppListRetrievalFailed
+            })
+    }
+
     /// Get the XML games, updating from remote if needed.
     fn get_xml_games(&self) -> Result<XmlGames, SamError> {
         const ONE_WEEK_SECS: u64 = 7 * 24 * 60 * 60;
+        // Instances loading the library at once wait for the first one's download
+        let _lock = self.lock_app_list();
         let should_update = match fs::metadata(&self.app_list_local) {
+            Ok(_) if self.offline => false,
             Ok(metadata) => {
                 let last_update = metadata
                     .modified()
@@ -178,6 +268,13 @@ impl<'a> AppLister<'a> {
                 let one_week_ago = SystemTime::now() - Duration::from_secs(ONE_WEEK_SECS);
                 last_update < one_week_ago
             }
+            Err(_) if self.offline => {
+                eprintln!(
+                    "[ORCHESTRATOR] Offline and no cached app list in {}",
+                    &self.app_list_local
+                );
+                return Err(SamError::AppListRetrievalFailed);
+            }
             Err(_) => true,
         };
 
@@ -188,8 +285,7 @@ impl<'a> AppLister<'a> {
                 "[ORCHESTRATOR] App list loaded. Saving in:  {}",
                 &self.app_list_local
             );
-            fs::write(&self.app_list_local, &app_list_str)
-                .map_err(|_| SamError::AppListRetrievalFailed)?;
+            self.save_app_list_str(&app_list_str)?;
             xml_games
         } else {
             dev_println!("[ORCHESTRATOR] Loading app list from local location");
@@ -245,24 +341,63 @@ impl<'a> AppLister<'a> {
         None
     }
 
-    /// Get an AppModel for a given app_id and XmlGame.
+    /// Get the URL of the square icon for a given app, on the same CDN as the logo.
+    fn get_app_icon_url(&self, app_id: &AppId_t) -> Option<String> {
+        let candidate = match self
+            .steam_apps_001
+            .get_app_data(app_id, &SteamApps001AppDataKeys::Icon.as_string())
+        {
+            Ok(val) => val,
+            Err(e) => {
+                log::warn!("Failed to get Icon for app {}: {e}", app_id);
+                String::new()
+            }
+        };
+        if candidate.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "https://cdn.steamstatic.com/steamcommunity/public/images/apps/{app_id}/{candidate}.jpg"
+        ))
+    }
+
+    /// Get an AppModel for a given app_id and XmlGame. Missing data is looked up in Steam's
+    /// `appinfo.vdf`, then gets a placeholder rather than failing, so one bad entry does not
+    /// cost the whole library: the name becomes `App <id>` and the developer is left empty.
     pub fn get_app(&self, app_id: AppId_t, xml_game: &XmlGame) -> Result<AppModel, SamError> {
-        let app_name = self
+        let app_name = match self
             .steam_apps_001
-            .get_app_data(&app_id, &SteamApps001AppDataKeys::Name.as_string())
-            .map_err(|e| {
-                log::error!("Failed to get app name for {}: {e}", app_id);
-                SamError::AppListRetrievalFailed
-            })?;
+            .get_app_data(&app_id, &SteamApps001AppDataKeys::Name.as_string()) {
+            Ok(val) if !val.trim().is_empty() => Some(val),
+            Ok(_) => {
+                log::warn!("App {} has no name", app_id);
+                None
+            }
+            Err(e) => {
+                log::warn!("Failed to get app name for {}: {e}", app_id);
+                None
+            }
+        };
         let developer = match self
             .steam_apps_001
             .get_app_data(&app_id, &SteamApps001AppDataKeys::Developer.as_string()) {
-            Ok(val) => val,
+            Ok(val) => Some(val.trim().to_owned()),
             Err(e) => {
                 log::warn!("Failed to get developer for {}: {e}", app_id);
-                "Unknown".to_string()
+                None
             }
         };
+        let offline_app_info = if app_name.is_none() || developer.is_none() {
+            self.get_offline_app_info(app_id)
+        } else {
+            None
+        };
+        let app_name = app_name
+            .or_else(|| offline_app_info.as_ref().and_then(app_info_name))
+            .unwrap_or_else(|| format!("App {app_id}"));
+        let developer = developer
+            .or_else(|| offline_app_info.as_ref().and_then(app_info_developer))
+            .unwrap_or_default();
         let metacritic_score: Option<u8> = self
             .steam_apps_001
             .get_app_data(
@@ -272,41 +407,104 @@ impl<'a> AppLister<'a> {
             .ok()
             .and_then(|s| s.parse().ok());
         let image_url = self.get_app_image_url(&app_id);
+        let icon_url = self.get_app_icon_url(&app_id);
         let app_type = xml_game
             .app_type
             .as_deref()
-            .map_or(Ok(AppModelType::App), AppModelType::from_str)
-            .map_err(|_| SamError::AppListRetrievalFailed)?;
+            .and_then(|app_type| app_type.parse().ok())
+            .unwrap_or(AppModelType::App);
         Ok(AppModel {
             app_id,
             app_name,
             image_url,
+            icon_url,
             app_type,
             developer,
             metacritic_score,
         })
     }
 
-    /// Get all owned apps as AppModel.
+    /// Get all owned apps as AppModel. Apps whose data can't be read are skipped.
     pub fn get_owned_apps(&self) -> Result<Vec<AppModel>, SamError> {
         let xml_games = self.get_xml_games()?;
-        let mut models = Vec::with_capacity(xml_games.games.len());
-        for xml_game in xml_games.games.iter() {
-            let app_id: AppId_t = xml_game.app_id;
-            match self.steam_apps.is_subscribed_app(app_id) {
-                Ok(true) => {
-                    match self.get_app(app_id, xml_game) {
-                        Ok(app) => models.push(app),
-                        Err(_) => log::warn!("Failed to get app model for {}", app_id),
-                    }
-                }
-                Ok(false) => continue,
+        let (models, skipped) = collect_owned_apps(
+            &xml_games.games,
+            |app_id| self.steam_apps.is_subscribed_app(app_id),
+            |app_id, xml_game| self.get_app(app_id, xml_game),
+            self.cancelled,
+        )?;
+        if skipped > 0 {
+            eprintln!("[ORCHESTRATOR] Skipped {skipped} owned apps that could not be read");
+        }
+        Ok(models)
+    }
+
+    /// Get the apps installed on this machine as AppModel, whether or not games.xml knows them.
+    /// Relies on the whitelisted `ISteamAppList` interface.
+    pub fn get_installed_apps(&self, app_list: &SteamAppList) -> Result<Vec<AppModel>, SamError> {
+        let app_ids = app_list.get_installed_apps().map_err(|e| {
+            log::error!("Failed to list installed apps: {e}");
+            SamError::AppListRetrievalFailed
+        })?;
+
+        let mut models = Vec::with_capacity(app_ids.len());
+        for app_id in app_ids {
+            let xml_game = XmlGame {
+                app_id,
+                app_type: None,
+            };
+            match self.get_app(app_id, &xml_game) {
+                Ok(app) => models.push(app),
+                Err(_) => log::warn!("Failed to get app model for installed app {}", app_id),
+            }
+        }
+
+        Ok(models)
+    }
+}
+
+/// Builds the models of the owned apps among `games`, skipping the ones `get_app` fails on
+/// so one bad entry does not cost the whole library. Returns the models and how many owned
+/// apps were skipped. Fails with `Cancelled` as soon as `cancelled` is set.
+pub(crate) fn collect_owned_apps<E: Display>(
+    games: &[XmlGame],
+    is_subscribed: impl Fn(AppId_t) -> Result<bool, E>,
+    get_app: impl Fn(AppId_t, &XmlGame) -> Result<AppModel, SamError>,
+    cancelled: Option<&AtomicBool>,
+) -> Result<(Vec<AppModel>, usize), SamError> {
+    let mut models = Vec::with_capacity(games.len());
+    let mut failed_checks = 0usize;
+    let mut skipped = 0usize;
+    for xml_game in games {
+        if cancelled.is_some_and(|cancelled| cancelled.load(Ordering::Relaxed)) {
+            dev_println!("[ORCHESTRATOR] Listing the owned apps was cancelled");
+            return Err(SamError::Cancelled);
+        }
+        let app_id: AppId_t = xml_game.app_id;
+        match is_subscribed(app_id) {
+            Ok(true) => match get_app(app_id, xml_game) {
+                Ok(app) => models.push(app),
                 Err(e) => {
-                    log::warn!("Failed to check is_subscribed_app for {}: {e}", app_id);
-                    continue;
+                    log::warn!("Failed to get app model for {}: {e}", app_id);
+                    skipped += 1;
                 }
+            },
+            Ok(false) => continue,
+            Err(e) => {
+                log::warn!("Failed to check is_subscribed_app for {}: {e}", app_id);
+                failed_checks += 1;
             }
         }
-        Ok(models)
     }
+
+    // An empty list is only trustworthy if Steam actually answered for every app
+    if models.is_empty() && failed_checks > 0 {
+        eprintln!(
+            "[ORCHESTRATOR] Ownership check failed for {failed_checks} of {} apps",
+            games.len()
+        );
+        return Err(SamError::OwnershipCheckFailed);
+    }
+
+    Ok((models, skipped))
 }
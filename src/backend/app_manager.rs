@@ -18,34 +18,67 @@ use crate::{
         connected_steam::ConnectedSteam,
         key_value::KeyValue,
         stat_definitions::{
-            AchievementDefinition, AchievementInfo, BaseStatDefinition, FloatStatDefinition,
-            FloatStatInfo, IntStatInfo, IntegerStatDefinition, StatDefinition, StatInfo,
+            AchievementDefinition, AchievementInfo, AchievementProgress, BaseStatDefinition, FloatStatDefinition,
+            FloatStatInfo, IntStatInfo, IntegerStatDefinition, ReadOnlyStatDefinition,
+            Permission, ReadOnlyStatInfo, SchemaCheck, StatDefinition, StatInfo, UserAchievement,
         },
+        steam_backend::SteamBackend,
         types::UserStatType,
     },
     dev_println,
-    steam_client::{
-        steamworks_types::{
-            AppId_t, EResult, GlobalAchievementPercentagesReady_t, UserStatsReceived_t,
-        },
-        wrapper_types::SteamCallbackId,
-    },
-    utils::{app_paths::get_user_game_stats_schema_path, ipc_types::SamError},
+    steam_client::{steamworks_types::AppId_t, wrapper_types::SteamClientError},
+    utils::ipc_types::{AchievementResults, SamError},
+};
+use std::{
+    cell::{Cell, OnceCell, RefCell},
+    collections::HashSet,
+    env, thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
-use std::{env, path::PathBuf, time::UNIX_EPOCH};
+
+/// How long fetched achievements are served from memory before asking Steam again.
+const ACHIEVEMENTS_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// How many times a Steam call failing in a way that may pass is tried again, and the delay
+/// before the first retry, doubled for each one after.
+const TRANSIENT_RETRIES: u32 = 2;
+const TRANSIENT_RETRY_DELAY: Duration = Duration::from_millis(50);
 
 /// Manages stats, achievements, and definitions for a Steam app.
 #[derive(Debug)]
-pub struct AppManager {
+pub struct AppManager<B: SteamBackend = ConnectedSteam> {
     app_id: AppId_t,
-    connected_steam: ConnectedSteam,
+    steam: B,
     definitions_loaded: bool,
     user_stats_received: bool,
     achievement_definitions: Vec<AchievementDefinition>,
     stat_definitions: Vec<StatDefinition>,
+    /// Cleared when Steam has not downloaded the app's schema. Achievements then come from
+    /// Steam directly, and stats can't be listed.
+    schema_available: bool,
+    /// Achievement ids Steam lists for the app, read the first time a call about one fails.
+    live_achievements: OnceCell<HashSet<String>>,
+    /// Last `get_achievements` result, dropped on any write to stats or achievements.
+    achievements_cache: RefCell<Option<(Instant, Vec<AchievementInfo>)>>,
+    /// When set, writes are logged and reported as successful without reaching Steam.
+    dry_run: bool,
+    /// When set, stored writes are read back and fail with `VerificationFailed` if they
+    /// didn't take.
+    verify_writes: bool,
+    /// Least time between two achievement writes or stores, so a batch doesn't change
+    /// hundreds of achievements within milliseconds. Zero turns it off.
+    write_interval: Duration,
+    /// When the last achievement write or store was sent to Steam.
+    last_write: Cell<Option<Instant>>,
+    /// Language used for achievement and stat names instead of Steam's current one.
+    language_override: Option<String>,
+    /// Set when a write reached Steam but storing it failed, until a store succeeds.
+    pending_store: Cell<bool>,
+    /// When stats were last stored successfully.
+    last_stored: Cell<Option<SystemTime>>,
 }
 
-impl<'a> AppManager {
+impl AppManager<ConnectedSteam> {
     pub fn new_connected(app_id: AppId_t) -> Result<Self, Box<dyn std::error::Error>> {
         unsafe {
             env::set_var("SteamAppId", app_id.to_string());
@@ -58,76 +91,90 @@ impl<'a> AppManager {
             }
         };
 
-        Ok(Self {
+        Ok(Self::with_backend(app_id, connected_steam))
+    }
+}
+
+impl<B: SteamBackend> AppManager<B> {
+    /// Creates a manager on top of any Steam backend, such as a mock in tests.
+    pub fn with_backend(app_id: AppId_t, steam: B) -> Self {
+        Self {
             app_id,
-            connected_steam,
+            steam,
             definitions_loaded: false,
             user_stats_received: false,
             achievement_definitions: vec![],
             stat_definitions: vec![],
-        })
+            schema_available: true,
+            live_achievements: OnceCell::new(),
+            achievements_cache: RefCell::new(None),
+            dry_run: false,
+            verify_writes: false,
+            write_interval: Duration::ZERO,
+            last_write: Cell::new(None),
+            language_override: None,
+            pending_store: Cell::new(false),
+            last_stored: Cell::new(None),
+        }
     }
 
-    fn request_current_stats(&mut self) -> Result<(), SamError> {
-        if self.user_stats_received {
-            return Ok(());
-        }
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
 
-        let steam_id = self
-            .connected_steam
-            .user
-            .get_steam_id()
-            .map_err(|_| SamError::UnknownError)?;
-        dev_println!(
-            "[APP SERVER] Requesting current stats for current user: {:?}",
-            steam_id
-        );
-        let callback_handle = self
-            .connected_steam
-            .user_stats
-            .request_user_stats(steam_id)
-            .map_err(|_| SamError::UnknownError)?;
-
-        // Try for 10 seconds at 60 fps
-        for _ in 0..600 {
-            if self
-                .connected_steam
-                .utils
-                .is_api_call_completed(callback_handle)
-                .map_err(|_| SamError::UnknownError)?
-            {
-                let result = self
-                    .connected_steam
-                    .utils
-                    .get_api_call_result::<UserStatsReceived_t>(
-                        callback_handle,
-                        SteamCallbackId::UserStatsReceived,
-                    )
-                    .map_err(|_| SamError::UnknownError)?;
-
-                dev_println!("[APP SERVER] User stats received callback result: {result:?}");
-
-                if result.m_eResult == EResult::k_EResultOK {
-                    self.user_stats_received = true;
-                }
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
 
-                break;
-            }
+    pub fn set_verify_writes(&mut self, verify_writes: bool) {
+        self.verify_writes = verify_writes;
+    }
+
+    pub fn set_write_interval(&mut self, write_interval: Duration) {
+        self.write_interval = write_interval;
+    }
 
-            std::thread::sleep(std::time::Duration::from_millis(17));
+    /// Uses `language` for achievement and stat names. `None` or empty follows Steam.
+    pub fn set_language_override(&mut self, language: Option<String>) {
+        let language = language.filter(|language| !language.is_empty());
+        if language == self.language_override {
+            return;
         }
+        self.language_override = language;
+        // The names loaded so far are in the previous language
+        if self.definitions_loaded {
+            self.definitions_loaded = false;
+            self.invalidate_achievements_cache();
+        }
+    }
+
+    #[cfg(test)]
+    pub fn backend(&self) -> &B {
+        &self.steam
+    }
 
+    fn request_current_stats(&mut self) -> Result<(), SamError> {
+        if self.user_stats_received {
+            return Ok(());
+        }
+
+        self.user_stats_received = self.steam.request_current_stats()?;
         Ok(())
     }
 
     // Reference: https://github.com/gibbed/SteamAchievementManager/blob/master/SAM.Game/Manager.cs
     pub fn load_definitions(&mut self) -> Result<(), SamError> {
         self.request_current_stats()?;
-        let schema_path = get_user_game_stats_schema_path(&self.app_id)?;
-        let bin_file = PathBuf::from(schema_path);
-
-        let kv = KeyValue::load_as_binary(bin_file).map_err(|_| SamError::UnknownError)?;
-        let current_language = self.connected_steam.apps.get_current_game_language();
+        let kv = match self.steam.load_schema(self.app_id) {
+            Ok(kv) => kv,
+            Err(SamError::SchemaUnavailable) => return self.load_live_definitions(),
+            Err(e) => return Err(e),
+        };
+        self.schema_available = true;
+        let current_language = match &self.language_override {
+            Some(language) => language.clone(),
+            None => self.steam.current_game_language(),
+        };
         let stats = kv.get(&self.app_id.to_string());
         let stats = stats.get("stats");
 
@@ -145,26 +192,37 @@ impl<'a> AppManager {
                 stat.get("type").as_i32(0)
             };
 
-            let type_ =
-                UserStatType::try_from(raw_type as u8).map_err(|_| SamError::UnknownError)?;
+            // Types added after the ones SamRewritten knows are still shown, read-only
+            let Ok(type_) = UserStatType::try_from(raw_type as u8) else {
+                stat_definitions.push(StatDefinition::ReadOnly(
+                    self.read_only_stat_definition(stat, raw_type, &current_language),
+                ));
+                continue;
+            };
 
             match type_ {
                 UserStatType::Invalid => {
                     continue;
                 }
 
+                // A bit field packed in an integer can't be edited as a number
+                UserStatType::Integer if stat.get("bits").valid => {
+                    stat_definitions.push(StatDefinition::ReadOnly(
+                        self.read_only_stat_definition(stat, raw_type, &current_language),
+                    ));
+                }
+
                 UserStatType::Integer => {
                     let id = stat.get("name").as_string("");
-                    let name = Self::get_localized_string(
-                        stat.get("display").get("name"),
-                        &current_language,
-                        &id,
-                    );
+                    let name = stat
+                        .get("display")
+                        .get("name")
+                        .as_localized_string(&current_language, &id);
                     stat_definitions.push(StatDefinition::Integer(IntegerStatDefinition {
                         base: BaseStatDefinition {
                             id: stat.get("name").as_string(""),
                             display_name: name,
-                            permission: stat.get("permission").as_i32(0),
+                            permission: Permission(stat.get("permission").as_i32(0)),
                             app_id: self.app_id,
                         },
                         min_value: stat.get("min").as_i32(i32::MIN),
@@ -178,16 +236,15 @@ impl<'a> AppManager {
 
                 UserStatType::Float | UserStatType::AverageRate => {
                     let id = stat.get("name").as_string("");
-                    let name = Self::get_localized_string(
-                        stat.get("display").get("name"),
-                        &current_language,
-                        &id,
-                    );
+                    let name = stat
+                        .get("display")
+                        .get("name")
+                        .as_localized_string(&current_language, &id);
                     stat_definitions.push(StatDefinition::Float(FloatStatDefinition {
                         base: BaseStatDefinition {
                             id: stat.get("name").as_string(""),
                             display_name: name,
-                            permission: stat.get("permission").as_i32(0),
+                            permission: Permission(stat.get("permission").as_i32(0)),
                             app_id: self.app_id,
                         },
                         min_value: stat.get("min").as_f32(f32::MIN),
@@ -204,22 +261,20 @@ impl<'a> AppManager {
                             continue;
                         }
 
-                        if bits.1.valid == false || bits.1.children.is_empty() {
+                        if !bits.1.valid || bits.1.children.is_empty() {
                             continue;
                         }
 
                         for bit in bits.1.children.iter() {
                             let id = bit.1.get("name").as_string("");
-                            let name = Self::get_localized_string(
-                                bit.1.get("display").get("name"),
-                                &current_language,
-                                &id,
-                            );
-                            let description = Self::get_localized_string(
-                                bit.1.get("display").get("desc"),
-                                &current_language,
-                                "",
-                            );
+                            let name = bit.1
+                                .get("display")
+                                .get("name")
+                                .as_localized_string(&current_language, &id);
+                            let description = bit.1
+                                .get("display")
+                                .get("desc")
+                                .as_localized_string(&current_language, "");
 
                             achievement_definitions.push(AchievementDefinition {
                                 id,
@@ -229,7 +284,9 @@ impl<'a> AppManager {
                                 icon_normal: format!("https://cdn.steamstatic.com/steamcommunity/public/images/apps/{}/{}", self.app_id, bit.1.get("display").get("icon").as_string("")),
                                 icon_locked: format!("https://cdn.steamstatic.com/steamcommunity/public/images/apps/{}/{}", self.app_id, bit.1.get("display").get("icon_gray").as_string("")),
                                 is_hidden: bit.1.get("display").get("hidden").as_bool(false),
-                                permission: bit.1.get("permission").as_i32(0),
+                                permission: Permission(bit.1.get("permission").as_i32(0)),
+                                progress: Self::achievement_progress(bit.1.get("progress")),
+                                order: achievement_definitions.len() as u32,
                             })
                         }
                     }
@@ -244,41 +301,210 @@ impl<'a> AppManager {
         Ok(())
     }
 
-    // Reference: https://github.com/gibbed/SteamAchievementManager/blob/master/SAM.Game/Manager.cs#L420
-    pub fn get_achievements(&mut self) -> Result<Vec<AchievementInfo>, SamError> {
-        let callback_handle = self
-            .connected_steam
-            .user_stats
-            .request_global_achievement_percentages()
-            .map_err(|_| SamError::UnknownError)?;
-        let mut global_stats_fetched = EResult::k_EResultFail;
-
-        // Try for 10 seconds at 60 fps
-        for _ in 0..600 {
-            if self
-                .connected_steam
-                .utils
-                .is_api_call_completed(callback_handle)
-                .map_err(|_| SamError::UnknownError)?
+    /// Asks Steam for the achievements when the schema isn't on disk. Steam only knows their
+    /// names, descriptions and whether they are hidden, and can't list the stats.
+    fn load_live_definitions(&mut self) -> Result<(), SamError> {
+        dev_println!("[APP SERVER] No schema for app {}, asking Steam for achievements", self.app_id);
+        let count = self.steam.get_num_achievements()?;
+        let mut achievement_definitions = Vec::with_capacity(count as usize);
+        for index in 0..count {
+            let id = self.steam.get_achievement_name(index)?;
+            let attribute = |key: &str| {
+                self.steam
+                    .get_achievement_display_attribute(&id, key)
+                    .unwrap_or_default()
+            };
+            let name = Some(attribute("name"))
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| id.clone());
+            achievement_definitions.push(AchievementDefinition {
+                app_id: self.app_id,
+                name,
+                description: attribute("desc"),
+                icon_normal: String::new(),
+                icon_locked: String::new(),
+                is_hidden: attribute("hidden") == "1",
+                permission: Permission::default(),
+                progress: None,
+                order: index,
+                id,
+            });
+        }
+
+        self.stat_definitions = vec![];
+        self.achievement_definitions = achievement_definitions;
+        self.schema_available = false;
+        self.definitions_loaded = true;
+        Ok(())
+    }
+
+    /// Reads which stat unlocks an achievement, when the schema links one.
+    fn achievement_progress(progress: &KeyValue) -> Option<AchievementProgress> {
+        let value = progress.get("value");
+        if !progress.valid || value.get("operation").as_string("") != "statvalue" {
+            return None;
+        }
+        let stat_id = value.get("operand1").as_string("");
+        let max_value = progress.get("max_val").as_f32(0f32);
+        if stat_id.is_empty() || max_value <= 0f32 {
+            return None;
+        }
+        Some(AchievementProgress { stat_id, max_value })
+    }
+
+    fn read_only_stat_definition(
+        &self,
+        stat: &KeyValue,
+        raw_type: i32,
+        current_language: &str,
+    ) -> ReadOnlyStatDefinition {
+        let id = stat.get("name").as_string("");
+        let display_name =
+            stat.get("display").get("name").as_localized_string(current_language, &id);
+        let bits = stat
+            .get("bits")
+            .children
+            .iter()
+            .filter_map(|(index, bit)| {
+                let index = index.parse::<u32>().ok()?;
+                let fallback = bit.get("name").as_string(&index.to_string());
+                let name = bit
+                    .get("display")
+                    .get("name")
+                    .as_localized_string(current_language, &fallback);
+                Some((index, name))
+            })
+            .collect();
+
+        ReadOnlyStatDefinition {
+            base: BaseStatDefinition {
+                id,
+                display_name,
+                permission: Permission(stat.get("permission").as_i32(0)),
+                app_id: self.app_id,
+            },
+            raw_type,
+            bits,
+        }
+    }
+
+    /// Calls Steam, trying again a few times after a short delay when it says it is busy or
+    /// did not answer in time.
+    fn retry_transient<T>(
+        &self,
+        call: impl Fn(&B) -> Result<T, SteamClientError>,
+    ) -> Result<T, SteamClientError> {
+        let mut delay = TRANSIENT_RETRY_DELAY;
+        for _ in 0..TRANSIENT_RETRIES {
+            match call(&self.steam) {
+                Err(e) if e.is_transient() => {
+                    dev_println!("[APP SERVER] Steam call failed ({e}), retrying in {delay:?}");
+                    thread::sleep(delay);
+                    delay *= 2;
+                }
+                result => return result,
+            }
+        }
+        call(&self.steam)
+    }
+
+    /// Calls Steam about an achievement, trying again when the call fails although Steam
+    /// lists the achievement, which it only does while busy. An achievement missing from
+    /// Steam, as with an outdated schema, fails right away. Writes go through here too:
+    /// Steam refuses them without changing anything, so trying again can't apply one twice.
+    fn retry_achievement<T>(
+        &self,
+        achievement_id: &str,
+        call: impl Fn(&B) -> Result<T, SteamClientError>,
+    ) -> Result<T, SteamClientError> {
+        self.retry_transient(|steam| match call(steam) {
+            Err(SteamClientError::UnknownError)
+                if self
+                    .live_achievements()
+                    .is_ok_and(|ids| ids.contains(achievement_id)) =>
             {
-                let result = self
-                    .connected_steam
-                    .utils
-                    .get_api_call_result::<GlobalAchievementPercentagesReady_t>(
-                        callback_handle,
-                        SteamCallbackId::GlobalAchievementPercentagesReady,
-                    )
-                    .map_err(|_| SamError::UnknownError)?;
-                global_stats_fetched = result.m_eResult;
-                dev_println!(
-                    "[APP SERVER] Global achievement percentages callback result: {result:?}"
-                );
-                break;
+                Err(SteamClientError::Busy)
             }
+            result => result,
+        })
+    }
 
-            std::thread::sleep(std::time::Duration::from_millis(17));
+    /// The achievement ids Steam lists for the app, read once. A name Steam fails to give
+    /// for an index below its own count is tried again, it is busy.
+    fn live_achievements(&self) -> Result<&HashSet<String>, SteamClientError> {
+        if let Some(ids) = self.live_achievements.get() {
+            return Ok(ids);
+        }
+        let count = self.steam.get_num_achievements()?;
+        let mut ids = HashSet::with_capacity(count as usize);
+        for index in 0..count {
+            let id = self.retry_transient(|steam| {
+                steam
+                    .get_achievement_name(index)
+                    .map_err(|_| SteamClientError::Busy)
+            })?;
+            ids.insert(id);
+        }
+        Ok(self.live_achievements.get_or_init(|| ids))
+    }
+
+    /// Waits until `write_interval` has passed since the last write to Steam.
+    fn throttle_write(&self) {
+        if let Some(last_write) = self.last_write.get() {
+            let wait = self.write_interval.saturating_sub(last_write.elapsed());
+            if !wait.is_zero() {
+                thread::sleep(wait);
+            }
+        }
+        if !self.write_interval.is_zero() {
+            self.last_write.set(Some(Instant::now()));
+        }
+    }
+
+    /// Forgets the cached achievements, so the next `get_achievements` asks Steam again.
+    fn invalidate_achievements_cache(&self) {
+        self.achievements_cache.borrow_mut().take();
+    }
+
+    /// Whether the schema marks the achievement as protected, meaning only the game's
+    /// servers may change it and Steam would reject the write. Loads the definitions first,
+    /// without them every achievement would look unprotected.
+    pub fn is_protected_achievement(&mut self, achievement_id: &str) -> Result<bool, SamError> {
+        if !self.definitions_loaded {
+            self.load_definitions()?;
+        }
+
+        Ok(self
+            .achievement_definitions
+            .iter()
+            .any(|definition| {
+                definition.id == achievement_id && definition.permission.is_protected()
+            }))
+    }
+
+    // Reference: https://github.com/gibbed/SteamAchievementManager/blob/master/SAM.Game/Manager.cs#L420
+    pub fn get_achievements(&mut self) -> Result<Vec<AchievementInfo>, SamError> {
+        if let Some((fetched_at, achievements)) = self.achievements_cache.borrow().as_ref()
+            && fetched_at.elapsed() < ACHIEVEMENTS_CACHE_TTL
+        {
+            dev_println!("[APP SERVER] Serving achievements from cache");
+            return Ok(achievements.clone());
         }
 
+        let achievements = self.fetch_achievements()?;
+        *self.achievements_cache.borrow_mut() = Some((Instant::now(), achievements.clone()));
+        Ok(achievements)
+    }
+
+    /// Like `get_achievements`, but asks Steam again even when the cache is fresh.
+    pub fn refresh_achievements(&mut self) -> Result<Vec<AchievementInfo>, SamError> {
+        self.invalidate_achievements_cache();
+        self.live_achievements.take();
+        self.get_achievements()
+    }
+
+    fn fetch_achievements(&mut self) -> Result<Vec<AchievementInfo>, SamError> {
+        let global_stats_fetched = self.steam.request_global_achievement_percentages()?;
         let mut achievement_infos: Vec<AchievementInfo> = vec![];
 
         if !self.definitions_loaded {
@@ -289,67 +515,125 @@ impl<'a> AppManager {
             if def.id.is_empty() {
                 continue;
             }
+            if let Some(info) = self.achievement_info(def, global_stats_fetched) {
+                achievement_infos.push(info);
+            }
+        }
 
-            let def_id = &def.id;
-            match self
-                .connected_steam
-                .user_stats
-                .get_achievement_and_unlock_time(def_id)
-            {
-                Ok((is_achieved, unlock_time)) => {
-                    let global_achieved_percent = if global_stats_fetched == EResult::k_EResultFail
-                    {
-                        None
-                    } else {
-                        match self
-                            .connected_steam
-                            .user_stats
-                            .get_achievement_achieved_percent(def_id)
-                        {
-                            Ok(percent) => Some(percent),
-                            Err(_) => {
-                                dev_println!(
-                                    "[APP SERVER] Failed to get achievement percent for achievement: {def_id}"
-                                );
-                                None
-                            }
-                        }
-                    };
+        Ok(achievement_infos)
+    }
 
-                    let icon_normal = &def.icon_normal;
-                    let icon_locked = if def.icon_locked.is_empty() {
-                        icon_normal
-                    } else {
-                        &def.icon_locked
-                    };
+    /// Reads a single achievement from Steam, to update it after a change without fetching
+    /// the whole list.
+    pub fn get_achievement(&mut self, achievement_id: &str) -> Result<AchievementInfo, SamError> {
+        if !self.definitions_loaded {
+            self.load_definitions()?;
+        }
+        let def = self
+            .achievement_definitions
+            .iter()
+            .find(|def| def.id == achievement_id)
+            .ok_or(SamError::UnknownError)?;
+        // Percentages stay available once fetched, a failed lookup only leaves this one out
+        self.achievement_info(def, true)
+            .ok_or(SamError::UnknownError)
+    }
 
-                    achievement_infos.push(AchievementInfo {
-                        id: def_id.clone(),
-                        is_achieved,
-                        unlock_time: if is_achieved && unlock_time > 0 {
-                            UNIX_EPOCH
-                                .checked_add(std::time::Duration::from_secs(unlock_time as u64))
-                        } else {
-                            None
-                        },
-                        icon_normal: icon_normal.clone(),
-                        icon_locked: icon_locked.clone(),
-                        permission: def.permission,
-                        name: def.name.clone(),
-                        description: def.description.clone(),
-                        global_achieved_percent,
-                    });
-                }
-                Err(_) => {
+    /// Compares the achievements of the schema with the ones Steam lists. Without a schema,
+    /// the definitions are Steam's own and there is nothing to compare.
+    pub fn check_schema(&mut self) -> Result<SchemaCheck, SamError> {
+        if !self.definitions_loaded {
+            self.load_definitions()?;
+        }
+        if !self.schema_available {
+            return Ok(SchemaCheck::default());
+        }
+
+        let live_ids = self.live_achievements()?;
+        let schema_ids: HashSet<&str> = self
+            .achievement_definitions
+            .iter()
+            .map(|def| def.id.as_str())
+            .filter(|id| !id.is_empty())
+            .collect();
+
+        let mut check = SchemaCheck {
+            only_in_schema: schema_ids
+                .iter()
+                .filter(|id| !live_ids.contains(**id))
+                .map(|id| id.to_string())
+                .collect(),
+            only_in_steam: live_ids
+                .iter()
+                .filter(|id| !schema_ids.contains(id.as_str()))
+                .cloned()
+                .collect(),
+        };
+        check.only_in_schema.sort();
+        check.only_in_steam.sort();
+        if check.mismatch_count() > 0 {
+            log::warn!(
+                "[APP SERVER] Schema of app {} is outdated: {} achievements only in it, {} only in Steam",
+                self.app_id,
+                check.only_in_schema.len(),
+                check.only_in_steam.len()
+            );
+        }
+        Ok(check)
+    }
+
+    /// Reads the state of an achievement from Steam, `None` if Steam doesn't know it. Its
+    /// global percentage is only looked up when `global_stats_fetched`.
+    fn achievement_info(
+        &self,
+        def: &AchievementDefinition,
+        global_stats_fetched: bool,
+    ) -> Option<AchievementInfo> {
+        let def_id = &def.id;
+        let Ok((is_achieved, unlock_time)) =
+            self.retry_achievement(def_id, |steam| steam.get_achievement_and_unlock_time(def_id))
+        else {
+            dev_println!("[APP SERVER] Failed to get achievement info for achievement: {def_id}");
+            return None;
+        };
+
+        let global_achieved_percent = if !global_stats_fetched {
+            None
+        } else {
+            self.steam
+                .get_achievement_achieved_percent(def_id)
+                .inspect_err(|_| {
                     dev_println!(
-                        "[APP SERVER] Failed to get achievement info for achievement: {def_id}"
+                        "[APP SERVER] Failed to get achievement percent for achievement: {def_id}"
                     );
-                    continue;
-                }
-            }
-        }
+                })
+                .ok()
+        };
 
-        Ok(achievement_infos)
+        let icon_normal = &def.icon_normal;
+        let icon_locked = if def.icon_locked.is_empty() {
+            icon_normal
+        } else {
+            &def.icon_locked
+        };
+
+        Some(AchievementInfo {
+            id: def_id.clone(),
+            is_achieved,
+            unlock_time: if is_achieved && unlock_time > 0 {
+                UNIX_EPOCH.checked_add(std::time::Duration::from_secs(unlock_time as u64))
+            } else {
+                None
+            },
+            icon_normal: icon_normal.clone(),
+            icon_locked: icon_locked.clone(),
+            permission: def.permission,
+            name: def.name.clone(),
+            description: def.description.clone(),
+            global_achieved_percent,
+            progress: def.progress.clone(),
+            order: def.order,
+        })
     }
 
     // Reference: https://github.com/gibbed/SteamAchievementManager/blob/master/SAM.Game/Manager.cs#L519
@@ -359,6 +643,9 @@ impl<'a> AppManager {
         if !self.definitions_loaded {
             self.load_definitions()?;
         }
+        if !self.schema_available {
+            return Err(SamError::SchemaUnavailable);
+        }
 
         for stat in self.stat_definitions.iter() {
             match stat {
@@ -367,11 +654,7 @@ impl<'a> AppManager {
                         continue;
                     }
 
-                    let stat_value = match self
-                        .connected_steam
-                        .user_stats
-                        .get_stat_float(&definition.base.id)
-                    {
+                    let stat_value = match self.steam.get_stat_float(&definition.base.id) {
                         Ok(value) => value,
                         Err(_stat_id) => {
                             let _stat_id = definition.base.id.to_string();
@@ -390,6 +673,9 @@ impl<'a> AppManager {
                         original_value: stat_value,
                         is_increment_only: definition.increment_only,
                         permission: definition.base.permission,
+                        min_value: definition.min_value,
+                        max_value: definition.max_value,
+                        max_change: definition.max_change,
                     }));
                 }
 
@@ -398,11 +684,7 @@ impl<'a> AppManager {
                         continue;
                     }
 
-                    let stat_value = match self
-                        .connected_steam
-                        .user_stats
-                        .get_stat_i32(&definition.base.id)
-                    {
+                    let stat_value = match self.steam.get_stat_i32(&definition.base.id) {
                         Ok(value) => value,
                         Err(_stat_id) => {
                             let _stat_id = definition.base.id.to_string();
@@ -421,6 +703,36 @@ impl<'a> AppManager {
                         original_value: stat_value,
                         is_increment_only: definition.increment_only,
                         permission: definition.base.permission,
+                        min_value: definition.min_value,
+                        max_value: definition.max_value,
+                        max_change: definition.max_change,
+                    }));
+                }
+
+                StatDefinition::ReadOnly(definition) => {
+                    if definition.base.id.is_empty() {
+                        continue;
+                    }
+
+                    let stat_value = match self.steam.get_stat_i32(&definition.base.id) {
+                        Ok(value) => value,
+                        Err(_) => {
+                            log::debug!(
+                                "[APP SERVER] Failed to get stat info for stat {} of type {}",
+                                definition.base.id,
+                                definition.raw_type
+                            );
+                            continue;
+                        }
+                    };
+
+                    statistics_info.push(StatInfo::ReadOnly(ReadOnlyStatInfo {
+                        id: definition.base.id.clone(),
+                        app_id: definition.base.app_id,
+                        display_name: definition.base.display_name.clone(),
+                        permission: definition.base.permission,
+                        value: stat_value,
+                        bits: definition.bits.clone(),
                     }));
                 }
             };
@@ -429,103 +741,472 @@ impl<'a> AppManager {
         Ok(statistics_info)
     }
 
-    pub fn set_achievement(&self, achievement_id: &str, unlock: bool) -> Result<bool, SamError> {
+    /// Reads which achievements another Steam user unlocked in this app, to compare with
+    /// the current user. Nothing is written to either profile.
+    pub fn get_user_achievements(&mut self, steam_id: u64) -> Result<Vec<UserAchievement>, SamError> {
+        if !self.definitions_loaded {
+            self.load_definitions()?;
+        }
+
+        if !self.steam.request_user_stats(steam_id)? {
+            return Err(SamError::UserStatsUnavailable);
+        }
+
+        let user_achievements = self
+            .achievement_definitions
+            .iter()
+            .filter(|definition| !definition.id.is_empty())
+            // Achievements Steam has no state for are left out rather than shown as locked
+            .filter_map(|definition| {
+                let (is_achieved, _) = self
+                    .steam
+                    .get_user_achievement_and_unlock_time(steam_id, &definition.id)
+                    .ok()?;
+                Some(UserAchievement {
+                    id: definition.id.clone(),
+                    is_achieved,
+                })
+            })
+            .collect();
+        Ok(user_achievements)
+    }
+
+    pub fn set_achievement(
+        &mut self,
+        achievement_id: &str,
+        unlock: bool,
+    ) -> Result<bool, SamError> {
+        if self.is_protected_achievement(achievement_id)? {
+            return Err(SamError::ProtectedAchievement(achievement_id.to_owned()));
+        }
+
+        if self.dry_run {
+            let action = if unlock { "unlock" } else { "lock" };
+            eprintln!("[APP SERVER] Dry run: would {action} achievement {achievement_id}");
+            return Ok(true);
+        }
+
+        self.invalidate_achievements_cache();
+
+        // Skip the FFI call and the store round-trip when Steam already agrees with us
+        if let Ok((achieved, _)) = self.steam.get_achievement_and_unlock_time(achievement_id)
+            && achieved == unlock
+        {
+            dev_println!(
+                "[APP SERVER] Achievement {achievement_id} already in requested state, skipping"
+            );
+            return Ok(true);
+        }
+
+        self.throttle_write();
         if unlock {
-            match self
-                .connected_steam
-                .user_stats
-                .set_achievement(achievement_id)
-            {
-                Ok(_) => self
-                    .connected_steam
-                    .user_stats
-                    .store_stats()
-                    .map_err(|_| SamError::UnknownError),
-                Err(_) => Err(SamError::UnknownError),
-            }
+            self.retry_achievement(achievement_id, |steam| steam.set_achievement(achievement_id))?;
         } else {
-            match self
-                .connected_steam
-                .user_stats
-                .clear_achievement(achievement_id)
+            self.retry_achievement(achievement_id, |steam| {
+                steam.clear_achievement(achievement_id)
+            })?;
+        }
+        let stored = self.store_stats()?;
+        if stored {
+            self.verify_achievement(achievement_id, unlock)?;
+        }
+        Ok(stored)
+    }
+
+    /// With `verify_writes`, checks that Steam reports the achievement in the state just
+    /// stored.
+    fn verify_achievement(&self, achievement_id: &str, unlock: bool) -> Result<(), SamError> {
+        if !self.verify_writes {
+            return Ok(());
+        }
+        match self.steam.get_achievement_and_unlock_time(achievement_id) {
+            Ok((achieved, _)) if achieved == unlock => Ok(()),
+            _ => Err(SamError::VerificationFailed(achievement_id.to_owned())),
+        }
+    }
+
+    /// With `verify_writes`, checks that Steam reports the stat at the value just stored.
+    fn verify_stat(&self, stat_name: &str, matches: impl FnOnce(&B) -> bool) -> Result<(), SamError> {
+        if !self.verify_writes || matches(&self.steam) {
+            return Ok(());
+        }
+        Err(SamError::VerificationFailed(stat_name.to_owned()))
+    }
+
+    /// Sets several achievements to the same state, storing stats once at the end.
+    /// Returns the result of each achievement, in order. Achievements changed but not stored
+    /// fail with `StoreFailed`.
+    pub fn set_achievements(
+        &mut self,
+        achievement_ids: &[String],
+        unlock: bool,
+    ) -> Result<AchievementResults, SamError> {
+        if self.dry_run {
+            let action = if unlock { "unlock" } else { "lock" };
+            eprintln!("[APP SERVER] Dry run: would {action} achievements {achievement_ids:?}");
+            return Ok(achievement_ids.iter().map(|id| (id.clone(), Ok(()))).collect());
+        }
+
+        self.invalidate_achievements_cache();
+
+        let mut results = Vec::with_capacity(achievement_ids.len());
+        let mut written = Vec::new();
+
+        for achievement_id in achievement_ids {
+            if self.is_protected_achievement(achievement_id)? {
+                dev_println!("[APP SERVER] Skipping protected achievement {achievement_id} in batch");
+                let error = SamError::ProtectedAchievement(achievement_id.clone());
+                results.push((achievement_id.clone(), Err(error)));
+                continue;
+            }
+
+            if let Ok((achieved, _)) = self.steam.get_achievement_and_unlock_time(achievement_id)
+                && achieved == unlock
             {
-                Ok(_) => self
-                    .connected_steam
-                    .user_stats
-                    .store_stats()
-                    .map_err(|_| SamError::UnknownError),
-                Err(_) => Err(SamError::UnknownError),
+                results.push((achievement_id.clone(), Ok(())));
+                continue;
+            }
+
+            self.throttle_write();
+            let result = if unlock {
+                self.retry_achievement(achievement_id, |steam| {
+                    steam.set_achievement(achievement_id)
+                })
+            } else {
+                self.retry_achievement(achievement_id, |steam| {
+                    steam.clear_achievement(achievement_id)
+                })
+            };
+
+            match result {
+                Ok(()) => {
+                    written.push(results.len());
+                    results.push((achievement_id.clone(), Ok(())));
+                }
+                Err(e) => {
+                    dev_println!("[APP SERVER] Failed to set achievement {achievement_id} in batch");
+                    results.push((achievement_id.clone(), Err(e.into())));
+                }
             }
         }
+
+        let stored = self.store_stats()?;
+        for index in written {
+            let (achievement_id, result) = &mut results[index];
+            if !stored {
+                *result = Err(SamError::StoreFailed);
+            } else if let Err(e) = self.verify_achievement(achievement_id, unlock) {
+                *result = Err(e);
+            }
+        }
+        Ok(results)
     }
 
-    pub fn set_stat_i32(&self, stat_name: &str, stat_value: i32) -> Result<bool, SamError> {
-        match self
-            .connected_steam
-            .user_stats
-            .set_stat_i32(stat_name, stat_value)
-        {
-            Ok(_) => self
-                .connected_steam
-                .user_stats
-                .store_stats()
-                .map_err(|_| SamError::UnknownError),
-            Err(_) => Err(SamError::UnknownError),
+    /// Unlocks an achievement that follows a stat by raising the stat to the value the schema
+    /// links to the achievement, for achievements whose flag does not stick when set directly.
+    /// Returns whether Steam reports the achievement unlocked afterwards.
+    pub fn unlock_achievement_via_stat(&mut self, achievement_id: &str) -> Result<bool, SamError> {
+        if !self.definitions_loaded {
+            self.load_definitions()?;
+        }
+
+        let progress = self
+            .achievement_definitions
+            .iter()
+            .find(|definition| definition.id == achievement_id)
+            .and_then(|definition| definition.progress.clone())
+            .ok_or_else(|| SamError::NoProgressStat(achievement_id.to_owned()))?;
+        if self.is_protected_achievement(achievement_id)? {
+            return Err(SamError::ProtectedAchievement(achievement_id.to_owned()));
+        }
+        let stat = self
+            .stat_definitions
+            .iter()
+            .find(|stat| match stat {
+                StatDefinition::Float(definition) => definition.base.id == progress.stat_id,
+                StatDefinition::Integer(definition) => definition.base.id == progress.stat_id,
+                StatDefinition::ReadOnly(definition) => definition.base.id == progress.stat_id,
+            })
+            .ok_or_else(|| SamError::NoProgressStat(achievement_id.to_owned()))?;
+
+        if self.dry_run {
+            eprintln!(
+                "[APP SERVER] Dry run: would set stat {} to {} to unlock achievement {achievement_id}",
+                progress.stat_id, progress.max_value
+            );
+            return Ok(true);
         }
+
+        self.invalidate_achievements_cache();
+
+        // Never lower the stat, it may already be past the threshold. The stat setters refuse
+        // raising it by more than `max_change`, which Steam would drop, and throttle the store.
+        let protected = || SamError::ProtectedStat(progress.stat_id.clone());
+        match stat.clone() {
+            StatDefinition::Integer(definition) => {
+                if definition.base.permission.is_protected() {
+                    return Err(protected());
+                }
+                let current = self.retry_transient(|steam| steam.get_stat_i32(&progress.stat_id))?;
+                let target = (progress.max_value.ceil() as i32).min(definition.max_value);
+                self.set_stat_i32(&progress.stat_id, current.max(target))?;
+            }
+            StatDefinition::Float(definition) => {
+                if definition.base.permission.is_protected() {
+                    return Err(protected());
+                }
+                let current =
+                    self.retry_transient(|steam| steam.get_stat_float(&progress.stat_id))?;
+                let target = progress.max_value.min(definition.max_value);
+                self.set_stat_f32(&progress.stat_id, current.max(target))?;
+            }
+            StatDefinition::ReadOnly(_) => {
+                return Err(SamError::NoProgressStat(achievement_id.to_owned()));
+            }
+        }
+
+        let (achieved, _) = self.retry_achievement(achievement_id, |steam| {
+            steam.get_achievement_and_unlock_time(achievement_id)
+        })?;
+        Ok(achieved)
     }
 
-    pub fn set_stat_f32(&self, stat_name: &str, stat_value: f32) -> Result<bool, SamError> {
-        match self
-            .connected_steam
-            .user_stats
-            .set_stat_float(stat_name, stat_value)
-        {
-            Ok(_) => self
-                .connected_steam
-                .user_stats
-                .store_stats()
-                .map_err(|_| SamError::UnknownError),
-            Err(_) => Err(SamError::UnknownError),
+    /// Largest change Steam accepts for the stat in one store, 0 when unlimited or unknown.
+    fn max_stat_change(&self, stat_id: &str) -> f64 {
+        self.stat_definitions
+            .iter()
+            .find_map(|stat| match stat {
+                StatDefinition::Integer(definition) if definition.base.id == stat_id => {
+                    Some(definition.max_change as f64)
+                }
+                StatDefinition::Float(definition) if definition.base.id == stat_id => {
+                    Some(definition.max_change as f64)
+                }
+                _ => None,
+            })
+            .unwrap_or(0.0)
+    }
+
+    /// Sets an integer stat and stores it. Steam drops stores that change a stat by more than
+    /// its `max_change`, those are refused with `StatChangeTooLarge` instead.
+    pub fn set_stat_i32(&mut self, stat_name: &str, stat_value: i32) -> Result<bool, SamError> {
+        if !self.definitions_loaded {
+            self.load_definitions()?;
+        }
+        let max_change = self.max_stat_change(stat_name);
+        if max_change > 0.0 {
+            let current = self.steam.get_stat_i32(stat_name)?;
+            check_stat_change(stat_name, current as f64, stat_value as f64, max_change)?;
         }
+
+        if self.dry_run {
+            eprintln!("[APP SERVER] Dry run: would set stat {stat_name} to {stat_value}");
+            return Ok(true);
+        }
+
+        self.invalidate_achievements_cache();
+
+        self.steam.set_stat_i32(stat_name, stat_value)?;
+        let stored = self.store_stats()?;
+        if stored {
+            self.verify_stat(stat_name, |steam| {
+                steam.get_stat_i32(stat_name).is_ok_and(|value| value == stat_value)
+            })?;
+        }
+        Ok(stored)
+    }
+
+    /// Sets a float stat and stores it, refusing changes beyond `max_change` like
+    /// [`Self::set_stat_i32`].
+    pub fn set_stat_f32(&mut self, stat_name: &str, stat_value: f32) -> Result<bool, SamError> {
+        if !self.definitions_loaded {
+            self.load_definitions()?;
+        }
+        let max_change = self.max_stat_change(stat_name);
+        if max_change > 0.0 {
+            let current = self.steam.get_stat_float(stat_name)?;
+            // Room for the rounding of a value brought to the limit before it became an f32
+            let rounding = (current.abs().max(stat_value.abs()) * f32::EPSILON) as f64;
+            check_stat_change(stat_name, current as f64, stat_value as f64, max_change + rounding)?;
+        }
+
+        if self.dry_run {
+            eprintln!("[APP SERVER] Dry run: would set stat {stat_name} to {stat_value}");
+            return Ok(true);
+        }
+
+        self.invalidate_achievements_cache();
+
+        self.steam.set_stat_float(stat_name, stat_value)?;
+        let stored = self.store_stats()?;
+        if stored {
+            self.verify_stat(stat_name, |steam| {
+                steam.get_stat_float(stat_name).is_ok_and(|value| value == stat_value)
+            })?;
+        }
+        Ok(stored)
+    }
+
+    /// Sets one stat back to its schema default, 0 when the schema has none, and stores it.
+    /// The other stats and the achievements are left alone. Returns the value it was set to.
+    pub fn reset_stat(&mut self, stat_id: &str) -> Result<f64, SamError> {
+        if !self.definitions_loaded {
+            self.load_definitions()?;
+        }
+
+        let definition = self.stat_definitions.iter().find(|stat| match stat {
+            StatDefinition::Float(definition) => definition.base.id == stat_id,
+            StatDefinition::Integer(definition) => definition.base.id == stat_id,
+            StatDefinition::ReadOnly(definition) => definition.base.id == stat_id,
+        });
+        let (value, stored) = match definition.cloned() {
+            Some(StatDefinition::Integer(definition)) => {
+                let value = definition.default_value;
+                if definition.base.permission.is_protected() {
+                    return Err(SamError::ProtectedStat(stat_id.to_owned()));
+                }
+                let current = self.steam.get_stat_i32(stat_id)?;
+                check_stat_reset(
+                    stat_id,
+                    current as f64,
+                    value as f64,
+                    definition.increment_only,
+                    definition.max_change as f64,
+                )?;
+                (value as f64, self.set_stat_i32(stat_id, value)?)
+            }
+            Some(StatDefinition::Float(definition)) => {
+                let value = definition.default_value;
+                if definition.base.permission.is_protected() {
+                    return Err(SamError::ProtectedStat(stat_id.to_owned()));
+                }
+                let current = self.steam.get_stat_float(stat_id)?;
+                check_stat_reset(
+                    stat_id,
+                    current as f64,
+                    value as f64,
+                    definition.increment_only,
+                    definition.max_change as f64,
+                )?;
+                (value as f64, self.set_stat_f32(stat_id, value)?)
+            }
+            Some(StatDefinition::ReadOnly(_)) => {
+                dev_println!("[APP SERVER] Stat {stat_id} is read-only, not resetting it");
+                return Err(SamError::ReadOnlyStat(stat_id.to_owned()));
+            }
+            // Without a schema, Steam still tells integer stats from float ones
+            None if self.steam.get_stat_i32(stat_id).is_ok() => {
+                (0.0, self.set_stat_i32(stat_id, 0)?)
+            }
+            None => (0.0, self.set_stat_f32(stat_id, 0.0)?),
+        };
+        if !stored {
+            return Err(SamError::StoreFailed);
+        }
+        Ok(value)
+    }
+
+    /// Stores stats, keeping track of whether changes are left to store.
+    fn store_stats(&self) -> Result<bool, SamError> {
+        self.throttle_write();
+        let stored = self.steam.store_stats().map_err(|_| SamError::UnknownError);
+        if matches!(stored, Ok(true)) {
+            self.pending_store.set(false);
+            self.last_stored.set(Some(SystemTime::now()));
+        } else {
+            self.pending_store.set(true);
+        }
+        stored
+    }
+
+    /// Stores the changes a previous store did not save, if any. Returns whether a store ran
+    /// and succeeded.
+    pub fn store_pending_changes(&self) -> Result<bool, SamError> {
+        if self.dry_run || !self.pending_store.get() {
+            return Ok(false);
+        }
+        self.store_stats()
+    }
+
+    /// When stats were last stored successfully, `None` if not during this session.
+    pub fn last_stored(&self) -> Option<SystemTime> {
+        self.last_stored.get()
     }
 
     pub fn disconnect(&self) {
-        self.connected_steam.shutdown();
+        self.steam.shutdown();
     }
 
     pub fn reset_all_stats(&self, achievements_too: bool) -> Result<bool, SamError> {
-        match self
-            .connected_steam
-            .user_stats
-            .reset_all_stats(achievements_too)
-        {
-            Ok(_) => self
-                .connected_steam
-                .user_stats
-                .store_stats()
-                .map_err(|_| SamError::UnknownError),
-            Err(_) => Err(SamError::UnknownError),
+        if self.dry_run {
+            let scope = if achievements_too { "stats and achievements" } else { "stats" };
+            eprintln!("[APP SERVER] Dry run: would reset all {scope}");
+            return Ok(true);
+        }
+
+        self.invalidate_achievements_cache();
+
+        match self.steam.reset_all_stats(achievements_too) {
+            Ok(_) => self.store_stats(),
+            Err(e) => Err(e.into()),
         }
     }
 
-    fn get_localized_string(kv: &KeyValue, language: &str, default_value: &str) -> String {
-        let name = kv.get(language).as_string("");
-        if !name.is_empty() {
-            return name;
+    /// Resets all stats, and the achievements too when `achievements_too` is set. Steam resets
+    /// them all in one call, so `on_progress(done, total)` reports the achievements checked
+    /// afterwards. Returns `Ok(false)` when Steam did not store the reset or left one unlocked.
+    pub fn reset_all_stats_with_progress(
+        &mut self,
+        achievements_too: bool,
+        mut on_progress: impl FnMut(u32, u32),
+    ) -> Result<bool, SamError> {
+        let check = achievements_too && !self.dry_run;
+        if check && !self.definitions_loaded {
+            self.load_definitions()?;
+        }
+        let stored = self.reset_all_stats(achievements_too)?;
+        if !check || !stored {
+            return Ok(stored);
         }
 
-        if language != "english" {
-            let name = kv.get("english").as_string("");
-            if !name.is_empty() {
-                return name;
+        let total = self.achievement_definitions.len() as u32;
+        let mut all_locked = true;
+        for (index, definition) in self.achievement_definitions.iter().enumerate() {
+            if let Ok((true, _)) = self.steam.get_achievement_and_unlock_time(&definition.id) {
+                dev_println!("[APP SERVER] Achievement {} is still unlocked", definition.id);
+                all_locked = false;
             }
+            on_progress(index as u32 + 1, total);
         }
+        Ok(all_locked)
+    }
+}
 
-        let name = kv.as_string("");
-        if !name.is_empty() {
-            return name;
-        }
+/// Refuses changing a stat from `current` to `value` by more than `max_change`, which Steam
+/// would not store.
+fn check_stat_change(
+    stat_id: &str,
+    current: f64,
+    value: f64,
+    max_change: f64,
+) -> Result<(), SamError> {
+    if max_change > 0.0 && (value - current).abs() > max_change {
+        return Err(SamError::StatChangeTooLarge(stat_id.to_owned()));
+    }
+    Ok(())
+}
 
-        default_value.to_string()
+/// Refuses resetting a stat from `current` to `default` when Steam would not store it, as it
+/// would then only answer with an opaque store failure.
+fn check_stat_reset(
+    stat_id: &str,
+    current: f64,
+    default: f64,
+    increment_only: bool,
+    max_change: f64,
+) -> Result<(), SamError> {
+    if increment_only && default < current {
+        return Err(SamError::StatIncrementOnly(stat_id.to_owned()));
     }
+    check_stat_change(stat_id, current, default, max_change)
 }
@@ -15,6 +15,7 @@
 
 use crate::steam_client::{
     create_client::create_steam_client,
+    steam_app_list_wrapper::SteamAppList,
     steam_apps_001_wrapper::SteamApps001,
     steam_apps_wrapper::SteamApps,
     steam_client_wrapper::SteamClient,
@@ -22,7 +23,9 @@ use crate::steam_client::{
     steam_user_wrapper::SteamUser,
     steam_utils_wrapper::SteamUtils,
     steamworks_types::{HSteamPipe, HSteamUser},
+    wrapper_types::SteamClientError,
 };
+use crate::utils::ipc_types::SamError;
 
 /// Manages a live connection to the Steam client and interfaces.
 #[derive(Debug)]
@@ -39,17 +42,34 @@ pub struct ConnectedSteam {
 
 impl ConnectedSteam {
     /// Create a new live connection to the Steam client and interfaces.
+    /// Fails with `SamError::SteamNotLoggedIn` when Steam runs without a user.
     #[inline]
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
         let client = create_steam_client()?;
         let h_pipe = client.create_steam_pipe()?;
-        let h_user = client.connect_to_global_user(h_pipe)?;
+        let h_user = match client.connect_to_global_user(h_pipe) {
+            Ok(h_user) => h_user,
+            Err(SteamClientError::UserConnectionFailed) => {
+                let _ = client.release_steam_pipe(h_pipe);
+                return Err(Box::new(SamError::SteamNotLoggedIn));
+            }
+            Err(e) => return Err(e.into()),
+        };
         let apps = client.get_isteam_apps(h_user, h_pipe)?;
         let utils = client.get_isteam_utils(h_pipe)?;
         let apps_001 = client.get_isteam_apps_001(h_user, h_pipe)?;
         let user_stats = client.get_isteam_user_stats(h_user, h_pipe)?;
         let user = client.get_isteam_user(h_user, h_pipe)?;
 
+        // The interfaces load without a user, but every call needing one fails
+        let logged_on = user.is_logged_on()?
+            && user.get_steam_id().is_ok_and(|steam_id| steam_id.m_steamid != 0);
+        if !logged_on {
+            client.release_user(h_pipe, h_user);
+            let _ = client.release_steam_pipe(h_pipe);
+            return Err(Box::new(SamError::SteamNotLoggedIn));
+        }
+
         Ok(Self {
             h_pipe,
             h_user,
@@ -62,6 +82,12 @@ impl ConnectedSteam {
         })
     }
 
+    /// Get the `ISteamAppList` interface. It is not part of `new` because Steam only
+    /// grants it to whitelisted apps.
+    pub fn app_list(&self) -> Result<SteamAppList, SteamClientError> {
+        self.client.get_isteam_app_list(self.h_user, self.h_pipe)
+    }
+
     /// Cleanly shut down the Steam connection and release resources.
     #[inline]
     pub fn shutdown(&self) {
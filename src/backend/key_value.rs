@@ -19,11 +19,13 @@ use std::{
     collections::HashMap,
     error::Error,
     fmt,
-    fs::File,
-    io::{Read, Seek},
+    fs,
+    io::{Cursor, Read, Seek},
     path::Path,
     sync::LazyLock,
 };
+#[cfg(test)]
+use std::io::Write;
 
 /// Errors that can occur when working with KeyValue structures.
 #[derive(Debug)]
@@ -95,7 +97,7 @@ impl TryFrom<u8> for KeyValueType {
 }
 
 /// The data held by a KeyValue node.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum KeyValueData {
     None,
     String(String),
@@ -166,6 +168,29 @@ impl KeyValue {
         }
     }
 
+    /// Returns the child for `language`, falling back to English, then to the value itself,
+    /// then to the provided default.
+    pub fn as_localized_string(&self, language: &str, default: &str) -> String {
+        let name = self.get(language).as_string("");
+        if !name.is_empty() {
+            return name;
+        }
+
+        if language != "english" {
+            let name = self.get("english").as_string("");
+            if !name.is_empty() {
+                return name;
+            }
+        }
+
+        let name = self.as_string("");
+        if !name.is_empty() {
+            return name;
+        }
+
+        default.to_string()
+    }
+
     /// Returns the value as an i32, or the provided default if invalid.
     pub fn as_i32(&self, default: i32) -> i32 {
         if !self.valid {
@@ -228,14 +253,64 @@ impl KeyValue {
 
     /// Loads a KeyValue tree from a binary file.
     pub fn load_as_binary<P: AsRef<Path>>(path: P) -> Result<Self, KeyValueError> {
-        let mut file = File::open(path)?;
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Parses a KeyValue tree from binary data held in memory.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, KeyValueError> {
         let mut kv = Self::root();
-        kv.read_as_binary(&mut file)?;
+        kv.read_as_binary(&mut Cursor::new(bytes))?;
         Ok(kv)
     }
 
+    /// Serializes this node's children to binary data, in the format `from_bytes` reads.
+    #[cfg(test)]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, KeyValueError> {
+        let mut output = Cursor::new(Vec::new());
+        self.write_as_binary(&mut output)?;
+        Ok(output.into_inner())
+    }
+
+    /// Writes this node's children to a binary stream, followed by an end marker.
+    #[cfg(test)]
+    pub fn write_as_binary<W: Write>(&self, output: &mut W) -> Result<(), KeyValueError> {
+        for child in self.children.values() {
+            let kv_type = match child.data {
+                KeyValueData::None => KeyValueType::None,
+                KeyValueData::String(_) => KeyValueType::String,
+                KeyValueData::Int32(_) => KeyValueType::Int32,
+                KeyValueData::Float32(_) => KeyValueType::Float32,
+                KeyValueData::UInt64(_) => KeyValueType::UInt64,
+                KeyValueData::Color(_) => KeyValueType::Color,
+            };
+            output.write_all(&[kv_type as u8])?;
+            Self::write_string_unicode(output, &child.name)?;
+
+            match &child.data {
+                KeyValueData::None => child.write_as_binary(output)?,
+                KeyValueData::String(s) => Self::write_string_unicode(output, s)?,
+                KeyValueData::Int32(i) => output.write_all(&i.to_le_bytes())?,
+                KeyValueData::Float32(f) => output.write_all(&f.to_le_bytes())?,
+                KeyValueData::UInt64(u) => output.write_all(&u.to_le_bytes())?,
+                KeyValueData::Color(c) => output.write_all(&c.to_le_bytes())?,
+            }
+        }
+        output.write_all(&[KeyValueType::End as u8])?;
+        Ok(())
+    }
+
     /// Reads a KeyValue tree from a binary stream.
     pub fn read_as_binary<R: Read + Seek>(&mut self, input: &mut R) -> Result<(), KeyValueError> {
+        self.read_as_binary_with_keys(input, None)
+    }
+
+    /// Like `read_as_binary`, for trees whose key names are stored as indexes into
+    /// `key_table` when one is given, as in `appinfo.vdf` since version 29.
+    pub fn read_as_binary_with_keys<R: Read + Seek>(
+        &mut self,
+        input: &mut R,
+        key_table: Option<&[String]>,
+    ) -> Result<(), KeyValueError> {
         loop {
             let mut type_byte = [0u8];
             input.read_exact(&mut type_byte)?;
@@ -245,7 +320,17 @@ impl KeyValue {
                 break;
             }
 
-            let name = Self::read_string_unicode(input)?;
+            let name = match key_table {
+                Some(key_table) => {
+                    let mut buf = [0u8; 4];
+                    input.read_exact(&mut buf)?;
+                    let index = u32::from_le_bytes(buf) as usize;
+                    key_table.get(index).cloned().ok_or_else(|| {
+                        KeyValueError::Format(format!("Key index {index} is out of the key table"))
+                    })?
+                }
+                None => Self::read_string_unicode(input)?,
+            };
             let mut current = KeyValue {
                 name,
                 data: KeyValueData::None,
@@ -255,7 +340,7 @@ impl KeyValue {
 
             match kv_type {
                 KeyValueType::None => {
-                    current.read_as_binary(input)?;
+                    current.read_as_binary_with_keys(input, key_table)?;
                 }
                 KeyValueType::String => {
                     current.data = KeyValueData::String(Self::read_string_unicode(input)?);
@@ -340,6 +425,28 @@ impl KeyValue {
     pub fn read_string_unicode(input: &mut dyn Read) -> Result<String, KeyValueError> {
         Self::read_string_internal_dynamic(input, KeyValueEncoding::Utf8, '\0')
     }
+
+    /// Writes a string followed by a null terminator to a stream.
+    #[cfg(test)]
+    fn write_string_unicode<W: Write>(output: &mut W, value: &str) -> Result<(), KeyValueError> {
+        if value.contains('\0') {
+            return Err(KeyValueError::Format(format!(
+                "String contains a null character: {:?}",
+                value
+            )));
+        }
+        output.write_all(value.as_bytes())?;
+        output.write_all(&[0])?;
+        Ok(())
+    }
+}
+
+/// Two trees are equal when their names, data and children match, whatever the
+/// order children were inserted in.
+impl PartialEq for KeyValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.data == other.data && self.children == other.children
+    }
 }
 
 impl fmt::Display for KeyValue {
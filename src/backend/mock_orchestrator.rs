@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Synthetic answers for the development app 0, so the UI and the IPC can be tried without
+// Steam. Debug builds always answer for app 0 this way. With `--mock`, the orchestrator
+// answers everything from here and never connects to Steam, and the library lists app 0.
+
+use crate::backend::{
+    app_lister::{AppModel, AppModelType},
+    stat_definitions::{AchievementInfo, Permission, SchemaCheck, StatInfo},
+};
+use crate::utils::ipc_types::{
+    AchievementResults, SamError, SamSerializable, SteamCommand, SteamResponse,
+};
+
+/// App id of the development app.
+pub const MOCK_APP_ID: u32 = 0;
+const MOCK_ACHIEVEMENT_COUNT: u32 = 999;
+
+/// The `i`th achievement of the development app, unlocked when `i` is even.
+fn mock_achievement(i: u32) -> AchievementInfo {
+    AchievementInfo {
+        id: format!("DEV_ACH_{i}"),
+        is_achieved: i.is_multiple_of(2),
+        name: format!("Development achievement {i}"),
+        global_achieved_percent: None,
+        permission: Permission::default(),
+        description: "Description".to_string(),
+        icon_locked: "".to_string(),
+        icon_normal: "".to_string(),
+        unlock_time: None,
+        progress: None,
+        order: i,
+    }
+}
+
+fn mock_app() -> AppModel {
+    AppModel {
+        app_id: MOCK_APP_ID,
+        app_name: "Development app".to_string(),
+        image_url: None,
+        icon_url: None,
+        app_type: AppModelType::App,
+        developer: "SamRewritten".to_string(),
+        metacritic_score: None,
+    }
+}
+
+/// Answers `command` with synthetic data, `None` if it isn't one the mock knows. The
+/// library and app-wide commands are only answered when `library` is set, as with `--mock`.
+pub fn mock_response(command: &SteamCommand, library: bool) -> Option<Vec<u8>> {
+    let response = match command {
+        SteamCommand::GetOwnedAppList if library => {
+            SteamResponse::Success(vec![mock_app()]).sam_serialize()
+        }
+        SteamCommand::GetInstalledAppList if library => {
+            SteamResponse::Success(Vec::<AppModel>::new()).sam_serialize()
+        }
+        SteamCommand::Status if library => SteamResponse::Success(true).sam_serialize(),
+        SteamCommand::StopApps if library => SteamResponse::Success(true).sam_serialize(),
+        SteamCommand::LaunchApp(MOCK_APP_ID) | SteamCommand::StopApp(MOCK_APP_ID) => {
+            SteamResponse::Success(true).sam_serialize()
+        }
+        SteamCommand::GetAchievements(MOCK_APP_ID, _)
+        | SteamCommand::RefreshAchievements(MOCK_APP_ID, _) => {
+            let achievements: Vec<AchievementInfo> =
+                (1..=MOCK_ACHIEVEMENT_COUNT).map(mock_achievement).collect();
+            SteamResponse::Success(achievements).sam_serialize()
+        }
+        SteamCommand::GetAchievement(MOCK_APP_ID, achievement_id) => match achievement_id
+            .strip_prefix("DEV_ACH_")
+            .and_then(|i| i.parse().ok())
+        {
+            Some(i) => SteamResponse::Success(mock_achievement(i)).sam_serialize(),
+            None => SteamResponse::<AchievementInfo>::Error(SamError::UnknownError).sam_serialize(),
+        },
+        SteamCommand::GetStats(MOCK_APP_ID) => {
+            SteamResponse::Success(Vec::<StatInfo>::new()).sam_serialize()
+        }
+        SteamCommand::CheckSchema(MOCK_APP_ID) => {
+            SteamResponse::Success(SchemaCheck::default()).sam_serialize()
+        }
+        SteamCommand::SetAchievement(MOCK_APP_ID, ..) => {
+            SteamResponse::Success(true).sam_serialize()
+        }
+        SteamCommand::SetAchievements(MOCK_APP_ID, _, achievement_ids) => {
+            let results: AchievementResults =
+                achievement_ids.iter().map(|id| (id.clone(), Ok(()))).collect();
+            SteamResponse::Success(results).sam_serialize()
+        }
+        _ => return None,
+    };
+    Some(response)
+}
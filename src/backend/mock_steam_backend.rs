@@ -0,0 +1,471 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    backend::{
+        key_value::{KeyValue, KeyValueData},
+        steam_backend::SteamBackend,
+        types::UserStatType,
+    },
+    steam_client::{steamworks_types::AppId_t, wrapper_types::SteamClientError},
+    utils::ipc_types::SamError,
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+};
+
+/// In-memory Steam backend. Builds a schema from what it is given and records writes.
+#[derive(Debug, Default)]
+pub struct MockSteamBackend {
+    pub language: String,
+    pub percentages_available: bool,
+    schema_entries: Vec<KeyValue>,
+    /// Makes `load_schema` fail as when Steam has not downloaded the schema.
+    pub schema_missing: bool,
+    /// Achievement names as Steam reports them without a schema, by id.
+    display_names: HashMap<String, String>,
+    pub achievements: RefCell<HashMap<String, (bool, u32)>>,
+    pub achieved_percents: RefCell<HashMap<String, f32>>,
+    pub int_stats: RefCell<HashMap<String, i32>>,
+    pub float_stats: RefCell<HashMap<String, f32>>,
+    /// Achievements of other users, by SteamID64. Users missing here have private stats.
+    pub other_users: HashMap<u64, HashMap<String, bool>>,
+    /// Achievement or stat ids whose writes fail.
+    pub failing_ids: RefCell<HashSet<String>>,
+    /// Achievement or stat ids whose writes succeed but change nothing, as when Steam
+    /// ignores them.
+    pub ignored_ids: RefCell<HashSet<String>>,
+    /// Achievements Steam unlocks on store once a stat reaches a value, by achievement id.
+    pub progress_unlocks: HashMap<String, (String, i32)>,
+    /// Number of `SetAchievement`/`ClearAchievement` calls that reached the backend.
+    pub achievement_writes: Cell<usize>,
+    pub store_calls: Cell<usize>,
+    /// Number of reads and writes of achievements still to fail, as when Steam is busy.
+    pub busy_calls: Cell<usize>,
+    /// Makes `store_stats` report a failure, as when Steam can't save.
+    pub store_fails: Cell<bool>,
+}
+
+fn kv_leaf(name: &str, data: KeyValueData) -> KeyValue {
+    let mut node = KeyValue::root();
+    node.name = name.to_owned();
+    node.data = data;
+    node
+}
+
+fn kv_insert(parent: &mut KeyValue, child: KeyValue) {
+    parent.children.insert(child.name.clone(), child);
+}
+
+fn kv_display(name: &str, description: &str) -> KeyValue {
+    let mut display_name = kv_leaf("name", KeyValueData::None);
+    kv_insert(
+        &mut display_name,
+        kv_leaf("english", KeyValueData::String(name.to_owned())),
+    );
+    let mut desc = kv_leaf("desc", KeyValueData::None);
+    kv_insert(
+        &mut desc,
+        kv_leaf("english", KeyValueData::String(description.to_owned())),
+    );
+
+    let mut display = kv_leaf("display", KeyValueData::None);
+    kv_insert(&mut display, display_name);
+    kv_insert(&mut display, desc);
+    display
+}
+
+impl MockSteamBackend {
+    pub fn new() -> Self {
+        Self {
+            language: "english".to_owned(),
+            percentages_available: true,
+            ..Default::default()
+        }
+    }
+
+    /// Adds an achievement to its own schema group.
+    pub fn with_achievement(mut self, id: &str, name: &str, achieved: bool, permission: i32) -> Self {
+        let mut bit = kv_leaf("0", KeyValueData::None);
+        kv_insert(&mut bit, kv_leaf("name", KeyValueData::String(id.to_owned())));
+        kv_insert(&mut bit, kv_display(name, ""));
+        kv_insert(&mut bit, kv_leaf("permission", KeyValueData::Int32(permission)));
+
+        let mut bits = kv_leaf("bits", KeyValueData::None);
+        kv_insert(&mut bits, bit);
+
+        let mut entry = kv_leaf("", KeyValueData::None);
+        kv_insert(
+            &mut entry,
+            kv_leaf("type", KeyValueData::Int32(UserStatType::Achievements as i32)),
+        );
+        kv_insert(&mut entry, bits);
+
+        self.schema_entries.push(entry);
+        self.display_names.insert(id.to_owned(), name.to_owned());
+        self.achievements
+            .get_mut()
+            .insert(id.to_owned(), (achieved, if achieved { 1 } else { 0 }));
+        self
+    }
+
+    /// Adds a locked achievement that unlocks once the integer stat `stat_id` reaches `max`.
+    pub fn with_progress_achievement(mut self, id: &str, stat_id: &str, max: i32) -> Self {
+        self = self.with_achievement(id, id, false, 0);
+
+        let mut value = kv_leaf("value", KeyValueData::None);
+        kv_insert(&mut value, kv_leaf("operation", KeyValueData::String("statvalue".to_owned())));
+        kv_insert(&mut value, kv_leaf("operand1", KeyValueData::String(stat_id.to_owned())));
+        let mut progress = kv_leaf("progress", KeyValueData::None);
+        kv_insert(&mut progress, kv_leaf("min_val", KeyValueData::Int32(0)));
+        kv_insert(&mut progress, kv_leaf("max_val", KeyValueData::Int32(max)));
+        kv_insert(&mut progress, value);
+
+        if let Some(bit) = self
+            .schema_entries
+            .last_mut()
+            .and_then(|entry| entry.children.get_mut("bits"))
+            .and_then(|bits| bits.children.get_mut("0"))
+        {
+            kv_insert(bit, progress);
+        }
+        self.progress_unlocks
+            .insert(id.to_owned(), (stat_id.to_owned(), max));
+        self
+    }
+
+    /// Makes Steam list an achievement the schema doesn't define.
+    pub fn with_live_only_achievement(mut self, id: &str) -> Self {
+        self.achievements.get_mut().insert(id.to_owned(), (false, 0));
+        self
+    }
+
+    /// Makes Steam forget an achievement the schema defines, as when the schema is outdated.
+    pub fn without_live_achievement(mut self, id: &str) -> Self {
+        self.achievements.get_mut().remove(id);
+        self
+    }
+
+    /// Names the last achievement added in `language` too.
+    pub fn with_translated_name(mut self, language: &str, name: &str) -> Self {
+        if let Some(display_name) = self
+            .schema_entries
+            .last_mut()
+            .and_then(|entry| entry.children.get_mut("bits"))
+            .and_then(|bits| bits.children.get_mut("0"))
+            .and_then(|bit| bit.children.get_mut("display"))
+            .and_then(|display| display.children.get_mut("name"))
+        {
+            kv_insert(display_name, kv_leaf(language, KeyValueData::String(name.to_owned())));
+        }
+        self
+    }
+
+    pub fn with_int_stat(mut self, id: &str, value: i32, min: i32, max: i32) -> Self {
+        let mut entry = kv_leaf("", KeyValueData::None);
+        kv_insert(
+            &mut entry,
+            kv_leaf("type", KeyValueData::Int32(UserStatType::Integer as i32)),
+        );
+        kv_insert(&mut entry, kv_leaf("name", KeyValueData::String(id.to_owned())));
+        kv_insert(&mut entry, kv_display(id, ""));
+        kv_insert(&mut entry, kv_leaf("min", KeyValueData::Int32(min)));
+        kv_insert(&mut entry, kv_leaf("max", KeyValueData::Int32(max)));
+
+        self.schema_entries.push(entry);
+        self.int_stats.get_mut().insert(id.to_owned(), value);
+        self
+    }
+
+    /// Limits how much the last stat added may change in one store.
+    pub fn with_max_change(mut self, max_change: i32) -> Self {
+        if let Some(entry) = self.schema_entries.last_mut() {
+            kv_insert(entry, kv_leaf("maxchange", KeyValueData::Int32(max_change)));
+        }
+        self
+    }
+
+    /// Sets the schema permission of the last stat added.
+    pub fn with_stat_permission(mut self, permission: i32) -> Self {
+        if let Some(entry) = self.schema_entries.last_mut() {
+            kv_insert(entry, kv_leaf("permission", KeyValueData::Int32(permission)));
+        }
+        self
+    }
+
+    /// Sets the schema default of the last stat added.
+    pub fn with_default(mut self, default: i32) -> Self {
+        if let Some(entry) = self.schema_entries.last_mut() {
+            kv_insert(entry, kv_leaf("default", KeyValueData::Int32(default)));
+        }
+        self
+    }
+
+    /// Sets the schema default of the last stat added, a float one.
+    pub fn with_float_default(mut self, default: f32) -> Self {
+        if let Some(entry) = self.schema_entries.last_mut() {
+            kv_insert(entry, kv_leaf("default", KeyValueData::Float32(default)));
+        }
+        self
+    }
+
+    /// Makes the last stat added only accept higher values.
+    pub fn with_increment_only(mut self) -> Self {
+        if let Some(entry) = self.schema_entries.last_mut() {
+            kv_insert(entry, kv_leaf("incrementonly", KeyValueData::Int32(1)));
+        }
+        self
+    }
+
+    pub fn with_float_stat(mut self, id: &str, value: f32) -> Self {
+        let mut entry = kv_leaf("", KeyValueData::None);
+        kv_insert(
+            &mut entry,
+            kv_leaf("type", KeyValueData::Int32(UserStatType::Float as i32)),
+        );
+        kv_insert(&mut entry, kv_leaf("name", KeyValueData::String(id.to_owned())));
+        kv_insert(&mut entry, kv_display(id, ""));
+
+        self.schema_entries.push(entry);
+        self.float_stats.get_mut().insert(id.to_owned(), value);
+        self
+    }
+
+    /// Adds an integer-valued stat with any schema type, as a bit field when `bits` names bits.
+    pub fn with_raw_stat(mut self, id: &str, raw_type: i32, value: i32, bits: &[&str]) -> Self {
+        let mut entry = kv_leaf("", KeyValueData::None);
+        kv_insert(&mut entry, kv_leaf("type", KeyValueData::Int32(raw_type)));
+        kv_insert(&mut entry, kv_leaf("name", KeyValueData::String(id.to_owned())));
+        kv_insert(&mut entry, kv_display(id, ""));
+        if !bits.is_empty() {
+            let mut bits_node = kv_leaf("bits", KeyValueData::None);
+            for (index, name) in bits.iter().enumerate() {
+                let mut bit = kv_leaf(&index.to_string(), KeyValueData::None);
+                kv_insert(&mut bit, kv_display(name, ""));
+                kv_insert(&mut bits_node, bit);
+            }
+            kv_insert(&mut entry, bits_node);
+        }
+
+        self.schema_entries.push(entry);
+        self.int_stats.get_mut().insert(id.to_owned(), value);
+        self
+    }
+
+    /// Gives another user a public profile with this achievement state.
+    pub fn with_user_achievement(mut self, steam_id: u64, id: &str, achieved: bool) -> Self {
+        self.other_users
+            .entry(steam_id)
+            .or_default()
+            .insert(id.to_owned(), achieved);
+        self
+    }
+
+    pub fn is_achieved(&self, id: &str) -> bool {
+        self.achievements
+            .borrow()
+            .get(id)
+            .is_some_and(|(achieved, _)| *achieved)
+    }
+
+    /// Fails while `busy_calls` lasts, counting the call.
+    fn check_busy(&self) -> Result<(), SteamClientError> {
+        match self.busy_calls.get() {
+            0 => Ok(()),
+            left => {
+                self.busy_calls.set(left - 1);
+                Err(SteamClientError::UnknownError)
+            }
+        }
+    }
+
+    fn write_achievement(&self, name: &str, achieved: bool) -> Result<(), SteamClientError> {
+        self.check_busy()?;
+        self.achievement_writes.set(self.achievement_writes.get() + 1);
+        if self.failing_ids.borrow().contains(name) {
+            return Err(SteamClientError::UnknownError);
+        }
+        if self.ignored_ids.borrow().contains(name) {
+            return Ok(());
+        }
+        match self.achievements.borrow_mut().get_mut(name) {
+            Some(entry) => {
+                *entry = (achieved, if achieved { 1 } else { 0 });
+                Ok(())
+            }
+            None => Err(SteamClientError::UnknownError),
+        }
+    }
+}
+
+impl SteamBackend for MockSteamBackend {
+    fn load_schema(&self, app_id: AppId_t) -> Result<KeyValue, SamError> {
+        if self.schema_missing {
+            return Err(SamError::SchemaUnavailable);
+        }
+        let mut stats = kv_leaf("stats", KeyValueData::None);
+        for (index, entry) in self.schema_entries.iter().enumerate() {
+            let mut entry = entry.clone();
+            entry.name = index.to_string();
+            kv_insert(&mut stats, entry);
+        }
+
+        let mut app = kv_leaf(&app_id.to_string(), KeyValueData::None);
+        kv_insert(&mut app, stats);
+
+        let mut root = KeyValue::root();
+        kv_insert(&mut root, app);
+        Ok(root)
+    }
+
+    fn current_game_language(&self) -> String {
+        self.language.clone()
+    }
+
+    fn request_current_stats(&self) -> Result<bool, SamError> {
+        Ok(true)
+    }
+
+    fn request_user_stats(&self, steam_id: u64) -> Result<bool, SamError> {
+        Ok(self.other_users.contains_key(&steam_id))
+    }
+
+    fn get_user_achievement_and_unlock_time(
+        &self,
+        steam_id: u64,
+        name: &str,
+    ) -> Result<(bool, u32), SteamClientError> {
+        self.other_users
+            .get(&steam_id)
+            .and_then(|achievements| achievements.get(name))
+            .map(|achieved| (*achieved, if *achieved { 1 } else { 0 }))
+            .ok_or(SteamClientError::UnknownError)
+    }
+
+    fn request_global_achievement_percentages(&self) -> Result<bool, SamError> {
+        Ok(self.percentages_available)
+    }
+
+    fn get_achievement_and_unlock_time(&self, name: &str) -> Result<(bool, u32), SteamClientError> {
+        self.check_busy()?;
+        self.achievements
+            .borrow()
+            .get(name)
+            .copied()
+            .ok_or(SteamClientError::UnknownError)
+    }
+
+    fn get_achievement_achieved_percent(&self, name: &str) -> Result<f32, SteamClientError> {
+        self.achieved_percents
+            .borrow()
+            .get(name)
+            .copied()
+            .ok_or(SteamClientError::UnknownError)
+    }
+
+    fn get_num_achievements(&self) -> Result<u32, SteamClientError> {
+        Ok(self.achievements.borrow().len() as u32)
+    }
+
+    fn get_achievement_name(&self, index: u32) -> Result<String, SteamClientError> {
+        let mut ids: Vec<String> = self.achievements.borrow().keys().cloned().collect();
+        ids.sort();
+        ids.into_iter()
+            .nth(index as usize)
+            .ok_or(SteamClientError::UnknownError)
+    }
+
+    fn get_achievement_display_attribute(&self, name: &str, key: &str) -> Result<String, SteamClientError> {
+        match key {
+            "name" => Ok(self.display_names.get(name).cloned().unwrap_or_default()),
+            _ => Ok(String::new()),
+        }
+    }
+
+    fn set_achievement(&self, name: &str) -> Result<(), SteamClientError> {
+        self.write_achievement(name, true)
+    }
+
+    fn clear_achievement(&self, name: &str) -> Result<(), SteamClientError> {
+        self.write_achievement(name, false)
+    }
+
+    fn get_stat_i32(&self, name: &str) -> Result<i32, SteamClientError> {
+        self.int_stats
+            .borrow()
+            .get(name)
+            .copied()
+            .ok_or(SteamClientError::UnknownError)
+    }
+
+    fn get_stat_float(&self, name: &str) -> Result<f32, SteamClientError> {
+        self.float_stats
+            .borrow()
+            .get(name)
+            .copied()
+            .ok_or(SteamClientError::UnknownError)
+    }
+
+    fn set_stat_i32(&self, name: &str, value: i32) -> Result<(), SteamClientError> {
+        if self.failing_ids.borrow().contains(name) {
+            return Err(SteamClientError::UnknownError);
+        }
+        if self.ignored_ids.borrow().contains(name) {
+            return Ok(());
+        }
+        self.int_stats.borrow_mut().insert(name.to_owned(), value);
+        Ok(())
+    }
+
+    fn set_stat_float(&self, name: &str, value: f32) -> Result<(), SteamClientError> {
+        if self.failing_ids.borrow().contains(name) {
+            return Err(SteamClientError::UnknownError);
+        }
+        if self.ignored_ids.borrow().contains(name) {
+            return Ok(());
+        }
+        self.float_stats.borrow_mut().insert(name.to_owned(), value);
+        Ok(())
+    }
+
+    fn store_stats(&self) -> Result<bool, SteamClientError> {
+        self.store_calls.set(self.store_calls.get() + 1);
+        if self.store_fails.get() {
+            return Ok(false);
+        }
+        let int_stats = self.int_stats.borrow();
+        for (id, (stat_id, max)) in &self.progress_unlocks {
+            if int_stats.get(stat_id).is_some_and(|value| value >= max) {
+                self.achievements.borrow_mut().insert(id.clone(), (true, 1));
+            }
+        }
+        Ok(true)
+    }
+
+    fn reset_all_stats(&self, achievements_too: bool) -> Result<bool, SteamClientError> {
+        self.int_stats.borrow_mut().values_mut().for_each(|value| *value = 0);
+        self.float_stats.borrow_mut().values_mut().for_each(|value| *value = 0.0);
+        if achievements_too {
+            self.achievements
+                .borrow_mut()
+                .values_mut()
+                .for_each(|entry| *entry = (false, 0));
+        }
+        Ok(true)
+    }
+
+    fn shutdown(&self) {}
+}
@@ -13,12 +13,23 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod achievement_summary;
 pub mod app;
+pub mod app_info;
 pub mod app_lister;
 pub mod app_manager;
 pub mod connected_steam;
 pub mod key_value;
+pub mod mock_orchestrator;
+#[cfg(test)]
+pub mod mock_steam_backend;
 pub mod orchestrator;
+pub mod recent_errors;
+pub mod rpc_server;
+pub mod shared_orchestrator;
+pub mod stale_processes;
 pub mod stat_definitions;
+pub mod steam_backend;
 mod tests;
 pub mod types;
+pub mod warm_apps;
@@ -13,39 +13,198 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-#[cfg(debug_assertions)]
-use crate::backend::stat_definitions::{AchievementInfo, StatInfo};
-use crate::backend::{app_lister::AppLister, connected_steam::ConnectedSteam};
+use crate::backend::{
+    achievement_summary::{get_achievement_summaries, search_achievements},
+    app_lister::AppLister,
+    connected_steam::ConnectedSteam,
+    mock_orchestrator::mock_response,
+    stat_definitions::SchemaCheck,
+    recent_errors::{ErrorWatch, RecentErrors},
+    rpc_server::{RpcReplies, spawn_rpc_listener},
+    shared_orchestrator::{
+        Client, Owner, RelayEnd, Role, claim_role, relay_to_primary, spawn_peer_listener,
+    },
+    stale_processes::kill_stale_children,
+    warm_apps::{WarmApps, shutdown_app_server},
+};
 use crate::dev_println;
 use crate::utils::{
-    app_paths::get_executable_path,
+    app_paths::{get_executable_path, get_steamclient_lib_path},
     bidir_child::BidirChild,
-    ipc_types::{SamError, SamSerializable, SteamCommand, SteamResponse},
+    settings::load_settings,
+    ipc_types::{
+        Diagnostics, MAX_MESSAGE_LENGTH, SamError, SamSerializable, SteamCommand, SteamResponse,
+        is_progress_message,
+    },
 };
 use interprocess::unnamed_pipe::{Recver, Sender};
+use serde::Serialize;
 use std::{
-    collections::HashMap,
-    io::{Read, Write},
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{self, Read, Write},
     process::Command,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Sender as ChannelSender, channel},
+    },
+    thread,
+    time::Duration,
 };
 
+/// Set by the command reader when the GUI cancels the owned apps being listed.
+static APP_LIST_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// The clients using each running app server. An app server opened by several windows is
+/// only stopped once the last of them lets it go.
+#[derive(Debug, Default)]
+pub struct AppOwners(HashMap<u32, HashSet<Owner>>);
+
+impl AppOwners {
+    /// Records that `owner` uses `app_id`. Returns false if it already did.
+    pub fn claim(&mut self, app_id: u32, owner: Owner) -> bool {
+        self.0.entry(app_id).or_default().insert(owner)
+    }
+
+    /// Records that `owner` is done with `app_id`. Returns `None` if it wasn't using it,
+    /// else whether nobody uses the app anymore.
+    pub fn release(&mut self, app_id: u32, owner: Owner) -> Option<bool> {
+        let owners = self.0.get_mut(&app_id)?;
+        if !owners.remove(&owner) {
+            return None;
+        }
+        let unused = owners.is_empty();
+        if unused {
+            self.0.remove(&app_id);
+        }
+        Some(unused)
+    }
+
+    /// Records that `owner` is done with all its apps, and returns the ones nobody uses
+    /// anymore.
+    pub fn release_all(&mut self, owner: Owner) -> Vec<u32> {
+        let apps: Vec<u32> = self
+            .0
+            .iter()
+            .filter(|(_, owners)| owners.contains(&owner))
+            .map(|(app_id, _)| *app_id)
+            .collect();
+        apps.into_iter()
+            .filter(|app_id| self.release(*app_id, owner) == Some(true))
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// The app servers of this orchestrator: the running ones with the clients using them, and
+/// the ones kept warm after their last client let them go.
+#[derive(Default)]
+struct AppServers {
+    running: HashMap<u32, BidirChild>,
+    owners: AppOwners,
+    warm: WarmApps,
+}
+
+impl AppServers {
+    /// Shuts down the app servers `owner` was the last one to use, right away.
+    fn release_all(&mut self, owner: Owner) {
+        for app_id in self.owners.release_all(owner) {
+            if let Some(mut child) = self.running.remove(&app_id) {
+                dev_println!("[ORCHESTRATOR] Sending shutdown command to app {app_id}");
+                shutdown_app_server(&mut child);
+            }
+        }
+        record_children(&self.running, &self.warm);
+    }
+
+    /// Shuts down every app server, running or kept warm, and waits for them.
+    fn stop_all(&mut self) {
+        for (app_id, child) in self.running.iter_mut() {
+            dev_println!("[ORCHESTRATOR] Sending shutdown command to app {app_id}");
+            shutdown_app_server(child);
+        }
+        self.running.clear();
+        self.owners.clear();
+        self.warm.shutdown_all();
+        record_children(&self.running, &self.warm);
+    }
+}
+
 /// Sends a command to a child app process and returns the response as bytes.
 fn send_app_command(bidir: &mut BidirChild, command: SteamCommand) -> Vec<u8> {
     let command = command.sam_serialize();
-    let mut buffer_len = [0u8; std::mem::size_of::<usize>()];
     if let Err(e) = bidir.tx.write_all(&command) {
         eprintln!("[ORCHESTRATOR] Error sending command: {e}");
         return SteamResponse::<()>::Error(SamError::SocketCommunicationFailed).sam_serialize();
     }
 
-    if let Err(e) = bidir.rx.read_exact(&mut buffer_len) {
+    read_app_message(&mut bidir.rx)
+}
+
+/// Sends a command to an app server and relays its responses to `tx` as they arrive,
+/// progress messages included, up to the final one.
+pub fn relay_app_command(
+    child_tx: &mut impl Write,
+    child_rx: &mut impl Read,
+    command: SteamCommand,
+    tx: &mut dyn Write,
+    context: &str,
+) {
+    let command = command.sam_serialize();
+    if let Err(e) = child_tx.write_all(&command) {
+        eprintln!("[ORCHESTRATOR] Error sending command: {e}");
+        respond(tx, SteamResponse::<()>::Error(SamError::SocketCommunicationFailed), context);
+        return;
+    }
+
+    loop {
+        let message = read_app_message(child_rx);
+        send_response(tx, &message, context);
+        let payload = &message[std::mem::size_of::<usize>().min(message.len())..];
+        if !is_progress_message(payload) {
+            return;
+        }
+    }
+}
+
+/// Forwards a per-app command to the app server of `app_id`, or answers `AppMismatchError`
+/// when that app is not running.
+pub fn forward_to_child(
+    children_processes: &mut HashMap<u32, BidirChild>,
+    app_id: u32,
+    command: SteamCommand,
+    tx: &mut dyn Write,
+) {
+    match children_processes.get_mut(&app_id) {
+        Some(bidir) => {
+            relay_app_command(&mut bidir.tx, &mut bidir.rx, command, tx, "Forwarded command")
+        }
+        None => respond(
+            tx,
+            SteamResponse::<()>::Error(SamError::AppMismatchError),
+            "App not running",
+        ),
+    }
+}
+
+/// Reads one length-prefixed message from a child app process, prefix included.
+fn read_app_message(rx: &mut impl Read) -> Vec<u8> {
+    let mut buffer_len = [0u8; std::mem::size_of::<usize>()];
+    if let Err(e) = rx.read_exact(&mut buffer_len) {
         eprintln!("[ORCHESTRATOR] Error reading length from pipe: {e}");
         return SteamResponse::<()>::Error(SamError::SocketCommunicationFailed).sam_serialize();
     }
 
     let data_length = usize::from_le_bytes(buffer_len);
+    if data_length > MAX_MESSAGE_LENGTH {
+        eprintln!("[ORCHESTRATOR] Message length {data_length} is too large, the pipe is corrupt");
+        return SteamResponse::<()>::Error(SamError::SocketCommunicationFailed).sam_serialize();
+    }
     let mut buffer = vec![0u8; data_length];
-    if let Err(e) = bidir.rx.read_exact(&mut buffer) {
+    if let Err(e) = rx.read_exact(&mut buffer) {
         eprintln!("[ORCHESTRATOR] Error reading message from pipe: {e}");
         return SteamResponse::<()>::Error(SamError::SocketCommunicationFailed).sam_serialize();
     }
@@ -57,51 +216,339 @@ fn send_app_command(bidir: &mut BidirChild, command: SteamCommand) -> Vec<u8> {
     result
 }
 
+/// Records the running and parked app servers in the session pid file.
+fn record_children(children_processes: &HashMap<u32, BidirChild>, warm_apps: &WarmApps) {
+    let running = children_processes
+        .iter()
+        .map(|(app_id, child)| (*app_id, child.child.id()))
+        .collect();
+    warm_apps.record_children(running);
+}
+
 /// Helper to send a response and log errors concisely.
-fn send_response<T: AsRef<[u8]>>(tx: &mut Sender, response: T, context: &str) {
+fn send_response<T: AsRef<[u8]>>(tx: &mut dyn Write, response: T, context: &str) {
     if let Err(e) = tx.write_all(response.as_ref()) {
         eprintln!("[ORCHESTRATOR] Failed to send response ({}): {e}", context);
     }
 }
 
+/// Serializes and sends a response, see `send_response`.
+fn respond<T: Serialize>(tx: &mut dyn Write, response: SteamResponse<T>, context: &str) {
+    send_response(tx, response.sam_serialize(), context);
+}
+
+/// Reads the GUI's commands on their own thread, starting with `pending` if any, so that
+/// a termination signal can queue a `Shutdown` between them. A closed pipe means the GUI
+/// is gone and also shuts down, either way the app servers are stopped and Steam is
+/// released before exiting. `CancelAppList` is not queued but flags the listing under way.
+fn spawn_command_reader(
+    mut parent_rx: Recver,
+    pending: Option<SteamCommand>,
+    sender: ChannelSender<(SteamCommand, Client)>,
+) {
+    let signal_sender = sender.clone();
+    if let Err(e) = ctrlc::set_handler(move || {
+        eprintln!("[ORCHESTRATOR] Termination signal received, shutting down");
+        // The GUI did not ask for it, it must not get an answer it would take for another's
+        signal_sender.send((SteamCommand::Shutdown, Client::Silent(Owner::Gui))).ok();
+    }) {
+        eprintln!("[ORCHESTRATOR] Failed to install the signal handler: {e}");
+    }
+
+    let queue = move |command: SteamCommand| {
+        match command {
+            SteamCommand::CancelAppList => {
+                APP_LIST_CANCELLED.store(true, Ordering::Relaxed);
+                return true;
+            }
+            // A cancel read later is for this listing, not an earlier one
+            SteamCommand::GetOwnedAppList => APP_LIST_CANCELLED.store(false, Ordering::Relaxed),
+            _ => {}
+        }
+        sender.send((command, Client::Gui)).is_ok()
+    };
+
+    thread::spawn(move || {
+        if let Some(command) = pending
+            && !queue(command)
+        {
+            return;
+        }
+        loop {
+            match SteamCommand::from_recver(&mut parent_rx) {
+                Ok(message) => {
+                    if !queue(message) {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    eprintln!("[ORCHESTRATOR] No message from pipe: {e}");
+                    queue(SteamCommand::Shutdown);
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// What an orchestrator taking over Steam starts with.
+struct TakeOver {
+    /// The orchestrator lock, unless this one runs standalone.
+    lock: Option<File>,
+    /// The command the previous primary orchestrator did not get, if any.
+    pending: Option<SteamCommand>,
+    /// The app servers the GUI was using with the previous primary orchestrator.
+    held_apps: HashSet<u32>,
+}
+
+/// Settles which orchestrator talks to Steam. Returns once this one does, or `None` when
+/// the GUI is done already.
+fn take_over_steam(parent_tx: &mut Sender, parent_rx: &mut Recver) -> Option<TakeOver> {
+    let mut pending = None;
+    let mut held_apps = HashSet::new();
+    loop {
+        let lock = match claim_role() {
+            Role::Primary(lock) => Some(lock),
+            Role::Standalone => None,
+            Role::Relay(stream) => {
+                dev_println!("[ORCHESTRATOR] Relaying to the running orchestrator");
+                let end =
+                    relay_to_primary(parent_tx, parent_rx, &stream, pending.take(), &mut held_apps);
+                match end {
+                    RelayEnd::Done => return None,
+                    RelayEnd::PrimaryGone(command) => pending = command,
+                }
+                continue;
+            }
+        };
+        return Some(TakeOver { lock, pending, held_apps });
+    }
+}
+
+/// Asks the app server of `app_id` to compare its schema with Steam. Returns whether it got
+/// the question, the answer is read with [`read_schema_mismatches`].
+fn ask_schema_check(app_id: u32, child: &mut BidirChild) -> bool {
+    let command = SteamCommand::CheckSchema(app_id).sam_serialize();
+    child
+        .tx
+        .write_all(&command)
+        .inspect_err(|e| eprintln!("[ORCHESTRATOR] Error sending command: {e}"))
+        .is_ok()
+}
+
+/// Reads how many achievements the schema and Steam disagree on, as the app server answers
+/// [`ask_schema_check`].
+fn read_schema_mismatches(child: &mut BidirChild) -> Option<usize> {
+    let message = read_app_message(&mut child.rx);
+    let payload = &message[size_of::<usize>().min(message.len())..];
+    match serde_json::from_slice::<SteamResponse<SchemaCheck>>(payload) {
+        Ok(SteamResponse::Success(check)) => Some(check.mismatch_count()),
+        _ => None,
+    }
+}
+
+/// Gathers the backend state for bug reports.
+fn get_diagnostics(
+    app_servers: &mut AppServers,
+    recent_errors: &RecentErrors,
+    steam_connected: bool,
+) -> Diagnostics {
+    let mut running_apps: Vec<u32> = app_servers.running.keys().copied().collect();
+    running_apps.sort_unstable();
+    // Every app server checks at once, so the slowest one sets the wait rather than all of them
+    let asked: Vec<u32> = running_apps
+        .iter()
+        .copied()
+        .filter(|app_id| {
+            app_servers
+                .running
+                .get_mut(app_id)
+                .is_some_and(|child| ask_schema_check(*app_id, child))
+        })
+        .collect();
+    let schema_mismatches = asked
+        .iter()
+        .filter_map(|app_id| {
+            let child = app_servers.running.get_mut(app_id)?;
+            let count = read_schema_mismatches(child)?;
+            (count > 0).then_some((*app_id, count))
+        })
+        .collect();
+    let mut parked_apps: Vec<u32> =
+        app_servers.warm.pids().into_iter().map(|(app_id, _)| app_id).collect();
+    parked_apps.sort_unstable();
+    Diagnostics {
+        steam_connected,
+        steamclient_path: get_steamclient_lib_path()
+            .ok()
+            .map(|path| path.display().to_string()),
+        running_apps,
+        parked_apps,
+        recent_errors: recent_errors.to_vec(),
+        schema_mismatches,
+    }
+}
+
 /// Main backend event loop: handles Steam connection, app processes, and command dispatch.
-pub fn orchestrator(parent_tx: &mut Sender, parent_rx: &mut Recver) -> i32 {
+pub fn orchestrator(
+    parent_tx: &mut Sender,
+    mut parent_rx: Recver,
+    dry_run: bool,
+    offline: bool,
+    serve: bool,
+    mock: bool,
+) -> i32 {
+    // A mock orchestrator must neither serve nor relay to the instances using Steam
+    let TakeOver { lock, pending, held_apps } = if mock {
+        TakeOver { lock: None, pending: None, held_apps: HashSet::new() }
+    } else {
+        match take_over_steam(parent_tx, &mut parent_rx) {
+            Some(take_over) => take_over,
+            None => return 0,
+        }
+    };
+
+    let mut dry_run = dry_run;
     let mut connected_steam: Option<ConnectedSteam> = None;
-    let mut children_processes: HashMap<u32, BidirChild> = HashMap::new();
+    let mut app_servers = AppServers::default();
+    let recent_errors = RecentErrors::default();
+
+    kill_stale_children();
+    let (sender, commands) = channel();
+    if lock.is_some() {
+        spawn_peer_listener(sender.clone());
+        if serve {
+            spawn_rpc_listener(sender.clone());
+        }
+    } else if serve {
+        eprintln!("[ORCHESTRATOR] Not the primary orchestrator, not serving JSON-RPC");
+    }
+    // The app servers of the previous primary orchestrator went with it
+    for app_id in held_apps {
+        sender.send((SteamCommand::LaunchApp(app_id), Client::Silent(Owner::Gui))).ok();
+    }
+    spawn_command_reader(parent_rx, pending, sender);
 
     loop {
         dev_println!("[ORCHESTRATOR] Main loop...");
 
-        let message = match SteamCommand::from_recver(parent_rx) {
-            Ok(msg) => msg,
-            Err(e) => {
-                eprintln!("[ORCHESTRATOR] No message from pipe: {e}");
-                break 0;
-            }
+        let Ok((message, client)) = commands.recv() else {
+            break 0;
         };
 
         dev_println!("[ORCHESTRATOR] Received message: {message:?}");
 
+        let mut peer_tx;
+        let mut rpc_tx;
+        let mut silent_tx;
+        let parent_tx: &mut dyn Write = match &client {
+            Client::Gui => parent_tx,
+            Client::Peer(_, stream) => {
+                peer_tx = &**stream;
+                &mut peer_tx
+            }
+            Client::Rpc(_, replies) => {
+                rpc_tx = RpcReplies(replies.clone());
+                &mut rpc_tx
+            }
+            Client::Silent(_) => {
+                silent_tx = io::sink();
+                &mut silent_tx
+            }
+        };
+        let mut watched_tx = ErrorWatch::new(parent_tx, format!("{message:?}"), &recent_errors);
+        let parent_tx: &mut dyn Write = &mut watched_tx;
+
+        // Another instance closing down only lets go of its app servers, Steam and the others'
+        // are left to the rest. Only the GUI may stop everything.
+        if client.owner() != Owner::Gui && message == SteamCommand::Shutdown {
+            app_servers.release_all(client.owner());
+            respond(parent_tx, SteamResponse::Success(true), "Peer Shutdown");
+            continue;
+        }
+
+        // Dry-run is a local setting and must work before Steam is reachable
+        if let SteamCommand::SetDryRun(enabled) = message {
+            dry_run = enabled;
+            for child in app_servers.running.values_mut() {
+                send_app_command(child, SteamCommand::SetDryRun(enabled));
+            }
+            respond(parent_tx, SteamResponse::Success(true), "SetDryRun");
+            continue;
+        }
+
+        // For when Steam restarted or misbehaves, the next command connects again
+        if message == SteamCommand::Reconnect {
+            dev_println!("[ORCHESTRATOR] Reconnecting to Steam");
+            app_servers.stop_all();
+            if let Some(connected_steam) = connected_steam.take() {
+                connected_steam.shutdown();
+            }
+            respond(parent_tx, SteamResponse::Success(true), "Reconnect");
+            continue;
+        }
+
+        // Only the GUI's listing can be cancelled, by its command reader
+        if message == SteamCommand::CancelAppList {
+            respond(parent_tx, SteamResponse::Success(false), "CancelAppList");
+            continue;
+        }
+
+        // Only looks at the app servers, answers even while Steam is unreachable
+        if message == SteamCommand::ListRunningApps {
+            let mut running_apps: Vec<u32> = app_servers.running.keys().copied().collect();
+            running_apps.sort_unstable();
+            respond(parent_tx, SteamResponse::Success(running_apps), "ListRunningApps");
+            continue;
+        }
+
+        // Answers while Steam is unreachable too, which is when the recent errors matter
+        if message == SteamCommand::GetDiagnostics {
+            let diagnostics = get_diagnostics(
+                &mut app_servers,
+                &recent_errors,
+                connected_steam.is_some(),
+            );
+            respond(parent_tx, SteamResponse::Success(diagnostics), "GetDiagnostics");
+            continue;
+        }
+
+        // Debug builds answer for the development app 0, `--mock` for everything it can
+        if (mock || cfg!(debug_assertions))
+            && let Some(response) = mock_response(&message, mock)
+        {
+            send_response(parent_tx, response, "Mock");
+            continue;
+        }
+        if mock && message != SteamCommand::Shutdown {
+            respond(
+                parent_tx,
+                SteamResponse::<()>::Error(SamError::SteamConnectionFailed),
+                "Mock unavailable",
+            );
+            continue;
+        }
+
         if connected_steam.is_none() {
             if message == SteamCommand::Shutdown {
-                let response = SteamResponse::Success(true).sam_serialize();
-                if let Err(e) = parent_tx.write_all(&response) {
-                    eprintln!("[ORCHESTRATOR] Failed to send response: {e}");
-                }
+                respond(parent_tx, SteamResponse::Success(true), "Shutdown");
                 dev_println!("[ORCHESTRATOR] Exiting");
                 break 0;
             }
 
             connected_steam = match ConnectedSteam::new() {
                 Ok(c) => Some(c),
-                Err(_e) => {
-                    dev_println!("[ORCHESTRATOR] Error connecting to Steam: {e}");
-                    let response: SteamResponse<String> =
-                        SteamResponse::Error(SamError::SteamConnectionFailed);
-                    let response = response.sam_serialize();
-                    if let Err(e) = parent_tx.write_all(&response) {
-                        eprintln!("[ORCHESTRATOR] Failed to send response: {e}");
-                    }
+                Err(e) => {
+                    eprintln!("[ORCHESTRATOR] Error connecting to Steam: {e}");
+                    // A missing library or user is worth telling apart, the user can fix it
+                    let error = match e.downcast::<SamError>() {
+                        Ok(e) if matches!(
+                            *e,
+                            SamError::SteamClientNotFound(_) | SamError::SteamNotLoggedIn
+                        ) => *e,
+                        _ => SamError::SteamConnectionFailed,
+                    };
+                    respond(parent_tx, SteamResponse::<()>::Error(error), "Steam connection");
                     continue;
                 }
             };
@@ -114,7 +561,15 @@ pub fn orchestrator(parent_tx: &mut Sender, parent_rx: &mut Recver) -> i32 {
                 continue;
             }
         };
-        let continue_running = process_command(message, parent_tx, &mut children_processes, cs);
+        let continue_running = process_command(
+            message,
+            client.owner(),
+            parent_tx,
+            &mut app_servers,
+            dry_run,
+            offline,
+            cs,
+        );
         if !continue_running {
             break 0;
         }
@@ -124,8 +579,11 @@ pub fn orchestrator(parent_tx: &mut Sender, parent_rx: &mut Recver) -> i32 {
 /// Handles a single SteamCommand, dispatching to the appropriate logic.
 fn process_command(
     command: SteamCommand,
-    tx: &mut Sender,
-    children_processes: &mut HashMap<u32, BidirChild>,
+    owner: Owner,
+    tx: &mut dyn Write,
+    app_servers: &mut AppServers,
+    dry_run: bool,
+    offline: bool,
     connected_steam: &mut ConnectedSteam,
 ) -> bool {
     match command {
@@ -133,202 +591,182 @@ fn process_command(
             dev_println!("[ORCHESTRATOR] Received GetOwnedAppList");
             let apps_001 = &connected_steam.apps_001;
             let apps = &connected_steam.apps;
-            let app_lister = AppLister::new(apps_001, apps);
+            let settings = load_settings();
+            let app_lister = AppLister::new(
+                apps_001,
+                apps,
+                settings.steam_language,
+                offline || settings.offline,
+            )
+            .with_cancel_flag(&APP_LIST_CANCELLED);
 
             match app_lister.get_owned_apps() {
                 Ok(apps) => {
-                    let response = SteamResponse::Success(apps).sam_serialize();
-                    send_response(tx, response, "GetOwnedAppList");
+                    respond(tx, SteamResponse::Success(apps), "GetOwnedAppList");
                 }
                 Err(e) => {
                     dev_println!("[ORCHESTRATOR] Error getting owned apps: {e}");
-                    let response = SteamResponse::<()>::Error(e).sam_serialize();
-                    send_response(tx, response, "GetOwnedAppList Error");
+                    respond(tx, SteamResponse::<()>::Error(e), "GetOwnedAppList Error");
+                }
+            };
+        }
+        SteamCommand::GetInstalledAppList => {
+            dev_println!("[ORCHESTRATOR] Received GetInstalledAppList");
+            let app_list = match connected_steam.app_list() {
+                Ok(app_list) => app_list,
+                Err(e) => {
+                    eprintln!("[ORCHESTRATOR] ISteamAppList is unavailable: {e}");
+                    respond(tx, SteamResponse::<()>::Error(SamError::AppListRetrievalFailed), "GetInstalledAppList unavailable");
+                    return true;
+                }
+            };
+            let settings = load_settings();
+            let app_lister = AppLister::new(
+                &connected_steam.apps_001,
+                &connected_steam.apps,
+                settings.steam_language,
+                offline || settings.offline,
+            );
+
+            match app_lister.get_installed_apps(&app_list) {
+                Ok(apps) => {
+                    respond(tx, SteamResponse::Success(apps), "GetInstalledAppList");
+                }
+                Err(e) => {
+                    respond(tx, SteamResponse::<()>::Error(e), "GetInstalledAppList Error");
                 }
             };
         }
         SteamCommand::LaunchApp(app_id) => {
             dev_println!("[ORCHESTRATOR] LaunchApp {}", app_id);
-            #[cfg(debug_assertions)]
-            if app_id == 0 {
-                let response = SteamResponse::<bool>::Success(true).sam_serialize();
-                send_response(tx, response, "LaunchApp dev");
+            if app_servers.running.contains_key(&app_id) {
+                // Another window has it open, both use the same app server
+                if app_servers.owners.claim(app_id, owner) {
+                    respond(tx, SteamResponse::Success(true), "LaunchApp shared");
+                } else {
+                    eprintln!("[ORCHESTRATOR] App {} is already running", app_id);
+                    respond(tx, SteamResponse::<()>::Error(SamError::UnknownError), "LaunchApp already running");
+                }
                 return true;
             }
-            if children_processes.contains_key(&app_id) {
-                eprintln!("[ORCHESTRATOR] App {} is already running", app_id);
-                let response = SteamResponse::<()>::Error(SamError::UnknownError).sam_serialize();
-                send_response(tx, response, "LaunchApp already running");
+            let language = load_settings().steam_language;
+            if let Some(mut child) = app_servers.warm.take(app_id, &language) {
+                dev_println!("[ORCHESTRATOR] Reusing the app server kept running for {app_id}");
+                // Dry-run may have been toggled while it was parked
+                send_app_command(&mut child, SteamCommand::SetDryRun(dry_run));
+                app_servers.running.insert(app_id, child);
+                app_servers.owners.claim(app_id, owner);
+                record_children(&app_servers.running, &app_servers.warm);
+                respond(tx, SteamResponse::Success(true), "LaunchApp reused");
                 return true;
             }
             let current_exe = match get_executable_path() {
                 Ok(path) => path,
                 Err(e) => {
                     eprintln!("[ORCHESTRATOR] Failed to get executable path: {e}");
-                    let response = SteamResponse::<()>::Error(SamError::UnknownError).sam_serialize();
-                    send_response(tx, response, "LaunchApp exe path error");
+                    respond(tx, SteamResponse::<()>::Error(SamError::UnknownError), "LaunchApp exe path error");
                     return true;
                 }
             };
-            let child = match BidirChild::new(Command::new(current_exe).arg(format!("--app={app_id}"))) {
+            let mut command = Command::new(current_exe);
+            command.arg(format!("--app={app_id}"));
+            if dry_run {
+                command.arg("--dry-run");
+            }
+            let child = match BidirChild::new(&mut command) {
                 Ok(child) => child,
                 Err(e) => {
                     eprintln!("[ORCHESTRATOR] Could not create app server process: {e}");
-                    let response = SteamResponse::<()>::Error(SamError::UnknownError).sam_serialize();
-                    send_response(tx, response, "LaunchApp child error");
+                    respond(tx, SteamResponse::<()>::Error(SamError::UnknownError), "LaunchApp child error");
                     return true;
                 }
             };
-            children_processes.insert(app_id, child);
-            let response = SteamResponse::Success(true).sam_serialize();
-            send_response(tx, response, "LaunchApp success");
+            app_servers.running.insert(app_id, child);
+            app_servers.owners.claim(app_id, owner);
+            record_children(&app_servers.running, &app_servers.warm);
+            respond(tx, SteamResponse::Success(true), "LaunchApp success");
         }
         SteamCommand::StopApp(app_id) => {
-            #[cfg(debug_assertions)]
-            if app_id == 0 {
-                let response = SteamResponse::<bool>::Success(true).sam_serialize();
-                send_response(tx, response, "StopApp dev");
-                return true;
-            }
-            if !children_processes.contains_key(&app_id) {
-                eprintln!("[ORCHESTRATOR] App {} is not running", app_id);
-                let response = SteamResponse::<()>::Error(SamError::UnknownError).sam_serialize();
-                send_response(tx, response, "StopApp not running");
-                return true;
+            match app_servers.owners.release(app_id, owner) {
+                None => {
+                    eprintln!("[ORCHESTRATOR] App {} is not running", app_id);
+                    respond(tx, SteamResponse::<()>::Error(SamError::UnknownError), "StopApp not running");
+                    return true;
+                }
+                // Still open in another window
+                Some(false) => {
+                    respond(tx, SteamResponse::Success(true), "StopApp shared");
+                    return true;
+                }
+                Some(true) => {}
             }
-            let mut bidir_opt = children_processes.remove(&app_id);
-            let bidir = match bidir_opt.as_mut() {
+            let bidir = match app_servers.running.remove(&app_id) {
                 Some(b) => b,
                 _none => {
                     eprintln!("[ORCHESTRATOR] No child process found for app_id {app_id}");
                     return true;
                 }
             };
-            let response = send_app_command(bidir, SteamCommand::Shutdown);
-            if let Err(e) = bidir.child.wait() {
-                eprintln!("[ORCHESTRATOR] Failed to wait child process: {e}");
-            }
-            send_response(tx, response, "StopApp");
+            // Keep it around for a while, the user often comes back to the same app
+            let settings = load_settings();
+            let grace = Duration::from_secs(settings.keep_app_running_secs);
+            app_servers.warm.park(app_id, bidir, grace, settings.steam_language);
+            record_children(&app_servers.running, &app_servers.warm);
+            respond(tx, SteamResponse::Success(true), "StopApp");
         }
+        // Stops every app server of the owner, and the parked ones, which are nobody's. The
+        // windows of one GUI process share an owner, it sends them all back to the library
+        // first. Other processes and RPC connections keep theirs.
         SteamCommand::StopApps => {
             dev_println!("[ORCHESTRATOR] StopApps");
-            for (_app_id, child) in children_processes.iter_mut() {
-                send_app_command(child, SteamCommand::Shutdown);
-                dev_println!("[ORCHESTRATOR] Sent shutdown command to app {app_id}");
-                if let Err(e) = child.child.wait() {
-                    eprintln!("[ORCHESTRATOR] Failed to wait child process: {e}");
-                }
-            }
-            children_processes.clear();
-            let response = SteamResponse::Success(true).sam_serialize();
-            send_response(tx, response, "StopApps");
+            app_servers.release_all(owner);
+            app_servers.warm.shutdown_all();
+            record_children(&app_servers.running, &app_servers.warm);
+            respond(tx, SteamResponse::Success(true), "StopApps");
         }
+        // The other instances take over and launch their app servers again
         SteamCommand::Shutdown => {
-            for (_app_id, child) in children_processes.iter_mut() {
-                send_app_command(child, SteamCommand::Shutdown);
-                dev_println!("[ORCHESTRATOR] Sent shutdown command to app {app_id}");
-                if let Err(e) = child.child.wait() {
-                    eprintln!("[ORCHESTRATOR] Failed to wait child process: {e}");
-                }
-            }
+            app_servers.stop_all();
             connected_steam.shutdown();
-            let response = SteamResponse::Success(true).sam_serialize();
-            send_response(tx, response, "Shutdown");
+            respond(tx, SteamResponse::Success(true), "Shutdown");
             return false;
         }
         SteamCommand::Status => {
-            let response = SteamResponse::Success(true).sam_serialize();
-            send_response(tx, response, "Status");
-        }
-        SteamCommand::GetAchievements(app_id) => {
-            #[cfg(debug_assertions)]
-            if app_id == 0 {
-                let mut ach_infos = vec![];
-                for i in 1..1000 {
-                    let ach_info = AchievementInfo {
-                        id: format!("DEV_ACH_{i}"),
-                        is_achieved: (i % 2) == 0,
-                        name: format!("Development achievement {i}"),
-                        global_achieved_percent: None,
-                        permission: 0,
-                        description: "Description".to_string(),
-                        icon_locked: "".to_string(),
-                        icon_normal: "".to_string(),
-                        unlock_time: None,
-                    };
-                    ach_infos.push(ach_info);
-                }
-                let response = SteamResponse::<Vec<AchievementInfo>>::Success(ach_infos).sam_serialize();
-                send_response(tx, response, "GetAchievements dev");
-                return true;
-            }
-            if let Some(bidir) = children_processes.get_mut(&app_id) {
-                let response = send_app_command(bidir, SteamCommand::GetAchievements(app_id));
-                send_response(tx, response, "GetAchievements");
-            } else {
-                let response = SteamResponse::<()>::Error(SamError::AppMismatchError).sam_serialize();
-                send_response(tx, response, "GetAchievements not running");
-            }
-        }
-        SteamCommand::GetStats(app_id) => {
-            #[cfg(debug_assertions)]
-            if app_id == 0 {
-                let response = SteamResponse::<Vec<StatInfo>>::Success(vec![]).sam_serialize();
-                send_response(tx, response, "GetStats dev");
-                return true;
-            }
-            if let Some(bidir) = children_processes.get_mut(&app_id) {
-                let response = send_app_command(bidir, SteamCommand::GetStats(app_id));
-                send_response(tx, response, "GetStats");
-            } else {
-                let response = SteamResponse::<()>::Error(SamError::AppMismatchError).sam_serialize();
-                send_response(tx, response, "GetStats not running");
-            }
-        }
-        SteamCommand::SetAchievement(app_id, unlocked, achievement_id) => {
-            #[cfg(debug_assertions)]
-            if app_id == 0 {
-                let response = SteamResponse::<bool>::Success(true).sam_serialize();
-                send_response(tx, response, "SetAchievement dev");
-                return true;
-            }
-            if let Some(bidir) = children_processes.get_mut(&app_id) {
-                let response = send_app_command(
-                    bidir,
-                    SteamCommand::SetAchievement(app_id, unlocked, achievement_id),
-                );
-                send_response(tx, response, "SetAchievement");
-            } else {
-                let response = SteamResponse::<()>::Error(SamError::AppMismatchError).sam_serialize();
-                send_response(tx, response, "SetAchievement not running");
-            }
+            respond(tx, SteamResponse::Success(true), "Status");
         }
-        SteamCommand::SetIntStat(app_id, stat_id, value) => {
-            if let Some(bidir) = children_processes.get_mut(&app_id) {
-                let response = send_app_command(bidir, SteamCommand::SetIntStat(app_id, stat_id, value));
-                send_response(tx, response, "SetIntStat");
-            } else {
-                let response = SteamResponse::<()>::Error(SamError::AppMismatchError).sam_serialize();
-                send_response(tx, response, "SetIntStat not running");
-            }
+        // Handled by the main loop, which owns the dry-run flag and the Steam connection
+        SteamCommand::SetDryRun(_)
+        | SteamCommand::Reconnect
+        | SteamCommand::ListRunningApps
+        | SteamCommand::GetDiagnostics
+        | SteamCommand::CancelAppList => {}
+        SteamCommand::GetAchievementSummaries(app_ids) => {
+            let summaries = get_achievement_summaries(&app_ids);
+            respond(tx, SteamResponse::Success(summaries), "GetAchievementSummaries");
         }
-        SteamCommand::SetFloatStat(app_id, stat_id, value) => {
-            if let Some(bidir) = children_processes.get_mut(&app_id) {
-                let response = send_app_command(bidir, SteamCommand::SetFloatStat(app_id, stat_id, value));
-                send_response(tx, response, "SetFloatStat");
-            } else {
-                let response = SteamResponse::<()>::Error(SamError::AppMismatchError).sam_serialize();
-                send_response(tx, response, "SetFloatStat not running");
-            }
+        SteamCommand::SearchAchievements(app_ids, query) => {
+            let language = load_settings()
+                .steam_language
+                .filter(|language| !language.is_empty())
+                .unwrap_or_else(|| connected_steam.apps.get_current_game_language());
+            let matches = search_achievements(&app_ids, &query, &language);
+            respond(tx, SteamResponse::Success(matches), "SearchAchievements");
         }
-        SteamCommand::ResetStats(app_id, achievements_too) => {
-            if let Some(bidir) = children_processes.get_mut(&app_id) {
-                let response = send_app_command(bidir, SteamCommand::ResetStats(app_id, achievements_too));
-                send_response(tx, response, "ResetStats");
-            } else {
-                let response = SteamResponse::<()>::Error(SamError::AppMismatchError).sam_serialize();
-                send_response(tx, response, "ResetStats not running");
-            }
+        command @ (SteamCommand::GetAchievements(app_id, _)
+        | SteamCommand::RefreshAchievements(app_id, _)
+        | SteamCommand::GetAchievement(app_id, _)
+        | SteamCommand::GetStats(app_id)
+        | SteamCommand::SetAchievement(app_id, ..)
+        | SteamCommand::SetAchievements(app_id, ..)
+        | SteamCommand::GetUserAchievements(app_id, _)
+        | SteamCommand::UnlockAchievementViaStat(app_id, _)
+        | SteamCommand::SetIntStat(app_id, ..)
+        | SteamCommand::SetFloatStat(app_id, ..)
+        | SteamCommand::ResetStats(app_id, _)
+        | SteamCommand::ResetStat(app_id, _)
+        | SteamCommand::GetLastStored(app_id)
+        | SteamCommand::CheckSchema(app_id)) => {
+            forward_to_child(&mut app_servers.running, app_id, command, tx);
         }
     };
     true
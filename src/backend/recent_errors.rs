@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// The orchestrator keeps the last errors it answered commands with, so that bug reports
+// show intermittent failures without the user digging through the logs. Responses are
+// watched on their way to the client, which catches the errors of the app servers too.
+
+use crate::utils::ipc_types::{RecentError, response_error};
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    io::{self, Write},
+    time::SystemTime,
+};
+
+/// Number of errors kept for diagnostics.
+const RECENT_ERRORS_CAPACITY: usize = 20;
+
+/// The latest errors, oldest first.
+#[derive(Debug, Default)]
+pub struct RecentErrors {
+    errors: RefCell<VecDeque<RecentError>>,
+}
+
+impl RecentErrors {
+    /// Notes the error `message`, a length-prefixed response to `command`, carries if any.
+    pub fn record_response(&self, command: &str, message: &[u8]) {
+        let payload = &message[size_of::<usize>().min(message.len())..];
+        let Some(error) = response_error(payload) else {
+            return;
+        };
+        let mut errors = self.errors.borrow_mut();
+        if errors.len() == RECENT_ERRORS_CAPACITY {
+            errors.pop_front();
+        }
+        errors.push_back(RecentError {
+            time: SystemTime::now(),
+            command: command.to_owned(),
+            error,
+        });
+    }
+
+    pub fn to_vec(&self) -> Vec<RecentError> {
+        self.errors.borrow().iter().cloned().collect()
+    }
+}
+
+/// Writes the responses to a command through to its client, recording the errors.
+pub struct ErrorWatch<'a> {
+    inner: &'a mut dyn Write,
+    command: String,
+    recent_errors: &'a RecentErrors,
+}
+
+impl<'a> ErrorWatch<'a> {
+    pub fn new(inner: &'a mut dyn Write, command: String, recent_errors: &'a RecentErrors) -> Self {
+        Self {
+            inner,
+            command,
+            recent_errors,
+        }
+    }
+}
+
+impl Write for ErrorWatch<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    // Responses are sent whole with `write_all`, one message per call
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.recent_errors.record_response(&self.command, buf);
+        self.inner.write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,310 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// With `--serve`, the primary orchestrator also takes JSON-RPC 2.0 requests from other
+// programs on a local socket, `rpc.sock` in the app cache directory (on Windows, a named
+// pipe whose name is in `rpc.pipe` there). Requests and responses are one JSON object per
+// line. The method is the name of a `SteamCommand` and the params are its fields, in an
+// array, as in `{"jsonrpc":"2.0","id":1,"method":"GetAchievements","params":[480,null]}`.
+// The result is what the command succeeds with, and a failure is an error whose `data`
+// is the `SamError`. Commands reporting progress send `progress` notifications, carrying
+// the request id, before their response. A `Shutdown` only stops the app servers the
+// client launched and nobody else uses, as does the client disconnecting. Commands that
+// would pull the rug from under the GUI, as resetting stats or closing every app, are refused.
+
+use crate::backend::shared_orchestrator::{Client, Owner, next_connection_id};
+use crate::dev_println;
+use crate::utils::app_paths::get_app_cache_dir;
+use crate::utils::ipc_types::{SamError, SteamCommand, SteamResponse};
+use interprocess::local_socket::{ListenerOptions, Name, Stream, prelude::*};
+use serde_json::{Value, json};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    sync::mpsc,
+    thread,
+};
+
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INTERNAL_ERROR: i32 = -32603;
+/// Code of the errors the command itself failed with.
+const COMMAND_ERROR: i32 = -32000;
+
+/// Takes the responses the orchestrator writes for an RPC client, as it writes to the GUI.
+pub struct RpcReplies(pub mpsc::Sender<Vec<u8>>);
+
+impl Write for RpcReplies {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .send(buf.to_vec())
+            .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Where the socket is on Unix, and where the name of the pipe is written on Windows.
+fn get_socket_path() -> io::Result<std::path::PathBuf> {
+    let dir = get_app_cache_dir().map_err(|_| io::Error::other("no app cache directory"))?;
+    let file_name = if cfg!(windows) { "rpc.pipe" } else { "rpc.sock" };
+    Ok(std::path::PathBuf::from(dir).join(file_name))
+}
+
+/// The socket lives in the app cache directory, so it is private to the user.
+#[cfg(unix)]
+fn get_socket_name() -> io::Result<Name<'static>> {
+    use interprocess::local_socket::GenericFilePath;
+
+    get_socket_path()?.to_fs_name::<GenericFilePath>()
+}
+
+/// Named pipes are machine-wide on Windows, and anybody could take a name known in advance
+/// to get the requests. The name is random instead, and published in the app cache
+/// directory, which is private to the user, for clients to find it.
+#[cfg(windows)]
+fn get_socket_name() -> io::Result<Name<'static>> {
+    use interprocess::local_socket::GenericNamespaced;
+    use std::hash::{BuildHasher, RandomState};
+
+    let suffix = RandomState::new().hash_one(std::process::id());
+    let name = format!("samrewritten-rpc-{suffix:016x}");
+    std::fs::write(get_socket_path()?, &name)?;
+    name.to_ns_name::<GenericNamespaced>()
+}
+
+/// Whether an RPC client may send `command`. The others act on the GUI's app servers or
+/// settings behind its back.
+pub fn allowed_over_rpc(command: &SteamCommand) -> bool {
+    match command {
+        SteamCommand::StopApps
+        | SteamCommand::ResetStats(..)
+        | SteamCommand::ResetStat(..)
+        | SteamCommand::SetDryRun(_)
+        | SteamCommand::Reconnect
+        | SteamCommand::CancelAppList => false,
+        SteamCommand::GetOwnedAppList
+        | SteamCommand::GetInstalledAppList
+        | SteamCommand::LaunchApp(_)
+        | SteamCommand::StopApp(_)
+        | SteamCommand::Shutdown
+        | SteamCommand::Status
+        | SteamCommand::GetAchievements(..)
+        | SteamCommand::RefreshAchievements(..)
+        | SteamCommand::GetAchievement(..)
+        | SteamCommand::GetStats(_)
+        | SteamCommand::SetAchievement(..)
+        | SteamCommand::SetAchievements(..)
+        | SteamCommand::UnlockAchievementViaStat(..)
+        | SteamCommand::GetUserAchievements(..)
+        | SteamCommand::SetIntStat(..)
+        | SteamCommand::SetFloatStat(..)
+        | SteamCommand::GetDiagnostics
+        | SteamCommand::GetAchievementSummaries(_)
+        | SteamCommand::SearchAchievements(..)
+        | SteamCommand::GetLastStored(_)
+        | SteamCommand::ListRunningApps
+        | SteamCommand::CheckSchema(_) => true,
+    }
+}
+
+/// Turns a JSON-RPC method and params into the command they name, `None` if there is none.
+pub fn parse_rpc_command(method: &str, params: Option<Value>) -> Option<SteamCommand> {
+    let candidates = match params {
+        None | Some(Value::Null) => vec![Value::String(method.to_owned())],
+        Some(Value::Array(fields)) if fields.is_empty() => vec![Value::String(method.to_owned())],
+        // A command with a single field takes it bare, not in an array
+        Some(Value::Array(fields)) if fields.len() == 1 => {
+            vec![json!({ method: fields[0] }), json!({ method: fields })]
+        }
+        Some(params) => vec![json!({ method: params })],
+    };
+    candidates
+        .into_iter()
+        .find_map(|candidate| serde_json::from_value(candidate).ok())
+}
+
+fn rpc_error(id: &Value, code: i32, message: &str, data: Option<Value>) -> Value {
+    let mut error = json!({ "code": code, "message": message });
+    if let Some(data) = data {
+        error["data"] = data;
+    }
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+/// Turns one response of the orchestrator into the JSON-RPC message sent for request `id`.
+/// Returns whether it was the final response rather than a progress report.
+pub fn rpc_message(id: &Value, payload: &[u8]) -> (Value, bool) {
+    match serde_json::from_slice::<SteamResponse<Value>>(payload) {
+        // Writes made in dry-run mode succeed as far as the client is concerned
+        Ok(SteamResponse::Success(result) | SteamResponse::Simulated(result)) => {
+            (json!({ "jsonrpc": "2.0", "id": id, "result": result }), true)
+        }
+        Ok(SteamResponse::Error(error)) => {
+            let data = serde_json::to_value(&error).ok();
+            (rpc_error(id, COMMAND_ERROR, &error.to_string(), data), true)
+        }
+        Ok(SteamResponse::Progress(done, total)) => (
+            json!({
+                "jsonrpc": "2.0",
+                "method": "progress",
+                "params": { "id": id, "done": done, "total": total },
+            }),
+            false,
+        ),
+        Err(e) => (rpc_error(id, INTERNAL_ERROR, &e.to_string(), None), true),
+    }
+}
+
+fn send_line(mut stream: &Stream, message: &Value) -> io::Result<()> {
+    let mut line = message.to_string();
+    line.push('\n');
+    stream.write_all(line.as_bytes())
+}
+
+/// Queues `command` with the orchestrator and sends its responses to the client, until the
+/// final one.
+fn run_command(
+    stream: &Stream,
+    commands: &mpsc::Sender<(SteamCommand, Client)>,
+    connection: usize,
+    id: &Value,
+    command: SteamCommand,
+) -> io::Result<()> {
+    let (reply_sender, replies) = mpsc::channel();
+    if commands.send((command, Client::Rpc(connection, reply_sender))).is_err() {
+        return send_line(stream, &rpc_error(id, INTERNAL_ERROR, "Shutting down", None));
+    }
+
+    // Messages are length-prefixed, as on the pipes, and may arrive in pieces
+    let mut pending = Vec::new();
+    let prefix_len = size_of::<usize>();
+    // Ends once the orchestrator is done with the command and dropped its sender
+    while let Ok(bytes) = replies.recv() {
+        pending.extend_from_slice(&bytes);
+        while pending.len() >= prefix_len {
+            let mut length = [0u8; size_of::<usize>()];
+            length.copy_from_slice(&pending[..prefix_len]);
+            let end = prefix_len + usize::from_le_bytes(length);
+            if pending.len() < end {
+                break;
+            }
+            let (message, is_final) = rpc_message(id, &pending[prefix_len..end]);
+            pending.drain(..end);
+            send_line(stream, &message)?;
+            if is_final {
+                return Ok(());
+            }
+        }
+    }
+
+    // No response at all, the command was dropped
+    let error = SamError::UnknownError;
+    let data = serde_json::to_value(&error).ok();
+    send_line(stream, &rpc_error(id, COMMAND_ERROR, &error.to_string(), data))
+}
+
+/// Answers the requests of one client until it disconnects.
+fn serve_client(stream: Stream, commands: mpsc::Sender<(SteamCommand, Client)>) {
+    let connection = next_connection_id();
+    let reader = BufReader::new(&stream);
+    for line in reader.lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let request: Value = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let error = rpc_error(&Value::Null, PARSE_ERROR, &e.to_string(), None);
+                if send_line(&stream, &error).is_err() {
+                    break;
+                }
+                continue;
+            }
+        };
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let Some(method) = request.get("method").and_then(Value::as_str) else {
+            let error = rpc_error(&id, INVALID_REQUEST, "Invalid request", None);
+            if send_line(&stream, &error).is_err() {
+                break;
+            }
+            continue;
+        };
+
+        let sent = match parse_rpc_command(method, request.get("params").cloned()) {
+            Some(command) if !allowed_over_rpc(&command) => {
+                let message = format!("{method} is not available over JSON-RPC");
+                send_line(&stream, &rpc_error(&id, METHOD_NOT_FOUND, &message, None))
+            }
+            Some(command) => {
+                dev_println!("[RPC] Received command: {command:?}");
+                run_command(&stream, &commands, connection, &id, command)
+            }
+            None => {
+                let message = format!("No command {method} with these params");
+                send_line(&stream, &rpc_error(&id, METHOD_NOT_FOUND, &message, None))
+            }
+        };
+        if sent.is_err() {
+            break;
+        }
+    }
+    dev_println!("[RPC] Client disconnected");
+    let owner = Owner::Connection(connection);
+    commands.send((SteamCommand::Shutdown, Client::Silent(owner))).ok();
+}
+
+/// Accepts JSON-RPC clients and queues their commands with the GUI's ones.
+/// Must only be called by the primary orchestrator, which owns the socket name.
+pub fn spawn_rpc_listener(commands: mpsc::Sender<(SteamCommand, Client)>) {
+    let listener = get_socket_name().and_then(|name| {
+        // Left behind by a crashed primary, the lock says nobody else uses it
+        #[cfg(unix)]
+        if let Ok(path) = get_socket_path() {
+            let _ = std::fs::remove_file(path);
+        }
+        ListenerOptions::new().name(name).create_sync()
+    });
+    let listener = match listener {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("[ORCHESTRATOR] Failed to listen for RPC clients: {e}");
+            return;
+        }
+    };
+    dev_println!("[ORCHESTRATOR] Serving JSON-RPC requests");
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("[ORCHESTRATOR] Failed to accept an RPC client: {e}");
+                    continue;
+                }
+            };
+            dev_println!("[RPC] Client connected");
+            let commands = commands.clone();
+            thread::spawn(move || serve_client(stream, commands));
+        }
+    });
+}
@@ -0,0 +1,373 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Only one orchestrator per user talks to Steam. The first one to start takes
+// `orchestrator.lock` in the app cache directory and listens on a local socket. Orchestrators
+// started by other SamRewritten instances find the lock taken and relay their GUI's commands
+// to the first one instead of opening a second Steam connection. If the first orchestrator
+// goes away, a relaying one takes over and launches again the app servers its GUI was using.
+
+use crate::dev_println;
+use crate::utils::{
+    app_paths::get_app_cache_dir,
+    ipc_types::{
+        MAX_MESSAGE_LENGTH, SamError, SamSerializable, SteamCommand, SteamResponse,
+        is_progress_message,
+    },
+};
+use interprocess::local_socket::{ListenerOptions, Name, Stream, prelude::*};
+use interprocess::unnamed_pipe::{Recver, Sender};
+use std::{
+    collections::HashSet,
+    fs::{File, OpenOptions},
+    io::{self, Read, Write},
+    path::PathBuf,
+    sync::{
+        Arc, mpsc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+const LOCK_FILE_NAME: &str = "orchestrator.lock";
+/// Time given to a starting orchestrator to open its socket.
+const CONNECT_ATTEMPTS: u32 = 20;
+const CONNECT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Where a command came from, and so where its responses go.
+pub enum Client {
+    /// The GUI that started this orchestrator, through its pipes.
+    Gui,
+    /// An orchestrator relaying another SamRewritten instance, with its connection id.
+    Peer(usize, Arc<Stream>),
+    /// A program driving SamRewritten through JSON-RPC, see `rpc_server`, with its
+    /// connection id.
+    Rpc(usize, mpsc::Sender<Vec<u8>>),
+    /// A command queued on behalf of `Owner` by the orchestrator itself. Nobody reads the
+    /// responses, they are dropped.
+    Silent(Owner),
+}
+
+/// Who uses an app server. A server shared by several clients keeps running until the last
+/// of them lets it go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Owner {
+    /// The GUI that started this orchestrator.
+    Gui,
+    /// A relaying orchestrator or a JSON-RPC client, by connection id.
+    Connection(usize),
+}
+
+impl Client {
+    pub fn owner(&self) -> Owner {
+        match self {
+            Client::Gui => Owner::Gui,
+            Client::Peer(connection, _) | Client::Rpc(connection, _) => {
+                Owner::Connection(*connection)
+            }
+            Client::Silent(owner) => *owner,
+        }
+    }
+}
+
+/// Ids for the connections of other instances and JSON-RPC clients, unique for the process.
+pub fn next_connection_id() -> usize {
+    static NEXT_CONNECTION_ID: AtomicUsize = AtomicUsize::new(0);
+    NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// What this orchestrator does, decided once at startup.
+pub enum Role {
+    /// Talks to Steam and serves the other instances. Holds the lock for as long as it lives.
+    Primary(File),
+    /// Relays its GUI's commands to the primary orchestrator.
+    Relay(Stream),
+    /// Talks to Steam on its own, when the lock or the socket can't be used.
+    Standalone,
+}
+
+/// Outcome of relaying commands to the primary orchestrator.
+pub enum RelayEnd {
+    /// The GUI is gone or shut down, this orchestrator can exit.
+    Done,
+    /// The primary orchestrator went away. The command it did not get, if any, is returned
+    /// so this orchestrator can handle it once it took over.
+    PrimaryGone(Option<SteamCommand>),
+}
+
+fn get_lock_file_path() -> Option<PathBuf> {
+    get_app_cache_dir()
+        .ok()
+        .map(|dir| PathBuf::from(dir).join(LOCK_FILE_NAME))
+}
+
+/// The socket lives next to the lock file, so it is private to the user.
+#[cfg(unix)]
+fn get_socket_name() -> io::Result<Name<'static>> {
+    use interprocess::local_socket::GenericFilePath;
+
+    let path = get_lock_file_path()
+        .ok_or_else(|| io::Error::other("no app cache directory"))?
+        .with_extension("sock");
+    path.to_fs_name::<GenericFilePath>()
+}
+
+/// The primary orchestrator listens where the others connect.
+#[cfg(unix)]
+fn new_socket_name() -> io::Result<Name<'static>> {
+    get_socket_name()
+}
+
+/// Where the primary orchestrator publishes the name of its pipe on Windows.
+#[cfg(windows)]
+fn get_pipe_name_path() -> io::Result<PathBuf> {
+    get_lock_file_path()
+        .map(|path| path.with_extension("pipe"))
+        .ok_or_else(|| io::Error::other("no app cache directory"))
+}
+
+/// The name of the primary orchestrator's pipe, as it published it.
+#[cfg(windows)]
+fn get_socket_name() -> io::Result<Name<'static>> {
+    use interprocess::local_socket::GenericNamespaced;
+
+    std::fs::read_to_string(get_pipe_name_path()?)?
+        .trim()
+        .to_owned()
+        .to_ns_name::<GenericNamespaced>()
+}
+
+/// Named pipes are machine-wide on Windows, and anybody could take a name known in advance
+/// to get or forge the commands. The name is random instead, like the RPC pipe's, and
+/// published next to the lock file, which is private to the user.
+#[cfg(windows)]
+fn new_socket_name() -> io::Result<Name<'static>> {
+    use interprocess::local_socket::GenericNamespaced;
+    use std::hash::{BuildHasher, RandomState};
+
+    let suffix = RandomState::new().hash_one(std::process::id());
+    let name = format!("samrewritten-orchestrator-{suffix:016x}");
+    std::fs::write(get_pipe_name_path()?, &name)?;
+    name.to_ns_name::<GenericNamespaced>()
+}
+
+fn connect_to_primary() -> io::Result<Stream> {
+    let mut attempt = 1;
+    loop {
+        // On Windows, the primary may not have published its pipe name yet
+        match get_socket_name().and_then(Stream::connect) {
+            Ok(stream) => return Ok(stream),
+            Err(e) if attempt >= CONNECT_ATTEMPTS => return Err(e),
+            Err(_) => {
+                attempt += 1;
+                thread::sleep(CONNECT_RETRY_DELAY);
+            }
+        }
+    }
+}
+
+/// Takes the orchestrator lock, or connects to the orchestrator holding it.
+pub fn claim_role() -> Role {
+    let Some(path) = get_lock_file_path() else {
+        return Role::Standalone;
+    };
+    let lock = match OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+    {
+        Ok(lock) => lock,
+        Err(e) => {
+            eprintln!("[ORCHESTRATOR] Failed to open {}: {e}", path.display());
+            return Role::Standalone;
+        }
+    };
+
+    match lock.try_lock() {
+        Ok(()) => Role::Primary(lock),
+        Err(std::fs::TryLockError::WouldBlock) => match connect_to_primary() {
+            Ok(stream) => Role::Relay(stream),
+            Err(e) => {
+                eprintln!("[ORCHESTRATOR] Another orchestrator is running but unreachable: {e}");
+                Role::Standalone
+            }
+        },
+        Err(std::fs::TryLockError::Error(e)) => {
+            eprintln!("[ORCHESTRATOR] Failed to lock {}: {e}", path.display());
+            Role::Standalone
+        }
+    }
+}
+
+/// Accepts the relaying orchestrators and queues their commands with the GUI's ones.
+/// Must only be called by the primary orchestrator, which owns the socket name.
+pub fn spawn_peer_listener(commands: mpsc::Sender<(SteamCommand, Client)>) {
+    let listener = new_socket_name().and_then(|name| {
+        // Left behind by a crashed primary, the lock says nobody else uses it
+        #[cfg(unix)]
+        if let Some(path) = get_lock_file_path() {
+            let _ = std::fs::remove_file(path.with_extension("sock"));
+        }
+        ListenerOptions::new().name(name).create_sync()
+    });
+    let listener = match listener {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("[ORCHESTRATOR] Failed to listen for other instances: {e}");
+            return;
+        }
+    };
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => Arc::new(stream),
+                Err(e) => {
+                    eprintln!("[ORCHESTRATOR] Failed to accept another instance: {e}");
+                    continue;
+                }
+            };
+            dev_println!("[ORCHESTRATOR] Another instance connected");
+            let commands = commands.clone();
+            let connection = next_connection_id();
+            thread::spawn(move || {
+                let mut reader = &*stream;
+                while let Ok(command) = SteamCommand::from_recver(&mut reader) {
+                    if commands
+                        .send((command, Client::Peer(connection, stream.clone())))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+                // Gone without a word maybe, its app servers are no one's anymore
+                let owner = Owner::Connection(connection);
+                commands.send((SteamCommand::Shutdown, Client::Silent(owner))).ok();
+            });
+        }
+    });
+}
+
+/// Reads one length-prefixed message, prefix included.
+fn read_message(rx: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut buffer_len = [0u8; size_of::<usize>()];
+    rx.read_exact(&mut buffer_len)?;
+    let data_length = usize::from_le_bytes(buffer_len);
+    if data_length > MAX_MESSAGE_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message length {data_length} is too large"),
+        ));
+    }
+    let mut message = buffer_len.to_vec();
+    message.resize(buffer_len.len() + data_length, 0);
+    rx.read_exact(&mut message[buffer_len.len()..])?;
+    Ok(message)
+}
+
+/// Whether a final response is a success, as for a `LaunchApp` or `StopApp`.
+fn is_success(message: &[u8]) -> bool {
+    let payload = &message[size_of::<usize>().min(message.len())..];
+    matches!(
+        serde_json::from_slice::<SteamResponse<serde_json::Value>>(payload),
+        Ok(SteamResponse::Success(_) | SteamResponse::Simulated(_))
+    )
+}
+
+/// Launches the app servers in `held_apps` with the primary orchestrator, for the GUI was
+/// using them with an orchestrator that went away. The responses are the relay's own.
+fn reclaim_apps(mut primary: &Stream, held_apps: &HashSet<u32>) -> io::Result<()> {
+    for app_id in held_apps {
+        primary.write_all(&SteamCommand::LaunchApp(*app_id).sam_serialize())?;
+        read_message(&mut primary)?;
+    }
+    Ok(())
+}
+
+/// Passes the GUI's commands to the primary orchestrator and its responses back, starting
+/// with `pending` if any, until one side goes away. `held_apps` follows the app servers the
+/// GUI launched, so they can be launched again when the primary orchestrator changes.
+pub fn relay_to_primary(
+    parent_tx: &mut Sender,
+    parent_rx: &mut Recver,
+    stream: &Stream,
+    mut pending: Option<SteamCommand>,
+    held_apps: &mut HashSet<u32>,
+) -> RelayEnd {
+    let mut primary = stream;
+    if let Err(e) = reclaim_apps(primary, held_apps) {
+        eprintln!("[ORCHESTRATOR] Lost the primary orchestrator: {e}");
+        return RelayEnd::PrimaryGone(pending);
+    }
+    loop {
+        let command = match pending.take() {
+            Some(command) => command,
+            None => match SteamCommand::from_recver(parent_rx) {
+                Ok(command) => command,
+                Err(_) => return RelayEnd::Done,
+            },
+        };
+        // Answers nothing, and the primary's listing isn't ours to cancel
+        if command == SteamCommand::CancelAppList {
+            continue;
+        }
+        let is_shutdown = command == SteamCommand::Shutdown;
+
+        if let Err(e) = primary.write_all(&command.sam_serialize()) {
+            eprintln!("[ORCHESTRATOR] Lost the primary orchestrator: {e}");
+            return RelayEnd::PrimaryGone(Some(command));
+        }
+
+        loop {
+            let message = match read_message(&mut primary) {
+                Ok(message) => message,
+                Err(e) => {
+                    // The command may have been applied, it can't be replayed safely
+                    eprintln!("[ORCHESTRATOR] Lost the primary orchestrator: {e}");
+                    let response = SteamResponse::<()>::Error(SamError::SocketCommunicationFailed);
+                    if let Err(e) = parent_tx.write_all(&response.sam_serialize()) {
+                        eprintln!("[ORCHESTRATOR] Failed to send response: {e}");
+                    }
+                    return RelayEnd::PrimaryGone(None);
+                }
+            };
+            if let Err(e) = parent_tx.write_all(&message) {
+                eprintln!("[ORCHESTRATOR] Failed to send response: {e}");
+                return RelayEnd::Done;
+            }
+            if !is_progress_message(&message[size_of::<usize>()..]) {
+                if is_success(&message) {
+                    match command {
+                        SteamCommand::LaunchApp(app_id) => {
+                            held_apps.insert(app_id);
+                        }
+                        SteamCommand::StopApp(app_id) => {
+                            held_apps.remove(&app_id);
+                        }
+                        SteamCommand::StopApps | SteamCommand::Shutdown => held_apps.clear(),
+                        _ => {}
+                    }
+                }
+                break;
+            }
+        }
+
+        if is_shutdown {
+            return RelayEnd::Done;
+        }
+    }
+}
@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Tracks the app server processes spawned by an orchestrator in a small pid file, so that
+// a later orchestrator can kill the ones left behind if the previous session crashed.
+//
+// Each orchestrator owns `sessions/orchestrator-<pid>.pids` in the app cache directory,
+// with one `<app_id> <pid>` line per running app server. A file whose orchestrator is no
+// longer alive belongs to a crashed session.
+
+use crate::dev_println;
+use crate::utils::app_paths::get_app_cache_dir;
+use std::{
+    fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+const SESSION_FILE_PREFIX: &str = "orchestrator-";
+const SESSION_FILE_EXTENSION: &str = "pids";
+
+fn get_sessions_dir() -> Option<PathBuf> {
+    let cache_dir = get_app_cache_dir().ok()?;
+    let sessions_dir = PathBuf::from(cache_dir).join("sessions");
+    if let Err(e) = fs::create_dir_all(&sessions_dir) {
+        eprintln!("[ORCHESTRATOR] Failed to create sessions dir: {e}");
+        return None;
+    }
+    Some(sessions_dir)
+}
+
+fn get_session_file_path(orchestrator_pid: u32) -> Option<PathBuf> {
+    get_sessions_dir().map(|dir| {
+        dir.join(format!(
+            "{SESSION_FILE_PREFIX}{orchestrator_pid}.{SESSION_FILE_EXTENSION}"
+        ))
+    })
+}
+
+/// Returns true if `pid` is alive and was started with an argument beginning with `marker`.
+#[cfg(target_os = "linux")]
+fn is_our_process(pid: u32, marker: &str) -> bool {
+    let Ok(cmdline) = fs::read(format!("/proc/{pid}/cmdline")) else {
+        return false;
+    };
+    cmdline
+        .split(|byte| *byte == 0)
+        .any(|arg| arg.starts_with(marker.as_bytes()))
+}
+
+/// Returns true if `pid` is alive and runs our executable. Windows does not expose other
+/// processes' arguments without extra APIs, so the image name is the best we can check.
+#[cfg(target_os = "windows")]
+fn is_our_process(pid: u32, _marker: &str) -> bool {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let Ok(output) = Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .stderr(Stdio::null())
+        .output()
+    else {
+        return false;
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .to_lowercase()
+        .contains("samrewritten")
+}
+
+#[cfg(target_os = "linux")]
+fn kill_process(pid: u32) {
+    let _ = Command::new("kill")
+        .args(["-9", &pid.to_string()])
+        .stderr(Stdio::null())
+        .status();
+}
+
+#[cfg(target_os = "windows")]
+fn kill_process(pid: u32) {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let _ = Command::new("taskkill")
+        .args(["/PID", &pid.to_string(), "/F"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Kills the app servers left behind by orchestrators that are no longer running.
+/// Best effort: any failure is logged and otherwise ignored.
+pub fn kill_stale_children() {
+    let Some(sessions_dir) = get_sessions_dir() else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(&sessions_dir) else {
+        return;
+    };
+    let own_pid = std::process::id();
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(orchestrator_pid) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(SESSION_FILE_PREFIX))
+            .and_then(|name| name.strip_suffix(&format!(".{SESSION_FILE_EXTENSION}")))
+            .and_then(|pid| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        if orchestrator_pid == own_pid || is_our_process(orchestrator_pid, "--orchestrator") {
+            continue;
+        }
+
+        let contents = fs::read_to_string(&path).unwrap_or_default();
+        for line in contents.lines() {
+            let mut parts = line.split_whitespace();
+            let (Some(app_id), Some(Ok(pid))) =
+                (parts.next(), parts.next().map(str::parse::<u32>))
+            else {
+                continue;
+            };
+            if is_our_process(pid, "--app=") {
+                eprintln!("[ORCHESTRATOR] Killing stale app server {pid} for app {app_id}");
+                kill_process(pid);
+            }
+        }
+
+        if let Err(e) = fs::remove_file(&path) {
+            eprintln!("[ORCHESTRATOR] Failed to remove stale session file: {e}");
+        }
+    }
+}
+
+/// Records the currently running app servers of this orchestrator, as `(app_id, pid)` pairs.
+/// The file is removed once no app server is running anymore.
+pub fn write_children_pid_file(children: impl IntoIterator<Item = (u32, u32)>) {
+    let Some(path) = get_session_file_path(std::process::id()) else {
+        return;
+    };
+
+    let contents: String = children
+        .into_iter()
+        .map(|(app_id, pid)| format!("{app_id} {pid}\n"))
+        .collect();
+    if contents.is_empty() {
+        if path.exists() && fs::remove_file(&path).is_err() {
+            eprintln!("[ORCHESTRATOR] Failed to remove session file");
+        }
+        return;
+    }
+
+    if let Err(e) = fs::write(&path, contents) {
+        eprintln!("[ORCHESTRATOR] Failed to write session file: {e}");
+    } else {
+        dev_println!("[ORCHESTRATOR] Session file updated: {}", path.display());
+    }
+}
@@ -65,6 +65,35 @@ impl BitOrAssign for StatFlags {
     }
 }
 
+/// Permission bits of a stat or achievement, from its schema. Serialized as the raw integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Permission(pub i32);
+
+impl Permission {
+    /// Only the game's servers may change the value, Steam rejects the other writes.
+    const PROTECTED_BIT: i32 = 2;
+
+    pub fn bits(&self) -> i32 {
+        self.0
+    }
+
+    pub fn is_protected(&self) -> bool {
+        (self.0 & Self::PROTECTED_BIT) != 0
+    }
+
+    /// Whether bits other than the protected one are set, their meaning is unknown.
+    pub fn is_unknown(&self) -> bool {
+        (self.0 & !Self::PROTECTED_BIT) != 0
+    }
+}
+
+impl From<i32> for Permission {
+    fn from(bits: i32) -> Self {
+        Permission(bits)
+    }
+}
+
 /// Error returned when attempting to modify a protected stat.
 #[derive(Debug, Clone)]
 pub struct StatIsProtectedError {
@@ -93,11 +122,12 @@ impl fmt::Display for StatIsProtectedError {
 
 impl std::error::Error for StatIsProtectedError {}
 
-/// Definition of a stat (float or integer).
+/// Definition of a stat (float, integer, or only readable).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StatDefinition {
     Float(FloatStatDefinition),
     Integer(IntegerStatDefinition),
+    ReadOnly(ReadOnlyStatDefinition),
 }
 
 /// Common fields for all stat definitions.
@@ -106,7 +136,7 @@ pub struct BaseStatDefinition {
     pub id: String,
     pub app_id: u32,
     pub display_name: String,
-    pub permission: i32,
+    pub permission: Permission,
 }
 
 /// Definition for a floating-point stat.
@@ -132,11 +162,22 @@ pub struct IntegerStatDefinition {
     pub default_value: i32,
 }
 
-/// Runtime info for a stat (float or integer).
+/// Definition for a stat that is a bit field, or whose schema type is not a number type.
+/// Its value is read as an integer and shown, but never written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadOnlyStatDefinition {
+    pub base: BaseStatDefinition,
+    pub raw_type: i32,
+    /// Names of the bits, by bit index. Empty when the stat is not a bit field.
+    pub bits: Vec<(u32, String)>,
+}
+
+/// Runtime info for a stat (float, integer, or only readable).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum StatInfo {
     Float(FloatStatInfo),
     Integer(IntStatInfo),
+    ReadOnly(ReadOnlyStatInfo),
 }
 
 impl StatInfo {
@@ -144,6 +185,7 @@ impl StatInfo {
         match self {
             StatInfo::Float(f) => &f.id,
             StatInfo::Integer(i) => &i.id,
+            StatInfo::ReadOnly(r) => &r.id,
         }
     }
 
@@ -151,13 +193,15 @@ impl StatInfo {
         match self {
             StatInfo::Float(f) => &f.display_name,
             StatInfo::Integer(i) => &i.display_name,
+            StatInfo::ReadOnly(r) => &r.display_name,
         }
     }
 
-    pub fn permission(&self) -> i32 {
+    pub fn permission(&self) -> Permission {
         match self {
             StatInfo::Float(f) => f.permission,
             StatInfo::Integer(i) => i.permission,
+            StatInfo::ReadOnly(r) => r.permission,
         }
     }
 
@@ -165,6 +209,7 @@ impl StatInfo {
         match self {
             StatInfo::Float(f) => f.is_modified(),
             StatInfo::Integer(i) => i.is_modified(),
+            StatInfo::ReadOnly(_) => false,
         }
     }
 
@@ -172,6 +217,7 @@ impl StatInfo {
         match self {
             StatInfo::Float(f) => f.extra(),
             StatInfo::Integer(i) => i.extra(),
+            StatInfo::ReadOnly(r) => r.extra(),
         }
     }
 }
@@ -183,9 +229,13 @@ pub struct FloatStatInfo {
     pub app_id: u32,
     pub display_name: String,
     pub is_increment_only: bool,
-    pub permission: i32,
+    pub permission: Permission,
     pub original_value: f32,
     pub float_value: f32,
+    pub min_value: f32,
+    pub max_value: f32,
+    /// Largest change Steam accepts in one store, 0 when unlimited.
+    pub max_change: f32,
 }
 
 impl FloatStatInfo {
@@ -194,7 +244,7 @@ impl FloatStatInfo {
     }
 
     pub fn set_value(&mut self, value: f32) -> Result<(), StatIsProtectedError> {
-        if (self.permission & 2) != 0 && !self.float_value.eq(&value) {
+        if self.permission.is_protected() && !self.float_value.eq(&value) {
             return Err(StatIsProtectedError::new());
         }
         self.float_value = value;
@@ -210,10 +260,10 @@ impl FloatStatInfo {
         if self.is_increment_only {
             flags |= StatFlags::INCREMENT_ONLY;
         }
-        if (self.permission & 2) != 0 {
+        if self.permission.is_protected() {
             flags |= StatFlags::PROTECTED;
         }
-        if (self.permission & !2) != 0 {
+        if self.permission.is_unknown() {
             flags |= StatFlags::UNKNOWN_PERMISSION;
         }
         flags
@@ -227,9 +277,13 @@ pub struct IntStatInfo {
     pub app_id: u32,
     pub display_name: String,
     pub is_increment_only: bool,
-    pub permission: i32,
+    pub permission: Permission,
     pub original_value: i32,
     pub int_value: i32,
+    pub min_value: i32,
+    pub max_value: i32,
+    /// Largest change Steam accepts in one store, 0 when unlimited.
+    pub max_change: i32,
 }
 
 impl IntStatInfo {
@@ -238,7 +292,7 @@ impl IntStatInfo {
     }
 
     pub fn set_value(&mut self, value: i32) -> Result<(), StatIsProtectedError> {
-        if (self.permission & 2) != 0 && self.int_value != value {
+        if self.permission.is_protected() && self.int_value != value {
             return Err(StatIsProtectedError::new());
         }
         self.int_value = value;
@@ -254,10 +308,55 @@ impl IntStatInfo {
         if self.is_increment_only {
             flags |= StatFlags::INCREMENT_ONLY;
         }
-        if (self.permission & 2) != 0 {
+        if self.permission.is_protected() {
             flags |= StatFlags::PROTECTED;
         }
-        if (self.permission & !2) != 0 {
+        if self.permission.is_unknown() {
+            flags |= StatFlags::UNKNOWN_PERMISSION;
+        }
+        flags
+    }
+}
+
+/// Brings `value` within `max_change` of `original`, the stored value, since Steam drops
+/// stores that change a stat more than that. A `max_change` of 0 or less means no limit.
+pub fn limit_stat_change(original: f64, max_change: f64, value: f64) -> f64 {
+    if max_change <= 0.0 {
+        return value;
+    }
+    value.clamp(original - max_change, original + max_change)
+}
+
+/// Runtime info for a stat that is shown but can't be edited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadOnlyStatInfo {
+    pub id: String,
+    pub app_id: u32,
+    pub display_name: String,
+    pub permission: Permission,
+    pub value: i32,
+    /// Names of the bits, by bit index. Empty when the stat is not a bit field.
+    pub bits: Vec<(u32, String)>,
+}
+
+impl ReadOnlyStatInfo {
+    /// Names of the bits set in the value, in bit order.
+    pub fn set_bits(&self) -> Vec<&str> {
+        let mut bits: Vec<&(u32, String)> = self
+            .bits
+            .iter()
+            .filter(|(index, _)| *index < 32 && (self.value as u32) & (1 << index) != 0)
+            .collect();
+        bits.sort_by_key(|(index, _)| *index);
+        bits.into_iter().map(|(_, name)| name.as_str()).collect()
+    }
+
+    pub fn extra(&self) -> StatFlags {
+        let mut flags = StatFlags::NONE;
+        if self.permission.is_protected() {
+            flags |= StatFlags::PROTECTED;
+        }
+        if self.permission.is_unknown() {
             flags |= StatFlags::UNKNOWN_PERMISSION;
         }
         flags
@@ -274,7 +373,18 @@ pub struct AchievementDefinition {
     pub icon_normal: String,
     pub icon_locked: String,
     pub is_hidden: bool,
-    pub permission: i32,
+    pub permission: Permission,
+    pub progress: Option<AchievementProgress>,
+    /// Position of the achievement in the schema, the order the game lists them in.
+    pub order: u32,
+}
+
+/// Stat an achievement follows, from the `progress` block of its schema entry.
+/// Steam unlocks the achievement itself once the stat reaches `max_value`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AchievementProgress {
+    pub stat_id: String,
+    pub max_value: f32,
 }
 
 impl fmt::Display for AchievementDefinition {
@@ -287,21 +397,75 @@ impl fmt::Display for AchievementDefinition {
             } else {
                 &self.name
             },
-            self.permission
+            self.permission.bits()
         )
     }
 }
 
+/// How many achievements and stats an app defines, to tell apps worth managing apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct AchievementSummary {
+    pub achievements: u32,
+    pub stats: u32,
+    /// Unlocked achievements as Steam last cached them for the user, when it did.
+    #[serde(default)]
+    pub unlocked: Option<u32>,
+}
+
+impl AchievementSummary {
+    /// Whether there is anything at all to manage.
+    pub fn is_empty(&self) -> bool {
+        self.achievements == 0 && self.stats == 0
+    }
+}
+
+/// Achievements the schema on disk and Steam's live list disagree on, when the schema
+/// is outdated. Both are sorted by id.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SchemaCheck {
+    /// In the schema, unknown to Steam.
+    pub only_in_schema: Vec<String>,
+    /// Listed by Steam, missing from the schema.
+    pub only_in_steam: Vec<String>,
+}
+
+impl SchemaCheck {
+    /// Number of achievements only one side lists.
+    pub fn mismatch_count(&self) -> usize {
+        self.only_in_schema.len() + self.only_in_steam.len()
+    }
+}
+
+/// An achievement found by a search across the library.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AchievementMatch {
+    pub app_id: u32,
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Whether the user unlocked it, as Steam last cached it, when it did.
+    pub unlocked: Option<bool>,
+}
+
+/// Another user's state for one achievement, used to compare profiles.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserAchievement {
+    pub id: String,
+    pub is_achieved: bool,
+}
+
 /// Runtime info for an achievement.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AchievementInfo {
     pub id: String,
     pub is_achieved: bool,
     pub unlock_time: Option<SystemTime>,
-    pub permission: i32,
+    pub permission: Permission,
     pub icon_normal: String,
     pub icon_locked: String,
     pub name: String,
     pub description: String,
     pub global_achieved_percent: Option<f32>,
+    pub progress: Option<AchievementProgress>,
+    pub order: u32,
 }
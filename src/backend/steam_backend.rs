@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    backend::{connected_steam::ConnectedSteam, key_value::KeyValue},
+    dev_println,
+    steam_client::{
+        steamworks_types::{
+            AppId_t, CSteamID, EResult, GlobalAchievementPercentagesReady_t, SteamAPICall_t,
+            UserStatsReceived_t,
+        },
+        wrapper_types::{SteamCallbackId, SteamClientError},
+    },
+    utils::{app_paths::get_user_game_stats_schema_path, ipc_types::SamError},
+};
+use std::{fmt::Debug, path::PathBuf, time::Duration};
+
+/// How long Steam gets to answer an API call, such as a stats request.
+const API_CALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The Steam operations `AppManager` relies on.
+///
+/// `ConnectedSteam` talks to the real client. Tests use `MockSteamBackend` so the
+/// manager logic can run without Steam.
+pub trait SteamBackend: Debug {
+    /// Loads the `UserGameStatsSchema` key-value tree for an app. Fails with
+    /// `SchemaUnavailable` when Steam has not downloaded it.
+    fn load_schema(&self, app_id: AppId_t) -> Result<KeyValue, SamError>;
+    fn current_game_language(&self) -> String;
+    /// Requests the current user's stats and waits for them. Returns whether Steam answered with success.
+    fn request_current_stats(&self) -> Result<bool, SamError>;
+    /// Requests another user's stats for the current app and waits for them. Returns whether
+    /// Steam answered with success, which private profiles do not.
+    fn request_user_stats(&self, steam_id: u64) -> Result<bool, SamError>;
+    fn get_user_achievement_and_unlock_time(
+        &self,
+        steam_id: u64,
+        name: &str,
+    ) -> Result<(bool, u32), SteamClientError>;
+    /// Requests global achievement percentages and waits for them. Returns whether they are available.
+    fn request_global_achievement_percentages(&self) -> Result<bool, SamError>;
+    fn get_achievement_and_unlock_time(&self, name: &str) -> Result<(bool, u32), SteamClientError>;
+    fn get_achievement_achieved_percent(&self, name: &str) -> Result<f32, SteamClientError>;
+    fn get_num_achievements(&self) -> Result<u32, SteamClientError>;
+    fn get_achievement_name(&self, index: u32) -> Result<String, SteamClientError>;
+    fn get_achievement_display_attribute(&self, name: &str, key: &str) -> Result<String, SteamClientError>;
+    fn set_achievement(&self, name: &str) -> Result<(), SteamClientError>;
+    fn clear_achievement(&self, name: &str) -> Result<(), SteamClientError>;
+    fn get_stat_i32(&self, name: &str) -> Result<i32, SteamClientError>;
+    fn get_stat_float(&self, name: &str) -> Result<f32, SteamClientError>;
+    fn set_stat_i32(&self, name: &str, value: i32) -> Result<(), SteamClientError>;
+    fn set_stat_float(&self, name: &str, value: f32) -> Result<(), SteamClientError>;
+    fn store_stats(&self) -> Result<bool, SteamClientError>;
+    fn reset_all_stats(&self, achievements_too: bool) -> Result<bool, SteamClientError>;
+    fn shutdown(&self);
+}
+
+impl ConnectedSteam {
+    /// Waits up to `API_CALL_TIMEOUT` for an API call and returns its result, `None` if
+    /// Steam did not answer in time.
+    fn wait_for_api_call<T: Debug>(
+        &self,
+        callback_handle: SteamAPICall_t,
+        callback_id: SteamCallbackId,
+    ) -> Result<Option<T>, SamError> {
+        match self
+            .utils
+            .wait_for_api_call::<T>(callback_handle, callback_id, API_CALL_TIMEOUT)
+        {
+            Ok(result) => Ok(Some(result)),
+            Err(SteamClientError::Timeout) => {
+                eprintln!("[APP SERVER] Steam did not answer API call {callback_handle} in time");
+                Ok(None)
+            }
+            Err(_) => Err(SamError::UnknownError),
+        }
+    }
+}
+
+impl SteamBackend for ConnectedSteam {
+    fn load_schema(&self, app_id: AppId_t) -> Result<KeyValue, SamError> {
+        let schema_path = PathBuf::from(get_user_game_stats_schema_path(&app_id)?);
+        if !schema_path.exists() {
+            return Err(SamError::SchemaUnavailable);
+        }
+        KeyValue::load_as_binary(schema_path).map_err(|_| SamError::UnknownError)
+    }
+
+    fn current_game_language(&self) -> String {
+        self.apps.get_current_game_language()
+    }
+
+    fn request_current_stats(&self) -> Result<bool, SamError> {
+        let steam_id = self.user.get_steam_id().map_err(|_| SamError::UnknownError)?;
+        dev_println!(
+            "[APP SERVER] Requesting current stats for current user: {:?}",
+            steam_id
+        );
+        self.request_user_stats(steam_id.m_steamid)
+    }
+
+    fn request_user_stats(&self, steam_id: u64) -> Result<bool, SamError> {
+        let callback_handle = self
+            .user_stats
+            .request_user_stats(CSteamID { m_steamid: steam_id })
+            .map_err(|_| SamError::UnknownError)?;
+
+        let result = self.wait_for_api_call::<UserStatsReceived_t>(
+            callback_handle,
+            SteamCallbackId::UserStatsReceived,
+        )?;
+        dev_println!("[APP SERVER] User stats received callback result: {result:?}");
+
+        Ok(result.is_some_and(|result| result.m_eResult == EResult::k_EResultOK))
+    }
+
+    fn request_global_achievement_percentages(&self) -> Result<bool, SamError> {
+        let callback_handle = self
+            .user_stats
+            .request_global_achievement_percentages()
+            .map_err(|_| SamError::UnknownError)?;
+
+        let result = self.wait_for_api_call::<GlobalAchievementPercentagesReady_t>(
+            callback_handle,
+            SteamCallbackId::GlobalAchievementPercentagesReady,
+        )?;
+        dev_println!("[APP SERVER] Global achievement percentages callback result: {result:?}");
+
+        Ok(result.is_some_and(|result| result.m_eResult != EResult::k_EResultFail))
+    }
+
+    fn get_achievement_and_unlock_time(&self, name: &str) -> Result<(bool, u32), SteamClientError> {
+        self.user_stats.get_achievement_and_unlock_time(name)
+    }
+
+    fn get_user_achievement_and_unlock_time(
+        &self,
+        steam_id: u64,
+        name: &str,
+    ) -> Result<(bool, u32), SteamClientError> {
+        self.user_stats
+            .get_user_achievement_and_unlock_time(CSteamID { m_steamid: steam_id }, name)
+    }
+
+    fn get_achievement_achieved_percent(&self, name: &str) -> Result<f32, SteamClientError> {
+        self.user_stats.get_achievement_achieved_percent(name)
+    }
+
+    fn get_num_achievements(&self) -> Result<u32, SteamClientError> {
+        self.user_stats.get_num_achievements()
+    }
+
+    fn get_achievement_name(&self, index: u32) -> Result<String, SteamClientError> {
+        self.user_stats.get_achievement_name(index)
+    }
+
+    fn get_achievement_display_attribute(&self, name: &str, key: &str) -> Result<String, SteamClientError> {
+        self.user_stats.get_achievement_display_attribute(name, key)
+    }
+
+    fn set_achievement(&self, name: &str) -> Result<(), SteamClientError> {
+        self.user_stats.set_achievement(name)
+    }
+
+    fn clear_achievement(&self, name: &str) -> Result<(), SteamClientError> {
+        self.user_stats.clear_achievement(name)
+    }
+
+    fn get_stat_i32(&self, name: &str) -> Result<i32, SteamClientError> {
+        self.user_stats.get_stat_i32(name)
+    }
+
+    fn get_stat_float(&self, name: &str) -> Result<f32, SteamClientError> {
+        self.user_stats.get_stat_float(name)
+    }
+
+    fn set_stat_i32(&self, name: &str, value: i32) -> Result<(), SteamClientError> {
+        self.user_stats.set_stat_i32(name, value).map(|_| ())
+    }
+
+    fn set_stat_float(&self, name: &str, value: f32) -> Result<(), SteamClientError> {
+        self.user_stats.set_stat_float(name, value).map(|_| ())
+    }
+
+    fn store_stats(&self) -> Result<bool, SteamClientError> {
+        self.user_stats.store_stats()
+    }
+
+    fn reset_all_stats(&self, achievements_too: bool) -> Result<bool, SteamClientError> {
+        self.user_stats.reset_all_stats(achievements_too)
+    }
+
+    fn shutdown(&self) {
+        ConnectedSteam::shutdown(self);
+    }
+}
@@ -14,12 +14,43 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::backend::{
-        app_manager::AppManager, connected_steam::ConnectedSteam, key_value::KeyValue,
+        achievement_summary::{
+            achievement_bit_masks, count_unlocked_achievements, find_achievements, summarize_schema,
+        },
+        app_info::{AppInfoFile, app_info_developer, app_info_name},
+        app_lister::{AppModel, AppModelType, XmlGame, collect_owned_apps},
+        app_manager::AppManager,
+        connected_steam::ConnectedSteam,
+        key_value::{KeyValue, KeyValueData},
+        mock_steam_backend::MockSteamBackend,
+        orchestrator::{AppOwners, forward_to_child, relay_app_command},
+        recent_errors::{ErrorWatch, RecentErrors},
+        rpc_server::{allowed_over_rpc, parse_rpc_command, rpc_message},
+        shared_orchestrator::Owner,
+        stat_definitions::{
+            FloatStatInfo, IntStatInfo, Permission, SchemaCheck, StatFlags, StatInfo,
+            UserAchievement, limit_stat_change,
+        },
+        steam_backend::SteamBackend,
+        types::UserStatType,
+    };
+    use crate::steam_client::steam_apps_001_wrapper::{SteamApps001AppDataKeys, app_data_value};
+    use crate::utils::{
+        app_paths::resolve_steamclient_override,
+        format::{NumberSeparators, locale_number_separators, parse_localized_number},
+        ipc_types::{SamError, SamSerializable, SteamCommand, SteamResponse},
+    };
+    use std::{
+        collections::HashMap,
+        env, fs,
+        io::{Cursor, Write},
+        path::PathBuf,
+        sync::atomic::AtomicBool,
+        time::{Duration, Instant},
     };
-    use crate::steam_client::steam_apps_001_wrapper::SteamApps001AppDataKeys;
-    use std::{env, path::PathBuf};
 
     /// Test fetching achievements for a known app.
     #[test]
@@ -69,6 +100,7 @@ mod tests {
         };
         assert!(try_force(&SteamApps001AppDataKeys::Name.as_string()));
         assert!(try_force(&SteamApps001AppDataKeys::Logo.as_string()));
+        assert!(try_force(&SteamApps001AppDataKeys::Icon.as_string()));
         assert!(try_force(&SteamApps001AppDataKeys::SmallCapsule("english").as_string()));
         assert!(try_force("subscribed"));
         Ok(())
@@ -95,4 +127,1423 @@ mod tests {
         assert!(!format!("{kv:?}").is_empty(), "KeyValue should not be empty");
         Ok(())
     }
+
+    /// Test that the steamclient override accepts the library or its Steam directory.
+    #[test]
+    fn steamclient_override_resolution() -> Result<(), String> {
+        #[cfg(target_os = "linux")]
+        let lib_name = "linux64/steamclient.so";
+        #[cfg(target_os = "windows")]
+        let lib_name = "steamclient64.dll";
+
+        let steam_dir = env::temp_dir().join(format!("samrewritten-steam-{}", std::process::id()));
+        let lib_path = steam_dir.join(lib_name);
+        let lib_dir = lib_path.parent().ok_or("No parent directory")?;
+        fs::create_dir_all(lib_dir).map_err(|e| format!("Failed to create directory: {e}"))?;
+        fs::write(&lib_path, b"").map_err(|e| format!("Failed to create library: {e}"))?;
+
+        let steam_dir_str = steam_dir.display().to_string();
+        let from_file = resolve_steamclient_override(&lib_path.display().to_string());
+        let from_dir = resolve_steamclient_override(&steam_dir_str);
+        let missing = resolve_steamclient_override(&format!("{steam_dir_str}/missing"));
+        let _ = fs::remove_dir_all(&steam_dir);
+
+        assert_eq!(from_file.as_ref(), Some(&lib_path));
+        assert_eq!(from_dir.as_ref(), Some(&lib_path));
+        assert_eq!(missing, None);
+        Ok(())
+    }
+
+    fn kv_node(name: &str, data: KeyValueData) -> KeyValue {
+        let mut node = KeyValue::root();
+        node.name = name.to_owned();
+        node.data = data;
+        node
+    }
+
+    /// Test that a tree written to memory parses back to the same tree.
+    #[test]
+    fn keyval_bytes_round_trip() -> Result<(), String> {
+        let mut bit = kv_node("0", KeyValueData::None);
+        for child in [
+            kv_node("name", KeyValueData::String("ACH_WIN".to_owned())),
+            kv_node("permission", KeyValueData::Int32(-2)),
+            kv_node("min", KeyValueData::Float32(0.5)),
+            kv_node("unlock", KeyValueData::UInt64(u64::MAX)),
+            kv_node("color", KeyValueData::Color(0xFF00FF)),
+            kv_node("empty", KeyValueData::String(String::new())),
+        ] {
+            bit.children.insert(child.name.clone(), child);
+        }
+        let mut stats = kv_node("stats", KeyValueData::None);
+        stats.children.insert(bit.name.clone(), bit);
+        let mut tree = KeyValue::root();
+        tree.children.insert(stats.name.clone(), stats);
+
+        let bytes = tree
+            .to_bytes()
+            .map_err(|e| format!("Failed to write key value: {e}"))?;
+        let parsed =
+            KeyValue::from_bytes(&bytes).map_err(|e| format!("Failed to read key value: {e}"))?;
+
+        assert_eq!(parsed, tree);
+        assert_eq!(parsed.get("stats").get("0").get("permission").as_i32(0), -2);
+        assert_ne!(parsed, KeyValue::root());
+        Ok(())
+    }
+
+    /// Test that truncated data and unencodable names are reported as errors.
+    #[test]
+    fn keyval_bytes_errors() {
+        let mut tree = KeyValue::root();
+        let node = kv_node("name", KeyValueData::String("value".to_owned()));
+        tree.children.insert(node.name.clone(), node);
+        let bytes = tree.to_bytes().expect("Failed to write key value");
+
+        assert!(KeyValue::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(KeyValue::from_bytes(&[]).is_err());
+
+        let node = kv_node("bad\0name", KeyValueData::None);
+        tree.children.insert(node.name.clone(), node);
+        assert!(tree.to_bytes().is_err());
+    }
+
+    /// Test merging the schema with the user's achievement state, without Steam.
+    #[test]
+    fn mock_get_achievements_merges_schema() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("ACH_WIN", "Winner", true, 0)
+            .with_achievement("ACH_LOSE", "Loser", false, 2);
+        backend
+            .achieved_percents
+            .borrow_mut()
+            .insert("ACH_WIN".to_owned(), 12.5);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        let mut achievements = app_manager
+            .get_achievements()
+            .map_err(|e| format!("Failed to get achievements: {e}"))?;
+        achievements.sort_by(|a, b| a.id.cmp(&b.id));
+
+        assert_eq!(achievements.len(), 2);
+        assert_eq!(achievements[0].id, "ACH_LOSE");
+        assert!(!achievements[0].is_achieved);
+        assert!(achievements[0].permission.is_protected());
+        assert_eq!(achievements[0].global_achieved_percent, None);
+        assert_eq!(achievements[1].name, "Winner");
+        assert!(achievements[1].is_achieved);
+        assert!(achievements[1].unlock_time.is_some());
+        assert_eq!(achievements[1].global_achieved_percent, Some(12.5));
+        Ok(())
+    }
+
+    /// Test reading app metadata from appinfo.vdf, with key names inline (version 28) and
+    /// from the key table (version 29).
+    #[test]
+    fn app_info_versions() -> Result<(), String> {
+        let entry = |app_id: u32, header_size: usize, body: &[u8]| {
+            let mut entry = app_id.to_le_bytes().to_vec();
+            entry.extend(((header_size + body.len()) as u32).to_le_bytes());
+            entry.extend(vec![0u8; header_size]);
+            entry.extend(body);
+            entry
+        };
+
+        let mut association = kv_node("0", KeyValueData::None);
+        for (key, value) in [("type", "developer"), ("name", "Valve")] {
+            let leaf = kv_node(key, KeyValueData::String(value.to_owned()));
+            association.children.insert(key.to_owned(), leaf);
+        }
+        let mut associations = kv_node("associations", KeyValueData::None);
+        associations.children.insert("0".to_owned(), association);
+        let mut common = kv_node("common", KeyValueData::None);
+        let name = kv_node("name", KeyValueData::String("Spacewar".to_owned()));
+        common.children.insert("name".to_owned(), name);
+        common.children.insert("associations".to_owned(), associations);
+        let mut app_info = kv_node("appinfo", KeyValueData::None);
+        app_info.children.insert("common".to_owned(), common);
+        let mut root = KeyValue::root();
+        root.children.insert("appinfo".to_owned(), app_info);
+        let body = root.to_bytes().map_err(|e| e.to_string())?;
+
+        let mut v28 = vec![0x28, 0x44, 0x56, 0x07, 1, 0, 0, 0];
+        v28.extend(entry(480, 60, &body));
+        v28.extend(0u32.to_le_bytes());
+        let file = AppInfoFile::from_bytes(v28).map_err(|e| e.to_string())?;
+        let info = file
+            .get(480)
+            .map_err(|e| e.to_string())?
+            .ok_or("No app info for 480")?;
+        assert_eq!(app_info_name(&info).as_deref(), Some("Spacewar"));
+        assert_eq!(app_info_developer(&info).as_deref(), Some("Valve"));
+        assert!(file.get(10).map_err(|e| e.to_string())?.is_none());
+
+        // appinfo { common { name = "Spacewar" } }, keys by index
+        let mut body = vec![0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 1, 2, 0, 0, 0];
+        body.extend(b"Spacewar\0");
+        body.extend([8, 8, 8]);
+        let entries = entry(480, 60, &body);
+        let table_offset = 16 + entries.len() + 4;
+        let mut v29 = vec![0x29, 0x44, 0x56, 0x07, 1, 0, 0, 0];
+        v29.extend((table_offset as u64).to_le_bytes());
+        v29.extend(entries);
+        v29.extend(0u32.to_le_bytes());
+        v29.extend(3u32.to_le_bytes());
+        v29.extend(b"appinfo\0common\0name\0");
+        let file = AppInfoFile::from_bytes(v29).map_err(|e| e.to_string())?;
+        let info = file
+            .get(480)
+            .map_err(|e| e.to_string())?
+            .ok_or("No app info for 480")?;
+        assert_eq!(app_info_name(&info).as_deref(), Some("Spacewar"));
+        assert_eq!(app_info_developer(&info), None);
+
+        assert!(AppInfoFile::from_bytes(vec![0x27, 0x44, 0x56, 0x08, 1, 0, 0, 0]).is_err());
+        let mut truncated = vec![0x27, 0x44, 0x56, 0x07, 1, 0, 0, 0];
+        truncated.extend(entry(480, 40, &[0; 4])[..20].to_vec());
+        assert!(AppInfoFile::from_bytes(truncated).is_err());
+        Ok(())
+    }
+
+    /// Test that a batch skips achievements already in the requested state and stores once.
+    #[test]
+    fn mock_set_achievements_batch() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("A", "A", true, 0)
+            .with_achievement("B", "B", false, 0)
+            .with_achievement("C", "C", false, 0);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        let ids = vec!["A".to_owned(), "B".to_owned(), "C".to_owned()];
+        let results = app_manager
+            .set_achievements(&ids, true)
+            .map_err(|e| format!("Failed to set achievements: {e}"))?;
+
+        let backend = app_manager.backend();
+        let result_ids: Vec<&str> = results.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(result_ids, vec!["A", "B", "C"]);
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+        assert!(backend.is_achieved("B") && backend.is_achieved("C"));
+        assert_eq!(backend.achievement_writes.get(), 2);
+        assert_eq!(backend.store_calls.get(), 1);
+        Ok(())
+    }
+
+    /// Test that a failing achievement is reported on its own without stopping the batch,
+    /// and that a failed store is reported for the achievements it did not save.
+    #[test]
+    fn mock_set_achievements_partial_failure() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("A", "A", false, 0)
+            .with_achievement("B", "B", false, 0)
+            .with_achievement("C", "C", false, 0);
+        backend.failing_ids.borrow_mut().insert("A".to_owned());
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        let ids = vec!["A".to_owned(), "B".to_owned()];
+        let results = app_manager
+            .set_achievements(&ids, true)
+            .map_err(|e| format!("Failed to set achievements: {e}"))?;
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0].1.is_err());
+        assert_eq!(results[1], ("B".to_owned(), Ok(())));
+        assert!(app_manager.backend().is_achieved("B"));
+
+        app_manager.backend().store_fails.set(true);
+        let ids = vec!["B".to_owned(), "C".to_owned()];
+        let results = app_manager
+            .set_achievements(&ids, true)
+            .map_err(|e| format!("Failed to set achievements: {e}"))?;
+        assert_eq!(
+            results,
+            vec![
+                ("B".to_owned(), Ok(())),
+                ("C".to_owned(), Err(SamError::StoreFailed))
+            ]
+        );
+        Ok(())
+    }
+
+    /// Test that a single achievement already in the requested state is not written again.
+    #[test]
+    fn mock_set_achievement_noop() -> Result<(), String> {
+        let backend = MockSteamBackend::new().with_achievement("A", "A", true, 0);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        let result = app_manager
+            .set_achievement("A", true)
+            .map_err(|e| format!("Failed to set achievement: {e}"))?;
+
+        assert!(result);
+        assert_eq!(app_manager.backend().achievement_writes.get(), 0);
+        assert_eq!(app_manager.backend().store_calls.get(), 0);
+        Ok(())
+    }
+
+    /// Test reading back a single achievement after changing it.
+    #[test]
+    fn mock_get_single_achievement() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("A", "First", false, 0)
+            .with_achievement("B", "Second", false, 0);
+        let mut app_manager = AppManager::with_backend(480, backend);
+        app_manager
+            .set_achievement("B", true)
+            .map_err(|e| format!("Failed to set achievement: {e}"))?;
+
+        let achievement = app_manager
+            .get_achievement("B")
+            .map_err(|e| format!("Failed to get achievement: {e}"))?;
+        assert_eq!(achievement.name, "Second");
+        assert!(achievement.is_achieved);
+        assert!(app_manager.get_achievement("MISSING").is_err());
+        Ok(())
+    }
+
+    /// Test that achievements the schema and Steam disagree on are told apart.
+    #[test]
+    fn mock_check_schema_reports_mismatches() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("A", "A", false, 0)
+            .with_achievement("B", "B", false, 0)
+            .with_achievement("C", "C", false, 0);
+        let mut app_manager = AppManager::with_backend(480, backend);
+        let check = app_manager.check_schema().map_err(|e| e.to_string())?;
+        assert_eq!(check, SchemaCheck::default());
+
+        let backend = MockSteamBackend::new()
+            .with_achievement("A", "A", false, 0)
+            .with_achievement("B", "B", false, 0)
+            .without_live_achievement("B")
+            .with_live_only_achievement("NEW");
+        let mut app_manager = AppManager::with_backend(480, backend);
+        let check = app_manager.check_schema().map_err(|e| e.to_string())?;
+        assert_eq!(check.only_in_schema, vec!["B".to_string()]);
+        assert_eq!(check.only_in_steam, vec!["NEW".to_string()]);
+        assert_eq!(check.mismatch_count(), 2);
+        Ok(())
+    }
+
+    /// Test that names follow the language asked for, and fall back to English.
+    #[test]
+    fn mock_achievement_names_in_language() -> Result<(), String> {
+        let mut backend = MockSteamBackend::new()
+            .with_achievement("A", "Victory", false, 0)
+            .with_translated_name("french", "Victoire");
+        backend.language = "french".to_owned();
+        let mut app_manager = AppManager::with_backend(480, backend);
+        let mut names = |language: Option<&str>| -> Result<String, String> {
+            app_manager.set_language_override(language.map(str::to_owned));
+            let achievements = app_manager
+                .get_achievements()
+                .map_err(|e| format!("Failed to get achievements: {e}"))?;
+            Ok(achievements[0].name.clone())
+        };
+
+        assert_eq!(names(None)?, "Victoire");
+        assert_eq!(names(Some("english"))?, "Victory");
+        assert_eq!(names(Some("german"))?, "Victory");
+        assert_eq!(names(Some(""))?, "Victoire");
+        Ok(())
+    }
+
+    /// Test that Steam failing for a moment is retried, and failing for good is not hidden.
+    #[test]
+    fn mock_busy_steam_is_retried() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("A", "A", false, 0)
+            .with_achievement("B", "B", false, 0);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        app_manager.backend().busy_calls.set(2);
+        let achievements = app_manager
+            .get_achievements()
+            .map_err(|e| format!("Failed to get achievements: {e}"))?;
+        assert_eq!(achievements.len(), 2);
+
+        // The state check and the write each fail once
+        app_manager.backend().busy_calls.set(1);
+        assert_eq!(app_manager.set_achievement("A", true), Ok(true));
+        assert!(app_manager.backend().is_achieved("A"));
+
+        app_manager.backend().busy_calls.set(10);
+        assert!(app_manager.set_achievement("B", true).is_err());
+        assert!(!app_manager.backend().is_achieved("B"));
+        Ok(())
+    }
+
+    /// Test that an achievement Steam doesn't list fails right away instead of being retried.
+    #[test]
+    fn mock_missing_achievement_is_not_retried() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("GONE", "Gone", false, 0)
+            .without_live_achievement("GONE");
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        app_manager.backend().busy_calls.set(10);
+        let achievements = app_manager
+            .get_achievements()
+            .map_err(|e| format!("Failed to get achievements: {e}"))?;
+        assert!(achievements.is_empty());
+        assert_eq!(app_manager.backend().busy_calls.get(), 9);
+        Ok(())
+    }
+
+    /// Test that protected achievements are refused before reaching Steam.
+    #[test]
+    fn mock_protected_achievement_is_refused() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("OPEN", "Open", false, 0)
+            .with_achievement("PROTECTED", "Protected", false, 2);
+        let mut app_manager = AppManager::with_backend(480, backend);
+        app_manager
+            .get_achievements()
+            .map_err(|e| format!("Failed to get achievements: {e}"))?;
+
+        assert_eq!(
+            app_manager.set_achievement("PROTECTED", true),
+            Err(SamError::ProtectedAchievement("PROTECTED".to_owned()))
+        );
+        let batch = app_manager
+            .set_achievements(&["OPEN".to_owned(), "PROTECTED".to_owned()], true)
+            .map_err(|e| format!("Failed to set achievements: {e}"))?;
+
+        assert_eq!(
+            batch,
+            vec![
+                ("OPEN".to_owned(), Ok(())),
+                (
+                    "PROTECTED".to_owned(),
+                    Err(SamError::ProtectedAchievement("PROTECTED".to_owned()))
+                )
+            ]
+        );
+        assert!(app_manager.backend().is_achieved("OPEN"));
+        assert!(!app_manager.backend().is_achieved("PROTECTED"));
+        Ok(())
+    }
+
+    /// Test that a protected achievement is refused even before the definitions were loaded.
+    #[test]
+    fn mock_protected_achievement_is_refused_unloaded() {
+        let backend = MockSteamBackend::new().with_achievement("PROTECTED", "Protected", false, 2);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        assert_eq!(
+            app_manager.set_achievement("PROTECTED", true),
+            Err(SamError::ProtectedAchievement("PROTECTED".to_owned()))
+        );
+        assert!(!app_manager.backend().is_achieved("PROTECTED"));
+    }
+
+    /// Test that a batch is spaced out by the write interval, and that no interval adds no wait.
+    #[test]
+    fn mock_write_interval_spaces_out_batches() -> Result<(), String> {
+        let ids: Vec<String> = ["A", "B", "C"].iter().map(|id| id.to_string()).collect();
+        let backend = ids
+            .iter()
+            .fold(MockSteamBackend::new(), |backend, id| backend.with_achievement(id, id, false, 0));
+        let mut app_manager = AppManager::with_backend(480, backend);
+        app_manager.set_write_interval(Duration::from_millis(20));
+
+        // Three writes and a store, the first one isn't delayed
+        let started = Instant::now();
+        let results = app_manager
+            .set_achievements(&ids, true)
+            .map_err(|e| format!("Failed to set achievements: {e}"))?;
+        assert!(started.elapsed() >= Duration::from_millis(60));
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+        app_manager.set_write_interval(Duration::ZERO);
+        let started = Instant::now();
+        app_manager
+            .set_achievements(&ids, false)
+            .map_err(|e| format!("Failed to set achievements: {e}"))?;
+        assert!(started.elapsed() < Duration::from_millis(60));
+        Ok(())
+    }
+
+    /// Test that writes Steam accepts but ignores are reported once verification is on.
+    #[test]
+    fn mock_verify_writes() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("OPEN", "Open", false, 0)
+            .with_achievement("IGNORED", "Ignored", false, 0)
+            .with_int_stat("KILLS", 42, 0, 1000);
+        backend.ignored_ids.borrow_mut().insert("IGNORED".to_owned());
+        backend.ignored_ids.borrow_mut().insert("KILLS".to_owned());
+        let mut app_manager = AppManager::with_backend(480, backend);
+        app_manager
+            .get_achievements()
+            .map_err(|e| format!("Failed to get achievements: {e}"))?;
+
+        assert_eq!(app_manager.set_achievement("IGNORED", true), Ok(true));
+        assert_eq!(app_manager.set_stat_i32("KILLS", 50), Ok(true));
+
+        app_manager.set_verify_writes(true);
+        assert_eq!(
+            app_manager.set_achievement("IGNORED", true),
+            Err(SamError::VerificationFailed("IGNORED".to_owned()))
+        );
+        assert_eq!(
+            app_manager.set_stat_i32("KILLS", 50),
+            Err(SamError::VerificationFailed("KILLS".to_owned()))
+        );
+        let batch = app_manager
+            .set_achievements(&["OPEN".to_owned(), "IGNORED".to_owned()], true)
+            .map_err(|e| format!("Failed to set achievements: {e}"))?;
+        assert_eq!(
+            batch,
+            vec![
+                ("OPEN".to_owned(), Ok(())),
+                (
+                    "IGNORED".to_owned(),
+                    Err(SamError::VerificationFailed("IGNORED".to_owned()))
+                )
+            ]
+        );
+        Ok(())
+    }
+
+    /// Test that achievements still load from Steam when the schema isn't on disk, and that
+    /// stats report the missing schema instead of failing.
+    #[test]
+    fn mock_schema_missing_falls_back() -> Result<(), String> {
+        let mut backend = MockSteamBackend::new()
+            .with_achievement("WIN", "Winner", true, 0)
+            .with_achievement("LOSE", "Loser", false, 0)
+            .with_int_stat("KILLS", 42, 0, 1000);
+        backend.schema_missing = true;
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        let achievements = app_manager
+            .get_achievements()
+            .map_err(|e| format!("Failed to get achievements: {e}"))?;
+        let names: Vec<(&str, &str, bool)> = achievements
+            .iter()
+            .map(|a| (a.id.as_str(), a.name.as_str(), a.is_achieved))
+            .collect();
+        assert_eq!(names, vec![("LOSE", "Loser", false), ("WIN", "Winner", true)]);
+        assert!(matches!(
+            app_manager.get_statistics(),
+            Err(SamError::SchemaUnavailable)
+        ));
+        assert_eq!(app_manager.set_achievement("LOSE", true), Ok(true));
+        Ok(())
+    }
+
+    /// Test reading another profile's achievements, and a private profile's refusal.
+    #[test]
+    fn mock_compare_user_achievements() -> Result<(), String> {
+        let friend = 76561197960265729;
+        let backend = MockSteamBackend::new()
+            .with_achievement("A", "A", true, 0)
+            .with_achievement("B", "B", false, 0)
+            .with_user_achievement(friend, "A", false)
+            .with_user_achievement(friend, "B", true);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        let mut compared = app_manager
+            .get_user_achievements(friend)
+            .map_err(|e| format!("Failed to get user achievements: {e}"))?;
+        compared.sort_by(|a, b| a.id.cmp(&b.id));
+
+        assert_eq!(
+            compared,
+            vec![
+                UserAchievement { id: "A".to_owned(), is_achieved: false },
+                UserAchievement { id: "B".to_owned(), is_achieved: true },
+            ]
+        );
+        assert_eq!(
+            app_manager.get_user_achievements(friend + 1),
+            Err(SamError::UserStatsUnavailable)
+        );
+        assert!(app_manager.backend().is_achieved("A"));
+        assert_eq!(app_manager.backend().achievement_writes.get(), 0);
+        Ok(())
+    }
+
+    /// Test that dry-run mode reports success without writing anything.
+    #[test]
+    fn mock_dry_run_leaves_steam_untouched() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("A", "A", false, 0)
+            .with_int_stat("KILLS", 42, 0, 1000);
+        let mut app_manager = AppManager::with_backend(480, backend);
+        app_manager.set_dry_run(true);
+
+        let unlocked = app_manager
+            .set_achievement("A", true)
+            .map_err(|e| format!("Failed to set achievement: {e}"))?;
+        let stat_set = app_manager
+            .set_stat_i32("KILLS", 100)
+            .map_err(|e| format!("Failed to set stat: {e}"))?;
+        let reset = app_manager
+            .reset_all_stats_with_progress(true, |_, _| {})
+            .map_err(|e| format!("Failed to reset stats: {e}"))?;
+
+        assert!(unlocked && stat_set && reset);
+        assert!(!app_manager.backend().is_achieved("A"));
+        assert_eq!(app_manager.backend().int_stats.borrow().get("KILLS"), Some(&42));
+        assert_eq!(app_manager.backend().achievement_writes.get(), 0);
+        assert_eq!(app_manager.backend().store_calls.get(), 0);
+        Ok(())
+    }
+
+    /// Test counting achievements and stats straight from a schema.
+    #[test]
+    fn mock_summarize_schema() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("A", "A", false, 0)
+            .with_achievement("B", "B", true, 0)
+            .with_int_stat("KILLS", 42, 0, 1000);
+        let schema = backend
+            .load_schema(480)
+            .map_err(|e| format!("Failed to load schema: {e}"))?;
+
+        let summary = summarize_schema(&schema, 480);
+        assert_eq!((summary.achievements, summary.stats), (2, 1));
+        assert!(!summary.is_empty());
+
+        let empty_schema = MockSteamBackend::new()
+            .load_schema(480)
+            .map_err(|e| format!("Failed to load schema: {e}"))?;
+        assert!(summarize_schema(&empty_schema, 480).is_empty());
+        Ok(())
+    }
+
+    /// Test finding achievements by name or id in a schema, case-insensitively.
+    #[test]
+    fn find_achievements_in_schema() -> Result<(), String> {
+        let schema = MockSteamBackend::new()
+            .with_achievement("ACH_WIN", "Winner", false, 0)
+            .with_achievement("ACH_LOSE", "Good sport", false, 0)
+            .with_int_stat("WINS", 3, 0, 100)
+            .load_schema(480)
+            .map_err(|e| format!("Failed to load schema: {e}"))?;
+
+        let found = find_achievements(&schema, 480, "winner", "english");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0.id, "ACH_WIN");
+        assert_eq!(found[0].0.unlocked, None);
+
+        let mut ids: Vec<String> = find_achievements(&schema, 480, "ach_", "english")
+            .into_iter()
+            .map(|(achievement, _, _)| achievement.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, vec!["ACH_LOSE", "ACH_WIN"]);
+        assert!(find_achievements(&schema, 480, "wins", "english").is_empty());
+        Ok(())
+    }
+
+    /// Test counting unlocked achievements from a user's stats file.
+    #[test]
+    fn count_unlocked_from_user_stats() -> Result<(), String> {
+        let schema = MockSteamBackend::new()
+            .with_achievement("A", "A", false, 0)
+            .with_achievement("B", "B", false, 0)
+            .with_int_stat("KILLS", 42, 0, 1000)
+            .load_schema(480)
+            .map_err(|e| format!("Failed to load schema: {e}"))?;
+        let masks = achievement_bit_masks(&schema, 480);
+        assert_eq!(masks, HashMap::from([("0".to_owned(), 1), ("1".to_owned(), 1)]));
+
+        let group = |name: &str, data: i32| {
+            let mut group = KeyValue::root();
+            group.name = name.to_owned();
+            let mut data_node = KeyValue::root();
+            data_node.name = "data".to_owned();
+            data_node.data = KeyValueData::Int32(data);
+            group.children.insert(data_node.name.clone(), data_node);
+            group
+        };
+        let mut cache = KeyValue::root();
+        cache.name = "cache".to_owned();
+        // Bit 1 of the first group is not an achievement of the schema
+        for group in [group("0", 0b10), group("1", 0b1), group("2", 42)] {
+            cache.children.insert(group.name.clone(), group);
+        }
+        let mut user_stats = KeyValue::root();
+        user_stats.children.insert(cache.name.clone(), cache);
+
+        assert_eq!(count_unlocked_achievements(&user_stats, &masks), 1);
+        assert_eq!(count_unlocked_achievements(&KeyValue::root(), &masks), 0);
+        Ok(())
+    }
+
+    /// Test reading stats and their bounds from the schema.
+    #[test]
+    fn mock_get_statistics() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_int_stat("KILLS", 42, 0, 1000)
+            .with_max_change(100)
+            .with_float_stat("DISTANCE", 1.5);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        let stats = app_manager
+            .get_statistics()
+            .map_err(|e| format!("Failed to get stats: {e}"))?;
+
+        assert_eq!(stats.len(), 2);
+        for stat in stats {
+            match stat {
+                StatInfo::Integer(info) => {
+                    assert_eq!(info.id, "KILLS");
+                    assert_eq!(info.int_value, 42);
+                    assert_eq!((info.min_value, info.max_value), (0, 1000));
+                    assert_eq!(info.max_change, 100);
+                }
+                StatInfo::Float(info) => {
+                    assert_eq!(info.id, "DISTANCE");
+                    assert_eq!(info.float_value, 1.5);
+                    assert_eq!(info.max_change, 0.0);
+                }
+                StatInfo::ReadOnly(info) => panic!("Unexpected read-only stat {}", info.id),
+            }
+        }
+        Ok(())
+    }
+
+    /// Test resetting a single stat to its schema default, or 0 without one.
+    #[test]
+    fn mock_reset_single_stat() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_int_stat("KILLS", 42, 0, 1000)
+            .with_default(5)
+            .with_int_stat("DEATHS", 7, 0, 1000)
+            .with_float_stat("DISTANCE", 1.5);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        assert_eq!(app_manager.reset_stat("KILLS"), Ok(5.0));
+        assert_eq!(app_manager.reset_stat("DISTANCE"), Ok(0.0));
+
+        let stats = app_manager
+            .get_statistics()
+            .map_err(|e| format!("Failed to get stats: {e}"))?;
+        for stat in stats {
+            match stat {
+                StatInfo::Integer(info) if info.id == "KILLS" => assert_eq!(info.int_value, 5),
+                StatInfo::Integer(info) => assert_eq!(info.int_value, 7),
+                StatInfo::Float(info) => assert_eq!(info.float_value, 0.0),
+                StatInfo::ReadOnly(info) => panic!("Unexpected read-only stat {}", info.id),
+            }
+        }
+        Ok(())
+    }
+
+    /// Test resetting a float stat to its schema default.
+    #[test]
+    fn mock_reset_float_stat_to_default() {
+        let backend = MockSteamBackend::new()
+            .with_float_stat("DISTANCE", 12.5)
+            .with_float_default(2.5);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        assert_eq!(app_manager.reset_stat("DISTANCE"), Ok(2.5));
+        assert_eq!(app_manager.backend().get_stat_float("DISTANCE").ok(), Some(2.5));
+    }
+
+    /// Test that resetting refuses the stats Steam would not store the default of.
+    #[test]
+    fn mock_reset_stat_refusals() {
+        let backend = MockSteamBackend::new()
+            .with_int_stat("RANK", 4, 0, 100)
+            .with_stat_permission(2)
+            .with_int_stat("WINS", 4, 0, 100)
+            .with_increment_only()
+            .with_int_stat("KILLS", 500, 0, 1000)
+            .with_max_change(100)
+            .with_raw_stat("FUTURE", 9, 7, &[]);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        assert_eq!(
+            app_manager.reset_stat("RANK"),
+            Err(SamError::ProtectedStat("RANK".to_owned()))
+        );
+        assert_eq!(
+            app_manager.reset_stat("WINS"),
+            Err(SamError::StatIncrementOnly("WINS".to_owned()))
+        );
+        assert_eq!(
+            app_manager.reset_stat("KILLS"),
+            Err(SamError::StatChangeTooLarge("KILLS".to_owned()))
+        );
+        assert_eq!(
+            app_manager.reset_stat("FUTURE"),
+            Err(SamError::ReadOnlyStat("FUTURE".to_owned()))
+        );
+        assert_eq!(app_manager.backend().get_stat_i32("KILLS").ok(), Some(500));
+    }
+
+    /// Test that stat edits are kept within the change Steam accepts in one store.
+    #[test]
+    fn stat_change_limit() {
+        assert_eq!(limit_stat_change(42.0, 100.0, 500.0), 142.0);
+        assert_eq!(limit_stat_change(42.0, 100.0, -500.0), -58.0);
+        assert_eq!(limit_stat_change(42.0, 100.0, 100.0), 100.0);
+        assert_eq!(limit_stat_change(42.0, 0.0, 500.0), 500.0);
+    }
+
+    /// Test that the stat setters refuse changes beyond `max_change` of the stored value.
+    #[test]
+    fn mock_set_stat_within_max_change() {
+        let backend = MockSteamBackend::new()
+            .with_int_stat("KILLS", 42, 0, 1000)
+            .with_max_change(100)
+            .with_int_stat("DEATHS", 7, 0, 1000);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        assert_eq!(
+            app_manager.set_stat_i32("KILLS", 500),
+            Err(SamError::StatChangeTooLarge("KILLS".to_owned()))
+        );
+        assert_eq!(app_manager.backend().get_stat_i32("KILLS").ok(), Some(42));
+        assert_eq!(app_manager.set_stat_i32("KILLS", 142), Ok(true));
+        assert_eq!(app_manager.backend().get_stat_i32("KILLS").ok(), Some(142));
+        assert_eq!(app_manager.set_stat_i32("DEATHS", 500), Ok(true));
+        assert_eq!(app_manager.backend().get_stat_i32("DEATHS").ok(), Some(500));
+    }
+
+    /// Test reading numbers typed with either decimal separator and any digit grouping.
+    #[test]
+    fn parse_localized_numbers() {
+        assert_eq!(parse_localized_number(" 42 ", true), Ok(42.0));
+        assert_eq!(parse_localized_number("-7", true), Ok(-7.0));
+        assert_eq!(parse_localized_number("1,000", true), Ok(1000.0));
+        assert_eq!(parse_localized_number("1.234.567", true), Ok(1234567.0));
+        assert_eq!(parse_localized_number("1 234", true), Ok(1234.0));
+        assert_eq!(parse_localized_number("1\u{a0}234", true), Ok(1234.0));
+        assert_eq!(parse_localized_number("1'234", true), Ok(1234.0));
+        assert_eq!(parse_localized_number("1,5", false), Ok(1.5));
+        assert_eq!(parse_localized_number("1.5", false), Ok(1.5));
+        assert_eq!(parse_localized_number("1,234.5", false), Ok(1234.5));
+        assert_eq!(parse_localized_number("1.234,5", false), Ok(1234.5));
+        assert_eq!(parse_localized_number("1,000", false), Ok(1.0));
+
+        assert!(parse_localized_number("", true).is_err());
+        assert!(parse_localized_number("   ", false).is_err());
+        assert!(parse_localized_number("1,5", true).is_err());
+        assert!(parse_localized_number("12ab", true).is_err());
+        assert!(parse_localized_number("inf", false).is_err());
+        assert!(parse_localized_number("NaN", false).is_err());
+    }
+
+    /// Test picking the number separators of a locale.
+    #[test]
+    fn locale_separators() {
+        let separators = |grouping, decimal| NumberSeparators { grouping, decimal };
+        assert_eq!(locale_number_separators("en_US.UTF-8"), separators(',', '.'));
+        assert_eq!(locale_number_separators("C"), separators(',', '.'));
+        assert_eq!(locale_number_separators(""), separators(',', '.'));
+        assert_eq!(locale_number_separators("de_DE.UTF-8"), separators('.', ','));
+        assert_eq!(locale_number_separators("pt_BR"), separators('.', ','));
+        assert_eq!(locale_number_separators("fr_FR.UTF-8"), separators('\u{a0}', ','));
+        assert_eq!(locale_number_separators("sr_RS@latin"), separators('.', ','));
+        assert_eq!(locale_number_separators("de_CH.UTF-8"), separators('\'', '.'));
+        assert_eq!(locale_number_separators("it_CH"), separators('\'', '.'));
+        assert_eq!(locale_number_separators("fr_CH"), separators('\u{a0}', ','));
+    }
+
+    /// Test combining and checking stat flags.
+    #[test]
+    fn stat_flags_combine() {
+        assert!(StatFlags::NONE.is_empty());
+        assert!(StatFlags::NONE.contains(StatFlags::NONE));
+        assert!(!StatFlags::NONE.contains(StatFlags::PROTECTED));
+
+        let mut flags = StatFlags::INCREMENT_ONLY | StatFlags::PROTECTED;
+        assert_eq!(flags.bits(), 0b011);
+        assert!(flags.contains(StatFlags::INCREMENT_ONLY));
+        assert!(flags.contains(StatFlags::PROTECTED));
+        assert!(flags.contains(StatFlags::INCREMENT_ONLY | StatFlags::PROTECTED));
+        assert!(!flags.contains(StatFlags::UNKNOWN_PERMISSION));
+        assert!(!flags.contains(StatFlags::PROTECTED | StatFlags::UNKNOWN_PERMISSION));
+
+        flags |= StatFlags::UNKNOWN_PERMISSION;
+        assert_eq!(flags.bits(), 0b111);
+        flags |= StatFlags::PROTECTED;
+        assert_eq!(flags.bits(), 0b111);
+        assert!(!flags.is_empty());
+    }
+
+    /// Test the flags stats get from their permission bits and increment-only setting.
+    #[test]
+    fn stat_flags_from_permissions() {
+        let int_stat = |permission: i32, is_increment_only: bool| IntStatInfo {
+            id: "KILLS".to_string(),
+            app_id: 480,
+            display_name: "Kills".to_string(),
+            is_increment_only,
+            permission: Permission(permission),
+            original_value: 0,
+            int_value: 0,
+            min_value: 0,
+            max_value: 100,
+            max_change: 0,
+        };
+        let float_stat = |permission: i32, is_increment_only: bool| FloatStatInfo {
+            id: "DISTANCE".to_string(),
+            app_id: 480,
+            display_name: "Distance".to_string(),
+            is_increment_only,
+            permission: Permission(permission),
+            original_value: 0.0,
+            float_value: 0.0,
+            min_value: 0.0,
+            max_value: 100.0,
+            max_change: 0.0,
+        };
+
+        let cases = [
+            (0, false, StatFlags::NONE),
+            (0, true, StatFlags::INCREMENT_ONLY),
+            (2, false, StatFlags::PROTECTED),
+            (2, true, StatFlags::INCREMENT_ONLY | StatFlags::PROTECTED),
+            // Only bit 1 is known, any other one is reported
+            (1, false, StatFlags::UNKNOWN_PERMISSION),
+            (4, true, StatFlags::INCREMENT_ONLY | StatFlags::UNKNOWN_PERMISSION),
+            (3, false, StatFlags::PROTECTED | StatFlags::UNKNOWN_PERMISSION),
+            (
+                6,
+                true,
+                StatFlags::INCREMENT_ONLY | StatFlags::PROTECTED | StatFlags::UNKNOWN_PERMISSION,
+            ),
+            (-1, false, StatFlags::PROTECTED | StatFlags::UNKNOWN_PERMISSION),
+        ];
+        for (permission, is_increment_only, expected) in cases {
+            let int_flags = int_stat(permission, is_increment_only).extra();
+            assert_eq!(int_flags, expected, "int stat, permission {permission}");
+            let float_flags = float_stat(permission, is_increment_only).extra();
+            assert_eq!(float_flags, expected, "float stat, permission {permission}");
+            assert_eq!(
+                StatInfo::Integer(int_stat(permission, is_increment_only)).extra(),
+                expected
+            );
+        }
+    }
+
+    /// Test that bit fields and stats of unknown types are read but not editable.
+    #[test]
+    fn mock_read_only_statistics() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_int_stat("KILLS", 42, 0, 1000)
+            .with_raw_stat("ZONES", UserStatType::Integer as i32, 0b101, &["North", "East", "South"])
+            .with_raw_stat("FUTURE", 9, 7, &[]);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        let stats = app_manager
+            .get_statistics()
+            .map_err(|e| format!("Failed to get stats: {e}"))?;
+        assert_eq!(stats.len(), 3);
+
+        let read_only = |id: &str| {
+            stats.iter().find_map(|stat| match stat {
+                StatInfo::ReadOnly(info) if info.id == id => Some(info.clone()),
+                _ => None,
+            })
+        };
+        let zones = read_only("ZONES").ok_or("ZONES is not read-only")?;
+        assert_eq!(zones.set_bits(), vec!["North", "South"]);
+        let future = read_only("FUTURE").ok_or("FUTURE is not read-only")?;
+        assert_eq!(future.value, 7);
+        assert!(future.set_bits().is_empty());
+        assert!(stats.iter().all(|stat| !stat.is_modified()));
+        Ok(())
+    }
+
+    /// Test unlocking an achievement through the stat its schema links to it.
+    #[test]
+    fn mock_unlock_achievement_via_stat() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_int_stat("WINS", 3, 0, 100)
+            .with_progress_achievement("WIN_10", "WINS", 10)
+            .with_achievement("PLAIN", "PLAIN", false, 0);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        let achievements = app_manager
+            .get_achievements()
+            .map_err(|e| format!("Failed to get achievements: {e}"))?;
+        let progress = achievements
+            .iter()
+            .find(|achievement| achievement.id == "WIN_10")
+            .and_then(|achievement| achievement.progress.clone())
+            .ok_or("WIN_10 has no progress stat")?;
+        assert_eq!((progress.stat_id.as_str(), progress.max_value), ("WINS", 10.0));
+
+        assert_eq!(app_manager.unlock_achievement_via_stat("WIN_10"), Ok(true));
+        assert_eq!(app_manager.backend().get_stat_i32("WINS").ok(), Some(10));
+        assert!(app_manager.backend().is_achieved("WIN_10"));
+        assert_eq!(
+            app_manager.unlock_achievement_via_stat("PLAIN"),
+            Err(SamError::NoProgressStat("PLAIN".to_owned()))
+        );
+        Ok(())
+    }
+
+    /// Test that unlocking through a stat refuses protected stats and jumps past `max_change`.
+    #[test]
+    fn mock_unlock_achievement_via_limited_stat() {
+        let backend = MockSteamBackend::new()
+            .with_int_stat("WINS", 3, 0, 100)
+            .with_max_change(5)
+            .with_progress_achievement("WIN_10", "WINS", 10)
+            .with_int_stat("RANK", 0, 0, 100)
+            .with_stat_permission(2)
+            .with_progress_achievement("RANK_1", "RANK", 1);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        assert_eq!(
+            app_manager.unlock_achievement_via_stat("WIN_10"),
+            Err(SamError::StatChangeTooLarge("WINS".to_owned()))
+        );
+        assert_eq!(app_manager.backend().get_stat_i32("WINS").ok(), Some(3));
+        assert_eq!(
+            app_manager.unlock_achievement_via_stat("RANK_1"),
+            Err(SamError::ProtectedStat("RANK".to_owned()))
+        );
+        assert!(!app_manager.backend().is_achieved("WIN_10"));
+    }
+
+    /// Test that changes Steam failed to store are stored again by the auto-store, and only them.
+    #[test]
+    fn mock_store_pending_changes() {
+        let backend = MockSteamBackend::new().with_int_stat("WINS", 3, 0, 100);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        assert_eq!(app_manager.store_pending_changes(), Ok(false));
+        app_manager.backend().store_fails.set(true);
+        assert_eq!(app_manager.set_stat_i32("WINS", 5), Ok(false));
+        assert_eq!(app_manager.last_stored(), None);
+
+        app_manager.backend().store_fails.set(false);
+        assert_eq!(app_manager.store_pending_changes(), Ok(true));
+        assert!(app_manager.last_stored().is_some());
+        let store_calls = app_manager.backend().store_calls.get();
+        assert_eq!(app_manager.store_pending_changes(), Ok(false));
+        assert_eq!(app_manager.backend().store_calls.get(), store_calls);
+    }
+
+    /// Test that a write drops the achievements cache.
+    #[test]
+    fn mock_reset_invalidates_cache() -> Result<(), String> {
+        let backend = MockSteamBackend::new().with_achievement("A", "A", true, 0);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        let before = app_manager
+            .get_achievements()
+            .map_err(|e| format!("Failed to get achievements: {e}"))?;
+        assert!(before[0].is_achieved);
+
+        app_manager
+            .reset_all_stats(true)
+            .map_err(|e| format!("Failed to reset stats: {e}"))?;
+        let after = app_manager
+            .get_achievements()
+            .map_err(|e| format!("Failed to get achievements: {e}"))?;
+        assert!(!after[0].is_achieved);
+        Ok(())
+    }
+
+    /// Test that a refresh asks Steam again while a plain read is served from the cache.
+    #[test]
+    fn mock_refresh_bypasses_cache() -> Result<(), String> {
+        let backend = MockSteamBackend::new().with_achievement("A", "A", false, 0);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        app_manager
+            .get_achievements()
+            .map_err(|e| format!("Failed to get achievements: {e}"))?;
+        // Unlocked in game, behind the app server's back
+        app_manager
+            .backend()
+            .achievements
+            .borrow_mut()
+            .insert("A".to_owned(), (true, 1));
+
+        let cached = app_manager
+            .get_achievements()
+            .map_err(|e| format!("Failed to get achievements: {e}"))?;
+        assert!(!cached[0].is_achieved);
+        let refreshed = app_manager
+            .refresh_achievements()
+            .map_err(|e| format!("Failed to refresh achievements: {e}"))?;
+        assert!(refreshed[0].is_achieved);
+        Ok(())
+    }
+
+    /// Test that resetting with achievements reports one progress step per achievement.
+    #[test]
+    fn mock_reset_reports_progress() -> Result<(), String> {
+        let backend = MockSteamBackend::new()
+            .with_achievement("A", "A", true, 0)
+            .with_achievement("B", "B", true, 0)
+            .with_achievement("C", "C", false, 0);
+        let mut app_manager = AppManager::with_backend(480, backend);
+
+        let mut steps = vec![];
+        let success = app_manager
+            .reset_all_stats_with_progress(true, |done, total| steps.push((done, total)))
+            .map_err(|e| format!("Failed to reset stats: {e}"))?;
+
+        assert!(success);
+        assert_eq!(steps, vec![(1, 3), (2, 3), (3, 3)]);
+        assert!(!app_manager.backend().is_achieved("A"));
+        // Steam resets them all in one call, not one by one
+        assert_eq!(app_manager.backend().achievement_writes.get(), 0);
+        Ok(())
+    }
+
+    /// Test that an owned app failing to load is skipped rather than failing the library.
+    #[test]
+    fn owned_apps_skip_bad_entries() -> Result<(), String> {
+        let games: Vec<XmlGame> = [10, 20, 30, 40]
+            .into_iter()
+            .map(|app_id| XmlGame {
+                app_id,
+                app_type: None,
+            })
+            .collect();
+        let get_app = |app_id: u32, _: &XmlGame| match app_id {
+            20 => Err(SamError::AppListRetrievalFailed),
+            _ => Ok(AppModel {
+                app_id,
+                app_name: format!("App {app_id}"),
+                image_url: None,
+                icon_url: None,
+                app_type: AppModelType::App,
+                developer: String::new(),
+                metacritic_score: None,
+            }),
+        };
+
+        let (models, skipped) =
+            collect_owned_apps(&games, |app_id| Ok::<_, String>(app_id != 40), get_app, None)
+                .map_err(|e| e.to_string())?;
+        let app_ids: Vec<u32> = models.iter().map(|app| app.app_id).collect();
+        assert_eq!(app_ids, vec![10, 30]);
+        assert_eq!(skipped, 1);
+
+        // Steam never answering is still an error, not an empty library
+        let result = collect_owned_apps(&games, |_| Err("no answer"), get_app, None);
+        assert!(matches!(result, Err(SamError::OwnershipCheckFailed)));
+
+        // The user giving up stops the listing, whatever was found so far
+        let cancelled = AtomicBool::new(true);
+        let result =
+            collect_owned_apps(&games, |_| Ok::<_, String>(true), get_app, Some(&cancelled));
+        assert!(matches!(result, Err(SamError::Cancelled)));
+        Ok(())
+    }
+
+    /// Test that app types Valve adds later are kept instead of failing the library.
+    #[test]
+    fn app_type_from_games_xml() {
+        assert_eq!("Demo".parse(), Ok(AppModelType::Demo));
+        assert_eq!("".parse(), Ok(AppModelType::App));
+        let tool: AppModelType = "Tool".parse().unwrap_or(AppModelType::App);
+        assert_eq!(tool, AppModelType::Other("Tool".to_owned()));
+        assert_eq!(tool.to_string(), "Tool");
+    }
+
+    /// Test that IPC frames read back as written, one after the other.
+    #[test]
+    fn ipc_frame_round_trip() -> Result<(), String> {
+        // Well past a pipe buffer, so large messages are read in full
+        let many_ids: Vec<String> = (0..10_000).map(|i| format!("ACHIEVEMENT_{i}")).collect();
+        let commands = vec![
+            SteamCommand::Shutdown,
+            SteamCommand::SetAchievements(480, true, vec![]),
+            SteamCommand::SetAchievements(480, false, many_ids),
+            SteamCommand::GetUserAchievements(480, 76561197960287930),
+        ];
+        let mut frames = vec![];
+        for command in &commands {
+            frames.extend(command.sam_serialize());
+        }
+        assert!(frames.len() > 64 * 1024);
+
+        let mut pipe = Cursor::new(frames);
+        for command in commands {
+            let received = SteamCommand::from_recver(&mut pipe)
+                .map_err(|e| format!("Failed to read command: {e}"))?;
+            assert_eq!(received, command);
+        }
+        // Nothing left, the next read fails like a closed pipe
+        assert_eq!(
+            SteamCommand::from_recver(&mut pipe),
+            Err(SamError::SocketCommunicationFailed)
+        );
+
+        let responses = vec![
+            SteamResponse::Success(vec![1u32, 2, 3]),
+            SteamResponse::Progress(1, 3),
+            SteamResponse::Error(SamError::ProtectedAchievement("A".to_owned())),
+        ];
+        let mut frames = vec![];
+        for response in &responses {
+            frames.extend(response.sam_serialize());
+        }
+        let mut pipe = Cursor::new(frames);
+        for response in responses {
+            let received = SteamResponse::<Vec<u32>>::from_recver(&mut pipe)
+                .map_err(|e| format!("Failed to read response: {e}"))?;
+            assert_eq!(received, response);
+        }
+        Ok(())
+    }
+
+    /// Test that broken IPC frames are reported instead of misread.
+    #[test]
+    fn ipc_frame_errors() {
+        let frame = SteamCommand::GetAchievements(480, None).sam_serialize();
+        let prefix = std::mem::size_of::<usize>();
+
+        // Empty pipe, and a length prefix cut short
+        assert_eq!(
+            SteamCommand::from_recver(&mut Cursor::new(vec![])),
+            Err(SamError::SocketCommunicationFailed)
+        );
+        assert_eq!(
+            SteamCommand::from_recver(&mut Cursor::new(frame[..prefix - 1].to_vec())),
+            Err(SamError::SocketCommunicationFailed)
+        );
+
+        // Body shorter than announced
+        assert_eq!(
+            SteamCommand::from_recver(&mut Cursor::new(frame[..frame.len() - 1].to_vec())),
+            Err(SamError::SocketCommunicationFailed)
+        );
+
+        // Empty payload, and a length cutting the JSON short
+        assert_eq!(
+            SteamCommand::from_recver(&mut Cursor::new(0usize.to_le_bytes().to_vec())),
+            Err(SamError::SerializationFailed)
+        );
+        let mut short = frame.clone();
+        short[..prefix].copy_from_slice(&(frame.len() - prefix - 1).to_le_bytes());
+        assert_eq!(
+            SteamCommand::from_recver(&mut Cursor::new(short)),
+            Err(SamError::SerializationFailed)
+        );
+
+        // A length no message has, from a corrupt stream, is refused before allocating
+        let mut huge = usize::MAX.to_le_bytes().to_vec();
+        huge.extend_from_slice(&frame[prefix..]);
+        assert_eq!(
+            SteamCommand::from_recver(&mut Cursor::new(huge)),
+            Err(SamError::SocketCommunicationFailed)
+        );
+
+        // Bytes that can't be JSON, as when reading from the middle of another message
+        let mut garbled = 4usize.to_le_bytes().to_vec();
+        garbled.extend_from_slice(&[0xff, 0xfe, 0x00, 0x80]);
+        assert_eq!(
+            SteamCommand::from_recver(&mut Cursor::new(garbled)),
+            Err(SamError::FramingError)
+        );
+    }
+
+    /// Test that a forwarded command reaches the app server and all its responses, progress
+    /// included, come back to the GUI.
+    #[test]
+    fn relay_app_command_passes_progress_through() -> Result<(), String> {
+        let mut app_server_output = vec![];
+        for response in [
+            SteamResponse::Progress(1, 2),
+            SteamResponse::Progress(2, 2),
+            SteamResponse::Success(true),
+            // Belongs to the next command, must stay in the pipe
+            SteamResponse::Success(false),
+        ] {
+            app_server_output.extend(response.sam_serialize());
+        }
+        let mut app_server_tx = vec![];
+        let mut app_server_rx = Cursor::new(app_server_output);
+        let mut gui = vec![];
+
+        relay_app_command(
+            &mut app_server_tx,
+            &mut app_server_rx,
+            SteamCommand::ResetStats(480, true),
+            &mut gui,
+            "test",
+        );
+
+        assert_eq!(
+            SteamCommand::from_recver(&mut Cursor::new(app_server_tx)),
+            Ok(SteamCommand::ResetStats(480, true))
+        );
+        let mut gui = Cursor::new(gui);
+        let mut relayed = vec![];
+        while let Ok(response) = SteamResponse::<bool>::from_recver(&mut gui) {
+            relayed.push(response);
+        }
+        assert_eq!(
+            relayed,
+            vec![
+                SteamResponse::Progress(1, 2),
+                SteamResponse::Progress(2, 2),
+                SteamResponse::Success(true),
+            ]
+        );
+        assert_eq!(
+            SteamResponse::<bool>::from_recver(&mut app_server_rx),
+            Ok(SteamResponse::Success(false))
+        );
+        Ok(())
+    }
+
+    /// Test that forwarding fails cleanly when the app server is gone or was never started.
+    #[test]
+    fn forward_to_child_without_app_server() {
+        let mut gui = vec![];
+        relay_app_command(
+            &mut vec![],
+            &mut Cursor::new(vec![]),
+            SteamCommand::GetStats(480),
+            &mut gui,
+            "test",
+        );
+        assert_eq!(
+            SteamResponse::<()>::from_recver(&mut Cursor::new(gui)),
+            Ok(SteamResponse::Error(SamError::SocketCommunicationFailed))
+        );
+
+        let mut gui = vec![];
+        forward_to_child(&mut HashMap::new(), 480, SteamCommand::GetStats(480), &mut gui);
+        assert_eq!(
+            SteamResponse::<()>::from_recver(&mut Cursor::new(gui)),
+            Ok(SteamResponse::Error(SamError::AppMismatchError))
+        );
+    }
+
+    /// Test that an app server shared by two windows is only let go by the last of them.
+    #[test]
+    fn app_owners_release_shared_servers_last() {
+        let peer = Owner::Connection(1);
+        let mut owners = AppOwners::default();
+        assert!(owners.claim(480, Owner::Gui));
+        assert!(!owners.claim(480, Owner::Gui));
+        assert!(owners.claim(480, peer));
+        assert!(owners.claim(620, peer));
+
+        // The peer never opened 440, and the GUI closing 480 leaves it to the peer
+        assert_eq!(owners.release(440, peer), None);
+        assert_eq!(owners.release(480, Owner::Gui), Some(false));
+        assert_eq!(owners.release(480, Owner::Gui), None);
+
+        let mut unused = owners.release_all(peer);
+        unused.sort_unstable();
+        assert_eq!(unused, vec![480, 620]);
+        assert!(owners.release_all(peer).is_empty());
+    }
+
+    /// Test that JSON-RPC methods and params map to the commands they name.
+    #[test]
+    fn rpc_params_name_commands() {
+        use serde_json::json;
+
+        assert_eq!(parse_rpc_command("StopApps", None), Some(SteamCommand::StopApps));
+        assert_eq!(parse_rpc_command("StopApps", Some(json!([]))), Some(SteamCommand::StopApps));
+        assert_eq!(
+            parse_rpc_command("GetAchievements", Some(json!([480, "english"]))),
+            Some(SteamCommand::GetAchievements(480, Some("english".to_string())))
+        );
+        assert_eq!(
+            parse_rpc_command("GetAchievementSummaries", Some(json!([[10, 20]]))),
+            Some(SteamCommand::GetAchievementSummaries(vec![10, 20]))
+        );
+        assert_eq!(
+            parse_rpc_command("SetAchievement", Some(json!([480, true, "ACH"]))),
+            Some(SteamCommand::SetAchievement(480, true, "ACH".to_string()))
+        );
+        assert_eq!(parse_rpc_command("SetAchievement", Some(json!([480]))), None);
+        assert_eq!(parse_rpc_command("FormatDisk", None), None);
+    }
+
+    /// Test that RPC clients can't reset or close what the GUI is using.
+    #[test]
+    fn rpc_refuses_commands_acting_behind_the_gui() {
+        assert!(allowed_over_rpc(&SteamCommand::SetAchievement(480, true, "ACH".to_string())));
+        assert!(allowed_over_rpc(&SteamCommand::StopApp(480)));
+        assert!(!allowed_over_rpc(&SteamCommand::StopApps));
+        assert!(!allowed_over_rpc(&SteamCommand::ResetStats(480, true)));
+        assert!(!allowed_over_rpc(&SteamCommand::Reconnect));
+        assert!(!allowed_over_rpc(&SteamCommand::SetDryRun(false)));
+    }
+
+    /// Test that responses become JSON-RPC results, errors and progress notifications.
+    #[test]
+    fn rpc_messages_from_responses() -> Result<(), String> {
+        use serde_json::json;
+
+        let to_vec = |response: &SteamResponse<u32>| {
+            serde_json::to_vec(response).map_err(|e| format!("Serialization failed: {e}"))
+        };
+        let id = json!(7);
+
+        let (message, is_final) = rpc_message(&id, &to_vec(&SteamResponse::Success(42))?);
+        assert!(is_final);
+        assert_eq!(message, json!({ "jsonrpc": "2.0", "id": 7, "result": 42 }));
+
+        let (message, is_final) = rpc_message(&id, &to_vec(&SteamResponse::Progress(1, 3))?);
+        assert!(!is_final);
+        assert_eq!(message["method"], "progress");
+        assert_eq!(message["params"], json!({ "id": 7, "done": 1, "total": 3 }));
+
+        let error = SteamResponse::Error(SamError::AppMismatchError);
+        let (message, is_final) = rpc_message(&id, &to_vec(&error)?);
+        assert!(is_final);
+        assert_eq!(message["id"], 7);
+        assert_eq!(message["error"]["code"], -32000);
+        assert_eq!(message["error"]["data"], json!("AppMismatchError"));
+        Ok(())
+    }
+
+    /// Test that app data filling the whole buffer is told apart from a value that fits.
+    #[test]
+    fn app_data_value_detects_truncation() {
+        let mut buffer = vec![0u8; 8];
+        buffer[..4].copy_from_slice(b"Name");
+        assert_eq!(app_data_value(&buffer, 5), Some("Name".to_string()));
+
+        buffer[..7].copy_from_slice(b"LongerN");
+        assert_eq!(app_data_value(&buffer, 8), None);
+        assert_eq!(app_data_value(&[b'x'; 8], 8), None);
+        // Steam telling the length it needed
+        buffer[..4].copy_from_slice(b"Cut\0");
+        assert_eq!(app_data_value(&buffer, 300), None);
+    }
+
+    /// Test that only error responses are recorded, and only the latest twenty of them.
+    #[test]
+    fn recent_errors_keep_the_latest_failures() -> Result<(), String> {
+        let recent_errors = RecentErrors::default();
+        let mut sent = Vec::new();
+        let mut tx = ErrorWatch::new(&mut sent, "Status".to_string(), &recent_errors);
+        let mut send = |response: Vec<u8>| {
+            tx.write_all(&response)
+                .map_err(|e| format!("Failed to send response: {e}"))
+        };
+        send(SteamResponse::Success(true).sam_serialize())?;
+        send(SteamResponse::<()>::Progress(1, 2).sam_serialize())?;
+        assert!(recent_errors.to_vec().is_empty());
+
+        for _ in 0..30 {
+            send(SteamResponse::<()>::Error(SamError::StoreFailed).sam_serialize())?;
+        }
+        send(SteamResponse::<()>::Error(SamError::UnknownError).sam_serialize())?;
+        drop(tx);
+
+        let errors = recent_errors.to_vec();
+        assert_eq!(errors.len(), 20);
+        assert_eq!(errors.last().map(|e| &e.error), Some(&SamError::UnknownError));
+        assert!(errors.iter().all(|e| e.command == "Status"));
+        // The client still gets every response
+        assert!(!sent.is_empty());
+        Ok(())
+    }
 }
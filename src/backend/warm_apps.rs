@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// App servers the user just left are parked here instead of being shut down, so that
+// opening the same app again within the grace period reuses the running process.
+// A short-lived thread per parked app shuts it down once its grace period is over, and
+// records the app servers left in the session pid file.
+
+use crate::backend::stale_processes::write_children_pid_file;
+use crate::dev_println;
+use crate::utils::{
+    bidir_child::BidirChild,
+    ipc_types::{SamSerializable, SteamCommand},
+};
+use std::{
+    collections::HashMap,
+    io::Write,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// A parked app server, with when its grace period ends and the language it names
+/// achievements and stats in.
+type Parked = (BidirChild, Instant, Option<String>);
+
+/// App servers waiting to be either reused or shut down.
+#[derive(Debug, Clone, Default)]
+pub struct WarmApps {
+    parked: Arc<Mutex<HashMap<u32, Parked>>>,
+    /// `(app_id, pid)` of the app servers in use, as last recorded.
+    in_use: Arc<Mutex<Vec<(u32, u32)>>>,
+}
+
+/// How long an app server gets to exit after being asked to before it is killed.
+const APP_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Asks an app server to exit and waits for it, killing it if it hangs.
+pub fn shutdown_app_server(child: &mut BidirChild) {
+    if let Err(e) = child.tx.write_all(&SteamCommand::Shutdown.sam_serialize()) {
+        eprintln!("[ORCHESTRATOR] Failed to send shutdown to app server: {e}");
+    }
+    child.wait_or_kill(APP_SHUTDOWN_TIMEOUT);
+}
+
+impl WarmApps {
+    /// Keeps `child`, which names things in `language`, running for `grace`, then shuts it
+    /// down unless it was taken back.
+    pub fn park(
+        &self,
+        app_id: u32,
+        mut child: BidirChild,
+        grace: Duration,
+        language: Option<String>,
+    ) {
+        if grace.is_zero() {
+            shutdown_app_server(&mut child);
+            return;
+        }
+
+        let deadline = Instant::now() + grace;
+        let Ok(mut parked) = self.parked.lock() else {
+            shutdown_app_server(&mut child);
+            return;
+        };
+        parked.insert(app_id, (child, deadline, language));
+        drop(parked);
+
+        let warm_apps = self.clone();
+        thread::spawn(move || {
+            thread::sleep(grace);
+            let expired = match warm_apps.parked.lock() {
+                // A later park of the same app moved the deadline, its own thread handles it
+                Ok(mut parked) => match parked.get(&app_id) {
+                    Some((_, current_deadline, _)) if *current_deadline <= Instant::now() => {
+                        parked.remove(&app_id)
+                    }
+                    _ => None,
+                },
+                Err(_) => None,
+            };
+            if let Some((mut child, _, _)) = expired {
+                dev_println!("[ORCHESTRATOR] Grace period over, stopping app {app_id}");
+                shutdown_app_server(&mut child);
+                warm_apps.write_pid_file();
+            }
+        });
+    }
+
+    /// Takes back a parked app server, if it is still running. One parked before the language
+    /// changed to `language` would keep the names it loaded, it is shut down instead.
+    pub fn take(&self, app_id: u32, language: &Option<String>) -> Option<BidirChild> {
+        let (mut child, _, parked_language) = self.parked.lock().ok()?.remove(&app_id)?;
+        if parked_language != *language {
+            dev_println!("[ORCHESTRATOR] The language changed, stopping parked app {app_id}");
+            shutdown_app_server(&mut child);
+            return None;
+        }
+        match child.child.try_wait() {
+            Ok(None) => Some(child),
+            _ => None,
+        }
+    }
+
+    /// Shuts down every parked app server right away.
+    pub fn shutdown_all(&self) {
+        let drained: Vec<_> = match self.parked.lock() {
+            Ok(mut parked) => parked.drain().collect(),
+            Err(_) => return,
+        };
+        for (_app_id, (mut child, _, _)) in drained {
+            dev_println!("[ORCHESTRATOR] Stopping parked app {_app_id}");
+            shutdown_app_server(&mut child);
+        }
+    }
+
+    /// Records `in_use`, the `(app_id, pid)` of the app servers in use, and the parked ones
+    /// in the session pid file.
+    pub fn record_children(&self, in_use: Vec<(u32, u32)>) {
+        if let Ok(mut recorded) = self.in_use.lock() {
+            *recorded = in_use;
+        }
+        self.write_pid_file();
+    }
+
+    fn write_pid_file(&self) {
+        // Held while writing, so that a grace period ending can't write an older list last
+        let Ok(in_use) = self.in_use.lock() else {
+            return;
+        };
+        write_children_pid_file(in_use.iter().copied().chain(self.pids()));
+    }
+
+    /// `(app_id, pid)` of every parked app server.
+    pub fn pids(&self) -> Vec<(u32, u32)> {
+        match self.parked.lock() {
+            Ok(parked) => parked
+                .iter()
+                .map(|(app_id, (child, _, _))| (*app_id, child.child.id()))
+                .collect(),
+            Err(_) => vec![],
+        }
+    }
+}
@@ -14,9 +14,54 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use glib::Object;
-use gtk::glib;
+use gtk::{glib, prelude::*};
 
-use crate::backend::stat_definitions::AchievementInfo;
+use crate::backend::stat_definitions::{AchievementInfo, Permission};
+use crate::tr;
+use crate::utils::format::format_unlock_time;
+
+/// How rare an achievement is, from the share of players who unlocked it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    UltraRare,
+}
+
+impl Rarity {
+    /// Common above 50%, uncommon above 20%, rare from 5%, ultra-rare below 5%.
+    pub fn from_percent(percent: f32) -> Self {
+        if percent > 50.0 {
+            Rarity::Common
+        } else if percent > 20.0 {
+            Rarity::Uncommon
+        } else if percent >= 5.0 {
+            Rarity::Rare
+        } else {
+            Rarity::UltraRare
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            Rarity::Common => tr!("Common"),
+            Rarity::Uncommon => tr!("Uncommon"),
+            Rarity::Rare => tr!("Rare"),
+            Rarity::UltraRare => tr!("Ultra-rare"),
+        }
+    }
+
+    /// CSS class of the badge, styled in `ui_components::APP_CSS`.
+    pub fn css_class(self) -> &'static str {
+        match self {
+            Rarity::Common => "rarity-common",
+            Rarity::Uncommon => "rarity-uncommon",
+            Rarity::Rare => "rarity-rare",
+            Rarity::UltraRare => "rarity-ultra-rare",
+        }
+    }
+}
 
 glib::wrapper! {
     pub struct GAchievementObject(ObjectSubclass<imp::GAchievementObject>);
@@ -30,6 +75,10 @@ impl GAchievementObject {
             0.0
         });
         let global_achieved_percent_ok = info.global_achieved_percent.is_some();
+        let (progress_stat, progress_target) = match info.progress {
+            Some(progress) => (progress.stat_id, progress.max_value),
+            None => (String::new(), 0.0),
+        };
         let search_text = if info.description.is_empty() {
             info.name.clone()
         } else {
@@ -45,18 +94,49 @@ impl GAchievementObject {
             .property("name", info.name)
             .property("description", info.description)
             .property("is-achieved", info.is_achieved)
-            .property(
-                "unlock-time",
-                info.unlock_time.map(|time| format!("{time:#?}")),
-            )
+            .property("unlock-time", info.unlock_time.and_then(format_unlock_time))
             .property("icon-normal", info.icon_normal)
             .property("icon-locked", info.icon_locked)
-            .property("permission", info.permission)
+            .property("permission", info.permission.bits())
             .property("global-achieved-percent", global_achieved_percent)
             .property("global-achieved-percent-ok", global_achieved_percent_ok)
             .property("time-until-unlock", "...".to_string())
+            .property("selected", false)
+            .property("progress-stat", progress_stat)
+            .property("progress-target", progress_target)
+            .property("order", info.order)
             .build()
     }
+
+    /// Whether only the game's servers may change the achievement.
+    pub fn is_protected(&self) -> bool {
+        Permission(self.permission()).is_protected()
+    }
+
+    /// Takes what Steam reported in `fresh`, keeping the UI state of this object. Refuses
+    /// when the unlocked state differs: on a shown row, flipping it makes the switch write
+    /// the achievement back to Steam, so the caller swaps in the fresh object instead, which
+    /// then gets the selection of this one.
+    pub fn update_from(&self, fresh: &GAchievementObject) -> bool {
+        if self.is_achieved() != fresh.is_achieved() {
+            fresh.set_selected(self.selected());
+            return false;
+        }
+        self.set_property("unlock-time", fresh.unlock_time());
+        self.set_name(fresh.name());
+        self.set_description(fresh.description());
+        self.set_search_text(fresh.search_text());
+        self.set_icon_normal(fresh.icon_normal());
+        self.set_icon_locked(fresh.icon_locked());
+        self.set_permission(fresh.permission());
+        self.set_global_achieved_percent(fresh.global_achieved_percent());
+        self.set_global_achieved_percent_ok(fresh.global_achieved_percent_ok());
+        self.set_progress_stat(fresh.progress_stat());
+        self.set_progress_target(fresh.progress_target());
+        self.set_order(fresh.order());
+        self.set_recently_unlocked(fresh.recently_unlocked());
+        true
+    }
 }
 
 mod imp {
@@ -94,6 +174,29 @@ mod imp {
         global_achieved_percent_ok: Cell<bool>,
         #[property(get, set)]
         time_until_unlock: RefCell<String>,
+        /// Stat that unlocks this achievement once it reaches `progress_target`, empty if none.
+        #[property(get, set)]
+        progress_stat: RefCell<String>,
+        #[property(get, set)]
+        progress_target: Cell<f32>,
+        /// Position of the achievement in the game's schema.
+        #[property(get, set)]
+        order: Cell<u32>,
+        /// Transient UI state: ticked for a bulk action in the manual view.
+        #[property(get, set)]
+        selected: Cell<bool>,
+        /// Transient UI state: the details of this achievement are shown under its row.
+        #[property(get, set)]
+        expanded: Cell<bool>,
+        /// Transient UI state: another profile's state is shown next to this achievement.
+        #[property(get, set)]
+        is_compared: Cell<bool>,
+        /// Whether the compared profile unlocked this achievement.
+        #[property(get, set)]
+        compared_achieved: Cell<bool>,
+        /// Transient UI state: unlocked since the app was last shown, highlighted in its row.
+        #[property(get, set)]
+        recently_unlocked: Cell<bool>,
     }
 
     #[glib::object_subclass]
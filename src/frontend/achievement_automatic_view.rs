@@ -13,10 +13,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::frontend::MainApplication;
 use crate::frontend::achievement::GAchievementObject;
 use crate::frontend::custom_progress_bar_widget::CustomProgressBar;
 use crate::frontend::shimmer_image::ShimmerImage;
+use crate::tr;
 use gtk::ClosureExpression;
 use gtk::glib;
 use gtk::pango::EllipsizeMode;
@@ -30,7 +30,7 @@ const ICON_NORMAL: &str = "normal";
 const ICON_LOCKED: &str = "locked";
 
 /// Create the header for the automatic achievements view.
-fn create_header(application: &MainApplication) -> (ListBox, Button) {
+fn create_header() -> (ListBox, Button) {
     let list = ListBox::builder()
         .selection_mode(SelectionMode::None)
         .build();
@@ -40,14 +40,15 @@ fn create_header(application: &MainApplication) -> (ListBox, Button) {
         .build();
 
     let button_stop = Button::builder().icon_name("go-previous").build();
-    let label = Label::builder().label("Stop and go back").build();
+    let label = Label::builder().label(tr!("Stop and go back")).build();
 
     hbox.append(&button_stop);
     hbox.append(&label);
 
-    let application = application.clone();
-    button_stop.connect_clicked(move |_| {
-        application.activate_action("refresh_achievements_list", None);
+    button_stop.connect_clicked(move |button| {
+        if let Err(e) = button.activate_action("win.refresh_achievements_list", None) {
+            log::error!("Failed to activate refresh_achievements_list: {e}");
+        }
     });
 
     let list_box_row = ListBoxRow::builder()
@@ -65,11 +66,8 @@ fn create_header(application: &MainApplication) -> (ListBox, Button) {
 }
 
 /// Create the automatic achievements view.
-pub fn create_achievements_automatic_view(
-    timed_filtered_model: &NoSelection,
-    application: &MainApplication,
-) -> (Frame, Button) {
-    let (header, header_achievements_stop) = create_header(application);
+pub fn create_achievements_automatic_view(timed_filtered_model: &NoSelection) -> (Frame, Button) {
+    let (header, header_achievements_stop) = create_header();
 
     let achievements_list_factory = SignalListItemFactory::new();
 
@@ -108,10 +106,8 @@ pub fn create_achievements_automatic_view(
 
 /// Helper to setup a list item row for the automatic achievements view.
 fn setup_achievement_list_item(list_item: &gtk::ListItem) {
-    let normal_icon = ShimmerImage::new();
-    normal_icon.set_size_request(32, 32);
-    let locked_icon = ShimmerImage::new();
-    locked_icon.set_size_request(32, 32);
+    let normal_icon = ShimmerImage::with_size(32, 32);
+    let locked_icon = ShimmerImage::with_size(32, 32);
 
     let icon_stack = Stack::builder()
         .transition_type(StackTransitionType::RotateLeftRight)
@@ -14,29 +14,36 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
+    backend::stat_definitions::Permission,
     dev_println,
     frontend::{
-        MainApplication,
-        achievement::GAchievementObject,
-        achievement_view::count_unlocked_achievements,
+        achievement::{GAchievementObject, Rarity},
+        achievement_view::{count_unlocked_achievements, update_total_progress},
         custom_progress_bar_widget::CustomProgressBar,
-        request::{Request, SetAchievement},
+        request::{GetAchievement, Request, SetAchievement, SetAchievements, UnlockAchievementViaStat},
         shimmer_image::ShimmerImage,
+        toast::show_toast,
+    },
+    tr, trn,
+    utils::{
+        format::{format_localized_decimal, format_seconds_to_mm_ss},
+        ipc_types::{AchievementResults, SamError},
     },
-    utils::format::format_seconds_to_mm_ss,
 };
 use gtk::{
-    Adjustment, Align, Box, Button, ClosureExpression, Frame, Label, ListBox, ListBoxRow, ListItem,
-    ListView, NoSelection, Orientation, Overlay, ScrolledWindow, SelectionMode,
-    SignalListItemFactory, SpinButton, Stack, StackTransitionType, Switch, Widget,
+    ActionBar, Adjustment, Align, ApplicationWindow, Box, Button, CheckButton, ClosureExpression,
+    DropDown, Frame, Label, ListBox, ListBoxRow, ListItem, ListView, NoSelection, Orientation,
+    Overlay, Revealer, ScrolledWindow, SelectionMode, SignalListItemFactory, SpinButton, Stack,
+    StackTransitionType, Switch, ToggleButton, Widget,
     gio::{ListStore, spawn_blocking},
     glib::{self, MainContext, SignalHandlerId, clone, translate::FromGlib},
     pango::EllipsizeMode,
     prelude::*,
 };
 use std::{
-    cell::Cell,
+    cell::{Cell, RefCell},
     cmp::Ordering,
+    collections::{HashMap, HashSet},
     ffi::c_ulong,
     rc::Rc,
     sync::{Arc, atomic::AtomicBool},
@@ -48,16 +55,15 @@ fn create_header(
     achievement_views_stack: &Stack,
     raw_model: &ListStore,
     timed_raw_model: &ListStore,
-    application: &MainApplication,
 ) -> (ListBox, Adjustment, SpinButton, Button, Arc<AtomicBool>) {
     let list = ListBox::builder()
         .selection_mode(SelectionMode::None)
         .build();
     let hbox = Box::new(Orientation::Horizontal, 10);
 
-    let label_unlock = Label::new(Some("Get to"));
-    let label_achievements_over = Label::new(Some("unlocked over"));
-    let label_achievements_minutes = Label::new(Some("minutes"));
+    let label_unlock = Label::new(Some(&tr!("Get to")));
+    let label_achievements_over = Label::new(Some(&tr!("unlocked over")));
+    let label_achievements_minutes = Label::new(Some(&tr!("minutes")));
 
     let adjustment_achievements_count = Adjustment::builder()
         .lower(0.0)
@@ -82,12 +88,52 @@ fn create_header(
         .orientation(Orientation::Horizontal)
         .hexpand(true)
         .build();
-    let button_start = Button::builder().label("Start").build();
+    let button_start = Button::builder().label(tr!("Start")).build();
     let cancelled_task = Arc::new(AtomicBool::new(false));
 
-    // Header row: [Get to] [spin] [unlocked over] [spin] [minutes] [spacer] [Start]
+    // Quick presets: unlock the next N locked achievements, or all of them
+    let quick_unlock_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .css_classes(["linked"].as_slice())
+        .build();
+    let all_label = tr!("All");
+    for (label, count) in [("+1", Some(1)), ("+5", Some(5)), ("+10", Some(10)), (all_label.as_str(), None)] {
+        let quick_button = Button::builder().label(label).build();
+        quick_button.set_tooltip_text(Some(&match count {
+            Some(count) => tr!("Unlock the next {count} locked achievements", count = count),
+            None => tr!("Unlock all remaining achievements"),
+        }));
+        button_start
+            .bind_property("sensitive", &quick_button, "sensitive")
+            .sync_create()
+            .build();
+        quick_button.connect_clicked(clone!(
+            #[weak]
+            raw_model,
+            #[weak]
+            adjustment_achievements_count,
+            #[weak]
+            button_start,
+            move |_| {
+                let unlocked_achievements = count_unlocked_achievements(&raw_model) as f64;
+                let target = match count {
+                    Some(count) => unlocked_achievements + count as f64,
+                    None => adjustment_achievements_count.upper(),
+                };
+                let target = target
+                    .max(adjustment_achievements_count.lower())
+                    .min(adjustment_achievements_count.upper());
+                adjustment_achievements_count.set_value(target);
+                button_start.emit_clicked();
+            }
+        ));
+        quick_unlock_box.append(&quick_button);
+    }
+
+    // Header row: [Get to] [spin] [+1|+5|+10|All] [unlocked over] [spin] [minutes] [spacer] [Start]
     hbox.append(&label_unlock);
     hbox.append(&spin_button_achievements_count);
+    hbox.append(&quick_unlock_box);
     hbox.append(&label_achievements_over);
     hbox.append(&spin_button_minutes_count);
     hbox.append(&label_achievements_minutes);
@@ -100,8 +146,6 @@ fn create_header(
         #[strong]
         app_id,
         #[weak]
-        application,
-        #[weak]
         spin_button_minutes_count,
         #[weak]
         spin_button_achievements_count,
@@ -111,9 +155,9 @@ fn create_header(
         cancelled_task,
         #[weak]
         timed_raw_model,
-        move |_| {
+        move |button_start| {
             let unlocked_achievements = count_unlocked_achievements(&raw_model) as i32;
-            let _total_achievements = raw_model.n_items();
+            let total_achievements = raw_model.n_items();
             let desired_achievements = spin_button_achievements_count.value_as_int();
             let desired_minutes = spin_button_minutes_count.value_as_int();
             let achievements_to_unlock_count = (desired_achievements - unlocked_achievements) as usize;
@@ -123,18 +167,16 @@ fn create_header(
             }
             let mut achievements_to_unlock = Vec::with_capacity(achievements_to_unlock_count);
 
-            for achievement in &raw_model {
-                if let Ok(obj) = achievement {
-                    if let Ok(g_achievement) = obj.downcast::<GAchievementObject>() {
-                        if !g_achievement.is_achieved() && g_achievement.permission() & 2 == 0 {
-                            achievements_to_unlock.push(g_achievement);
-                            if achievements_to_unlock.len() == achievements_to_unlock_count {
-                                break;
-                            }
+            for obj in (&raw_model).into_iter().flatten() {
+                if let Ok(g_achievement) = obj.downcast::<GAchievementObject>() {
+                    if !g_achievement.is_achieved() && !g_achievement.is_protected() {
+                        achievements_to_unlock.push(g_achievement);
+                        if achievements_to_unlock.len() == achievements_to_unlock_count {
+                            break;
                         }
-                    } else {
-                        log::error!("Not a GAchievementObject in auto-unlock loop");
                     }
+                } else {
+                    log::error!("Not a GAchievementObject in auto-unlock loop");
                 }
             }
 
@@ -162,7 +204,7 @@ fn create_header(
                 }
             };
 
-            dev_println!("[CLIENT] Evaluation of automatic unlocking: unlocked: {unlocked_achievements}, total: {total_achievements}, desired: {desired_achievements}");
+            log::debug!("[CLIENT] Evaluation of automatic unlocking: unlocked: {unlocked_achievements}, total: {total_achievements}, desired: {desired_achievements}");
             if desired_minutes == 0 {
                 dev_println!("[CLIENT] Unlock desired achievements immediately");
                 for achievement_to_unlock in &achievements_to_unlock {
@@ -177,7 +219,9 @@ fn create_header(
                     }
                 }
 
-                application.activate_action("refresh_achievements_list", None);
+                if let Err(e) = button_start.activate_action("win.refresh_achievements_list", None) {
+                    log::error!("Failed to activate refresh_achievements_list: {e}");
+                }
                 return;
             }
 
@@ -188,7 +232,6 @@ fn create_header(
             let achievements_to_unlock = achievements_to_unlock; // move into async block
             let timed_raw_model = timed_raw_model.clone();
             let cancelled_task = cancelled_task.clone();
-            let desired_minutes = desired_minutes;
             MainContext::default().spawn_local(async move {
                 let mut elapsed = 0usize;
                 let need_elapsed = (desired_minutes * 60 * 1000) as usize;
@@ -266,6 +309,7 @@ fn create_header(
 }
 
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn create_achievements_manual_view(
     app_id: &Rc<Cell<Option<u32>>>,
     app_unlocked_achievements_count: &Rc<Cell<usize>>,
@@ -274,8 +318,8 @@ pub fn create_achievements_manual_view(
     timed_raw_model: &ListStore,
     achievement_views_stack: &Stack,
     app_achievement_count_value: &Label,
-    application: &MainApplication,
-) -> (Frame, Adjustment, SpinButton, Button, Arc<AtomicBool>) {
+    sort_dropdown: &DropDown,
+) -> (Frame, Adjustment, SpinButton, Button, Arc<AtomicBool>, CustomProgressBar) {
     let (
         header,
         header_achievements_adjustment,
@@ -283,13 +327,31 @@ pub fn create_achievements_manual_view(
         header_achievements_start,
         cancel_timed_unlock,
     ) = create_header(
-        &app_id,
-        &achievement_views_stack,
+        app_id,
+        achievement_views_stack,
         raw_model,
         timed_raw_model,
-        application,
     );
 
+    let total_progress_bar = CustomProgressBar::new();
+    total_progress_bar.set_hexpand(true);
+    let total_progress_label = Label::builder()
+        .halign(Align::Center)
+        .valign(Align::Center)
+        .build();
+    total_progress_bar
+        .bind_property("value", &total_progress_label, "label")
+        .transform_to(|_, value: f32| Some(tr!("{percent}% completed", percent = format!("{value:.0}"))))
+        .sync_create()
+        .build();
+    let total_progress_overlay = Overlay::builder()
+        .child(&total_progress_bar)
+        .margin_start(5)
+        .margin_end(5)
+        .margin_top(5)
+        .build();
+    total_progress_overlay.add_overlay(&total_progress_label);
+
     let achievements_list_factory = SignalListItemFactory::new();
 
     let app_achievements_list_view = ListView::builder()
@@ -302,18 +364,20 @@ pub fn create_achievements_manual_view(
         .vexpand(true)
         .build();
 
-    achievements_list_factory.connect_setup(move |_, list_item| {
-        if let Some(list_item) = list_item.downcast_ref::<gtk::ListItem>() {
-            setup_achievement_manual_list_item(list_item);
+    achievements_list_factory.connect_setup(clone!(
+        #[strong]
+        app_id,
+        move |_, list_item| {
+            if let Some(list_item) = list_item.downcast_ref::<gtk::ListItem>() {
+                setup_achievement_manual_list_item(list_item, &app_id);
+            }
         }
-    });
+    ));
 
     /// Helper to setup a list item row for the manual achievements view.
-    fn setup_achievement_manual_list_item(list_item: &gtk::ListItem) {
-        let normal_icon = ShimmerImage::new();
-        normal_icon.set_size_request(32, 32);
-        let locked_icon = ShimmerImage::new();
-        locked_icon.set_size_request(32, 32);
+    fn setup_achievement_manual_list_item(list_item: &gtk::ListItem, app_id: &Rc<Cell<Option<u32>>>) {
+        let normal_icon = ShimmerImage::with_size(32, 32);
+        let locked_icon = ShimmerImage::with_size(32, 32);
 
         let icon_stack = Stack::builder()
             .transition_type(StackTransitionType::RotateLeftRight)
@@ -328,17 +392,51 @@ pub fn create_achievements_manual_view(
             .build();
         icon_box.append(&icon_stack);
 
-        let protected_icon = gtk::Image::from_icon_name("action-unavailable-symbolic");
+        let protected_icon = gtk::Image::from_icon_name("changes-prevent-symbolic");
         protected_icon.set_margin_end(8);
-        protected_icon.set_tooltip_text(Some("This achievement is protected."));
+        protected_icon.set_tooltip_text(Some(&tr!(
+            "This achievement is protected: only the game's servers can change it, it can't be set from this computer."
+        )));
+
+        let rarity_badge = Label::builder()
+            .valign(Align::Center)
+            .margin_end(8)
+            .build();
+
+        let compared_icon = gtk::Image::builder()
+            .valign(Align::Center)
+            .margin_end(8)
+            .visible(false)
+            .build();
+
+        // Advanced: for achievements whose flag does not stick, raise the stat they follow
+        let via_stat_button = Button::builder()
+            .icon_name("go-last-symbolic")
+            .valign(Align::Center)
+            .margin_end(8)
+            .visible(false)
+            .build();
+        via_stat_button.add_css_class("flat");
 
         let switch = Switch::builder().valign(Align::Center).build();
 
+        let details_button = ToggleButton::builder()
+            .icon_name("pan-down-symbolic")
+            .valign(Align::Center)
+            .margin_end(8)
+            .tooltip_text(tr!("Show details"))
+            .build();
+        details_button.add_css_class("flat");
+
         let switch_box = Box::builder()
             .orientation(Orientation::Horizontal)
             .valign(Align::Start)
             .build();
+        switch_box.append(&details_button);
+        switch_box.append(&rarity_badge);
         switch_box.append(&protected_icon);
+        switch_box.append(&compared_icon);
+        switch_box.append(&via_stat_button);
         switch_box.append(&switch);
 
         let spacer = Box::builder()
@@ -357,6 +455,11 @@ pub fn create_achievements_manual_view(
         let global_percentage_progress_bar = CustomProgressBar::new();
         label_box.append(&name_label);
         label_box.append(&description_label);
+        let select_check = CheckButton::builder()
+            .valign(Align::Center)
+            .margin_end(8)
+            .tooltip_text(tr!("Select for bulk action"))
+            .build();
         let achievement_box = Box::builder()
             .orientation(Orientation::Horizontal)
             .margin_top(8)
@@ -364,6 +467,7 @@ pub fn create_achievements_manual_view(
             .margin_start(8)
             .margin_end(8)
             .build();
+        achievement_box.append(&select_check);
         achievement_box.append(&icon_box);
         achievement_box.append(&label_box);
         achievement_box.append(&spacer);
@@ -374,6 +478,17 @@ pub fn create_achievements_manual_view(
             .build();
         overlay.add_overlay(&achievement_box);
         overlay.set_measure_overlay(&achievement_box, true);
+
+        // The details are built when the row is first expanded, as most rows never are
+        let details_revealer = Revealer::new();
+        details_button
+            .bind_property("active", &details_revealer, "reveal-child")
+            .sync_create()
+            .build();
+
+        let row_box = Box::new(Orientation::Vertical, 0);
+        row_box.append(&overlay);
+        row_box.append(&details_revealer);
         let list_item = match list_item.downcast_ref::<gtk::ListItem>() {
             Some(li) => li,
             _ => {
@@ -381,7 +496,7 @@ pub fn create_achievements_manual_view(
                 return;
             }
         };
-        list_item.set_child(Some(&overlay));
+        list_item.set_child(Some(&row_box));
 
         list_item
             .property_expression("item")
@@ -395,18 +510,68 @@ pub fn create_achievements_manual_view(
 
         list_item
             .property_expression("item")
-            .chain_property::<GAchievementObject>("icon-normal")
-            .bind(&normal_icon, "url", Widget::NONE);
+            .chain_property::<GAchievementObject>("is-achieved")
+            .bind(&switch, "active", Widget::NONE);
 
         list_item
             .property_expression("item")
-            .chain_property::<GAchievementObject>("icon-locked")
-            .bind(&locked_icon, "url", Widget::NONE);
+            .chain_property::<GAchievementObject>("selected")
+            .bind(&select_check, "active", Widget::NONE);
 
         list_item
             .property_expression("item")
-            .chain_property::<GAchievementObject>("is-achieved")
-            .bind(&switch, "active", Widget::NONE);
+            .chain_property::<GAchievementObject>("expanded")
+            .bind(&details_button, "active", Widget::NONE);
+
+        via_stat_button.connect_clicked(clone!(
+            #[weak]
+            list_item,
+            #[strong]
+            app_id,
+            move |button| {
+                let (Some(achievement), Some(app_id)) = (
+                    list_item
+                        .item()
+                        .and_then(|item| item.downcast::<GAchievementObject>().ok()),
+                    app_id.get(),
+                ) else {
+                    return;
+                };
+                unlock_via_stat(button, app_id, &achievement);
+            }
+        ));
+
+        select_check.connect_toggled(clone!(
+            #[weak]
+            list_item,
+            move |check| {
+                if let Some(achievement) = list_item
+                    .item()
+                    .and_then(|item| item.downcast::<GAchievementObject>().ok())
+                {
+                    achievement.set_selected(check.is_active());
+                }
+            }
+        ));
+
+        // Toggled is emitted before the revealer follows `active`, so the details are ready
+        details_button.connect_toggled(clone!(
+            #[weak]
+            list_item,
+            #[weak]
+            details_revealer,
+            move |button| {
+                if button.is_active() && details_revealer.child().is_none() {
+                    details_revealer.set_child(Some(&create_achievement_details(&list_item)));
+                }
+                if let Some(achievement) = list_item
+                    .item()
+                    .and_then(|item| item.downcast::<GAchievementObject>().ok())
+                {
+                    achievement.set_expanded(button.is_active());
+                }
+            }
+        ));
 
         list_item
             .property_expression("item")
@@ -446,7 +611,7 @@ pub fn create_achievements_manual_view(
                     0
                 }
             };
-            let is_sensitive = (permission & 2) == 0;
+            let is_sensitive = !Permission(permission).is_protected();
             Some(is_sensitive.to_value())
         });
         let permission_protected_closure = glib::RustClosure::new(|values: &[glib::Value]| {
@@ -457,14 +622,14 @@ pub fn create_achievements_manual_view(
                     0
                 }
             };
-            let is_protected = (permission & 2) != 0;
+            let is_protected = Permission(permission).is_protected();
             Some(is_protected.to_value())
         });
 
         let visible_child_expr =
             ClosureExpression::new::<String>(&[is_achieved_expr], achieved_visible_icon_closure);
         let permission_sensitive_expr = ClosureExpression::new::<bool>(
-            &[permission_expr.clone()],
+            std::slice::from_ref(&permission_expr),
             permission_sensitive_closure,
         );
         let permission_protected_expr =
@@ -473,6 +638,40 @@ pub fn create_achievements_manual_view(
         visible_child_expr.bind(&icon_stack, "visible-child-name", Widget::NONE);
         permission_sensitive_expr.bind(&switch, "sensitive", Widget::NONE);
         permission_protected_expr.bind(&protected_icon, "visible", Widget::NONE);
+
+        // The rest of the row follows its achievement through one handler, rather than an
+        // expression watch per property, and only bound rows load their icons
+        let row = AchievementRow {
+            normal_icon,
+            locked_icon,
+            switch,
+            name_label,
+            rarity_badge,
+            via_stat_button,
+            compared_icon,
+            overlay,
+        };
+        let bound: RefCell<Option<(GAchievementObject, SignalHandlerId)>> = RefCell::default();
+        let row = Rc::new(row);
+        list_item.connect_item_notify(move |list_item| {
+            if let Some((achievement, handler)) = bound.take() {
+                achievement.disconnect(handler);
+            }
+            let Some(achievement) = list_item.item().and_downcast::<GAchievementObject>() else {
+                row.clear();
+                return;
+            };
+            row.show(&achievement);
+            let handler = achievement.connect_notify_local(
+                None,
+                clone!(
+                    #[weak]
+                    row,
+                    move |achievement, _| row.show(achievement)
+                ),
+            );
+            bound.replace(Some((achievement, handler)));
+        });
     }
 
     achievements_list_factory.connect_bind(clone!(
@@ -490,6 +689,8 @@ pub fn create_achievements_manual_view(
         header_achievements_spinbox,
         #[weak]
         header_achievements_start,
+        #[weak]
+        total_progress_bar,
         move |_, list_item| unsafe {
             let list_item = match list_item.downcast_ref::<ListItem>() {
                 Some(li) => li,
@@ -508,14 +709,7 @@ pub fn create_achievements_manual_view(
                 }
             };
 
-            let switch = match list_item
-                .child()
-                .and_then(|child| child.downcast::<Overlay>().ok())
-                .and_then(|overlay| overlay.last_child())
-                .and_then(|main_box| main_box.last_child())
-                .and_then(|switch_box| switch_box.downcast::<Box>().ok())
-                .and_then(|switch_box| switch_box.last_child())
-                .and_then(|switch| switch.downcast::<Switch>().ok()) {
+            let switch = match row_switch(list_item) {
                 Some(sw) => sw,
                 _ => {
                     log::error!("achievements_list_factory::connect_bind: Could not find Switch widget");
@@ -545,6 +739,8 @@ pub fn create_achievements_manual_view(
                 header_achievements_spinbox,
                 #[weak]
                 header_achievements_start,
+                #[weak]
+                total_progress_bar,
                 move |switch| {
                     let raw_model_len = raw_model.n_items();
                     if !switch.is_sensitive() {
@@ -555,12 +751,23 @@ pub fn create_achievements_manual_view(
                     achievement_object.set_is_achieved(unlocked);
                     let achievement_id = achievement_id.clone();
                     let handle = spawn_blocking(move || {
-                        SetAchievement {
+                        let result = SetAchievement {
                             app_id,
-                            achievement_id,
+                            achievement_id: achievement_id.clone(),
                             unlocked,
                         }
-                        .request()
+                        .request();
+                        // Read the achievement back for its unlock time, rather than the whole list
+                        let fresh = match result {
+                            Ok(true) => GetAchievement {
+                                app_id,
+                                achievement_id,
+                            }
+                            .request()
+                            .ok(),
+                            _ => None,
+                        };
+                        (result, fresh)
                     });
                     MainContext::default().spawn_local(clone!(
                         #[strong]
@@ -573,9 +780,14 @@ pub fn create_achievements_manual_view(
                         achievement_object,
                         #[weak]
                         header_achievements_start,
+                        #[weak]
+                        total_progress_bar,
                         async move {
                             match handle.await {
-                                Ok(Ok(true)) => {
+                                Ok((Ok(true), fresh)) => {
+                                    if let Some(fresh) = fresh {
+                                        achievement_object.update_from(&GAchievementObject::new(fresh));
+                                    }
                                     let unlocked_achievements_count_value =
                                         app_unlocked_achievements_count.get();
 
@@ -591,6 +803,11 @@ pub fn create_achievements_manual_view(
 
                                     app_achievement_count_value
                                         .set_label(&format!("{new_unlocked_count} / {raw_model_len}"));
+                                    update_total_progress(
+                                        &total_progress_bar,
+                                        new_unlocked_count,
+                                        raw_model_len as usize,
+                                    );
 
                                     let lower =
                                         std::cmp::min(new_unlocked_count + 1, raw_model_len as usize);
@@ -604,7 +821,32 @@ pub fn create_achievements_manual_view(
                                         std::cmp::min(spinbox_value, raw_model_len as usize);
                                     header_achievements_spinbox.set_value(spinbox_value as f64);
                                 }
-                                Ok(Ok(false)) | Ok(Err(_)) | Err(_) => {
+                                Ok((Err(SamError::InvalidIdentifier(id)), _)) => {
+                                    log::error!("Achievement has an invalid identifier: {id:?}");
+                                    achievement_object.set_is_achieved(!unlocked);
+                                    switch.set_tooltip_text(Some(&tr!(
+                                        "This achievement has an invalid identifier in the game's schema and cannot be changed"
+                                    )));
+                                }
+                                Ok((Err(SamError::ProtectedAchievement(id)), _)) => {
+                                    log::warn!("Achievement {id:?} is protected");
+                                    achievement_object.set_is_achieved(!unlocked);
+                                    switch.set_tooltip_text(Some(&tr!(
+                                        "This achievement is protected: only the game's servers can change it"
+                                    )));
+                                }
+                                Ok((Err(SamError::VerificationFailed(id)), _)) => {
+                                    log::warn!("Steam ignored the change to achievement {id:?}");
+                                    achievement_object.set_is_achieved(!unlocked);
+                                    show_toast(
+                                        &switch,
+                                        &tr!(
+                                            "Steam ignored the change to {name}",
+                                            name = achievement_object.name()
+                                        ),
+                                    );
+                                }
+                                Ok((Ok(false) | Err(_), _)) | Err(_) => {
                                     log::error!("spawn_blocking task panicked or failed");
                                     achievement_object.set_is_achieved(!unlocked);
                                 }
@@ -629,14 +871,7 @@ pub fn create_achievements_manual_view(
             }
         };
 
-        let switch = match list_item
-            .child()
-            .and_then(|child| child.downcast::<Overlay>().ok())
-            .and_then(|overlay| overlay.last_child())
-            .and_then(|main_box| main_box.last_child())
-            .and_then(|switch_box| switch_box.downcast::<Box>().ok())
-            .and_then(|switch_box| switch_box.last_child())
-            .and_then(|switch| switch.downcast::<Switch>().ok()) {
+        let switch = match row_switch(list_item) {
             Some(sw) => sw,
             _ => {
                 log::error!("achievements_list_factory::connect_unbind: Could not find Switch widget");
@@ -654,9 +889,14 @@ pub fn create_achievements_manual_view(
         }
     });
 
+    let bulk_action_bar = create_bulk_action_bar(app_id, raw_model, filtered_model);
+    bulk_action_bar.pack_end(sort_dropdown);
+
     let vbox = Box::new(Orientation::Vertical, 5);
+    vbox.append(&total_progress_overlay);
     vbox.append(&header);
     vbox.append(&app_achievements_scrolled_window);
+    vbox.append(&bulk_action_bar);
     let app_achievements_frame = Frame::builder()
         .margin_end(15)
         .margin_start(15)
@@ -671,5 +911,658 @@ pub fn create_achievements_manual_view(
         header_achievements_spinbox,
         header_achievements_start,
         cancel_timed_unlock,
+        total_progress_bar,
+    )
+}
+
+/// The widgets of an achievement row that follow its achievement without an expression.
+struct AchievementRow {
+    normal_icon: ShimmerImage,
+    locked_icon: ShimmerImage,
+    switch: Switch,
+    name_label: Label,
+    rarity_badge: Label,
+    via_stat_button: Button,
+    compared_icon: gtk::Image,
+    overlay: Overlay,
+}
+
+impl AchievementRow {
+    /// Shows `achievement` in the row. Icons only load once drawn, so the hidden one waits
+    /// until the achievement is locked or unlocked.
+    fn show(&self, achievement: &GAchievementObject) {
+        for (icon, url) in [
+            (&self.normal_icon, achievement.icon_normal()),
+            (&self.locked_icon, achievement.icon_locked()),
+        ] {
+            if icon.url().as_deref() != Some(url.as_str()) {
+                icon.set_url(url.as_str());
+            }
+        }
+
+        let is_protected = Permission(achievement.permission()).is_protected();
+        self.switch.set_tooltip_text(
+            is_protected
+                .then(|| tr!("Protected achievements can't be set client-side"))
+                .as_deref(),
+        );
+        self.name_label.set_tooltip_text(
+            achievement
+                .unlock_time()
+                .map(|unlock_time| tr!("Unlocked on {unlock_time}", unlock_time = unlock_time))
+                .as_deref(),
+        );
+
+        let rarity = Rarity::from_percent(achievement.global_achieved_percent());
+        self.rarity_badge.set_label(&rarity.label());
+        self.rarity_badge.set_css_classes(&["rarity-badge", rarity.css_class()]);
+        self.rarity_badge.set_visible(achievement.global_achieved_percent_ok());
+
+        let stat = achievement.progress_stat();
+        self.via_stat_button.set_visible(
+            !stat.is_empty() && !achievement.is_achieved() && !is_protected,
+        );
+        self.via_stat_button.set_tooltip_text(Some(&tr!(
+            "Unlock by setting the stat {stat} to {target}",
+            stat = stat,
+            target = format_localized_decimal(achievement.progress_target() as f64, 0)
+        )));
+
+        // The compared profile's state, left of the user's own switch
+        let (icon_name, tooltip) = if achievement.compared_achieved() {
+            ("object-select-symbolic", tr!("Unlocked by the compared profile"))
+        } else {
+            ("window-close-symbolic", tr!("Locked for the compared profile"))
+        };
+        self.compared_icon.set_icon_name(Some(icon_name));
+        self.compared_icon.set_tooltip_text(Some(&tooltip));
+        self.compared_icon.set_visible(achievement.is_compared());
+
+        // Unlocked outside of SamRewritten since the app was last shown, as by playing
+        if achievement.recently_unlocked() {
+            self.overlay.add_css_class("recently-unlocked");
+        } else {
+            self.overlay.remove_css_class("recently-unlocked");
+        }
+    }
+
+    /// Lets go of the icons of a row no longer bound, and stops their loads.
+    fn clear(&self) {
+        self.normal_icon.reset();
+        self.locked_icon.reset();
+    }
+}
+
+/// Details of an achievement row: both icons at full size, the whole description, rarity
+/// and unlock time. Bound to the row's item like the rest of the row.
+fn create_achievement_details(list_item: &ListItem) -> Box {
+    let details_normal_icon = ShimmerImage::with_size(64, 64);
+    let details_locked_icon = ShimmerImage::with_size(64, 64);
+    details_normal_icon.set_tooltip_text(Some(&tr!("Unlocked")));
+    details_locked_icon.set_tooltip_text(Some(&tr!("Locked")));
+    let details_description_label = Label::builder()
+        .wrap(true)
+        .xalign(0.0)
+        .selectable(true)
+        .build();
+    let details_percent_label = Label::builder().xalign(0.0).build();
+    details_percent_label.add_css_class("dim-label");
+    let details_unlock_time_label = Label::builder().xalign(0.0).build();
+    details_unlock_time_label.add_css_class("dim-label");
+    let details_labels = Box::builder()
+        .orientation(Orientation::Vertical)
+        .valign(Align::Center)
+        .hexpand(true)
+        .spacing(4)
+        .build();
+    details_labels.append(&details_description_label);
+    details_labels.append(&details_percent_label);
+    details_labels.append(&details_unlock_time_label);
+    let details_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .margin_start(8)
+        .margin_end(8)
+        .margin_bottom(8)
+        .build();
+    details_box.append(&details_normal_icon);
+    details_box.append(&details_locked_icon);
+    details_box.append(&details_labels);
+
+    let item_expr = |property: &str| {
+        list_item
+            .property_expression("item")
+            .chain_property::<GAchievementObject>(property)
+    };
+    item_expr("description").bind(&details_description_label, "label", Widget::NONE);
+    item_expr("icon-normal").bind(&details_normal_icon, "url", Widget::NONE);
+    item_expr("icon-locked").bind(&details_locked_icon, "url", Widget::NONE);
+    item_expr("global-achieved-percent-ok").bind(&details_percent_label, "visible", Widget::NONE);
+    let details_unlock_time_closure = glib::RustClosure::new(|values: &[glib::Value]| {
+        let unlock_time = values
+            .get(1)
+            .and_then(|val| val.get::<Option<String>>().ok())
+            .flatten();
+        let label = match unlock_time {
+            Some(unlock_time) => tr!("Unlocked on {unlock_time}", unlock_time = unlock_time),
+            None => tr!("Not unlocked"),
+        };
+        Some(label.to_value())
+    });
+    ClosureExpression::new::<String>(&[item_expr("unlock-time")], details_unlock_time_closure)
+        .bind(&details_unlock_time_label, "label", Widget::NONE);
+    let details_percent_closure = glib::RustClosure::new(|values: &[glib::Value]| {
+        let percent = values.get(1).and_then(|val| val.get::<f32>().ok()).unwrap_or(0.0);
+        Some(
+            tr!(
+                "Unlocked by {percent}% of players",
+                percent = format_localized_decimal(percent as f64, 1)
+            )
+            .to_value(),
+        )
+    });
+    ClosureExpression::new::<String>(
+        &[item_expr("global-achieved-percent")],
+        details_percent_closure,
     )
+    .bind(&details_percent_label, "label", Widget::NONE);
+    details_box
+}
+
+/// The switch of a row made by `setup_achievement_manual_list_item`, last in the row's
+/// overlaid box, itself above the details.
+fn row_switch(list_item: &ListItem) -> Option<Switch> {
+    list_item
+        .child()
+        .and_then(|row_box| row_box.first_child())
+        .and_then(|overlay| overlay.downcast::<Overlay>().ok())
+        .and_then(|overlay| overlay.last_child())
+        .and_then(|main_box| main_box.last_child())
+        .and_then(|switch_box| switch_box.downcast::<Box>().ok())
+        .and_then(|switch_box| switch_box.last_child())
+        .and_then(|switch| switch.downcast::<Switch>().ok())
+}
+
+/// Create the bottom bar that applies a lock or unlock to the ticked achievements.
+fn create_bulk_action_bar(
+    app_id: &Rc<Cell<Option<u32>>>,
+    raw_model: &ListStore,
+    filtered_model: &NoSelection,
+) -> ActionBar {
+    let selected_label = Label::new(Some(&tr!("No achievement selected")));
+    let select_visible_button = Button::builder().label(tr!("Select all visible")).build();
+    let clear_selection_button = Button::builder()
+        .label(tr!("Clear selection"))
+        .sensitive(false)
+        .build();
+    let lock_button = Button::builder()
+        .label(tr!("Lock selected"))
+        .sensitive(false)
+        .build();
+    let unlock_button = Button::builder()
+        .label(tr!("Unlock selected"))
+        .sensitive(false)
+        .build();
+    unlock_button.add_css_class("suggested-action");
+
+    let action_bar = ActionBar::new();
+    action_bar.pack_start(&select_visible_button);
+    action_bar.pack_start(&clear_selection_button);
+    action_bar.set_center_widget(Some(&selected_label));
+    action_bar.pack_end(&unlock_button);
+    action_bar.pack_end(&lock_button);
+
+    let update_selection_state: Rc<dyn Fn()> = Rc::new(clone!(
+        #[weak]
+        raw_model,
+        #[weak]
+        selected_label,
+        #[weak]
+        clear_selection_button,
+        #[weak]
+        lock_button,
+        #[weak]
+        unlock_button,
+        move || {
+            let selected_count = raw_model
+                .iter::<GAchievementObject>()
+                .filter_map(Result::ok)
+                .filter(|achievement| achievement.selected())
+                .count();
+            match selected_count {
+                0 => selected_label.set_label(&tr!("No achievement selected")),
+                n => selected_label.set_label(&trn!(
+                    "{n} achievement selected",
+                    "{n} achievements selected",
+                    n,
+                    n = n
+                )),
+            }
+            clear_selection_button.set_sensitive(selected_count > 0);
+            lock_button.set_sensitive(selected_count > 0);
+            unlock_button.set_sensitive(selected_count > 0);
+        }
+    ));
+
+    // A refresh adds new objects and moves existing ones, which shows them as added again.
+    // Each object is watched for selection changes once, and no longer once it left the list.
+    let watched: Rc<RefCell<HashMap<GAchievementObject, SignalHandlerId>>> = Rc::default();
+    let prune_pending = Rc::new(Cell::new(false));
+    raw_model.connect_items_changed(clone!(
+        #[strong]
+        update_selection_state,
+        move |model, position, removed, added| {
+            // Removals come one by one during a refresh, the objects left are checked after it
+            if removed > 0 && !prune_pending.replace(true) {
+                glib::idle_add_local_once(clone!(
+                    #[weak]
+                    model,
+                    #[strong]
+                    watched,
+                    #[strong]
+                    prune_pending,
+                    move || {
+                        prune_pending.set(false);
+                        let current: HashSet<GAchievementObject> =
+                            model.iter().filter_map(Result::ok).collect();
+                        let gone = watched
+                            .borrow_mut()
+                            .extract_if(|achievement, _| !current.contains(achievement))
+                            .collect::<Vec<_>>();
+                        for (achievement, handler) in gone {
+                            achievement.disconnect(handler);
+                        }
+                    }
+                ));
+            }
+
+            let mut watched = watched.borrow_mut();
+            for index in position..position + added {
+                if let Some(achievement) = model
+                    .item(index)
+                    .and_then(|item| item.downcast::<GAchievementObject>().ok())
+                    && !watched.contains_key(&achievement)
+                {
+                    let update_selection_state = update_selection_state.clone();
+                    let handler =
+                        achievement.connect_selected_notify(move |_| update_selection_state());
+                    watched.insert(achievement, handler);
+                }
+            }
+            update_selection_state();
+        }
+    ));
+
+    select_visible_button.connect_clicked(clone!(
+        #[weak]
+        filtered_model,
+        move |_| {
+            for index in 0..filtered_model.n_items() {
+                if let Some(achievement) = filtered_model
+                    .item(index)
+                    .and_then(|item| item.downcast::<GAchievementObject>().ok())
+                {
+                    achievement.set_selected(true);
+                }
+            }
+        }
+    ));
+
+    clear_selection_button.connect_clicked(clone!(
+        #[weak]
+        raw_model,
+        move |_| {
+            for achievement in raw_model.iter::<GAchievementObject>().filter_map(Result::ok) {
+                achievement.set_selected(false);
+            }
+        }
+    ));
+
+    for (button, unlocked) in [(&lock_button, false), (&unlock_button, true)] {
+        button.connect_clicked(clone!(
+            #[strong]
+            app_id,
+            #[weak]
+            raw_model,
+            #[weak]
+            action_bar,
+            move |_| {
+                let Some(app_id) = app_id.get() else {
+                    log::error!("No App ID for bulk achievement update");
+                    return;
+                };
+                let achievement_ids: Vec<String> = raw_model
+                    .iter::<GAchievementObject>()
+                    .filter_map(Result::ok)
+                    .filter(|achievement| {
+                        achievement.selected()
+                            && !achievement.is_protected()
+                            && achievement.is_achieved() != unlocked
+                    })
+                    .map(|achievement| achievement.id())
+                    .collect();
+
+                if achievement_ids.is_empty() {
+                    dev_println!("[CLIENT] Selected achievements already in requested state");
+                    for achievement in raw_model.iter::<GAchievementObject>().filter_map(Result::ok) {
+                        achievement.set_selected(false);
+                    }
+                    return;
+                }
+
+                action_bar.set_sensitive(false);
+                let count = achievement_ids.len();
+                let handle = spawn_blocking(move || {
+                    SetAchievements {
+                        app_id,
+                        achievement_ids,
+                        unlocked,
+                    }
+                    .request()
+                });
+                MainContext::default().spawn_local(clone!(
+                    #[weak]
+                    action_bar,
+                    #[weak]
+                    raw_model,
+                    async move {
+                        report_batch_results(&action_bar, &raw_model, unlocked, count, handle.await);
+                        action_bar.set_sensitive(true);
+                        if let Err(e) = action_bar.activate_action("win.refresh_achievements_list", None) {
+                            log::error!("Failed to activate refresh_achievements_list: {e}");
+                        }
+                    }
+                ));
+            }
+        ));
+    }
+
+    action_bar
+}
+
+/// Tells how many achievements of a batch of `count` changed, and lists the ones that
+/// could not be.
+fn report_batch_results(
+    widget: &impl IsA<Widget>,
+    raw_model: &ListStore,
+    unlocked: bool,
+    count: usize,
+    result: Result<Result<AchievementResults, SamError>, std::boxed::Box<dyn std::any::Any + Send>>,
+) {
+    let results = match result {
+        Ok(Ok(results)) => results,
+        Ok(Err(e)) => return eprintln!("[CLIENT] Failed to set achievements: {e}"),
+        Err(e) => return log::error!("spawn_blocking task panicked: {:?}", e),
+    };
+    let failures: Vec<(String, SamError)> = results
+        .into_iter()
+        .filter_map(|(id, result)| Some((id, result.err()?)))
+        .collect();
+    let count = count.saturating_sub(failures.len());
+    if count > 0 {
+        let message = if unlocked {
+            trn!(
+                "Unlocked {count} achievement",
+                "Unlocked {count} achievements",
+                count,
+                count = count
+            )
+        } else {
+            trn!(
+                "Locked {count} achievement",
+                "Locked {count} achievements",
+                count,
+                count = count
+            )
+        };
+        show_toast(widget, &message);
+    }
+    if !failures.is_empty() {
+        eprintln!("[CLIENT] {} achievements could not be set", failures.len());
+        show_batch_failures(widget, raw_model, unlocked, &failures);
+    }
+}
+
+/// Asks before locking every unlocked achievement of the app, then locks them in one batch.
+/// Unlike resetting everything, the stats keep their values.
+pub fn confirm_lock_all_achievements(parent: &ApplicationWindow, app_id: u32, raw_model: &ListStore) {
+    let achievement_ids: Vec<String> = raw_model
+        .iter::<GAchievementObject>()
+        .filter_map(Result::ok)
+        .filter(|achievement| achievement.is_achieved() && !achievement.is_protected())
+        .map(|achievement| achievement.id())
+        .collect();
+    if achievement_ids.is_empty() {
+        show_toast(parent, &tr!("No unlocked achievement can be locked"));
+        return;
+    }
+
+    let window = gtk::Window::builder()
+        .title(tr!("Lock all achievements"))
+        .transient_for(parent)
+        .modal(true)
+        .resizable(false)
+        .default_width(360)
+        .build();
+    let count = achievement_ids.len();
+    let message_label = Label::builder()
+        .label(trn!(
+            "{count} unlocked achievement will be locked on your Steam account. Stats keep their values.",
+            "{count} unlocked achievements will be locked on your Steam account. Stats keep their values.",
+            count,
+            count = count
+        ))
+        .halign(Align::Start)
+        .wrap(true)
+        .build();
+    let cancel_button = Button::builder().label(tr!("Cancel")).build();
+    let lock_button = Button::builder()
+        .label(tr!("Lock all"))
+        .css_classes(["destructive-action"])
+        .build();
+    let button_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .halign(Align::End)
+        .build();
+    button_box.append(&cancel_button);
+    button_box.append(&lock_button);
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+    content.append(&message_label);
+    content.append(&button_box);
+    window.set_child(Some(&content));
+
+    cancel_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        move |_| window.close()
+    ));
+    lock_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        parent,
+        #[weak]
+        raw_model,
+        move |_| {
+            window.close();
+            let achievement_ids = achievement_ids.clone();
+            let handle = spawn_blocking(move || {
+                SetAchievements {
+                    app_id,
+                    achievement_ids,
+                    unlocked: false,
+                }
+                .request()
+            });
+            MainContext::default().spawn_local(clone!(
+                #[weak]
+                parent,
+                #[weak]
+                raw_model,
+                async move {
+                    report_batch_results(&parent, &raw_model, false, count, handle.await);
+                    if let Err(e) = WidgetExt::activate_action(&parent, "win.refresh_achievements_list", None) {
+                        log::error!("Failed to activate refresh_achievements_list: {e}");
+                    }
+                }
+            ));
+        }
+    ));
+    window.present();
+}
+
+/// Why an achievement of a batch could not be changed, in the user's words.
+fn describe_batch_failure(error: &SamError) -> String {
+    match error {
+        SamError::ProtectedAchievement(_) => {
+            tr!("Protected, only the game's servers can change it")
+        }
+        SamError::InvalidIdentifier(_) => tr!("Its identifier can't be passed to Steam"),
+        SamError::StoreFailed => tr!("Changed, but Steam did not save it"),
+        SamError::VerificationFailed(_) => tr!("Saved, but Steam ignored the change"),
+        _ => tr!("Steam refused the change"),
+    }
+}
+
+/// Lists the achievements of a batch that could not be changed, and why.
+fn show_batch_failures(
+    widget: &impl IsA<Widget>,
+    raw_model: &ListStore,
+    unlocked: bool,
+    failures: &[(String, SamError)],
+) {
+    let names: HashMap<String, String> = raw_model
+        .iter::<GAchievementObject>()
+        .filter_map(Result::ok)
+        .map(|achievement| (achievement.id(), achievement.name()))
+        .collect();
+
+    let title = if unlocked {
+        tr!("Some achievements were not unlocked")
+    } else {
+        tr!("Some achievements were not locked")
+    };
+    let window = gtk::Window::builder()
+        .title(&title)
+        .modal(true)
+        .default_width(420)
+        .build();
+    if let Some(parent) = widget.root().and_downcast::<gtk::Window>() {
+        window.set_transient_for(Some(&parent));
+    }
+
+    let list = ListBox::builder()
+        .selection_mode(SelectionMode::None)
+        .css_classes(["boxed-list"])
+        .build();
+    for (id, error) in failures {
+        let name = names.get(id).cloned().unwrap_or_else(|| id.clone());
+        let row = Box::builder()
+            .orientation(Orientation::Vertical)
+            .margin_top(6)
+            .margin_bottom(6)
+            .margin_start(8)
+            .margin_end(8)
+            .build();
+        row.append(
+            &Label::builder()
+                .label(&name)
+                .halign(Align::Start)
+                .ellipsize(EllipsizeMode::End)
+                .build(),
+        );
+        row.append(
+            &Label::builder()
+                .label(describe_batch_failure(error))
+                .halign(Align::Start)
+                .wrap(true)
+                .css_classes(["dim-label"])
+                .build(),
+        );
+        list.append(&row);
+    }
+
+    let close_button = Button::builder()
+        .label(tr!("Close"))
+        .halign(Align::End)
+        .build();
+    close_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        move |_| window.close()
+    ));
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+    content.append(
+        &ScrolledWindow::builder()
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .propagate_natural_height(true)
+            .max_content_height(400)
+            .child(&list)
+            .build(),
+    );
+    content.append(&close_button);
+    window.set_child(Some(&content));
+    window.present();
+}
+
+/// Raises the stat an achievement follows so that Steam unlocks it, then reloads the lists
+/// so the achievement and the stat show their new state.
+fn unlock_via_stat(button: &Button, app_id: u32, achievement: &GAchievementObject) {
+    button.set_sensitive(false);
+    let achievement_id = achievement.id();
+    let handle = spawn_blocking(move || {
+        UnlockAchievementViaStat {
+            app_id,
+            achievement_id,
+        }
+        .request()
+    });
+    MainContext::default().spawn_local(clone!(
+        #[weak]
+        button,
+        async move {
+            button.set_sensitive(true);
+            match handle.await {
+                Ok(Ok(true)) => {}
+                Ok(Ok(false)) => {
+                    eprintln!("[CLIENT] Steam did not unlock the achievement after its stat was set");
+                    show_toast(&button, &tr!("Steam did not unlock it after its stat was raised"));
+                }
+                Ok(Err(e)) => {
+                    eprintln!("[CLIENT] Failed to unlock the achievement via its stat: {e}");
+                    let reason = match e {
+                        SamError::ProtectedStat(_) => {
+                            tr!("Its stat is protected, only the game's servers can change it")
+                        }
+                        SamError::StatChangeTooLarge(_) => {
+                            tr!("Steam only lets its stat change a little at a time")
+                        }
+                        _ => tr!("Steam refused the change"),
+                    };
+                    show_toast(&button, &reason);
+                }
+                Err(_) => log::error!("[CLIENT] Threading task failed"),
+            }
+            if let Err(e) = button.activate_action("win.refresh_achievements_list", None) {
+                log::error!("Failed to activate refresh_achievements_list: {e}");
+            }
+        }
+    ));
 }
@@ -0,0 +1,284 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Searches an achievement by name across the whole library. The orchestrator reads the
+// schemas and stats files Steam keeps on disk, so no game is launched, and the apps are
+// searched a chunk at a time so progress shows and a new search or closing the window
+// stops the current one quickly.
+
+use crate::backend::stat_definitions::AchievementMatch;
+use crate::frontend::{
+    request::{Request, SearchAchievements},
+    steam_app::GSteamAppObject,
+};
+use crate::{tr, trn};
+use gtk::{
+    Align, ApplicationWindow, Box, Button, Label, Orientation, PolicyType, ProgressBar,
+    ScrolledWindow, SearchEntry, Separator, Window,
+    gio::{ListStore, spawn_blocking},
+    glib::{self, MainContext, clone},
+    pango::EllipsizeMode,
+    prelude::*,
+};
+use std::{cell::Cell, collections::HashMap, rc::Rc};
+
+/// Apps searched per request, so progress shows and cancelling takes effect quickly.
+const APPS_PER_REQUEST: usize = 50;
+
+/// One line per achievement found, under the name of its game.
+fn append_match(results: &Box, achievement: &AchievementMatch) {
+    let name_label = Label::builder()
+        .label(&achievement.name)
+        .halign(Align::Start)
+        .ellipsize(EllipsizeMode::End)
+        .build();
+    let description_label = Label::builder()
+        .label(&achievement.description)
+        .halign(Align::Start)
+        .ellipsize(EllipsizeMode::End)
+        .css_classes(["dim-label"])
+        .visible(!achievement.description.is_empty())
+        .build();
+    let text_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .hexpand(true)
+        .build();
+    text_box.append(&name_label);
+    text_box.append(&description_label);
+
+    let state_label = Label::builder().valign(Align::Center).build();
+    match achievement.unlocked {
+        Some(true) => {
+            state_label.set_label(&tr!("Unlocked"));
+            state_label.add_css_class("success");
+        }
+        Some(false) => state_label.set_label(&tr!("Locked")),
+        None => {
+            state_label.set_label(&tr!("Unknown"));
+            state_label.add_css_class("dim-label");
+        }
+    }
+
+    let row = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(12)
+        .margin_start(12)
+        .build();
+    row.append(&text_box);
+    row.append(&state_label);
+    results.append(&row);
+}
+
+/// Opens a window to search an achievement by name across the apps of the library.
+pub fn show_achievement_search(parent: &ApplicationWindow, list_store: &ListStore) {
+    let app_names: HashMap<u32, String> = list_store
+        .iter::<GSteamAppObject>()
+        .filter_map(Result::ok)
+        .map(|app| (app.app_id(), app.app_name()))
+        .collect();
+    let mut app_ids: Vec<u32> = app_names.keys().copied().collect();
+    app_ids.sort_unstable();
+    let app_names = Rc::new(app_names);
+    let app_ids = Rc::new(app_ids);
+
+    let window = Window::builder()
+        .title(tr!("Search achievements"))
+        .transient_for(parent)
+        .default_width(480)
+        .default_height(560)
+        .build();
+
+    let search_entry = SearchEntry::builder()
+        .placeholder_text(tr!("Achievement name"))
+        .hexpand(true)
+        .build();
+    let search_button = Button::builder().label(tr!("Search")).build();
+    let search_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .build();
+    search_box.append(&search_entry);
+    search_box.append(&search_button);
+
+    let status_label = Label::builder()
+        .label(trn!(
+            "Searches the achievements of the {count} app of the library.",
+            "Searches the achievements of the {count} apps of the library.",
+            app_ids.len(),
+            count = app_ids.len()
+        ))
+        .halign(Align::Start)
+        .wrap(true)
+        .build();
+    let progress_bar = ProgressBar::builder().visible(false).build();
+    let results = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(6)
+        .build();
+    let scrolled_window = ScrolledWindow::builder()
+        .hscrollbar_policy(PolicyType::Never)
+        .vexpand(true)
+        .child(&results)
+        .build();
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+    content.append(&search_box);
+    content.append(&status_label);
+    content.append(&progress_bar);
+    content.append(&scrolled_window);
+    window.set_child(Some(&content));
+
+    // Every search gets a number. A new search or closing the window bumps it, which stops
+    // the running one before its next request.
+    let generation = Rc::new(Cell::new(0u32));
+    window.connect_close_request(clone!(
+        #[strong]
+        generation,
+        move |_| {
+            generation.set(generation.get() + 1);
+            glib::Propagation::Proceed
+        }
+    ));
+
+    let start_search = clone!(
+        #[weak]
+        search_entry,
+        #[weak]
+        status_label,
+        #[weak]
+        progress_bar,
+        #[weak]
+        results,
+        #[strong]
+        generation,
+        move || {
+            let query = search_entry.text().trim().to_owned();
+            generation.set(generation.get() + 1);
+            let current = generation.get();
+            while let Some(child) = results.first_child() {
+                results.remove(&child);
+            }
+            status_label.remove_css_class("error");
+            if query.is_empty() {
+                progress_bar.set_visible(false);
+                status_label.set_label(&tr!("Type the name of an achievement to search."));
+                return;
+            }
+            status_label.set_label(&tr!("Searching..."));
+            progress_bar.set_fraction(0.0);
+            progress_bar.set_visible(true);
+
+            MainContext::default().spawn_local(clone!(
+                #[weak]
+                status_label,
+                #[weak]
+                progress_bar,
+                #[weak]
+                results,
+                #[strong]
+                generation,
+                #[strong]
+                app_ids,
+                #[strong]
+                app_names,
+                async move {
+                    let mut found = 0usize;
+                    let mut last_app_id = None;
+                    for (index, chunk) in app_ids.chunks(APPS_PER_REQUEST).enumerate() {
+                        let request = SearchAchievements {
+                            app_ids: chunk.to_vec(),
+                            query: query.clone(),
+                        };
+                        let handle = spawn_blocking(move || request.request());
+                        let matches = match handle.await {
+                            Ok(Ok(matches)) => matches,
+                            Ok(Err(e)) => {
+                                eprintln!("[CLIENT] Failed to search achievements: {e}");
+                                if generation.get() == current {
+                                    progress_bar.set_visible(false);
+                                    status_label.add_css_class("error");
+                                    status_label
+                                        .set_label(&tr!("Could not search the achievements."));
+                                }
+                                return;
+                            }
+                            Err(_) => {
+                                log::error!("[CLIENT] Threading task failed");
+                                return;
+                            }
+                        };
+                        if generation.get() != current {
+                            return;
+                        }
+
+                        for achievement in &matches {
+                            if last_app_id != Some(achievement.app_id) {
+                                if last_app_id.is_some() {
+                                    results.append(&Separator::new(Orientation::Horizontal));
+                                }
+                                last_app_id = Some(achievement.app_id);
+                                let app_name = app_names
+                                    .get(&achievement.app_id)
+                                    .cloned()
+                                    .unwrap_or_else(|| format!("App {}", achievement.app_id));
+                                let heading = Label::builder()
+                                    .label(&app_name)
+                                    .halign(Align::Start)
+                                    .ellipsize(EllipsizeMode::End)
+                                    .css_classes(["heading"])
+                                    .build();
+                                results.append(&heading);
+                            }
+                            append_match(&results, achievement);
+                        }
+                        found += matches.len();
+                        let done = (index * APPS_PER_REQUEST + chunk.len()) as f64;
+                        progress_bar.set_fraction(done / app_ids.len() as f64);
+                    }
+
+                    progress_bar.set_visible(false);
+                    if found == 0 {
+                        status_label.set_label(&tr!(
+                            "No achievement matches. Games never launched on this machine can't be searched."
+                        ));
+                    } else {
+                        status_label.set_label(&trn!(
+                            "Found {count} achievement.",
+                            "Found {count} achievements.",
+                            found,
+                            count = found
+                        ));
+                    }
+                }
+            ));
+        }
+    );
+    let start_search = Rc::new(start_search);
+
+    search_button.connect_clicked(clone!(
+        #[strong]
+        start_search,
+        move |_| start_search()
+    ));
+    search_entry.connect_activate(move |_| start_search());
+    window.present();
+}
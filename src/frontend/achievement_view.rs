@@ -13,15 +13,18 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::frontend::MainApplication;
 use crate::frontend::achievement::GAchievementObject;
 use crate::frontend::achievement_automatic_view::create_achievements_automatic_view;
 use crate::frontend::achievement_manual_view::create_achievements_manual_view;
+use crate::frontend::custom_progress_bar_widget::CustomProgressBar;
+use crate::tr;
 use gtk::gio::ListStore;
+use gtk::glib;
+use gtk::glib::clone;
 use gtk::prelude::*;
 use gtk::{
-    Adjustment, Button, CustomSorter, FilterListModel, Label, NoSelection, SortListModel,
-    SpinButton, Stack, StackTransitionType, StringFilter, StringFilterMatchMode,
+    Adjustment, Button, CustomSorter, DropDown, FilterListModel, Label, NoSelection,
+    SortListModel, SpinButton, Stack, StackTransitionType, StringFilter, StringFilterMatchMode,
 };
 use std::cell::Cell;
 use std::cmp::Ordering;
@@ -33,7 +36,6 @@ use std::sync::atomic::AtomicBool;
 pub fn create_achievements_view(
     app_id: Rc<Cell<Option<u32>>>,
     app_unlocked_achievements_count: Rc<Cell<usize>>,
-    application: &MainApplication,
     app_achievement_count_value: &Label,
 ) -> (
     Stack,
@@ -43,12 +45,13 @@ pub fn create_achievements_view(
     SpinButton,
     Button,
     Arc<AtomicBool>,
+    CustomProgressBar,
 ) {
     let app_achievements_model = ListStore::new::<GAchievementObject>();
     let app_timed_achievements_model = ListStore::new::<GAchievementObject>();
 
     let app_achievement_string_filter = StringFilter::builder()
-        .expression(&GAchievementObject::this_expression("search-text"))
+        .expression(GAchievementObject::this_expression("search-text"))
         .match_mode(StringFilterMatchMode::Substring)
         .ignore_case(true)
         .build();
@@ -87,11 +90,37 @@ pub fn create_achievements_view(
             }
         }
     });
+    let original_order_sorter = CustomSorter::new(|obj1, obj2| {
+        let (Some(achievement1), Some(achievement2)) = (
+            obj1.downcast_ref::<GAchievementObject>(),
+            obj2.downcast_ref::<GAchievementObject>(),
+        ) else {
+            log::error!("Not a GAchievementObject in original_order_sorter");
+            return Ordering::Equal.into();
+        };
+        achievement1.order().cmp(&achievement2.order()).into()
+    });
     let app_achievement_sort_model = SortListModel::builder()
         .model(&app_achievement_filter_model)
         .sorter(&global_achieved_percent_sorter)
         .build();
 
+    // The entries are indexes into the match below
+    let sort_dropdown =
+        DropDown::from_strings(&[tr!("Rarity").as_str(), tr!("Original order").as_str()]);
+    sort_dropdown.set_tooltip_text(Some(&tr!("Sort achievements")));
+    sort_dropdown.connect_selected_notify(clone!(
+        #[weak]
+        app_achievement_sort_model,
+        move |dropdown| {
+            let sorter = match dropdown.selected() {
+                1 => &original_order_sorter,
+                _ => &global_achieved_percent_sorter,
+            };
+            app_achievement_sort_model.set_sorter(Some(sorter));
+        }
+    ));
+
     let app_achievement_selection_model = NoSelection::new(Option::<ListStore>::None);
     app_achievement_selection_model.set_model(Some(&app_achievement_sort_model));
     let app_timed_achievement_selection_model = NoSelection::new(Option::<ListStore>::None);
@@ -107,6 +136,7 @@ pub fn create_achievements_view(
         achievements_manual_spinbox,
         achievements_manual_start,
         cancel_timed_unlock,
+        total_progress_bar,
     ) = create_achievements_manual_view(
         &app_id,
         &app_unlocked_achievements_count,
@@ -114,11 +144,11 @@ pub fn create_achievements_view(
         &app_achievements_model,
         &app_timed_achievements_model,
         &achievement_views_stack,
-        &app_achievement_count_value,
-        &application,
+        app_achievement_count_value,
+        &sort_dropdown,
     );
     let (achievements_automatic_frame, _achievements_automatic_stop) =
-        create_achievements_automatic_view(&app_timed_achievement_selection_model, &application);
+        create_achievements_automatic_view(&app_timed_achievement_selection_model);
 
     achievement_views_stack.add_named(&achievements_manual_frame, Some("manual"));
     achievement_views_stack.add_named(&achievements_automatic_frame, Some("automatic"));
@@ -131,6 +161,7 @@ pub fn create_achievements_view(
         achievements_manual_spinbox,
         achievements_manual_start,
         cancel_timed_unlock,
+        total_progress_bar,
     )
 }
 
@@ -138,11 +169,21 @@ pub fn create_achievements_view(
 pub fn count_unlocked_achievements(model: &ListStore) -> u32 {
     let mut count = 0;
     for ach in model.iter::<GAchievementObject>() {
-        if let Ok(g_achievement) = ach {
-            if g_achievement.is_achieved() {
-                count += 1;
-            }
+        if let Ok(g_achievement) = ach
+            && g_achievement.is_achieved()
+        {
+            count += 1;
         }
     }
     count
 }
+
+/// Update the overall completion bar from the unlocked and total achievement counts.
+pub fn update_total_progress(progress_bar: &CustomProgressBar, unlocked: usize, total: usize) {
+    let percent = if total == 0 {
+        0.0
+    } else {
+        unlocked as f32 * 100.0 / total as f32
+    };
+    progress_bar.set_value(percent);
+}
@@ -20,47 +20,259 @@ const STACK_EMPTY: &str = "empty";
 
 // --- Imports ---
 use crate::{
-    backend::app_lister::{AppModel, AppModelType},
+    backend::{
+        app_lister::{AppModel, AppModelType},
+        stat_definitions::AchievementSummary,
+    },
     frontend::{
         MainApplication,
         achievement::GAchievementObject,
-        app_list_view_callbacks::switch_from_app_list_to_app,
+        achievement_manual_view::confirm_lock_all_achievements,
+        achievement_search::show_achievement_search,
+        achievement_view::update_total_progress,
+        app_list_view_callbacks::{
+            clear_managed_apps, release_managed_app, switch_from_app_list_to_app,
+        },
         app_view::create_app_view,
-        application_actions::{set_app_action_enabled, setup_app_actions},
-        request::{GetAchievements, GetOwnedAppList, GetStats, Request, ResetStats, StopApp},
+        application_actions::{set_app_action_enabled, setup_app_actions, setup_search_actions},
+        batch_operations::create_selection_bar,
+        library_export::export_library_completion,
+        profile_compare::show_compare_dialog,
+        recent_apps::{create_recent_apps_bar, refresh_recent_apps_bar},
+        request::{
+            CheckSchema, GetAchievementSummaries, GetAchievements, GetOwnedAppList, GetStats, ListRunningApps,
+            Reconnect, Request, ResetStats, StopApp, StopApps, cancel_app_list,
+        },
         shimmer_image::ShimmerImage,
         stat::GStatObject,
         steam_app::GSteamAppObject,
+        steamclient_picker::create_steamclient_not_found_page,
+        toast::{create_toast_overlay, show_toast},
         ui_components::{
             create_about_dialog, create_context_menu_button,
-            set_context_popover_to_app_list_context,
+            set_context_popover_to_app_list_context, sync_list_store,
         },
     },
-    utils::{arguments::parse_gui_arguments, ipc_types::SamError},
+    tr, trn,
+    utils::{
+        arguments::{GuiArguments, parse_gui_arguments},
+        settings::{load_settings, save_settings},
+        ipc_types::SamError,
+    },
 };
 use gtk::{
-    Align, ApplicationWindow, Box, Button, FilterListModel, HeaderBar, IconSize, Image, Label,
-    ListItem, ListView, NoSelection, Orientation, PolicyType, ScrolledWindow, SearchEntry,
-    SignalListItemFactory, Spinner, Stack, StackTransitionType, StringFilter,
-    StringFilterMatchMode, Widget,
+    Align, ApplicationWindow, Box, Button, CheckButton, CustomFilter, CustomSorter, EveryFilter,
+    EventControllerKey, FilterChange, FilterListModel,
+    HeaderBar, INVALID_LIST_POSITION, IconSize, Image, Label, ListHeader, ListItem,
+    ListScrollFlags, ListView, Orientation, PolicyType, ScrolledWindow, SearchEntry,
+    SignalListItemFactory, SingleSelection, SortListModel, SorterChange, Spinner, Stack,
+    StackTransitionType, StringFilter, StringFilterMatchMode, ToggleButton, Widget, gdk,
     gio::{ApplicationCommandLine, ListStore, SimpleAction, spawn_blocking},
     glib::{self, ExitCode, MainContext, clone},
     prelude::*,
 };
 use gtk::glib::SignalHandlerId;
 use glib::translate::FromGlib;
-use log;
-use std::{cell::Cell, os::raw::c_ulong, process::Command, rc::Rc};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    os::raw::c_ulong,
+    process::Command,
+    rc::Rc,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+#[cfg(feature = "installed-apps")]
+use crate::frontend::request::GetInstalledAppList;
+
+/// Fetch the owned apps. With the `installed-apps` feature, installed apps missing from
+/// games.xml are appended when Steam grants the whitelisted app list interface.
+fn request_app_list() -> Result<Vec<AppModel>, SamError> {
+    #[allow(unused_mut)]
+    let mut apps = GetOwnedAppList.request()?;
+
+    #[cfg(feature = "installed-apps")]
+    match GetInstalledAppList.request() {
+        Ok(installed_apps) => {
+            for app in installed_apps {
+                if !apps.iter().any(|known| known.app_id == app.app_id) {
+                    apps.push(app);
+                }
+            }
+        }
+        Err(e) => eprintln!("[CLIENT] Could not list installed apps: {e}"),
+    }
+
+    Ok(apps)
+}
+
+/// Opens `uri` with the program the system has for it, such as Steam or the browser.
+fn open_uri(uri: &str) {
+    #[cfg(unix)]
+    let spawned = Command::new("xdg-open").arg(uri).spawn();
+    #[cfg(windows)]
+    let spawned = Command::new("cmd").arg("/C").arg("start").arg(uri).spawn();
+
+    match spawned {
+        Ok(mut child) => {
+            if let Err(e) = child.wait() {
+                log::error!("Failed to wait on child process: {e}");
+            }
+        }
+        Err(e) => {
+            log::error!("Could not start child process: {e}");
+        }
+    }
+}
 
 // --- Main UI Creation Function ---
+/// Flags the apps some window is managing, so their rows show it.
+fn mark_running_apps(list_store: &ListStore) {
+    let running = spawn_blocking(|| ListRunningApps.request());
+    MainContext::default().spawn_local(clone!(
+        #[weak]
+        list_store,
+        async move {
+            let running_app_ids: HashSet<u32> = match running.await {
+                Ok(Ok(app_ids)) => app_ids.into_iter().collect(),
+                Ok(Err(e)) => {
+                    eprintln!("[CLIENT] Failed to list the running apps: {e}");
+                    return;
+                }
+                Err(_) => {
+                    eprintln!("[CLIENT] Threading task failed");
+                    return;
+                }
+            };
+            for app in list_store.iter::<GSteamAppObject>().filter_map(Result::ok) {
+                app.set_is_running(running_app_ids.contains(&app.app_id()));
+            }
+        }
+    ));
+}
+
 pub fn create_main_ui(
     application: &MainApplication,
     cmd_line: &ApplicationCommandLine,
 ) -> ExitCode {
     let gui_args = parse_gui_arguments(cmd_line);
+    create_main_window(application, gui_args);
+    ExitCode::SUCCESS
+}
+
+/// Short progress shown on a library row: unlocked achievements out of the total, or the
+/// number of stats of apps without achievements.
+fn format_completion(summary: &AchievementSummary) -> String {
+    if summary.achievements > 0 {
+        let unlocked = summary
+            .unlocked
+            .map_or_else(|| "?".to_owned(), |unlocked| unlocked.to_string());
+        format!("{unlocked}/{}", summary.achievements)
+    } else if summary.stats > 0 {
+        trn!(
+            "{count} stat",
+            "{count} stats",
+            summary.stats,
+            count = summary.stats
+        )
+    } else {
+        String::new()
+    }
+}
+
+/// Ids of the unlocked achievements in `model`.
+fn unlocked_achievement_ids(model: &ListStore) -> HashSet<String> {
+    model
+        .iter::<GAchievementObject>()
+        .filter_map(Result::ok)
+        .filter(|achievement| achievement.is_achieved())
+        .map(|achievement| achievement.id())
+        .collect()
+}
+
+/// Warns when the schema of the app lists other achievements than Steam does, as when it is
+/// outdated and some achievements can't be managed.
+fn warn_outdated_schema(app_stack: &Stack, app_id: u32) {
+    let handle = spawn_blocking(move || CheckSchema { app_id }.request());
+    MainContext::default().spawn_local(clone!(
+        #[weak]
+        app_stack,
+        async move {
+            let Ok(Ok(check)) = handle.await else {
+                return;
+            };
+            let count = check.mismatch_count();
+            if count > 0 {
+                show_toast(
+                    &app_stack,
+                    &trn!(
+                        "This game's schema is outdated, {count} achievement differs from Steam's list",
+                        "This game's schema is outdated, {count} achievements differ from Steam's list",
+                        count,
+                        count = count
+                    ),
+                );
+            }
+        }
+    ));
+}
+
+/// Looks up the achievements and stats of every app in the background, then shows their
+/// completion on the rows and refilters the library to hide the empty ones.
+fn load_app_summaries(list_store: &ListStore, empty_filter: &CustomFilter) {
+    let apps: Vec<GSteamAppObject> = list_store
+        .iter::<GSteamAppObject>()
+        .filter_map(Result::ok)
+        .collect();
+    let app_ids = apps.iter().map(GSteamAppObject::app_id).collect();
+    let summaries = spawn_blocking(move || GetAchievementSummaries { app_ids }.request());
+    MainContext::default().spawn_local(clone!(
+        #[weak]
+        empty_filter,
+        async move {
+            let summaries = match summaries.await {
+                Ok(Ok(summaries)) => summaries,
+                Ok(Err(e)) => {
+                    eprintln!("[CLIENT] Failed to get achievement summaries: {e}");
+                    return;
+                }
+                Err(_) => {
+                    eprintln!("[CLIENT] Threading task failed");
+                    return;
+                }
+            };
+            let summaries: HashMap<u32, AchievementSummary> = summaries
+                .into_iter()
+                .filter_map(|(app_id, summary)| Some((app_id, summary?)))
+                .collect();
+            for app in &apps {
+                let summary = summaries.get(&app.app_id());
+                app.set_is_empty(summary.is_some_and(AchievementSummary::is_empty));
+                app.set_completion(summary.map(format_completion).unwrap_or_default());
+            }
+            empty_filter.changed(FilterChange::Different);
+        }
+    ));
+}
+
+/// Build and show one main window. Every window talks to the same orchestrator.
+fn create_main_window(application: &MainApplication, gui_args: GuiArguments) {
+    // Offline mode lasts for the whole process, other windows don't turn it off
+    let settings = load_settings();
+    if gui_args.offline || settings.offline {
+        ShimmerImage::set_offline(true);
+    }
+    ShimmerImage::set_reduced_motion(settings.reduce_motion);
     let launch_app_by_id_visible = Rc::new(Cell::new(false));
     let app_id = Rc::new(Cell::new(Option::<u32>::None));
     let app_unlocked_achievements_count = Rc::new(Cell::new(0usize));
+    // Achievements unlocked when each app was last shown in this session, to point out the
+    // ones unlocked since
+    let seen_unlocked = Rc::new(RefCell::new(HashMap::<u32, HashSet<String>>::new()));
 
     // Create the UI components for the app view
     let (
@@ -75,7 +287,7 @@ pub fn create_main_ui(
         app_developer_value,
         app_metacritic_value,
         app_metacritic_box,
-        _app_sidebar,
+        show_sidebar_action,
         app_achievements_model,
         app_achievement_string_filter,
         app_stat_model,
@@ -86,19 +298,29 @@ pub fn create_main_ui(
         achievements_manual_start,
         cancel_timed_unlock,
         app_achievements_stack,
-    ) = create_app_view(
-        app_id.clone(),
-        app_unlocked_achievements_count.clone(),
-        application,
-    );
+        achievements_total_progress,
+        app_loading_label,
+    ) = create_app_view(app_id.clone(), app_unlocked_achievements_count.clone());
 
     // --- UI Components ---
     // Loading box
     let list_spinner = Spinner::builder().margin_end(5).spinning(true).build();
-    let list_spinner_label = Label::builder().label("Loading...").build();
+    let list_spinner_label = Label::builder().label(tr!("Loading...")).build();
     let list_spinner_box = Box::builder().halign(Align::Center).build();
     list_spinner_box.append(&list_spinner);
     list_spinner_box.append(&list_spinner_label);
+    let list_cancel_button = Button::builder()
+        .label(tr!("Cancel"))
+        .halign(Align::Center)
+        .build();
+    let list_loading_box = Box::builder()
+        .spacing(20)
+        .valign(Align::Center)
+        .halign(Align::Center)
+        .orientation(Orientation::Vertical)
+        .build();
+    list_loading_box.append(&list_spinner_box);
+    list_loading_box.append(&list_cancel_button);
 
     // Empty search result box
     let app_list_no_result_icon = {
@@ -107,6 +329,16 @@ pub fn create_main_ui(
         icon
     };
     let app_list_no_result_label = Label::builder().build();
+    let app_list_retry_button = Button::builder()
+        .label(tr!("Retry"))
+        .halign(Align::Center)
+        .visible(false)
+        .build();
+    app_list_retry_button.connect_clicked(|button| {
+        if let Err(e) = button.activate_action("win.refresh_app_list", None) {
+            log::error!("Failed to activate refresh_app_list: {e}");
+        }
+    });
     let app_list_no_result_box = Box::builder()
         .spacing(20)
         .valign(Align::Center)
@@ -115,20 +347,31 @@ pub fn create_main_ui(
         .build();
     app_list_no_result_box.append(&app_list_no_result_icon);
     app_list_no_result_box.append(&app_list_no_result_label);
+    app_list_no_result_box.append(&app_list_retry_button);
 
     // Header bar
     let header_bar = HeaderBar::builder().show_title_buttons(true).build();
     let search_entry = SearchEntry::builder()
-        .placeholder_text("App name or App Id")
+        .placeholder_text(tr!("App name or App Id"))
         .build();
     let back_button = Button::builder()
         .icon_name("go-previous")
         .sensitive(false)
         .build();
     let (context_menu_button, _, menu_model) = create_context_menu_button();
+    let select_button = ToggleButton::builder()
+        .icon_name("object-select-symbolic")
+        .tooltip_text(tr!("Select apps"))
+        .build();
+    // Shows the icon of the app open, hidden in the library
+    let app_header_icon = ShimmerImage::with_size(24, 24);
+    app_header_icon.set_valign(Align::Center);
+    app_header_icon.set_visible(false);
     header_bar.pack_start(&back_button);
+    header_bar.pack_start(&app_header_icon);
     header_bar.pack_start(&search_entry);
     header_bar.pack_end(&context_menu_button);
+    header_bar.pack_end(&select_button);
 
     let list_scrolled_window = ScrolledWindow::builder()
         .hscrollbar_policy(PolicyType::Never)
@@ -145,40 +388,158 @@ pub fn create_main_ui(
     let list_stack = Stack::builder()
         .transition_type(StackTransitionType::SlideLeftRight)
         .build();
-    list_stack.add_named(&list_spinner_box, Some(STACK_LOADING));
+    list_stack.add_named(&list_loading_box, Some(STACK_LOADING));
     list_stack.add_named(&list_of_apps_or_no_result, Some(STACK_LIST));
     list_stack.add_named(&app_pane, Some("app"));
+    list_stack.set_vexpand(true);
+
+    // Recently managed apps, above the library
+    let (recent_apps_bar, recent_apps_box) = create_recent_apps_bar();
+    let main_box = Box::builder().orientation(Orientation::Vertical).build();
+    main_box.append(&recent_apps_bar);
+    main_box.append(&list_stack);
+
+    // Opens through the auto-open, right away when the library is shown, else once it loaded
+    let auto_open = gui_args.auto_open.clone();
+    let open_recent_app: Rc<dyn Fn(u32)> = Rc::new(clone!(
+        #[weak]
+        list_stack,
+        move |app_id| {
+            auto_open.set(app_id);
+            if list_stack.visible_child_name().as_deref() == Some(STACK_LIST) {
+                list_stack.notify("visible-child");
+            }
+        }
+    ));
 
     // --- App list models ---
     let list_factory = SignalListItemFactory::new();
     let list_store = ListStore::new::<GSteamAppObject>();
     let list_string_filter = StringFilter::builder()
-        .expression(&GSteamAppObject::this_expression("app_name"))
+        .expression(GSteamAppObject::this_expression("app_name"))
         .match_mode(StringFilterMatchMode::Substring)
         .ignore_case(true)
         .build();
+    let hide_empty_apps = Rc::new(Cell::new(load_settings().hide_empty_apps));
+    let list_empty_filter = CustomFilter::new(clone!(
+        #[strong]
+        hide_empty_apps,
+        move |item| {
+            !hide_empty_apps.get()
+                || !item
+                    .downcast_ref::<GSteamAppObject>()
+                    .is_some_and(GSteamAppObject::is_empty)
+        }
+    ));
+    let list_filter = EveryFilter::new();
+    list_filter.append(list_string_filter.clone());
+    list_filter.append(list_empty_filter.clone());
     let list_filter_model = FilterListModel::builder()
         .model(&list_store)
-        .filter(&list_string_filter)
+        .filter(&list_filter)
+        .build();
+    // Favorites first, then by name. Sections split the list into the two groups.
+    let list_sorter = CustomSorter::new(|a, b| {
+        let (Some(a), Some(b)) = (
+            a.downcast_ref::<GSteamAppObject>(),
+            b.downcast_ref::<GSteamAppObject>(),
+        ) else {
+            return gtk::Ordering::Equal;
+        };
+        b.is_favorite()
+            .cmp(&a.is_favorite())
+            .then_with(|| a.cmp_name(b))
+            .into()
+    });
+    let list_section_sorter = CustomSorter::new(|a, b| {
+        let is_favorite = |item: &glib::Object| {
+            item.downcast_ref::<GSteamAppObject>()
+                .is_some_and(GSteamAppObject::is_favorite)
+        };
+        is_favorite(b).cmp(&is_favorite(a)).into()
+    });
+    let list_sort_model = SortListModel::builder()
+        .model(&list_filter_model)
+        .sorter(&list_sorter)
+        .section_sorter(&list_section_sorter)
+        .build();
+    let list_header_factory = SignalListItemFactory::new();
+    list_header_factory.connect_setup(|_, header| {
+        if let Some(header) = header.downcast_ref::<ListHeader>() {
+            let label = Label::builder()
+                .halign(Align::Start)
+                .margin_start(12)
+                .margin_top(8)
+                .margin_bottom(4)
+                .css_classes(["heading"].as_slice())
+                .build();
+            header.set_child(Some(&label));
+        }
+    });
+    list_header_factory.connect_bind(|_, header| {
+        let Some(header) = header.downcast_ref::<ListHeader>() else {
+            return;
+        };
+        let Some(label) = header.child().and_downcast::<Label>() else {
+            return;
+        };
+        let is_favorite = header
+            .item()
+            .and_downcast::<GSteamAppObject>()
+            .is_some_and(|app| app.is_favorite());
+        label.set_label(&if is_favorite { tr!("Favorites") } else { tr!("All games") });
+        // Without favorites there is a single section, no need to name it
+        label.set_visible(is_favorite || header.start() > 0);
+    });
+    // A selection model lets arrow keys move between rows; nothing is selected until the
+    // user navigates, so the list looks the same as before at first.
+    let list_selection_model = SingleSelection::builder()
+        .model(&list_sort_model)
+        .autoselect(false)
+        .can_unselect(true)
         .build();
-    let list_selection_model = {
-        let model = NoSelection::new(Option::<ListStore>::None);
-        model.set_model(Some(&list_filter_model));
-        model
-    };
     let list_view = ListView::builder()
         .orientation(Orientation::Vertical)
         .show_separators(true)
         .model(&list_selection_model)
         .factory(&list_factory)
+        .header_factory(&list_header_factory)
         .build();
 
+    // Down arrow in the search entry jumps into the results, Enter then manages the app
+    let search_key_controller = EventControllerKey::new();
+    search_key_controller.connect_key_pressed(clone!(
+        #[weak]
+        list_view,
+        #[weak]
+        list_stack,
+        #[weak]
+        list_selection_model,
+        #[upgrade_or]
+        glib::Propagation::Proceed,
+        move |_, key, _, _| {
+            if key != gdk::Key::Down
+                || list_stack.visible_child_name().as_deref() != Some(STACK_LIST)
+                || list_selection_model.n_items() == 0
+            {
+                return glib::Propagation::Proceed;
+            }
+            let position = match list_selection_model.selected() {
+                INVALID_LIST_POSITION => 0,
+                selected => selected,
+            };
+            list_view.scroll_to(position, ListScrollFlags::FOCUS | ListScrollFlags::SELECT, None);
+            glib::Propagation::Stop
+        }
+    ));
+    search_entry.add_controller(search_key_controller);
+
     let window = ApplicationWindow::builder()
         .application(application)
         .title("SamRewritten")
         .default_width(1600)
         .default_height(1200)
-        .child(&list_stack)
+        .child(&create_toast_overlay(&main_box))
         .titlebar(&header_bar)
         .build();
 
@@ -189,7 +550,7 @@ pub fn create_main_ui(
         #[strong]
         app_id,
         #[weak]
-        application,
+        window,
         #[weak]
         menu_model,
         #[weak]
@@ -212,12 +573,14 @@ pub fn create_main_ui(
         app_label,
         #[weak]
         app_shimmer_image,
+        #[weak]
+        app_header_icon,
         move |list_view, position| {
             let Some(model) = list_view.model() else { return; };
             let Some(item) = model.item(position).and_downcast::<GSteamAppObject>() else { return; };
             switch_from_app_list_to_app(
                 &item,
-                application.clone(),
+                window.clone(),
                 &app_type_value,
                 &app_developer_value,
                 &app_achievement_count_value,
@@ -227,6 +590,7 @@ pub fn create_main_ui(
                 &app_metacritic_box,
                 &app_metacritic_value,
                 &app_shimmer_image,
+                &app_header_icon,
                 &app_label,
                 &menu_model,
                 &list_stack,
@@ -234,17 +598,110 @@ pub fn create_main_ui(
         }
     ));
 
+    // --- Selection mode ---
+    let (selection_bar, on_selection_changed) =
+        create_selection_bar(&window, &list_store, &select_button);
+    main_box.append(&selection_bar);
+
     // --- List item setup ---
-    list_factory.connect_setup(move |_, list_item| {
-        if let Some(list_item) = list_item.downcast_ref::<gtk::ListItem>() {
-            setup_list_item(list_item);
+    list_factory.connect_setup(clone!(
+        #[weak]
+        list_sorter,
+        #[weak]
+        select_button,
+        #[strong]
+        on_selection_changed,
+        move |_, list_item| {
+            if let Some(list_item) = list_item.downcast_ref::<gtk::ListItem>() {
+                setup_list_item(
+                    list_item,
+                    &list_sorter,
+                    &select_button,
+                    on_selection_changed.clone(),
+                );
+            }
         }
-    });
+    ));
 
     /// Helper to setup a list item row for the app list view.
-    fn setup_list_item(list_item: &ListItem) {
+    fn setup_list_item(
+        list_item: &ListItem,
+        list_sorter: &CustomSorter,
+        select_button: &ToggleButton,
+        on_selection_changed: Rc<dyn Fn()>,
+    ) {
+        // Only shown in selection mode
+        let select_check = CheckButton::builder()
+            .valign(Align::Center)
+            .margin_end(8)
+            .build();
+        select_button
+            .bind_property("active", &select_check, "visible")
+            .sync_create()
+            .build();
+        select_check.connect_toggled(clone!(
+            #[weak]
+            list_item,
+            move |check| {
+                let Some(app) = list_item.item().and_downcast::<GSteamAppObject>() else {
+                    return;
+                };
+                // Also fires when a recycled row is bound to another app
+                if app.is_selected() == check.is_active() {
+                    return;
+                }
+                app.set_is_selected(check.is_active());
+                on_selection_changed();
+            }
+        ));
+
+        let favorite_button = ToggleButton::builder()
+            .icon_name("non-starred-symbolic")
+            .valign(Align::Center)
+            .margin_end(8)
+            .tooltip_text(tr!("Pin to favorites"))
+            .css_classes(["flat"].as_slice())
+            .build();
+        favorite_button.connect_toggled(clone!(
+            #[weak]
+            list_item,
+            #[weak]
+            list_sorter,
+            move |button| {
+                let pinned = button.is_active();
+                button.set_icon_name(if pinned { "starred-symbolic" } else { "non-starred-symbolic" });
+                let Some(app) = list_item.item().and_downcast::<GSteamAppObject>() else {
+                    return;
+                };
+                // Also fires when a recycled row is bound to another app
+                if app.is_favorite() == pinned {
+                    return;
+                }
+                app.set_is_favorite(pinned);
+                let mut settings = load_settings();
+                settings.favorite_apps.retain(|app_id| *app_id != app.app_id());
+                if pinned {
+                    settings.favorite_apps.push(app.app_id());
+                }
+                save_settings(&settings);
+                list_sorter.changed(SorterChange::Different);
+            }
+        ));
+
         let image = ShimmerImage::new();
         let label = Label::builder().margin_start(20).build();
+        let running_icon = Image::builder()
+            .icon_name("document-edit-symbolic")
+            .margin_start(8)
+            .tooltip_text(tr!("Open in another window"))
+            .css_classes(["dim-label"])
+            .build();
+        let completion_label = Label::builder()
+            .margin_start(8)
+            .css_classes(["dim-label", "numeric"])
+            .visible(false)
+            .build();
+        completion_label.connect_label_notify(|label| label.set_visible(!label.label().is_empty()));
         let spacer = Box::builder()
             .orientation(Orientation::Horizontal)
             .hexpand(true)
@@ -268,14 +725,14 @@ pub fn create_main_ui(
                 .margin_start(20)
                 .build()
         };
-        let launch_button = make_button("media-playback-start-symbolic", "Launch");
+        let launch_button = make_button("media-playback-start-symbolic", &tr!("Launch"));
 
         let manage_box = {
             let icon = Image::builder()
                 .icon_name("document-edit-symbolic")
                 .pixel_size(11)
                 .build();
-            let label = Label::builder().label("Manage").build();
+            let label = Label::builder().label(tr!("Manage")).build();
             let box_ = Box::builder()
                 .spacing(8)
                 .margin_start(10)
@@ -290,10 +747,10 @@ pub fn create_main_ui(
             .css_classes(vec!["suggested-action"])
             .build();
         let manage_new_button = Button::builder().icon_name("window-new-symbolic").build();
-        if let Some(child) = manage_new_button.child() {
-            if let Ok(img) = child.downcast::<Image>() {
-                img.set_pixel_size(11);
-            }
+        if let Some(child) = manage_new_button.child()
+            && let Ok(img) = child.downcast::<Image>()
+        {
+            img.set_pixel_size(11);
         }
         let manage_button_box = Box::builder()
             .orientation(Orientation::Horizontal)
@@ -312,9 +769,13 @@ pub fn create_main_ui(
             .margin_start(8)
             .margin_end(8)
             .build();
+        entry.append(&select_check);
+        entry.append(&favorite_button);
         entry.append(&image);
         entry.append(&label);
+        entry.append(&running_icon);
         entry.append(&spacer);
+        entry.append(&completion_label);
         entry.append(&launch_button);
         entry.append(&manage_button_box);
 
@@ -334,6 +795,22 @@ pub fn create_main_ui(
             .property_expression("item")
             .chain_property::<GSteamAppObject>("image_url")
             .bind(&image, "url", Widget::NONE);
+        list_item
+            .property_expression("item")
+            .chain_property::<GSteamAppObject>("is_favorite")
+            .bind(&favorite_button, "active", Widget::NONE);
+        list_item
+            .property_expression("item")
+            .chain_property::<GSteamAppObject>("is_selected")
+            .bind(&select_check, "active", Widget::NONE);
+        list_item
+            .property_expression("item")
+            .chain_property::<GSteamAppObject>("is_running")
+            .bind(&running_icon, "visible", Widget::NONE);
+        list_item
+            .property_expression("item")
+            .chain_property::<GSteamAppObject>("completion")
+            .bind(&completion_label, "label", Widget::NONE);
     }
 
     // --- List item binding and unbinding ---
@@ -343,6 +820,8 @@ pub fn create_main_ui(
         #[weak]
         application,
         #[weak]
+        window,
+        #[weak]
         menu_model,
         #[weak]
         app_achievement_count_value,
@@ -364,6 +843,8 @@ pub fn create_main_ui(
         app_label,
         #[weak]
         app_shimmer_image,
+        #[weak]
+        app_header_icon,
         move |_, list_item| {
             let list_item = match list_item.downcast_ref::<ListItem>() {
                 Some(li) => li,
@@ -427,7 +908,7 @@ pub fn create_main_ui(
                 move |_| {
                     switch_from_app_list_to_app(
                         &steam_app_object,
-                        application.clone(),
+                        window.clone(),
                         &app_type_value,
                         &app_developer_value,
                         &app_achievement_count_value,
@@ -437,6 +918,7 @@ pub fn create_main_ui(
                         &app_metacritic_box,
                         &app_metacritic_value,
                         &app_shimmer_image,
+                        &app_header_icon,
                         &app_label,
                         &menu_model,
                         &list_stack,
@@ -448,60 +930,27 @@ pub fn create_main_ui(
                 manage_button.set_data("handler", handler.as_raw());
             }
 
-            let handler = manage_button_new_window.connect_clicked(move |_| {
-                use crate::get_executable_path;
-                let exe = match get_executable_path() {
-                    Ok(path) => path,
-                    Err(e) => {
-                        log::error!("Failed to get executable path: {e}");
-                        return;
-                    }
-                };
-                if let Err(e) = Command::new(exe)
-                    .arg(&format!("--auto-open={app_id_to_bind}"))
-                    .spawn() {
-                    log::error!("Could not start child process: {e}");
+            let handler = manage_button_new_window.connect_clicked(clone!(
+                #[weak]
+                application,
+                move |_| {
+                    create_main_window(
+                        &application,
+                        GuiArguments {
+                            auto_open: Rc::new(Cell::new(app_id_to_bind)),
+                            dry_run: false,
+                            offline: false,
+                        },
+                    );
                 }
-            });
+            ));
 
             unsafe {
                 manage_button_new_window.set_data("handler", handler.as_raw());
             }
 
             let handler = launch_button.connect_clicked(move |_| {
-                #[cfg(unix)]
-                {
-                    match Command::new("xdg-open")
-                        .arg(&format!("steam://run/{app_id_to_bind}"))
-                        .spawn() {
-                        Ok(mut child) => {
-                            if let Err(e) = child.wait() {
-                                log::error!("Failed to wait on child process: {e}");
-                            }
-                        }
-                        Err(e) => {
-                            log::error!("Could not start child process: {e}");
-                        }
-                    }
-                }
-
-                #[cfg(windows)]
-                {
-                    match Command::new("cmd")
-                        .arg("/C")
-                        .arg("start")
-                        .arg(&format!("steam://run/{app_id_to_bind}"))
-                        .spawn() {
-                        Ok(mut child) => {
-                            if let Err(e) = child.wait() {
-                                log::error!("Failed to wait on child process: {e}");
-                            }
-                        }
-                        Err(e) => {
-                            log::error!("Could not start child process: {e}");
-                        }
-                    }
-                }
+                open_uri(&format!("steam://run/{app_id_to_bind}"));
             });
 
             unsafe {
@@ -613,8 +1062,9 @@ pub fn create_main_ui(
                             app_id,
                             app_name: format!("App {app_id}"),
                             app_type: AppModelType::App,
-                            developer: "Unknown".to_string(),
+                            developer: String::new(),
                             image_url: None,
+                            icon_url: None,
                             metacritic_score: None,
                         }),
                     );
@@ -648,18 +1098,29 @@ pub fn create_main_ui(
         #[weak]
         menu_model,
         #[weak]
-        application,
+        window,
         #[weak]
         app_achievements_model,
         #[weak]
         app_stat_model,
         #[strong]
         cancel_timed_unlock,
+        #[strong]
+        seen_unlocked,
         move |_| {
             cancel_timed_unlock.store(true, std::sync::atomic::Ordering::Relaxed);
+            // With the changes made here, so they don't show as new next time
+            if let Some(app_id) = app_id.get()
+                && app_achievements_model.n_items() > 0
+            {
+                let unlocked = unlocked_achievement_ids(&app_achievements_model);
+                seen_unlocked.borrow_mut().insert(app_id, unlocked);
+            }
             list_stack.set_visible_child_name(STACK_LIST);
-            set_context_popover_to_app_list_context(&menu_model, &application);
-            if let Some(app_id) = app_id.take() {
+            set_context_popover_to_app_list_context(&menu_model, &window);
+            if let Some(app_id) = app_id.take()
+                && release_managed_app(app_id)
+            {
                 spawn_blocking(move || {
                     let _ = StopApp { app_id }.request();
                 });
@@ -677,7 +1138,48 @@ pub fn create_main_ui(
         }
     ));
 
+    // --- Window close handler ---
+    // Other windows may still be using the orchestrator, only let go of our own app
+    window.connect_close_request(clone!(
+        #[strong]
+        app_id,
+        #[strong]
+        cancel_timed_unlock,
+        move |_| {
+            cancel_timed_unlock.store(true, std::sync::atomic::Ordering::Relaxed);
+            if let Some(app_id) = app_id.take()
+                && release_managed_app(app_id)
+            {
+                spawn_blocking(move || {
+                    let _ = StopApp { app_id }.request();
+                });
+            }
+            glib::Propagation::Proceed
+        }
+    ));
+
     // --- App actions ---
+    let action_hide_empty_apps =
+        SimpleAction::new_stateful("hide_empty_apps", None, &hide_empty_apps.get().to_variant());
+    action_hide_empty_apps.connect_change_state(clone!(
+        #[strong]
+        hide_empty_apps,
+        #[weak]
+        list_empty_filter,
+        move |action, state| {
+            let Some(hide) = state.and_then(glib::Variant::get::<bool>) else {
+                return;
+            };
+            action.set_state(&hide.to_variant());
+            hide_empty_apps.set(hide);
+            let mut settings = load_settings();
+            settings.hide_empty_apps = hide;
+            save_settings(&settings);
+            list_empty_filter.changed(FilterChange::Different);
+        }
+    ));
+    window.add_action(&action_hide_empty_apps);
+
     let action_refresh_app_list = SimpleAction::new("refresh_app_list", None);
     action_refresh_app_list.connect_activate(clone!(
         #[strong]
@@ -685,19 +1187,26 @@ pub fn create_main_ui(
         #[strong]
         list_store,
         #[weak]
+        list_empty_filter,
+        #[weak]
         list_scrolled_window,
         #[weak]
         list_of_apps_or_no_result,
         #[weak]
         app_list_no_result_label,
         #[weak]
+        app_list_retry_button,
+        #[weak]
         list_stack,
         #[weak]
         search_entry,
+        #[weak]
+        list_cancel_button,
         move |_, _| {
             list_stack.set_visible_child_name(STACK_LOADING);
             search_entry.set_sensitive(false);
-            let apps = spawn_blocking(move || GetOwnedAppList.request());
+            list_cancel_button.set_sensitive(true);
+            let apps = spawn_blocking(request_app_list);
             MainContext::default().spawn_local(clone!(
                 #[weak]
                 list_view,
@@ -708,45 +1217,82 @@ pub fn create_main_ui(
                 #[weak]
                 app_list_no_result_label,
                 #[weak]
+                app_list_retry_button,
+                #[weak]
                 list_store,
                 #[weak]
+                list_empty_filter,
+                #[weak]
                 list_stack,
                 #[weak]
                 search_entry,
                 async move {
-                    match apps.await {
+                    let apps = apps.await;
+                    // The cancel button already left the library empty and usable
+                    if matches!(apps, Ok(Err(SamError::Cancelled))) {
+                        return;
+                    }
+                    app_list_retry_button.set_visible(matches!(
+                        apps,
+                        Ok(Err(
+                            SamError::AppListRetrievalFailed
+                                | SamError::OwnershipCheckFailed
+                                | SamError::SteamNotLoggedIn
+                        ))
+                    ));
+                    match apps {
                         Ok(Ok(app_vec)) => {
                             search_entry.set_sensitive(true);
                             if app_vec.is_empty() {
-                                app_list_no_result_label.set_text("No apps found on your account. Search for App Id to get started.");
+                                app_list_no_result_label.set_text(&tr!("No apps found on your account. Search for App Id to get started."));
                                 list_of_apps_or_no_result.set_visible_child_name(STACK_EMPTY);
                                 list_scrolled_window.set_child(Some(&list_view));
                                 list_stack.set_visible_child_name(STACK_LIST);
                             } else {
                                 list_store.remove_all();
-                                let mut models: Vec<GSteamAppObject> =
-                                    app_vec.into_iter().map(GSteamAppObject::new).collect();
-                                models.sort_by(|a, b| {
-                                    let a_name = a.property::<String>("app_name");
-                                    let b_name = b.property::<String>("app_name");
-                                    a_name.to_lowercase().cmp(&b_name.to_lowercase())
-                                });
+                                let favorite_apps = load_settings().favorite_apps;
+                                let models: Vec<GSteamAppObject> = app_vec
+                                    .into_iter()
+                                    .map(GSteamAppObject::new)
+                                    .inspect(|app| app.set_is_favorite(favorite_apps.contains(&app.app_id())))
+                                    .collect();
+                                // Sorted by list_sorter, favorites first
                                 list_store.extend_from_slice(&models);
+                                load_app_summaries(&list_store, &list_empty_filter);
                                 list_scrolled_window.set_child(Some(&list_view));
                                 list_stack.set_visible_child_name(STACK_LIST);
-                                app_list_no_result_label.set_text("No results. Check for spelling mistakes or try typing an App Id.");
+                                app_list_no_result_label.set_text(&tr!("No results. Check for spelling mistakes or try typing an App Id."));
                             }
                         },
-                        Ok(Err(sam_error)) if sam_error == SamError::AppListRetrievalFailed => {
+                        Ok(Err(SamError::OwnershipCheckFailed)) => {
+                            search_entry.set_sensitive(true);
+                            app_list_no_result_label.set_text(&tr!("Steam did not answer while checking your library. Make sure Steam is running and refresh the app list."));
+                            list_of_apps_or_no_result.set_visible_child_name(STACK_EMPTY);
+                            list_scrolled_window.set_child(Some(&list_view));
+                            list_stack.set_visible_child_name(STACK_LIST);
+                        },
+                        Ok(Err(SamError::AppListRetrievalFailed)) => {
                             search_entry.set_sensitive(true);
-                            app_list_no_result_label.set_text("Failed to load library. Check your internet connection. Search for App Id to get started.");
+                            app_list_no_result_label.set_text(&tr!("Failed to load library. Check your internet connection. Search for App Id to get started."));
                             list_of_apps_or_no_result.set_visible_child_name(STACK_EMPTY);
                             list_scrolled_window.set_child(Some(&list_view));
                             list_stack.set_visible_child_name(STACK_LIST);
                         },
+                        Ok(Err(SamError::SteamNotLoggedIn)) => {
+                            search_entry.set_sensitive(true);
+                            app_list_no_result_label.set_text(&tr!("Steam is running, but nobody is logged in. Please log into Steam, then retry."));
+                            list_of_apps_or_no_result.set_visible_child_name(STACK_EMPTY);
+                            list_scrolled_window.set_child(Some(&list_view));
+                            list_stack.set_visible_child_name(STACK_LIST);
+                        },
+                        Ok(Err(SamError::SteamClientNotFound(probed_paths))) => {
+                            let page = create_steamclient_not_found_page(&probed_paths);
+                            list_scrolled_window.set_child(Some(&page));
+                            list_stack.set_visible_child_name(STACK_LIST);
+                        }
                         Ok(Err(sam_error)) => {
                             eprintln!("[CLIENT] Unknown error: {}", sam_error);
-                            let label = Label::new(Some("SamRewritten could not connect to Steam. Is it running?"));
+                            let label = Label::new(Some(&tr!("SamRewritten could not connect to Steam. Is it running?")));
                             list_scrolled_window.set_child(Some(&label));
                             list_stack.set_visible_child_name(STACK_LIST);
                         }
@@ -759,6 +1305,159 @@ pub fn create_main_ui(
         }
     ));
 
+    // Stops the listing in the orchestrator, which answers the pending refresh with `Cancelled`
+    list_cancel_button.connect_clicked(clone!(
+        #[weak]
+        list_scrolled_window,
+        #[weak]
+        list_view,
+        #[weak]
+        list_of_apps_or_no_result,
+        #[weak]
+        app_list_no_result_label,
+        #[weak]
+        app_list_retry_button,
+        #[weak]
+        list_stack,
+        #[weak]
+        search_entry,
+        move |button| {
+            button.set_sensitive(false);
+            let handle = spawn_blocking(cancel_app_list);
+            MainContext::default().spawn_local(async move {
+                match handle.await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => eprintln!("[CLIENT] Failed to cancel the app list: {e}"),
+                    Err(_) => log::error!("[CLIENT] Threading task failed"),
+                }
+            });
+
+            search_entry.set_sensitive(true);
+            app_list_no_result_label.set_text(&tr!("Loading the library was cancelled. Search for App Id to get started."));
+            app_list_retry_button.set_visible(true);
+            list_of_apps_or_no_result.set_visible_child_name(STACK_EMPTY);
+            list_scrolled_window.set_child(Some(&list_view));
+            list_stack.set_visible_child_name(STACK_LIST);
+        }
+    ));
+
+    // The orchestrator drops its Steam connection and app servers, the refresh connects again
+    let action_reconnect_steam = SimpleAction::new("reconnect_steam", None);
+    action_reconnect_steam.connect_activate(clone!(
+        #[weak]
+        action_refresh_app_list,
+        move |_, _| {
+            let handle = spawn_blocking(|| Reconnect.request());
+            MainContext::default().spawn_local(clone!(
+                #[weak]
+                action_refresh_app_list,
+                async move {
+                    match handle.await {
+                        Ok(Ok(_)) => {}
+                        Ok(Err(e)) => eprintln!("[CLIENT] Failed to reconnect to Steam: {e}"),
+                        Err(_) => log::error!("[CLIENT] Threading task failed"),
+                    }
+                    action_refresh_app_list.activate(None);
+                }
+            ));
+        }
+    ));
+    window.add_action(&action_reconnect_steam);
+
+    // Lets "Close all sessions" send any window of this process back to the library
+    let action_back_to_library = SimpleAction::new("back_to_library", None);
+    action_back_to_library.connect_activate(clone!(
+        #[weak]
+        back_button,
+        move |_, _| back_button.emit_clicked()
+    ));
+    window.add_action(&action_back_to_library);
+
+    // The windows of this process share their app servers, so they all go back to the
+    // library before those and the ones kept running for a while are closed
+    let action_close_all_sessions = SimpleAction::new("close_all_sessions", None);
+    action_close_all_sessions.connect_activate(clone!(
+        #[weak]
+        window,
+        #[weak]
+        list_store,
+        move |_, _| {
+            if let Some(application) = window.application() {
+                for other in application.windows() {
+                    let _ = other.activate_action("win.back_to_library", None);
+                }
+            }
+            clear_managed_apps();
+            let handle = spawn_blocking(|| StopApps.request());
+            MainContext::default().spawn_local(clone!(
+                #[weak]
+                list_store,
+                async move {
+                    match handle.await {
+                        Ok(Ok(_)) => {}
+                        Ok(Err(e)) => eprintln!("[CLIENT] Failed to close all sessions: {e}"),
+                        Err(_) => log::error!("[CLIENT] Threading task failed"),
+                    }
+                    mark_running_apps(&list_store);
+                }
+            ));
+        }
+    ));
+    window.add_action(&action_close_all_sessions);
+
+    let action_export_library_completion = SimpleAction::new("export_library_completion", None);
+    action_export_library_completion.connect_activate(clone!(
+        #[weak]
+        window,
+        #[weak]
+        list_store,
+        move |_, _| export_library_completion(&window, &list_store)
+    ));
+    window.add_action(&action_export_library_completion);
+
+    let action_search_achievements = SimpleAction::new("search_achievements", None);
+    action_search_achievements.connect_activate(clone!(
+        #[weak]
+        window,
+        #[weak]
+        list_store,
+        move |_, _| show_achievement_search(&window, &list_store)
+    ));
+    window.add_action(&action_search_achievements);
+
+    let action_compare_profile = SimpleAction::new("compare_profile", None);
+    action_compare_profile.connect_activate(clone!(
+        #[strong]
+        app_id,
+        #[weak]
+        window,
+        #[weak]
+        app_achievements_model,
+        move |_, _| {
+            let Some(app_id) = app_id.get() else {
+                log::error!("No App ID for compare_profile");
+                return;
+            };
+            show_compare_dialog(&window, app_id, &app_achievements_model);
+        }
+    ));
+    window.add_action(&action_compare_profile);
+    window.add_action(&show_sidebar_action);
+
+    let action_open_community_stats = SimpleAction::new("open_community_stats", None);
+    action_open_community_stats.connect_activate(clone!(
+        #[strong]
+        app_id,
+        move |_, _| {
+            let Some(app_id) = app_id.get() else {
+                log::error!("No App ID for open_community_stats");
+                return;
+            };
+            open_uri(&format!("https://steamcommunity.com/stats/{app_id}/achievements"));
+        }
+    ));
+    window.add_action(&action_open_community_stats);
+
     let action_refresh_achievements_list = SimpleAction::new("refresh_achievements_list", None);
     action_refresh_achievements_list.set_enabled(false);
     action_refresh_achievements_list.connect_activate(clone!(
@@ -767,7 +1466,7 @@ pub fn create_main_ui(
         #[strong]
         app_unlocked_achievements_count,
         #[weak]
-        application,
+        window,
         #[weak]
         app_achievements_model,
         #[weak]
@@ -784,13 +1483,20 @@ pub fn create_main_ui(
         achievements_manual_start,
         #[weak]
         app_achievements_stack,
+        #[weak]
+        achievements_total_progress,
         #[strong]
         cancel_timed_unlock,
+        #[strong]
+        seen_unlocked,
         move |_, _| {
-            app_stack.set_visible_child_name(STACK_LOADING);
-            set_app_action_enabled(&application, "refresh_achievements_list", false);
-            app_achievements_model.remove_all();
-            app_stat_model.remove_all();
+            // Lists already shown are updated in place, without flashing the loading page.
+            // Refreshing them is asked for, so it bypasses the app server's cache.
+            let first_load = app_achievements_model.n_items() == 0 && app_stat_model.n_items() == 0;
+            if first_load {
+                app_stack.set_visible_child_name(STACK_LOADING);
+            }
+            set_app_action_enabled(&window, "refresh_achievements_list", false);
             cancel_timed_unlock.store(true, std::sync::atomic::Ordering::Relaxed);
             app_achievements_stack.set_visible_child_name("manual");
 
@@ -801,9 +1507,17 @@ pub fn create_main_ui(
                     return;
                 }
             };
+            // The list shown includes the changes made here, which are not news
+            let previously_unlocked = if app_achievements_model.n_items() > 0 {
+                Some(unlocked_achievement_ids(&app_achievements_model))
+            } else {
+                seen_unlocked.borrow().get(&app_id_copy).cloned()
+            };
             let handle = spawn_blocking(move || {
                 let achievements = GetAchievements {
                     app_id: app_id_copy,
+                    language: load_settings().steam_language,
+                    cached: first_load,
                 }
                 .request();
                 let stats = GetStats {
@@ -816,9 +1530,23 @@ pub fn create_main_ui(
             MainContext::default().spawn_local(clone!(
                 #[strong]
                 app_unlocked_achievements_count,
+                #[strong]
+                seen_unlocked,
                 async move {
-                    let Ok((Ok(achievements), Ok(stats))) = handle.await else {
-                        return app_stack.set_visible_child_name("failed");
+                    let (achievements, stats) = match handle.await {
+                        Ok((Ok(achievements), Ok(stats))) => (achievements, stats),
+                        // Achievements came from Steam directly, only the stats are missing. Told
+                        // once, not on every refresh.
+                        Ok((Ok(achievements), Err(SamError::SchemaUnavailable))) => {
+                            if app_achievements_model.n_items() == 0 {
+                                show_toast(
+                                    &app_stack,
+                                    &tr!("Steam has not downloaded this game's stats yet. Launch the game once to edit them."),
+                                );
+                            }
+                            (achievements, Vec::new())
+                        }
+                        _ => return app_stack.set_visible_child_name("failed"),
                     };
 
                     let achievement_len = achievements.len();
@@ -826,24 +1554,74 @@ pub fn create_main_ui(
                         achievements.iter().filter(|ach| ach.is_achieved).count();
                     app_unlocked_achievements_count.set(achievement_unlocked_len);
 
-                    app_stats_count_value.set_label(&format!("{}", stats.len()));
+                    let stats_len = stats.len();
+                    app_stats_count_value.set_label(&format!("{stats_len}"));
                     app_achievement_count_value
                         .set_label(&format!("{achievement_unlocked_len} / {achievement_len}"));
+                    update_total_progress(
+                        &achievements_total_progress,
+                        achievement_unlocked_len,
+                        achievement_len,
+                    );
+
+                    // Checked once per app, not on every refresh
+                    if app_achievements_model.n_items() == 0 && achievement_len > 0 {
+                        warn_outdated_schema(&app_stack, app_id_copy);
+                    }
+
+                    let unlocked: HashSet<String> = achievements
+                        .iter()
+                        .filter(|achievement| achievement.is_achieved)
+                        .map(|achievement| achievement.id.clone())
+                        .collect();
+                    let newly_unlocked: HashSet<String> = match &previously_unlocked {
+                        Some(previous) => unlocked.difference(previous).cloned().collect(),
+                        None => HashSet::new(),
+                    };
+                    if !newly_unlocked.is_empty() {
+                        let count = newly_unlocked.len();
+                        show_toast(
+                            &app_stack,
+                            &trn!(
+                                "{count} achievement was unlocked since you last looked",
+                                "{count} achievements were unlocked since you last looked",
+                                count,
+                                count = count
+                            ),
+                        );
+                    }
+                    seen_unlocked.borrow_mut().insert(app_id_copy, unlocked);
 
                     let objects: Vec<GAchievementObject> = achievements
                         .into_iter()
                         .map(GAchievementObject::new)
                         .collect();
-                    app_achievements_model.extend_from_slice(&objects);
+                    for object in &objects {
+                        object.set_recently_unlocked(newly_unlocked.contains(&object.id()));
+                    }
+                    sync_list_store(
+                        &app_achievements_model,
+                        objects,
+                        GAchievementObject::id,
+                        GAchievementObject::update_from,
+                    );
 
                     let objects: Vec<GStatObject> =
                         stats.into_iter().map(GStatObject::new).collect();
-                    app_stat_model.extend_from_slice(&objects);
+                    sync_list_store(
+                        &app_stat_model,
+                        objects,
+                        GStatObject::id,
+                        GStatObject::update_from,
+                    );
 
+                    // Games with stats only open on them
                     if achievement_len > 0 {
                         app_stack.set_visible_child_name("achievements");
+                    } else if stats_len > 0 {
+                        app_stack.set_visible_child_name("stats");
                     } else {
-                        app_stack.set_visible_child_name("empty");
+                        app_stack.set_visible_child_name("no_achievements");
                     }
 
                     achievements_manual_start
@@ -854,8 +1632,9 @@ pub fn create_main_ui(
                     achievements_manual_adjustment.set_upper(achievement_len as f64);
                     achievements_manual_adjustment.set_value(achievement_len as f64);
 
-                    set_app_action_enabled(&application, "refresh_achievements_list", true);
-                    set_app_action_enabled(&application, "clear_all_stats_and_achievements", true);
+                    set_app_action_enabled(&window, "refresh_achievements_list", true);
+                    set_app_action_enabled(&window, "clear_all_stats_and_achievements", true);
+                    set_app_action_enabled(&window, "lock_all_achievements", achievement_len > 0);
                 }
             ));
         }
@@ -868,7 +1647,7 @@ pub fn create_main_ui(
         #[strong]
         app_id,
         #[weak]
-        application,
+        window,
         #[weak]
         app_achievements_model,
         #[weak]
@@ -877,9 +1656,12 @@ pub fn create_main_ui(
         action_refresh_achievements_list,
         #[weak]
         app_stack,
+        #[weak]
+        app_loading_label,
         move |_, _| {
+            app_loading_label.set_label(&tr!("Resetting..."));
             app_stack.set_visible_child_name(STACK_LOADING);
-            set_app_action_enabled(&application, "clear_all_stats_and_achievements", false);
+            set_app_action_enabled(&window, "clear_all_stats_and_achievements", false);
             app_achievements_model.remove_all();
             app_stat_model.remove_all();
 
@@ -890,84 +1672,194 @@ pub fn create_main_ui(
                     return;
                 }
             };
+            // Packed as (done << 32) | total so the UI reads a consistent pair
+            let progress = Arc::new(AtomicU64::new(0));
+            let progress_writer = progress.clone();
             let handle = spawn_blocking(move || {
-                let success = ResetStats {
+                ResetStats {
                     app_id: app_id_copy,
                     achievements_too: true,
                 }
-                .request();
-                success
+                .request_with_progress(|done, total| {
+                    progress_writer.store(((done as u64) << 32) | total as u64, Ordering::Relaxed);
+                })
             });
 
+            let progress_source = glib::timeout_add_local(
+                Duration::from_millis(100),
+                clone!(
+                    #[weak]
+                    app_loading_label,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || {
+                        let packed = progress.load(Ordering::Relaxed);
+                        let (done, total) = (packed >> 32, packed & u32::MAX as u64);
+                        // Steam resets everything at once, the achievements are checked after
+                        if total > 0 {
+                            app_loading_label.set_label(&tr!("Checking {done} of {total}...", done = done, total = total));
+                        }
+                        glib::ControlFlow::Continue
+                    }
+                ),
+            );
+
             MainContext::default().spawn_local(clone!(async move {
-                let Ok(Ok(_success)) = handle.await else {
+                let result = handle.await;
+                progress_source.remove();
+                app_loading_label.set_label(&tr!("Loading..."));
+
+                let Ok(Ok(success)) = result else {
                     return app_stack.set_visible_child_name("failed");
                 };
+                if success {
+                    show_toast(&app_stack, &tr!("Stats and achievements reset"));
+                }
 
                 action_refresh_achievements_list.activate(None);
             }));
         }
     ));
 
+    let action_lock_all_achievements = SimpleAction::new("lock_all_achievements", None);
+    action_lock_all_achievements.set_enabled(false);
+    action_lock_all_achievements.connect_activate(clone!(
+        #[strong]
+        app_id,
+        #[weak]
+        window,
+        #[weak]
+        app_achievements_model,
+        move |_, _| {
+            let Some(app_id) = app_id.get() else {
+                log::error!("No App ID for lock_all_achievements");
+                return;
+            };
+            confirm_lock_all_achievements(&window, app_id, &app_achievements_model);
+        }
+    ));
+    window.add_action(&action_lock_all_achievements);
+
+    let steam_language = load_settings().steam_language.unwrap_or_default();
+    let action_steam_language = SimpleAction::new_stateful(
+        "steam_language",
+        Some(glib::VariantTy::STRING),
+        &steam_language.to_variant(),
+    );
+    action_steam_language.connect_change_state(clone!(
+        #[weak]
+        action_refresh_app_list,
+        move |action, state| {
+            let Some(language) = state.and_then(glib::Variant::get::<String>) else {
+                return;
+            };
+            action.set_state(&language.to_variant());
+            let mut settings = load_settings();
+            settings.steam_language = Some(language).filter(|language| !language.is_empty());
+            save_settings(&settings);
+            // Images and names come from the orchestrator, reload them in the new language
+            if action_refresh_app_list.is_enabled() {
+                action_refresh_app_list.activate(None);
+            }
+        }
+    ));
+    window.add_action(&action_steam_language);
+
+    // Another window may have opened or left an app meanwhile
+    window.connect_is_active_notify(clone!(
+        #[weak]
+        list_stack,
+        #[weak]
+        list_store,
+        move |window| {
+            if window.is_active() && list_stack.visible_child_name().as_deref() == Some(STACK_LIST) {
+                mark_running_apps(&list_store);
+            }
+        }
+    ));
+
     // --- Stack visible child notify handler ---
     list_stack.connect_visible_child_notify(clone!(
         #[weak]
         back_button,
         #[weak]
-        application,
+        select_button,
+        #[weak]
+        window,
         #[weak]
         app_stack,
         #[weak]
         search_entry,
         #[weak]
         action_refresh_app_list,
+        #[weak]
+        recent_apps_bar,
+        #[weak]
+        recent_apps_box,
+        #[weak]
+        app_header_icon,
+        #[strong]
+        open_recent_app,
         move |stack| {
+            // Selection mode belongs to the library, leaving it unticks everything
+            let in_list = stack.visible_child_name().as_deref() == Some(STACK_LIST);
+            if !in_list {
+                select_button.set_active(false);
+            }
+            select_button.set_sensitive(in_list);
+
+            if stack.visible_child_name().as_deref() != Some("app") {
+                app_header_icon.set_visible(false);
+            }
             if stack.visible_child_name().as_deref() == Some(STACK_LOADING) {
                 back_button.set_sensitive(false);
                 action_refresh_app_list.set_enabled(false);
+                refresh_recent_apps_bar(&recent_apps_bar, &recent_apps_box, open_recent_app.clone());
             } else if stack.visible_child_name().as_deref() == Some("app") {
+                recent_apps_bar.set_visible(false);
                 search_entry.set_text("");
-                search_entry.set_placeholder_text(Some("Achievement or stat..."));
+                search_entry.set_placeholder_text(Some(&tr!("Achievement or stat...")));
                 back_button.set_sensitive(true);
                 action_refresh_app_list.set_enabled(false);
             } else {
                 search_entry.set_text("");
-                search_entry.set_placeholder_text(Some("App name..."));
+                search_entry.set_placeholder_text(Some(&tr!("App name...")));
                 back_button.set_sensitive(false);
                 action_refresh_app_list.set_enabled(true);
+                refresh_recent_apps_bar(&recent_apps_bar, &recent_apps_box, open_recent_app.clone());
+                mark_running_apps(&list_store);
 
                 let auto_launch_app = gui_args.auto_open.get();
                 if auto_launch_app > 0 {
                     gui_args.auto_open.set(0);
 
-                    for ach in &list_store {
-                        if let Ok(obj) = ach {
-                            let g_app = match obj.downcast::<GSteamAppObject>() {
-                                Ok(g_app) => g_app,
-                                Err(_) => {
-                                    log::error!("Not a GSteamAppObject");
-                                    continue;
-                                }
-                            };
-                            if g_app.app_id() == auto_launch_app {
-                                switch_from_app_list_to_app(
-                                    &g_app,
-                                    application.clone(),
-                                    &app_type_value,
-                                    &app_developer_value,
-                                    &app_achievement_count_value,
-                                    &app_stats_count_value,
-                                    app_stack.clone(),
-                                    &app_id,
-                                    &app_metacritic_box,
-                                    &app_metacritic_value,
-                                    &app_shimmer_image,
-                                    &app_label,
-                                    &menu_model,
-                                    &stack,
-                                );
-                                break;
+                    for obj in (&list_store).into_iter().flatten() {
+                        let g_app = match obj.downcast::<GSteamAppObject>() {
+                            Ok(g_app) => g_app,
+                            Err(_) => {
+                                log::error!("Not a GSteamAppObject");
+                                continue;
                             }
+                        };
+                        if g_app.app_id() == auto_launch_app {
+                            switch_from_app_list_to_app(
+                                &g_app,
+                                window.clone(),
+                                &app_type_value,
+                                &app_developer_value,
+                                &app_achievement_count_value,
+                                &app_stats_count_value,
+                                app_stack.clone(),
+                                &app_id,
+                                &app_metacritic_box,
+                                &app_metacritic_value,
+                                &app_shimmer_image,
+                                &app_header_icon,
+                                &app_label,
+                                &menu_model,
+                                stack,
+                            );
+                            break;
                         }
                     }
                 }
@@ -978,18 +1870,20 @@ pub fn create_main_ui(
     // --- Initial state and actions setup ---
     app_stack.set_visible_child_name(STACK_LOADING);
     list_stack.set_visible_child_name(STACK_LOADING);
+    refresh_recent_apps_bar(&recent_apps_bar, &recent_apps_box, open_recent_app);
     action_refresh_app_list.activate(None);
     action_refresh_app_list.set_enabled(false);
 
     setup_app_actions(
         application,
+        &window,
         &about_dialog,
         &action_refresh_app_list,
         &action_refresh_achievements_list,
         &action_clear_all_stats_and_achievements,
+        gui_args.dry_run,
     );
+    setup_search_actions(&window, &search_entry);
 
     window.present();
-
-    ExitCode::SUCCESS
 }
@@ -13,24 +13,58 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::frontend::MainApplication;
 use crate::frontend::application_actions::set_app_action_enabled;
+use crate::frontend::recent_apps::record_recent_app;
 use crate::frontend::request::{LaunchApp, Request};
 use crate::frontend::shimmer_image::ShimmerImage;
 use crate::frontend::steam_app::GSteamAppObject;
 use crate::frontend::ui_components::set_context_popover_to_app_details_context;
+use crate::tr;
 use gtk::gio::{Menu, spawn_blocking};
 use gtk::glib::{MainContext, clone};
 use gtk::prelude::WidgetExt;
 use gtk::prelude::*;
-use gtk::{Box, Label, Stack};
-use std::cell::Cell;
+use gtk::{ApplicationWindow, Box, Label, Stack};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
 
+thread_local! {
+    /// How many windows currently manage each app. All windows share one orchestrator,
+    /// so an app server may only be stopped once the last window managing it leaves.
+    static MANAGED_APPS: RefCell<HashMap<u32, usize>> = RefCell::new(HashMap::new());
+}
+
+/// Records that one more window manages `app_id`.
+fn retain_managed_app(app_id: u32) {
+    MANAGED_APPS.with_borrow_mut(|apps| *apps.entry(app_id).or_insert(0) += 1);
+}
+
+/// Records that a window stopped managing `app_id`.
+/// Returns true when no window manages it anymore and its app server can be stopped.
+pub(crate) fn release_managed_app(app_id: u32) -> bool {
+    MANAGED_APPS.with_borrow_mut(|apps| match apps.get_mut(&app_id) {
+        Some(count) if *count > 1 => {
+            *count -= 1;
+            false
+        }
+        _ => {
+            apps.remove(&app_id);
+            true
+        }
+    })
+}
+
+/// Forgets every app the windows manage, once their app servers are all being stopped.
+pub(crate) fn clear_managed_apps() {
+    MANAGED_APPS.with_borrow_mut(HashMap::clear);
+}
+
 /// Switches the UI from the app list to the app details view and launches the app.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn switch_from_app_list_to_app(
     steam_app_object: &GSteamAppObject,
-    application: MainApplication,
+    window: ApplicationWindow,
     app_type_value_label: &Label,
     app_developer_value_label: &Label,
     app_achievement_count_value_label: &Label,
@@ -40,17 +74,25 @@ pub(crate) fn switch_from_app_list_to_app(
     app_metacritic_box: &Box,
     app_metacritic_value_label: &Label,
     app_shimmer_image: &ShimmerImage,
+    app_header_icon: &ShimmerImage,
     app_label: &Label,
     menu_model: &Menu,
     list_stack: &Stack,
 ) {
-    set_app_action_enabled(&application, "refresh_achievements_list", false);
+    set_app_action_enabled(&window, "refresh_achievements_list", false);
     app_type_value_label.set_label(&steam_app_object.app_type());
-    app_developer_value_label.set_label(&steam_app_object.developer());
+    let developer = steam_app_object.developer();
+    if developer.is_empty() {
+        app_developer_value_label.set_label(&tr!("Unknown"));
+    } else {
+        app_developer_value_label.set_label(&developer);
+    }
     app_achievement_count_value_label.set_label("...");
     app_stats_count_value_label.set_label("...");
     app_stack.set_visible_child_name("loading");
     app_id.set(Some(steam_app_object.app_id()));
+    retain_managed_app(steam_app_object.app_id());
+    record_recent_app(steam_app_object.app_id(), &steam_app_object.app_name());
     let metacritic_score = steam_app_object.metacritic_score();
     let has_metacritic = metacritic_score != u8::MAX;
     app_metacritic_box.set_visible(has_metacritic);
@@ -67,6 +109,19 @@ pub(crate) fn switch_from_app_list_to_app(
         app_shimmer_image.reset();
     }
 
+    // The square icon in the title bar tells which game the window is on
+    match steam_app_object.icon_url() {
+        Some(url) => {
+            app_header_icon.set_url(url.as_str());
+            app_header_icon.set_tooltip_text(Some(&steam_app_object.app_name()));
+            app_header_icon.set_visible(true);
+        }
+        None => {
+            app_header_icon.reset();
+            app_header_icon.set_visible(false);
+        }
+    }
+
     app_label.set_markup(&format!(
         "<span font_desc=\"Bold 16\">{}</span>",
         steam_app_object.app_name()
@@ -80,7 +135,7 @@ pub(crate) fn switch_from_app_list_to_app(
         .request()
     });
 
-    set_context_popover_to_app_details_context(menu_model, &application);
+    set_context_popover_to_app_details_context(menu_model, &window);
 
     MainContext::default().spawn_local(clone!(async move {
         match handle.await {
@@ -92,9 +147,9 @@ pub(crate) fn switch_from_app_list_to_app(
             }
         }
 
-        set_app_action_enabled(&application, "refresh_achievements_list", true);
-        set_app_action_enabled(&application, "clear_all_stats_and_achievements", true);
-        application.activate_action("refresh_achievements_list", None);
+        set_app_action_enabled(&window, "refresh_achievements_list", true);
+        set_app_action_enabled(&window, "clear_all_stats_and_achievements", true);
+        ActionGroupExt::activate_action(&window, "refresh_achievements_list", None);
     }));
 
     list_stack.set_visible_child_name("app");
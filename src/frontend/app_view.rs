@@ -14,18 +14,23 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use super::stat_view::create_stats_view;
-use crate::frontend::MainApplication;
 use crate::frontend::achievement_view::create_achievements_view;
+use crate::frontend::custom_progress_bar_widget::CustomProgressBar;
+use crate::frontend::request::{GetLastStored, Request};
 use crate::frontend::shimmer_image::ShimmerImage;
-use gtk::gio::ListStore;
-use gtk::glib::clone;
+use crate::tr;
+use crate::utils::{format::format_unlock_time, settings::load_settings};
+use gtk::gio::{ListStore, SimpleAction, spawn_blocking};
+use gtk::glib::{MainContext, clone};
 use gtk::pango::{EllipsizeMode, WrapMode};
 use gtk::prelude::*;
 use gtk::{
     Adjustment, Align, Box, Button, Label, Orientation, Separator, SpinButton, Spinner, Stack,
-    StackTransitionType, StringFilter, ToggleButton,
+    StackTransitionType, StringFilter, ToggleButton, Widget,
 };
-use gtk::{Paned, glib};
+use gtk::glib;
+#[cfg(not(feature = "adw"))]
+use gtk::Paned;
 use std::cell::Cell;
 use std::rc::Rc;
 use std::sync::Arc;
@@ -35,7 +40,8 @@ enum AppStackPage {
     Achievements,
     Stats,
     Failed,
-    Empty,
+    NoAchievements,
+    NoStats,
     Loading,
 }
 
@@ -45,7 +51,8 @@ impl AppStackPage {
             AppStackPage::Achievements => "achievements",
             AppStackPage::Stats => "stats",
             AppStackPage::Failed => "failed",
-            AppStackPage::Empty => "empty",
+            AppStackPage::NoAchievements => "no_achievements",
+            AppStackPage::NoStats => "no_stats",
             AppStackPage::Loading => "loading",
         }
     }
@@ -65,11 +72,137 @@ fn create_labeled_value_box(label: &str, value: &gtk::Label, margin_top: i32) ->
     box_widget
 }
 
+/// Dims a sidebar toggle whose category the game doesn't have, and says so on hover.
+fn show_availability(button: &ToggleButton, available: bool, unavailable_tooltip: &str) {
+    if available {
+        button.remove_css_class("dim-label");
+        button.set_tooltip_text(None);
+    } else {
+        button.add_css_class("dim-label");
+        button.set_tooltip_text(Some(unavailable_tooltip));
+    }
+}
+
+/// Seconds between two checks of when the current app last stored its stats.
+const LAST_SAVED_POLL_SECS: u32 = 5;
+
+/// Keeps the "last saved" line of the sidebar up to date while the app view is shown,
+/// for users who turned on the auto-store.
+fn watch_last_saved(app_id: Rc<Cell<Option<u32>>>, value_box: &Box, value: &Label) {
+    let in_flight = Rc::new(Cell::new(false));
+    glib::timeout_add_seconds_local(
+        LAST_SAVED_POLL_SECS,
+        clone!(
+            #[weak]
+            value_box,
+            #[weak]
+            value,
+            #[upgrade_or]
+            glib::ControlFlow::Break,
+            move || {
+                // The line itself stays hidden until the first answer, so look at the sidebar
+                let shown = value_box.parent().is_some_and(|sidebar| sidebar.is_mapped());
+                let Some(app_id) = app_id.get().filter(|_| shown) else {
+                    return glib::ControlFlow::Continue;
+                };
+                if in_flight.replace(true) {
+                    return glib::ControlFlow::Continue;
+                }
+                let handle = spawn_blocking(move || GetLastStored { app_id }.request());
+                MainContext::default().spawn_local(clone!(
+                    #[strong]
+                    in_flight,
+                    async move {
+                        in_flight.set(false);
+                        match handle.await {
+                            Ok(Ok(last_stored)) => {
+                                let text = last_stored
+                                    .and_then(format_unlock_time)
+                                    .unwrap_or_else(|| tr!("Not yet"));
+                                value.set_label(&text);
+                                value_box.set_visible(true);
+                            }
+                            Ok(Err(_)) | Err(_) => value_box.set_visible(false),
+                        }
+                    }
+                ));
+                glib::ControlFlow::Continue
+            }
+        ),
+    );
+}
+
+/// Lays the sidebar and the lists side by side. `show_sidebar_action` folds the sidebar away
+/// so the lists get the whole width.
+#[cfg(not(feature = "adw"))]
+fn create_app_pane(sidebar: &Box, content: &Stack, show_sidebar_action: &SimpleAction) -> Widget {
+    let app_pane = Paned::builder()
+        .orientation(Orientation::Horizontal)
+        .shrink_start_child(false)
+        .shrink_end_child(false)
+        .resize_start_child(false)
+        .start_child(sidebar)
+        .end_child(content)
+        .build();
+    show_sidebar_action.connect_change_state(clone!(
+        #[weak]
+        sidebar,
+        move |action, state| {
+            let Some(shown) = state.and_then(glib::Variant::get::<bool>) else {
+                return;
+            };
+            sidebar.set_visible(shown);
+            action.set_state(&shown.to_variant());
+        }
+    ));
+    app_pane.upcast()
+}
+
+/// Lays the sidebar and the lists side by side. Narrow windows fold the sidebar away, it
+/// then slides over the lists when `show_sidebar_action` shows it.
+#[cfg(feature = "adw")]
+fn create_app_pane(sidebar: &Box, content: &Stack, show_sidebar_action: &SimpleAction) -> Widget {
+    let split_view = adw::OverlaySplitView::builder()
+        .sidebar(sidebar)
+        .content(content)
+        .build();
+    let breakpoint = adw::Breakpoint::new(adw::BreakpointCondition::new_length(
+        adw::BreakpointConditionLengthType::MaxWidth,
+        720.0,
+        adw::LengthUnit::Sp,
+    ));
+    breakpoint.add_setter(&split_view, "collapsed", Some(&true.to_value()));
+    breakpoint.add_setter(&split_view, "show-sidebar", Some(&false.to_value()));
+    let app_pane = adw::BreakpointBin::builder()
+        .width_request(360)
+        .height_request(294)
+        .child(&split_view)
+        .build();
+    app_pane.add_breakpoint(breakpoint);
+
+    show_sidebar_action.connect_change_state(clone!(
+        #[weak]
+        split_view,
+        move |_, state| {
+            if let Some(shown) = state.and_then(glib::Variant::get::<bool>) {
+                split_view.set_show_sidebar(shown);
+            }
+        }
+    ));
+    // The breakpoint and the overlay's own dismissal change it too
+    split_view.connect_show_sidebar_notify(clone!(
+        #[weak]
+        show_sidebar_action,
+        move |split_view| show_sidebar_action.set_state(&split_view.shows_sidebar().to_variant())
+    ));
+    app_pane.upcast()
+}
+
 /// Create the main app view, including sidebar, achievements, and stats.
+#[allow(clippy::type_complexity)]
 pub fn create_app_view(
     app_id: Rc<Cell<Option<u32>>>,
     app_unlocked_achievements_count: Rc<Cell<usize>>,
-    application: &MainApplication,
 ) -> (
     Stack,
     ShimmerImage,
@@ -82,55 +215,66 @@ pub fn create_app_view(
     Label,
     Label,
     Box,
-    Box,
+    SimpleAction,
     ListStore,
     StringFilter,
     ListStore,
     StringFilter,
-    Paned,
+    Widget,
     Adjustment,
     SpinButton,
     Button,
     Arc<AtomicBool>,
     Stack,
+    CustomProgressBar,
+    Label,
 ) {
     // Spinner and loading label
+    let app_loading_label = Label::builder().label(tr!("Loading...")).build();
     let app_spinner_box = {
         let spinner = Spinner::builder().spinning(true).margin_end(5).build();
-        let label = Label::builder().label("Loading...").build();
         let box_ = Box::builder().halign(Align::Center).build();
         box_.append(&spinner);
-        box_.append(&label);
+        box_.append(&app_loading_label);
         box_
     };
 
     // Sidebar value boxes
     let app_achievement_count_value = Label::builder().halign(Align::End).build();
     let app_achievement_count_box = create_labeled_value_box(
-        "Achievements:",
+        &tr!("Achievements:"),
         &app_achievement_count_value,
         10,
     );
     let app_stats_count_value = Label::builder().halign(Align::End).build();
-    let app_stats_count_box = create_labeled_value_box("Stats:", &app_stats_count_value, 10);
+    let app_stats_count_box = create_labeled_value_box(&tr!("Stats:"), &app_stats_count_value, 10);
     let app_type_value = Label::builder().halign(Align::End).build();
-    let app_type_box = create_labeled_value_box("Type:", &app_type_value, 10);
+    let app_type_box = create_labeled_value_box(&tr!("Type:"), &app_type_value, 10);
     let app_developer_value = Label::builder()
         .halign(Align::End)
         .ellipsize(EllipsizeMode::End)
         .build();
-    let app_developer_box = create_labeled_value_box("Developer:", &app_developer_value, 20);
+    let app_developer_box = create_labeled_value_box(&tr!("Developer:"), &app_developer_value, 20);
     let app_metacritic_value = Label::builder().halign(Align::End).build();
-    let app_metacritic_box = create_labeled_value_box("Metacritic:", &app_metacritic_value, 10);
+    let app_metacritic_box = create_labeled_value_box(&tr!("Metacritic:"), &app_metacritic_value, 10);
+
+    let app_last_saved_value = Label::builder().halign(Align::End).build();
+    let app_last_saved_box = create_labeled_value_box(&tr!("Last saved:"), &app_last_saved_value, 10);
+    app_last_saved_box.set_visible(false);
 
     // Status labels
     let app_loading_failed_label = Label::builder()
-        .label("Failed to load app.")
+        .label(tr!("Failed to load app."))
+        .halign(Align::Center)
+        .valign(Align::Center)
+        .build();
+    let app_no_achievements_label = Label::builder()
+        .label(tr!("This game has no achievements."))
         .halign(Align::Center)
         .valign(Align::Center)
         .build();
-    let app_no_entries_value = Label::builder()
-        .label("No entries found.")
+    let app_no_stats_label = Label::builder()
+        .label(tr!("This game has no stats."))
         .halign(Align::Center)
         .valign(Align::Center)
         .build();
@@ -144,7 +288,7 @@ pub fn create_app_view(
         .build();
 
     // App image
-    let app_shimmer_image = ShimmerImage::new();
+    let app_shimmer_image = ShimmerImage::with_size(300, 113);
     app_shimmer_image.set_halign(Align::Start);
     // Wrap shimmer image in a Box for sidebar compatibility
     let app_shimmer_image_box = Box::builder()
@@ -153,9 +297,9 @@ pub fn create_app_view(
     app_shimmer_image_box.append(&app_shimmer_image);
 
     // Sidebar buttons
-    let app_achievements_button = ToggleButton::builder().label("Achievements").build();
+    let app_achievements_button = ToggleButton::builder().label(tr!("Achievements")).build();
     let app_stats_button = ToggleButton::builder()
-        .label("Stats")
+        .label(tr!("Stats"))
         .group(&app_achievements_button)
         .build();
     let app_button_box = Box::builder()
@@ -196,9 +340,13 @@ pub fn create_app_view(
         &app_achievement_count_box,
         &app_stats_count_box,
         &app_type_box,
+        &app_last_saved_box,
     ] {
         app_sidebar.append(widget);
     }
+    if load_settings().auto_store_secs > 0 {
+        watch_last_saved(app_id.clone(), &app_last_saved_box, &app_last_saved_value);
+    }
 
     // Main content views
     let (
@@ -209,10 +357,10 @@ pub fn create_app_view(
         achievements_manual_spinbox,
         achievements_manual_start,
         cancel_timed_unlock,
+        achievements_total_progress,
     ) = create_achievements_view(
         app_id.clone(),
         app_unlocked_achievements_count,
-        application,
         &app_achievement_count_value,
     );
     let (app_stat_scrolled_window, app_stat_model, app_stat_string_filter) = create_stats_view();
@@ -224,7 +372,11 @@ pub fn create_app_view(
     app_stack.add_named(&app_achievements_stack, Some(AppStackPage::Achievements.as_str()));
     app_stack.add_named(&app_stat_scrolled_window, Some(AppStackPage::Stats.as_str()));
     app_stack.add_named(&app_loading_failed_label, Some(AppStackPage::Failed.as_str()));
-    app_stack.add_named(&app_no_entries_value, Some(AppStackPage::Empty.as_str()));
+    app_stack.add_named(
+        &app_no_achievements_label,
+        Some(AppStackPage::NoAchievements.as_str()),
+    );
+    app_stack.add_named(&app_no_stats_label, Some(AppStackPage::NoStats.as_str()));
     app_stack.add_named(&app_spinner_box, Some(AppStackPage::Loading.as_str()));
 
     // Stack page change logic
@@ -233,13 +385,21 @@ pub fn create_app_view(
         app_achievements_button,
         #[weak]
         app_stats_button,
+        #[weak]
+        app_achievements_model,
+        #[weak]
+        app_stat_model,
         move |stack| {
             match stack.visible_child_name().as_deref() {
                 Some(x) if x == AppStackPage::Loading.as_str() || x == AppStackPage::Failed.as_str() => {
                     app_achievements_button.set_sensitive(false);
                     app_stats_button.set_sensitive(false);
+                    return;
                 }
-                Some(x) if x == AppStackPage::Achievements.as_str() => {
+                Some(x)
+                    if x == AppStackPage::Achievements.as_str()
+                        || x == AppStackPage::NoAchievements.as_str() =>
+                {
                     app_achievements_button.set_active(true);
                     app_stats_button.set_active(false);
                     app_achievements_button.set_sensitive(true);
@@ -252,6 +412,16 @@ pub fn create_app_view(
                     app_stats_button.set_sensitive(true);
                 }
             }
+            show_availability(
+                &app_achievements_button,
+                app_achievements_model.n_items() > 0,
+                &tr!("This game has no achievements"),
+            );
+            show_availability(
+                &app_stats_button,
+                app_stat_model.n_items() > 0,
+                &tr!("This game has no stats"),
+            );
         }
     ));
 
@@ -263,7 +433,7 @@ pub fn create_app_view(
         app_achievements_model,
         move |_| {
             if app_achievements_model.n_items() == 0 {
-                app_stack.set_visible_child_name(AppStackPage::Empty.as_str());
+                app_stack.set_visible_child_name(AppStackPage::NoAchievements.as_str());
             } else {
                 app_stack.set_visible_child_name(AppStackPage::Achievements.as_str());
             }
@@ -276,22 +446,16 @@ pub fn create_app_view(
         app_stat_model,
         move |_| {
             if app_stat_model.n_items() == 0 {
-                app_stack.set_visible_child_name(AppStackPage::Empty.as_str());
+                app_stack.set_visible_child_name(AppStackPage::NoStats.as_str());
             } else {
                 app_stack.set_visible_child_name(AppStackPage::Stats.as_str());
             }
         }
     ));
 
-    // App pane layout
-    let app_pane = Paned::builder()
-        .orientation(Orientation::Horizontal)
-        .shrink_start_child(false)
-        .shrink_end_child(false)
-        .resize_start_child(false)
-        .start_child(&app_sidebar)
-        .end_child(&app_stack)
-        .build();
+    // App pane layout, with a "show_sidebar" action to fold the sidebar away
+    let show_sidebar_action = SimpleAction::new_stateful("show_sidebar", None, &true.to_variant());
+    let app_pane = create_app_pane(&app_sidebar, &app_stack, &show_sidebar_action);
 
     // Return relevant widgets that need to be accessed from outside
     (
@@ -306,7 +470,7 @@ pub fn create_app_view(
         app_developer_value,
         app_metacritic_value,
         app_metacritic_box,
-        app_sidebar,
+        show_sidebar_action,
         app_achievements_model,
         app_achievement_string_filter,
         app_stat_model,
@@ -317,5 +481,7 @@ pub fn create_app_view(
         achievements_manual_start,
         cancel_timed_unlock,
         app_achievements_stack,
+        achievements_total_progress,
+        app_loading_label,
     )
 }
@@ -13,16 +13,41 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{dev_println, frontend::MainApplication};
-use gtk::{AboutDialog, gio::SimpleAction, prelude::*};
+use crate::{
+    dev_println,
+    frontend::{
+        MainApplication,
+        bug_report::report_bug,
+        request::{Request, SetDryRun},
+    },
+    tr,
+};
+use gtk::{
+    AboutDialog, ApplicationWindow, SearchEntry,
+    gio::{SimpleAction, spawn_blocking},
+    glib::{MainContext, Variant},
+    prelude::*,
+};
 
-/// Set up application actions and keyboard shortcuts.
+/// Window title, flagging dry-run mode so it can't be forgotten.
+fn window_title(dry_run: bool) -> String {
+    if dry_run {
+        tr!("SamRewritten (dry run)")
+    } else {
+        "SamRewritten".to_string()
+    }
+}
+
+/// Set up the actions of one main window, plus the application-wide ones and shortcuts.
+/// Window actions live under the `win.` prefix so several windows can coexist.
 pub fn setup_app_actions(
     application: &MainApplication,
+    window: &ApplicationWindow,
     about_dialog: &AboutDialog,
     refresh_app_list_action: &SimpleAction,
     refresh_achievements_list_action: &SimpleAction,
     reset_all_stats_and_achievements_action: &SimpleAction,
+    dry_run: bool,
 ) {
     let action_show_about_dialog = SimpleAction::new("about", None);
     let about_dialog_clone = about_dialog.clone();
@@ -30,10 +55,10 @@ pub fn setup_app_actions(
         about_dialog_clone.present();
     });
 
-    let action_quit = SimpleAction::new("quit", None);
-    let app_clone = application.clone();
-    action_quit.connect_activate(move |_, _| {
-        app_clone.quit();
+    let action_report_bug = SimpleAction::new("report_bug", None);
+    let window_clone = window.clone();
+    action_report_bug.connect_activate(move |_, _| {
+        report_bug(&window_clone);
     });
 
     [
@@ -41,20 +66,82 @@ pub fn setup_app_actions(
         refresh_achievements_list_action,
         reset_all_stats_and_achievements_action,
         &action_show_about_dialog,
-        &action_quit,
+        &action_report_bug,
     ]
     .iter()
-    .for_each(|action| application.add_action(*action));
+    .for_each(|action| window.add_action(*action));
+
+    // Application-wide actions only need registering for the first window
+    if let Some(action_dry_run) = application.lookup_action("dry_run") {
+        let dry_run = action_dry_run
+            .state()
+            .and_then(|state| state.get::<bool>())
+            .unwrap_or(false);
+        window.set_title(Some(&window_title(dry_run)));
+        return;
+    }
+    window.set_title(Some(&window_title(dry_run)));
+
+    let action_quit = SimpleAction::new("quit", None);
+    let app_clone = application.clone();
+    action_quit.connect_activate(move |_, _| {
+        app_clone.quit();
+    });
+    application.add_action(&action_quit);
+
+    // The orchestrator was started with --dry-run already when the flag was given
+    let action_dry_run = SimpleAction::new_stateful("dry_run", None, &dry_run.to_variant());
+    let app_clone = application.clone();
+    action_dry_run.connect_change_state(move |action, state| {
+        let Some(enabled) = state.and_then(Variant::get::<bool>) else {
+            return;
+        };
+        let handle = spawn_blocking(move || SetDryRun { enabled }.request());
+        let action = action.clone();
+        let app_clone = app_clone.clone();
+        MainContext::default().spawn_local(async move {
+            match handle.await {
+                Ok(Ok(_)) => {
+                    action.set_state(&enabled.to_variant());
+                    for window in app_clone.windows() {
+                        window.set_title(Some(&window_title(enabled)));
+                    }
+                }
+                Ok(Err(e)) => eprintln!("[CLIENT] Failed to switch dry-run mode: {e}"),
+                Err(_) => eprintln!("[CLIENT] Threading task failed"),
+            }
+        });
+    });
+    application.add_action(&action_dry_run);
 
     // Assign F5 to both refresh actions
-    ["app.refresh_app_list", "app.refresh_achievements_list"]
+    ["win.refresh_app_list", "win.refresh_achievements_list"]
         .iter()
         .for_each(|accel| application.set_accels_for_action(accel, &["F5"]));
+    application.set_accels_for_action("win.focus_search", &["<Control>f"]);
+    application.set_accels_for_action("win.show_sidebar", &["F9"]);
+}
+
+/// Set up the search shortcuts of a main window: Ctrl+F focuses the header bar's search
+/// entry, in the library as in an app, and Escape clears it and gives the focus back.
+pub fn setup_search_actions(window: &ApplicationWindow, search_entry: &SearchEntry) {
+    let action_focus_search = SimpleAction::new("focus_search", None);
+    let search_entry_clone = search_entry.clone();
+    action_focus_search.connect_activate(move |_, _| {
+        search_entry_clone.grab_focus();
+    });
+    window.add_action(&action_focus_search);
+
+    let window_clone = window.clone();
+    search_entry.connect_stop_search(move |search_entry| {
+        search_entry.set_text("");
+        GtkWindowExt::set_focus(&window_clone, None::<&gtk::Widget>);
+    });
 }
 
-/// Enable or disable a named application action.
-pub fn set_app_action_enabled(application: &MainApplication, action_name: &str, enabled: bool) {
-    match application.lookup_action(action_name) {
+/// Enable or disable a named action of a main window.
+pub fn set_app_action_enabled(window: &ApplicationWindow, action_name: &str, enabled: bool) {
+    match window.lookup_action(action_name) {
         Some(action) => {
             if let Some(simple_action) = action.downcast_ref::<SimpleAction>() {
                 simple_action.set_enabled(enabled);
@@ -0,0 +1,346 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Selection mode of the library: apps are ticked in the list, then exported or reset
+// together. A reset goes through the same commands as the app view, one app at a time,
+// launching each app server and letting it go afterwards.
+
+use crate::frontend::{
+    library_export::export_completion,
+    request::{LaunchApp, Request, ResetStats, StopApp},
+    steam_app::GSteamAppObject,
+};
+use crate::utils::ipc_types::SamError;
+use crate::{tr, trn};
+use gtk::{
+    ActionBar, Align, ApplicationWindow, Box, Button, CheckButton, Label, Orientation, PolicyType,
+    ProgressBar, ScrolledWindow, ToggleButton, Window,
+    gio::{ListStore, spawn_blocking},
+    glib::{self, MainContext, clone},
+    pango::EllipsizeMode,
+    prelude::*,
+};
+use std::{cell::Cell, rc::Rc};
+
+/// The ticked apps of the library, as `(app id, name)`.
+fn selected_apps(list_store: &ListStore) -> Vec<(u32, String)> {
+    list_store
+        .iter::<GSteamAppObject>()
+        .filter_map(Result::ok)
+        .filter(GSteamAppObject::is_selected)
+        .map(|app| (app.app_id(), app.app_name()))
+        .collect()
+}
+
+/// Creates the bar shown at the bottom of the library in selection mode, revealed while
+/// `select_button` is active. Leaving selection mode unticks every app. The returned
+/// callback updates the bar, rows call it when they are ticked.
+pub fn create_selection_bar(
+    window: &ApplicationWindow,
+    list_store: &ListStore,
+    select_button: &ToggleButton,
+) -> (ActionBar, Rc<dyn Fn()>) {
+    let count_label = Label::new(None);
+    let export_button = Button::builder()
+        .label(tr!("Export completion..."))
+        .sensitive(false)
+        .build();
+    let reset_button = Button::builder()
+        .label(tr!("Reset..."))
+        .css_classes(["destructive-action"])
+        .sensitive(false)
+        .build();
+    let selection_bar = ActionBar::builder().revealed(false).build();
+    selection_bar.pack_start(&count_label);
+    selection_bar.pack_end(&reset_button);
+    selection_bar.pack_end(&export_button);
+
+    let on_selection_changed: Rc<dyn Fn()> = Rc::new(clone!(
+        #[weak]
+        list_store,
+        #[weak]
+        count_label,
+        #[weak]
+        export_button,
+        #[weak]
+        reset_button,
+        move || {
+            let count = selected_apps(&list_store).len();
+            count_label.set_label(&trn!(
+                "{count} app selected",
+                "{count} apps selected",
+                count,
+                count = count
+            ));
+            export_button.set_sensitive(count > 0);
+            reset_button.set_sensitive(count > 0);
+        }
+    ));
+    on_selection_changed();
+
+    select_button.connect_toggled(clone!(
+        #[weak]
+        list_store,
+        #[weak]
+        selection_bar,
+        #[strong]
+        on_selection_changed,
+        move |button| {
+            selection_bar.set_revealed(button.is_active());
+            if !button.is_active() {
+                for app in list_store.iter::<GSteamAppObject>().filter_map(Result::ok) {
+                    app.set_is_selected(false);
+                }
+                on_selection_changed();
+            }
+        }
+    ));
+    export_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        list_store,
+        move |_| export_completion(&window, selected_apps(&list_store))
+    ));
+    reset_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        list_store,
+        move |_| confirm_batch_reset(&window, selected_apps(&list_store))
+    ));
+
+    (selection_bar, on_selection_changed)
+}
+
+/// Lists the apps about to be reset, and only enables the reset once the user ticked
+/// that they understand it can't be undone.
+fn confirm_batch_reset(parent: &ApplicationWindow, apps: Vec<(u32, String)>) {
+    if apps.is_empty() {
+        return;
+    }
+
+    let window = Window::builder()
+        .title(tr!("Reset selected apps"))
+        .transient_for(parent)
+        .modal(true)
+        .default_width(420)
+        .default_height(420)
+        .build();
+
+    let warning_label = Label::builder()
+        .label(trn!(
+            "Every achievement and stat of this {count} app will be reset on your Steam account.",
+            "Every achievement and stat of these {count} apps will be reset on your Steam account.",
+            apps.len(),
+            count = apps.len()
+        ))
+        .halign(Align::Start)
+        .wrap(true)
+        .build();
+    let app_list = Box::builder().orientation(Orientation::Vertical).build();
+    for (_, name) in &apps {
+        let label = Label::builder()
+            .label(name)
+            .halign(Align::Start)
+            .ellipsize(EllipsizeMode::End)
+            .build();
+        app_list.append(&label);
+    }
+    let scrolled_window = ScrolledWindow::builder()
+        .hscrollbar_policy(PolicyType::Never)
+        .vexpand(true)
+        .child(&app_list)
+        .build();
+    let understood_check = CheckButton::builder()
+        .label(tr!("I understand this cannot be undone"))
+        .build();
+
+    let cancel_button = Button::builder().label(tr!("Cancel")).build();
+    let reset_button = Button::builder()
+        .label(tr!("Reset"))
+        .css_classes(["destructive-action"])
+        .sensitive(false)
+        .build();
+    let button_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .halign(Align::End)
+        .build();
+    button_box.append(&cancel_button);
+    button_box.append(&reset_button);
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+    content.append(&warning_label);
+    content.append(&scrolled_window);
+    content.append(&understood_check);
+    content.append(&button_box);
+    window.set_child(Some(&content));
+
+    understood_check.connect_toggled(clone!(
+        #[weak]
+        reset_button,
+        move |check| reset_button.set_sensitive(check.is_active())
+    ));
+    cancel_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        move |_| window.close()
+    ));
+    reset_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        parent,
+        move |_| {
+            window.close();
+            run_batch_reset(&parent, apps.clone());
+        }
+    ));
+    window.present();
+}
+
+/// Resets one app the way the app view does: its app server is launched for the reset,
+/// and let go afterwards unless it was already open.
+fn reset_app(app_id: u32) -> Result<(), SamError> {
+    let launched = LaunchApp { app_id }.request().is_ok();
+    let result = ResetStats {
+        app_id,
+        achievements_too: true,
+    }
+    .request();
+    if launched && let Err(e) = (StopApp { app_id }).request() {
+        eprintln!("[CLIENT] Failed to stop app {app_id} after resetting it: {e}");
+    }
+    match result {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(SamError::StoreFailed),
+        Err(e) => Err(e),
+    }
+}
+
+fn run_batch_reset(parent: &ApplicationWindow, apps: Vec<(u32, String)>) {
+    let window = Window::builder()
+        .title(tr!("Reset selected apps"))
+        .transient_for(parent)
+        .modal(true)
+        .resizable(false)
+        .default_width(360)
+        .build();
+
+    let status_label = Label::builder().halign(Align::Start).wrap(true).build();
+    let progress_bar = ProgressBar::new();
+    let failures_label = Label::builder()
+        .halign(Align::Start)
+        .wrap(true)
+        .css_classes(["error"])
+        .visible(false)
+        .build();
+    let cancel_button = Button::builder()
+        .label(tr!("Cancel"))
+        .halign(Align::End)
+        .margin_top(8)
+        .build();
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+    content.append(&status_label);
+    content.append(&progress_bar);
+    content.append(&failures_label);
+    content.append(&cancel_button);
+    window.set_child(Some(&content));
+
+    // Cancelling or closing the window stops before the next app, the current one finishes
+    let cancelled = Rc::new(Cell::new(false));
+    cancel_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        move |_| window.close()
+    ));
+    window.connect_close_request(clone!(
+        #[strong]
+        cancelled,
+        move |_| {
+            cancelled.set(true);
+            glib::Propagation::Proceed
+        }
+    ));
+    window.present();
+
+    MainContext::default().spawn_local(clone!(
+        #[weak]
+        status_label,
+        #[weak]
+        progress_bar,
+        #[weak]
+        failures_label,
+        #[weak]
+        cancel_button,
+        async move {
+            let mut failed = Vec::new();
+            for (index, (app_id, name)) in apps.iter().enumerate() {
+                status_label.set_label(&tr!(
+                    "Resetting {name} ({done} of {total})...",
+                    name = name,
+                    done = index + 1,
+                    total = apps.len()
+                ));
+                let app_id = *app_id;
+                match spawn_blocking(move || reset_app(app_id)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        eprintln!("[CLIENT] Failed to reset app {app_id}: {e}");
+                        failed.push(name.clone());
+                    }
+                    Err(_) => {
+                        log::error!("[CLIENT] Threading task failed");
+                        return;
+                    }
+                }
+                if cancelled.get() {
+                    return;
+                }
+                progress_bar.set_fraction((index + 1) as f64 / apps.len() as f64);
+            }
+
+            let reset = apps.len() - failed.len();
+            status_label.set_label(&trn!(
+                "Reset {count} app.",
+                "Reset {count} apps.",
+                reset,
+                count = reset
+            ));
+            if !failed.is_empty() {
+                failures_label
+                    .set_label(&tr!("Could not reset: {names}", names = failed.join(", ")));
+                failures_label.set_visible(true);
+            }
+            cancel_button.set_label(&tr!("Close"));
+        }
+    ));
+}
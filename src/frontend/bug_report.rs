@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::frontend::request::{GetDiagnostics, Request};
+use crate::utils::{
+    format::format_unlock_time,
+    ipc_types::{Diagnostics, RecentError, SamError},
+    log_buffer::recent_log_lines,
+};
+use gtk::{
+    ApplicationWindow, UriLauncher,
+    gio::{Cancellable, spawn_blocking},
+    glib::{self, MainContext, clone},
+    prelude::*,
+};
+use std::fmt::Write;
+
+const NEW_ISSUE_URL: &str = "https://github.com/PaulCombal/SamRewritten/issues/new";
+
+/// Log lines included in the report.
+const REPORT_LOG_LINES: usize = 30;
+
+/// Browsers and GitHub reject longer URLs, the body then only goes to the clipboard.
+const MAX_ISSUE_URL_LENGTH: usize = 7000;
+
+fn format_app_ids(app_ids: &[u32]) -> String {
+    if app_ids.is_empty() {
+        return "none".to_string();
+    }
+    app_ids
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Lists the recent errors in a section GitHub shows collapsed.
+fn write_recent_errors(report: &mut String, recent_errors: &[RecentError]) {
+    let _ = writeln!(
+        report,
+        "\n<details>\n<summary>Recent errors ({})</summary>\n\n```",
+        recent_errors.len()
+    );
+    for recent_error in recent_errors {
+        let time = format_unlock_time(recent_error.time).unwrap_or_default();
+        let _ = writeln!(report, "{time} {}: {}", recent_error.command, recent_error.error);
+    }
+    report.push_str("```\n\n</details>\n");
+}
+
+/// Builds the Markdown body of a bug report from the backend state and recent logs.
+fn build_bug_report(diagnostics: &Result<Diagnostics, SamError>) -> String {
+    let build = if cfg!(debug_assertions) { "debug" } else { "release" };
+    let os = glib::os_info("PRETTY_NAME")
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| std::env::consts::OS.to_string());
+
+    let mut report = String::new();
+    report.push_str("### Describe the bug\n\n<!-- What happened, and what did you expect? -->\n\n");
+    report.push_str("### Environment\n\n");
+    let _ = writeln!(report, "- SamRewritten: {} ({build})", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "- OS: {os} ({})", std::env::consts::ARCH);
+    let _ = writeln!(
+        report,
+        "- GTK: {}.{}.{}",
+        gtk::major_version(),
+        gtk::minor_version(),
+        gtk::micro_version()
+    );
+    match diagnostics {
+        Ok(diagnostics) => {
+            let connected = if diagnostics.steam_connected { "yes" } else { "no" };
+            let _ = writeln!(report, "- Steam connected: {connected}");
+            let _ = writeln!(
+                report,
+                "- steamclient: {}",
+                diagnostics.steamclient_path.as_deref().unwrap_or("not found")
+            );
+            let _ = writeln!(report, "- Running apps: {}", format_app_ids(&diagnostics.running_apps));
+            let _ = writeln!(report, "- Kept running apps: {}", format_app_ids(&diagnostics.parked_apps));
+            if !diagnostics.schema_mismatches.is_empty() {
+                let mismatches: Vec<String> = diagnostics
+                    .schema_mismatches
+                    .iter()
+                    .map(|(app_id, count)| format!("{app_id} ({count} achievements)"))
+                    .collect();
+                let _ = writeln!(report, "- Outdated schemas: {}", mismatches.join(", "));
+            }
+            if !diagnostics.recent_errors.is_empty() {
+                write_recent_errors(&mut report, &diagnostics.recent_errors);
+            }
+        }
+        Err(error) => {
+            let _ = writeln!(report, "- Steam connected: no ({error})");
+        }
+    }
+
+    report.push_str("\n### Recent log\n\n```\n");
+    for line in recent_log_lines(REPORT_LOG_LINES) {
+        report.push_str(&line);
+        report.push('\n');
+    }
+    report.push_str("```\n");
+    report
+}
+
+/// Gathers diagnostics, copies the resulting bug report to the clipboard and opens
+/// a new issue on the tracker with the report prefilled.
+pub fn report_bug(window: &ApplicationWindow) {
+    let handle = spawn_blocking(|| GetDiagnostics.request());
+    MainContext::default().spawn_local(clone!(
+        #[weak]
+        window,
+        async move {
+            let diagnostics = handle.await.unwrap_or_else(|_| {
+                eprintln!("[CLIENT] Threading task failed");
+                Err(SamError::UnknownError)
+            });
+            let report = build_bug_report(&diagnostics);
+            window.clipboard().set_text(&report);
+
+            let prefilled_url = format!(
+                "{NEW_ISSUE_URL}?body={}",
+                glib::Uri::escape_string(&report, None, false)
+            );
+            let url = if prefilled_url.len() <= MAX_ISSUE_URL_LENGTH {
+                prefilled_url
+            } else {
+                NEW_ISSUE_URL.to_string()
+            };
+            UriLauncher::new(&url).launch(Some(&window), Cancellable::NONE, |result| {
+                if let Err(e) = result {
+                    log::error!("[CLIENT] Failed to open the issue tracker: {e}");
+                }
+            });
+        }
+    ));
+}
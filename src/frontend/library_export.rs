@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Exports the completion of the whole library to a CSV file, from the achievement summaries
+// the orchestrator reads off the schemas and stats files Steam keeps on disk.
+
+use crate::backend::stat_definitions::AchievementSummary;
+use crate::frontend::{
+    request::{GetAchievementSummaries, Request},
+    steam_app::GSteamAppObject,
+};
+use crate::{tr, trn};
+use gtk::{
+    Align, ApplicationWindow, Box, Button, FileDialog, Label, Orientation, ProgressBar, Window,
+    gio::{Cancellable, ListStore, spawn_blocking},
+    glib::{self, MainContext, clone},
+    prelude::*,
+};
+use std::{cell::Cell, collections::HashMap, fmt::Write, path::PathBuf, rc::Rc};
+
+/// Apps summarized per request, so progress shows and cancelling takes effect quickly.
+const SUMMARIES_PER_REQUEST: usize = 50;
+
+fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// One line per app. Counts Steam has no local data for are left empty.
+fn build_completion_csv(
+    apps: &[(u32, String)],
+    summaries: &HashMap<u32, AchievementSummary>,
+) -> String {
+    let mut csv = String::from("app_id,name,unlocked,total,percent\n");
+    for (app_id, name) in apps {
+        let summary = summaries.get(app_id);
+        let total = summary.map(|summary| summary.achievements);
+        let unlocked = summary.and_then(|summary| summary.unlocked);
+        let percent = match (unlocked, total) {
+            (Some(unlocked), Some(total)) if total > 0 => {
+                format!("{:.1}", unlocked as f64 * 100.0 / total as f64)
+            }
+            _ => String::new(),
+        };
+        let _ = writeln!(
+            csv,
+            "{app_id},{},{},{},{percent}",
+            escape_csv_field(name),
+            unlocked
+                .map(|unlocked| unlocked.to_string())
+                .unwrap_or_default(),
+            total.map(|total| total.to_string()).unwrap_or_default(),
+        );
+    }
+    csv
+}
+
+/// Asks where to save, then summarizes every app of the library into a CSV file.
+pub fn export_library_completion(window: &ApplicationWindow, list_store: &ListStore) {
+    let apps: Vec<(u32, String)> = list_store
+        .iter::<GSteamAppObject>()
+        .filter_map(Result::ok)
+        .map(|app| (app.app_id(), app.app_name()))
+        .collect();
+    export_completion(window, apps);
+}
+
+/// Asks where to save, then summarizes the given apps, as `(app id, name)`, into a CSV file.
+pub fn export_completion(window: &ApplicationWindow, apps: Vec<(u32, String)>) {
+    if apps.is_empty() {
+        return;
+    }
+
+    let dialog = FileDialog::builder()
+        .title(tr!("Export library completion"))
+        .modal(true)
+        .initial_name("library-completion.csv")
+        .build();
+    dialog.save(
+        Some(window),
+        Cancellable::NONE,
+        clone!(
+            #[weak]
+            window,
+            move |result| {
+                if let Some(path) = result.ok().and_then(|file| file.path()) {
+                    show_export_progress(&window, apps, path);
+                }
+            }
+        ),
+    );
+}
+
+fn show_export_progress(parent: &ApplicationWindow, apps: Vec<(u32, String)>, path: PathBuf) {
+    let window = Window::builder()
+        .title(tr!("Export library completion"))
+        .transient_for(parent)
+        .modal(true)
+        .resizable(false)
+        .default_width(360)
+        .build();
+
+    let status_label = Label::builder()
+        .label(tr!("Reading achievement summaries..."))
+        .halign(Align::Start)
+        .wrap(true)
+        .build();
+    let progress_bar = ProgressBar::new();
+    let cancel_button = Button::builder()
+        .label(tr!("Cancel"))
+        .halign(Align::End)
+        .margin_top(8)
+        .build();
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+    content.append(&status_label);
+    content.append(&progress_bar);
+    content.append(&cancel_button);
+    window.set_child(Some(&content));
+
+    // Cancelling or closing the window stops the export before the next request
+    let cancelled = Rc::new(Cell::new(false));
+    cancel_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        move |_| window.close()
+    ));
+    window.connect_close_request(clone!(
+        #[strong]
+        cancelled,
+        move |_| {
+            cancelled.set(true);
+            glib::Propagation::Proceed
+        }
+    ));
+    window.present();
+
+    MainContext::default().spawn_local(clone!(
+        #[weak]
+        status_label,
+        #[weak]
+        progress_bar,
+        #[weak]
+        cancel_button,
+        async move {
+            let mut summaries = HashMap::with_capacity(apps.len());
+            for (index, chunk) in apps.chunks(SUMMARIES_PER_REQUEST).enumerate() {
+                let app_ids: Vec<u32> = chunk.iter().map(|(app_id, _)| *app_id).collect();
+                let handle = spawn_blocking(move || GetAchievementSummaries { app_ids }.request());
+                let chunk_summaries = match handle.await {
+                    Ok(Ok(chunk_summaries)) => chunk_summaries,
+                    Ok(Err(e)) => {
+                        eprintln!("[CLIENT] Failed to get achievement summaries: {e}");
+                        status_label.set_css_classes(&["error"]);
+                        status_label.set_label(&tr!("Could not read the achievement summaries."));
+                        cancel_button.set_label(&tr!("Close"));
+                        return;
+                    }
+                    Err(_) => {
+                        log::error!("[CLIENT] Threading task failed");
+                        return;
+                    }
+                };
+                if cancelled.get() {
+                    return;
+                }
+                summaries.extend(
+                    chunk_summaries
+                        .into_iter()
+                        .filter_map(|(app_id, summary)| Some((app_id, summary?))),
+                );
+                let done = (index * SUMMARIES_PER_REQUEST + chunk.len()) as f64;
+                progress_bar.set_fraction(done / apps.len() as f64);
+            }
+
+            let csv = build_completion_csv(&apps, &summaries);
+            let written = spawn_blocking(move || std::fs::write(&path, csv)).await;
+            match written {
+                Ok(Ok(())) => {
+                    status_label.set_label(&trn!(
+                        "Exported the completion of {count} app.",
+                        "Exported the completion of {count} apps.",
+                        apps.len(),
+                        count = apps.len()
+                    ));
+                }
+                Ok(Err(e)) => {
+                    eprintln!("[CLIENT] Failed to write the library completion: {e}");
+                    status_label.set_css_classes(&["error"]);
+                    status_label.set_label(&tr!("Could not write the file."));
+                }
+                Err(_) => log::error!("[CLIENT] Threading task failed"),
+            }
+            cancel_button.set_label(&tr!("Close"));
+        }
+    ));
+}
@@ -14,36 +14,52 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 // --- Standard Library Imports ---
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
 
 // --- External Crate Imports ---
 use gtk::{glib::ExitCode, prelude::*};
+use interprocess::unnamed_pipe::Sender;
 use once_cell::sync::Lazy;
 
 // --- Internal Crate Imports ---
 use crate::APP_ID;
 use crate::frontend::request::Request;
 use crate::utils::bidir_child::BidirChild;
+use crate::utils::i18n::init_i18n;
+use crate::utils::log_buffer::init_logger;
 use app_list_view::create_main_ui;
+use ui_components::load_css;
 
 /// Global state for the orchestrator process.
 pub static DEFAULT_PROCESS: Lazy<RwLock<Option<BidirChild>>> = Lazy::new(|| RwLock::new(None));
 
+/// Second sender to the orchestrator, for commands sent while a request holds
+/// `DEFAULT_PROCESS`.
+pub static INTERRUPT_SENDER: Lazy<Mutex<Option<Sender>>> = Lazy::new(|| Mutex::new(None));
+
 // --- Module Declarations (alphabetical) ---
 mod achievement;
 mod achievement_automatic_view;
 mod achievement_manual_view;
+mod achievement_search;
 mod achievement_view;
 mod app_list_view;
 mod app_list_view_callbacks;
 mod app_view;
 mod application_actions;
+mod batch_operations;
+mod bug_report;
 mod custom_progress_bar_widget;
+mod library_export;
+mod profile_compare;
+mod recent_apps;
 mod request;
 mod shimmer_image;
 mod stat;
 mod stat_view;
 mod steam_app;
+mod steamclient_picker;
+mod toast;
 mod ui_components;
 
 // --- Main Application Logic ---
@@ -79,6 +95,17 @@ pub type MainApplication = adw::Application;
 
 /// Entry point for the main UI, sets up the application and event loop.
 pub fn main_ui(orchestrator: BidirChild) -> ExitCode {
+    init_i18n();
+    init_logger();
+
+    match orchestrator.try_clone_tx() {
+        Ok(sender) => {
+            if let Ok(mut guard) = INTERRUPT_SENDER.lock() {
+                *guard = Some(sender);
+            }
+        }
+        Err(e) => log::error!("[CLIENT] Failed to clone the orchestrator pipe: {e}"),
+    }
     match DEFAULT_PROCESS.write() {
         Ok(mut guard) => {
             *guard = Some(orchestrator);
@@ -130,7 +157,8 @@ pub fn main_ui(orchestrator: BidirChild) -> ExitCode {
         }
     }
 
-    main_app.connect_command_line(|app, cmd| create_main_ui(app, cmd));
+    main_app.connect_startup(|_| load_css());
+    main_app.connect_command_line(create_main_ui);
     main_app.connect_shutdown(move |_| shutdown());
     main_app.run()
 }
@@ -0,0 +1,228 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Read-only comparison of the current app's achievements with another Steam profile.
+// The other profile's state only lives on the achievement objects, nothing is written.
+
+use crate::frontend::{
+    achievement::GAchievementObject,
+    request::{GetUserAchievements, Request},
+};
+use crate::tr;
+use crate::utils::ipc_types::SamError;
+use gtk::{
+    Align, ApplicationWindow, Box, Button, Entry, Label, Orientation, Window,
+    gio::{ListStore, spawn_blocking},
+    glib::{self, MainContext, clone},
+    prelude::*,
+};
+use std::collections::HashMap;
+
+/// SteamID64 of the first individual account, lower ones are groups or invalid.
+const FIRST_INDIVIDUAL_STEAM_ID: u64 = 76561197960265728;
+
+/// Reads a SteamID64, either as is or from a `steamcommunity.com/profiles/...` URL.
+fn parse_steam_id(input: &str) -> Option<u64> {
+    let input = input.trim().trim_end_matches('/');
+    let id = match input.rsplit_once("/profiles/") {
+        Some((_, id)) => id,
+        None => input,
+    };
+    id.parse::<u64>()
+        .ok()
+        .filter(|id| *id >= FIRST_INDIVIDUAL_STEAM_ID)
+}
+
+fn achievement_objects(model: &ListStore) -> impl Iterator<Item = GAchievementObject> + '_ {
+    model.iter::<GAchievementObject>().filter_map(Result::ok)
+}
+
+/// Shows the other profile's state next to each achievement and returns how many
+/// of the listed achievements they unlocked.
+fn apply_comparison(model: &ListStore, compared: &HashMap<String, bool>) -> usize {
+    let mut unlocked = 0;
+    for achievement in achievement_objects(model) {
+        match compared.get(&achievement.id()) {
+            Some(is_achieved) => {
+                achievement.set_compared_achieved(*is_achieved);
+                achievement.set_is_compared(true);
+                unlocked += usize::from(*is_achieved);
+            }
+            None => achievement.set_is_compared(false),
+        }
+    }
+    unlocked
+}
+
+fn clear_comparison(model: &ListStore) {
+    for achievement in achievement_objects(model) {
+        achievement.set_is_compared(false);
+    }
+}
+
+/// Asks for a profile, then marks which achievements it unlocked in the current app.
+pub fn show_compare_dialog(parent: &ApplicationWindow, app_id: u32, model: &ListStore) {
+    let window = Window::builder()
+        .title(tr!("Compare with a profile"))
+        .transient_for(parent)
+        .modal(true)
+        .resizable(false)
+        .default_width(360)
+        .build();
+
+    let hint_label = Label::builder()
+        .label(tr!("SteamID64 or profile URL of a public profile"))
+        .halign(Align::Start)
+        .wrap(true)
+        .build();
+    hint_label.add_css_class("dim-label");
+    let entry = Entry::builder()
+        .placeholder_text("https://steamcommunity.com/profiles/7656119...")
+        .build();
+    let status_label = Label::builder()
+        .halign(Align::Start)
+        .wrap(true)
+        .visible(false)
+        .build();
+
+    let stop_button = Button::builder().label(tr!("Stop comparing")).build();
+    stop_button.set_visible(achievement_objects(model).any(|achievement| achievement.is_compared()));
+    let close_button = Button::builder().label(tr!("Close")).build();
+    let compare_button = Button::builder().label(tr!("Compare")).build();
+    compare_button.add_css_class("suggested-action");
+    let buttons_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .halign(Align::End)
+        .margin_top(8)
+        .build();
+    buttons_box.append(&stop_button);
+    buttons_box.append(&close_button);
+    buttons_box.append(&compare_button);
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+    content.append(&hint_label);
+    content.append(&entry);
+    content.append(&status_label);
+    content.append(&buttons_box);
+    window.set_child(Some(&content));
+
+    close_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        move |_| window.close()
+    ));
+
+    stop_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        model,
+        move |_| {
+            clear_comparison(&model);
+            window.close();
+        }
+    ));
+
+    compare_button.connect_clicked(clone!(
+        #[weak]
+        entry,
+        #[weak]
+        status_label,
+        #[weak]
+        stop_button,
+        #[weak]
+        model,
+        move |compare_button| {
+            let Some(steam_id) = parse_steam_id(&entry.text()) else {
+                status_label.set_css_classes(&["error"]);
+                status_label.set_label(&tr!("This is not a valid SteamID64 or profile URL."));
+                status_label.set_visible(true);
+                return;
+            };
+
+            compare_button.set_sensitive(false);
+            status_label.set_css_classes(&["dim-label"]);
+            status_label.set_label(&tr!("Loading the profile's achievements..."));
+            status_label.set_visible(true);
+
+            let handle =
+                spawn_blocking(move || GetUserAchievements { app_id, steam_id }.request());
+            MainContext::default().spawn_local(clone!(
+                #[weak]
+                compare_button,
+                #[weak]
+                status_label,
+                #[weak]
+                stop_button,
+                #[weak]
+                model,
+                async move {
+                    compare_button.set_sensitive(true);
+                    let user_achievements = match handle.await {
+                        Ok(Ok(user_achievements)) => user_achievements,
+                        Ok(Err(SamError::UserStatsUnavailable)) => {
+                            status_label.set_css_classes(&["error"]);
+                            status_label.set_label(&tr!(
+                                "Steam did not share this profile's achievements. Its game details may be private."
+                            ));
+                            return;
+                        }
+                        Ok(Err(e)) => {
+                            log::error!("[CLIENT] Failed to get the profile's achievements: {e}");
+                            status_label.set_css_classes(&["error"]);
+                            status_label
+                                .set_label(&tr!("Could not load the profile's achievements."));
+                            return;
+                        }
+                        Err(_) => {
+                            log::error!("[CLIENT] Threading task failed");
+                            return;
+                        }
+                    };
+
+                    let compared: HashMap<String, bool> = user_achievements
+                        .into_iter()
+                        .map(|achievement| (achievement.id, achievement.is_achieved))
+                        .collect();
+                    let unlocked = apply_comparison(&model, &compared);
+                    status_label.set_css_classes(&[]);
+                    status_label.set_label(&tr!(
+                        "This profile unlocked {unlocked} of {total} achievements.",
+                        unlocked = unlocked,
+                        total = compared.len()
+                    ));
+                    stop_button.set_visible(true);
+                }
+            ));
+        }
+    ));
+
+    entry.connect_activate(clone!(
+        #[weak]
+        compare_button,
+        move |_| compare_button.emit_clicked()
+    ));
+
+    window.present();
+    entry.grab_focus();
+}
@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// The apps last opened in the app view, kept in the settings and shown as buttons above
+// the library, so they can be reopened without searching for them.
+
+use crate::tr;
+use crate::utils::settings::{RecentApp, load_settings, save_settings};
+use gtk::{Align, Box, Button, Label, Orientation, PolicyType, ScrolledWindow, prelude::*};
+use std::rc::Rc;
+
+/// Number of recently managed apps remembered.
+const MAX_RECENT_APPS: usize = 8;
+
+/// Puts an app first in the recently managed apps.
+pub fn record_recent_app(app_id: u32, app_name: &str) {
+    let mut settings = load_settings();
+    if settings
+        .recent_apps
+        .first()
+        .is_some_and(|recent| recent.app_id == app_id)
+    {
+        return;
+    }
+    settings
+        .recent_apps
+        .retain(|recent| recent.app_id != app_id);
+    settings.recent_apps.insert(
+        0,
+        RecentApp {
+            app_id,
+            app_name: app_name.to_owned(),
+        },
+    );
+    settings.recent_apps.truncate(MAX_RECENT_APPS);
+    save_settings(&settings);
+}
+
+/// Creates the bar of recently managed apps, and the box its buttons go in.
+pub fn create_recent_apps_bar() -> (Box, Box) {
+    let title = Label::builder().label(tr!("Recent:")).build();
+    title.add_css_class("dim-label");
+    let buttons_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(6)
+        .build();
+    let buttons_scrolled_window = ScrolledWindow::builder()
+        .hscrollbar_policy(PolicyType::Automatic)
+        .vscrollbar_policy(PolicyType::Never)
+        .hexpand(true)
+        .child(&buttons_box)
+        .build();
+
+    let bar = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .margin_top(6)
+        .margin_bottom(6)
+        .margin_start(8)
+        .margin_end(8)
+        .valign(Align::Center)
+        .visible(false)
+        .build();
+    bar.append(&title);
+    bar.append(&buttons_scrolled_window);
+    (bar, buttons_box)
+}
+
+/// Fills the bar with one button per recently managed app, calling `open` with the app id
+/// of the clicked one. The bar is hidden while there are none.
+pub fn refresh_recent_apps_bar(bar: &Box, buttons_box: &Box, open: Rc<dyn Fn(u32)>) {
+    while let Some(child) = buttons_box.first_child() {
+        buttons_box.remove(&child);
+    }
+
+    let recent_apps = load_settings().recent_apps;
+    for recent in &recent_apps {
+        let button = Button::builder()
+            .label(&recent.app_name)
+            .tooltip_text(tr!("Manage {app_name}", app_name = recent.app_name))
+            .build();
+        let app_id = recent.app_id;
+        let open = open.clone();
+        button.connect_clicked(move |_| open(app_id));
+        buttons_box.append(&button);
+    }
+    bar.set_visible(!recent_apps.is_empty());
+}
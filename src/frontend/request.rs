@@ -14,19 +14,35 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::backend::app_lister::AppModel;
-use crate::backend::stat_definitions::{AchievementInfo, StatInfo};
+use crate::backend::stat_definitions::{
+    AchievementInfo, AchievementMatch, AchievementSummary, SchemaCheck, StatInfo,
+    UserAchievement,
+};
 use crate::dev_println;
-use crate::frontend::DEFAULT_PROCESS;
-use crate::utils::ipc_types::{SamError, SamSerializable, SteamCommand, SteamResponse};
+use crate::frontend::{DEFAULT_PROCESS, INTERRUPT_SENDER};
+use crate::utils::ipc_types::{
+    AchievementResults, Diagnostics, MAX_MESSAGE_LENGTH, SamError, SamSerializable, SteamCommand,
+    SteamResponse,
+};
 use serde::de::DeserializeOwned;
 use std::fmt::Debug;
 use std::io::{Read, Write};
+use std::time::SystemTime;
 
 /// Trait for sending a request to the orchestrator and receiving a typed response.
 pub trait Request: Into<SteamCommand> + Debug + Clone {
     type Response: DeserializeOwned;
 
     fn request(self) -> Result<Self::Response, SamError> {
+        self.request_with_progress(|_, _| {})
+    }
+
+    /// Like `request`, but calls `on_progress(done, total)` for every progress message
+    /// received before the final response.
+    fn request_with_progress(
+        self,
+        mut on_progress: impl FnMut(u32, u32),
+    ) -> Result<Self::Response, SamError> {
         let mut guard = DEFAULT_PROCESS.write().map_err(|e| {
             eprintln!("[CLIENT] Failed to lock DEFAULT_PROCESS: {e}");
             SamError::SocketCommunicationFailed
@@ -40,26 +56,46 @@ pub trait Request: Into<SteamCommand> + Debug + Clone {
                 SamError::SocketCommunicationFailed
             })?;
 
-            let mut buffer_len = [0u8; std::mem::size_of::<usize>()];
-            bidir.rx.read_exact(&mut buffer_len).map_err(|e| {
-                eprintln!("[CLIENT] Error reading length from pipe: {e}");
-                SamError::SocketCommunicationFailed
-            })?;
-
-            let data_length = usize::from_le_bytes(buffer_len);
-            let mut buffer = vec![0u8; data_length];
-            bidir.rx.read_exact(&mut buffer).map_err(|e| {
-                eprintln!("[CLIENT] Error reading message from pipe: {e}");
-                SamError::SocketCommunicationFailed
-            })?;
-
-            let message = String::from_utf8_lossy(&buffer);
-            serde_json::from_str::<SteamResponse<Self::Response>>(&message)
-                .map_err(|error| {
-                    eprintln!("[CLIENT] Response deserialization failed: {error}");
+            loop {
+                let mut buffer_len = [0u8; std::mem::size_of::<usize>()];
+                bidir.rx.read_exact(&mut buffer_len).map_err(|e| {
+                    eprintln!("[CLIENT] Error reading length from pipe: {e}");
+                    SamError::SocketCommunicationFailed
+                })?;
+
+                let data_length = usize::from_le_bytes(buffer_len);
+                if data_length > MAX_MESSAGE_LENGTH {
+                    eprintln!(
+                        "[CLIENT] Response length {data_length} is too large, the pipe is corrupt"
+                    );
+                    return Err(SamError::SocketCommunicationFailed);
+                }
+                let mut buffer = vec![0u8; data_length];
+                bidir.rx.read_exact(&mut buffer).map_err(|e| {
+                    eprintln!("[CLIENT] Error reading message from pipe: {e}");
                     SamError::SocketCommunicationFailed
-                })
-                .and_then(|response| response.into())
+                })?;
+
+                let message = std::str::from_utf8(&buffer).map_err(|error| {
+                    eprintln!("[CLIENT] Response is not UTF-8, the pipe is out of sync: {error}");
+                    SamError::FramingError
+                })?;
+                let response = serde_json::from_str::<SteamResponse<Self::Response>>(message)
+                    .map_err(|error| {
+                        eprintln!("[CLIENT] Response deserialization failed: {error}");
+                        SamError::SocketCommunicationFailed
+                    })?;
+
+                if let SteamResponse::Progress(done, total) = response {
+                    on_progress(done, total);
+                    continue;
+                }
+                if let SteamResponse::Simulated(_) = response {
+                    dev_println!("[CLIENT] Dry run, the command was only simulated");
+                }
+
+                return response.into();
+            }
         } else {
             eprintln!("[CLIENT] No orchestrator process to shutdown");
             Err(SamError::SocketCommunicationFailed)
@@ -67,10 +103,34 @@ pub trait Request: Into<SteamCommand> + Debug + Clone {
     }
 }
 
+/// Asks the orchestrator to stop listing the owned apps, the pending `GetOwnedAppList` then
+/// fails with `Cancelled`. Sent aside from the requests, as one is waiting for the list.
+pub fn cancel_app_list() -> Result<(), SamError> {
+    let mut guard = INTERRUPT_SENDER.lock().map_err(|e| {
+        eprintln!("[CLIENT] Failed to lock INTERRUPT_SENDER: {e}");
+        SamError::SocketCommunicationFailed
+    })?;
+    let Some(sender) = guard.as_mut() else {
+        return Err(SamError::SocketCommunicationFailed);
+    };
+    dev_println!("[CLIENT] Cancelling the app list");
+    sender
+        .write_all(&SteamCommand::CancelAppList.sam_serialize())
+        .map_err(|e| {
+            eprintln!("[CLIENT] Error writing command to pipe: {e}");
+            SamError::SocketCommunicationFailed
+        })
+}
+
 /// Request to get the list of owned apps.
 #[derive(Debug, Clone)]
 pub struct GetOwnedAppList;
 
+/// Request to get the apps installed on this machine, through the whitelisted `ISteamAppList`.
+#[cfg(feature = "installed-apps")]
+#[derive(Debug, Clone)]
+pub struct GetInstalledAppList;
+
 /// Request to shut down the orchestrator.
 #[derive(Debug, Clone)]
 pub struct Shutdown;
@@ -87,15 +147,25 @@ pub struct StopApp {
     pub app_id: u32,
 }
 
-/// Request to stop all running apps.
+/// Request to stop all the apps this instance runs.
 #[derive(Debug, Clone)]
- #[allow(dead_code)]
- pub struct StopApps;
+pub struct StopApps;
 
 /// Request to get achievements for an app.
 #[derive(Debug, Clone)]
 pub struct GetAchievements {
     pub app_id: u32,
+    /// Language of the names, Steam's when `None`.
+    pub language: Option<String>,
+    /// Whether achievements the app server fetched moments ago will do.
+    pub cached: bool,
+}
+
+/// Request to get a single achievement of an app.
+#[derive(Debug, Clone)]
+pub struct GetAchievement {
+    pub app_id: u32,
+    pub achievement_id: String,
 }
 
 /// Request to get stats for an app.
@@ -104,6 +174,13 @@ pub struct GetStats {
     pub app_id: u32,
 }
 
+/// Request to read another user's achievements for an app, by SteamID64.
+#[derive(Debug, Clone)]
+pub struct GetUserAchievements {
+    pub app_id: u32,
+    pub steam_id: u64,
+}
+
 /// Request to set an achievement's unlocked state.
 #[derive(Debug, Clone)]
 pub struct SetAchievement {
@@ -112,6 +189,21 @@ pub struct SetAchievement {
     pub unlocked: bool,
 }
 
+/// Request to set several achievements to the same unlocked state at once.
+#[derive(Debug, Clone)]
+pub struct SetAchievements {
+    pub app_id: u32,
+    pub achievement_ids: Vec<String>,
+    pub unlocked: bool,
+}
+
+/// Request to unlock an achievement by raising the stat it follows.
+#[derive(Debug, Clone)]
+pub struct UnlockAchievementViaStat {
+    pub app_id: u32,
+    pub achievement_id: String,
+}
+
 /// Request to set an integer stat value.
 #[derive(Debug, Clone)]
 pub struct SetIntStat {
@@ -128,6 +220,13 @@ pub struct SetFloatStat {
     pub value: f32,
 }
 
+/// Request to set one stat back to its default, leaving everything else alone.
+#[derive(Debug, Clone)]
+pub struct ResetStat {
+    pub app_id: u32,
+    pub stat_id: String,
+}
+
 /// Request to reset stats (and optionally achievements) for an app.
 #[derive(Debug, Clone)]
 pub struct ResetStats {
@@ -135,10 +234,58 @@ pub struct ResetStats {
     pub achievements_too: bool,
 }
 
+/// Request to count the achievements and stats of several apps.
+#[derive(Debug, Clone)]
+pub struct GetAchievementSummaries {
+    pub app_ids: Vec<u32>,
+}
+
+/// Request to find the achievements of several apps whose name contains a text.
+#[derive(Debug, Clone)]
+pub struct SearchAchievements {
+    pub app_ids: Vec<u32>,
+    pub query: String,
+}
+
+/// Request to turn dry-run mode on or off.
+#[derive(Debug, Clone)]
+pub struct SetDryRun {
+    pub enabled: bool,
+}
+
+/// Request to compare the achievements of an app's schema with Steam's list.
+#[derive(Debug, Clone)]
+pub struct CheckSchema {
+    pub app_id: u32,
+}
+
+/// Request to know when an app last stored its stats.
+#[derive(Debug, Clone)]
+pub struct GetLastStored {
+    pub app_id: u32,
+}
+
+/// Request to drop the Steam connection and every app server, to start afresh.
+#[derive(Debug, Clone)]
+pub struct Reconnect;
+
+/// Request to list the apps some window is managing.
+#[derive(Debug, Clone)]
+pub struct ListRunningApps;
+
+/// Request to gather the backend state for a bug report.
+#[derive(Debug, Clone)]
+pub struct GetDiagnostics;
+
 impl Request for GetOwnedAppList {
     type Response = Vec<AppModel>;
 }
 
+#[cfg(feature = "installed-apps")]
+impl Request for GetInstalledAppList {
+    type Response = Vec<AppModel>;
+}
+
 impl Request for Shutdown {
     type Response = bool;
 }
@@ -159,14 +306,30 @@ impl Request for GetAchievements {
     type Response = Vec<AchievementInfo>;
 }
 
+impl Request for GetAchievement {
+    type Response = AchievementInfo;
+}
+
 impl Request for GetStats {
     type Response = Vec<StatInfo>;
 }
 
+impl Request for GetUserAchievements {
+    type Response = Vec<UserAchievement>;
+}
+
 impl Request for SetAchievement {
     type Response = bool;
 }
 
+impl Request for SetAchievements {
+    type Response = AchievementResults;
+}
+
+impl Request for UnlockAchievementViaStat {
+    type Response = bool;
+}
+
 impl Request for SetIntStat {
     type Response = bool;
 }
@@ -179,68 +342,193 @@ impl Request for ResetStats {
     type Response = bool;
 }
 
-impl Into<SteamCommand> for GetOwnedAppList {
-    fn into(self) -> SteamCommand {
+impl Request for ResetStat {
+    type Response = f64;
+}
+
+impl Request for GetAchievementSummaries {
+    type Response = Vec<(u32, Option<AchievementSummary>)>;
+}
+
+impl Request for SearchAchievements {
+    type Response = Vec<AchievementMatch>;
+}
+
+impl Request for SetDryRun {
+    type Response = bool;
+}
+
+impl Request for GetLastStored {
+    type Response = Option<SystemTime>;
+}
+
+impl Request for CheckSchema {
+    type Response = SchemaCheck;
+}
+
+impl Request for Reconnect {
+    type Response = bool;
+}
+
+impl Request for ListRunningApps {
+    type Response = Vec<u32>;
+}
+
+impl Request for GetDiagnostics {
+    type Response = Diagnostics;
+}
+
+impl From<GetOwnedAppList> for SteamCommand {
+    fn from(_: GetOwnedAppList) -> Self {
         SteamCommand::GetOwnedAppList
     }
 }
 
-impl Into<SteamCommand> for Shutdown {
-    fn into(self) -> SteamCommand {
+#[cfg(feature = "installed-apps")]
+impl From<GetInstalledAppList> for SteamCommand {
+    fn from(_: GetInstalledAppList) -> Self {
+        SteamCommand::GetInstalledAppList
+    }
+}
+
+impl From<Shutdown> for SteamCommand {
+    fn from(_: Shutdown) -> Self {
         SteamCommand::Shutdown
     }
 }
 
-impl Into<SteamCommand> for LaunchApp {
-    fn into(self) -> SteamCommand {
-        SteamCommand::LaunchApp(self.app_id)
+impl From<LaunchApp> for SteamCommand {
+    fn from(request: LaunchApp) -> Self {
+        SteamCommand::LaunchApp(request.app_id)
     }
 }
 
-impl Into<SteamCommand> for StopApp {
-    fn into(self) -> SteamCommand {
-        SteamCommand::StopApp(self.app_id)
+impl From<StopApp> for SteamCommand {
+    fn from(request: StopApp) -> Self {
+        SteamCommand::StopApp(request.app_id)
     }
 }
 
-impl Into<SteamCommand> for StopApps {
-    fn into(self) -> SteamCommand {
+impl From<StopApps> for SteamCommand {
+    fn from(_: StopApps) -> Self {
         SteamCommand::StopApps
     }
 }
 
-impl Into<SteamCommand> for GetAchievements {
-    fn into(self) -> SteamCommand {
-        SteamCommand::GetAchievements(self.app_id)
+impl From<GetAchievements> for SteamCommand {
+    fn from(request: GetAchievements) -> Self {
+        if request.cached {
+            SteamCommand::GetAchievements(request.app_id, request.language)
+        } else {
+            SteamCommand::RefreshAchievements(request.app_id, request.language)
+        }
+    }
+}
+
+impl From<GetAchievement> for SteamCommand {
+    fn from(request: GetAchievement) -> Self {
+        SteamCommand::GetAchievement(request.app_id, request.achievement_id)
+    }
+}
+
+impl From<GetStats> for SteamCommand {
+    fn from(request: GetStats) -> Self {
+        SteamCommand::GetStats(request.app_id)
+    }
+}
+
+impl From<GetUserAchievements> for SteamCommand {
+    fn from(request: GetUserAchievements) -> Self {
+        SteamCommand::GetUserAchievements(request.app_id, request.steam_id)
+    }
+}
+
+impl From<SetAchievement> for SteamCommand {
+    fn from(request: SetAchievement) -> Self {
+        SteamCommand::SetAchievement(request.app_id, request.unlocked, request.achievement_id)
+    }
+}
+
+impl From<SetAchievements> for SteamCommand {
+    fn from(request: SetAchievements) -> Self {
+        SteamCommand::SetAchievements(request.app_id, request.unlocked, request.achievement_ids)
+    }
+}
+
+impl From<UnlockAchievementViaStat> for SteamCommand {
+    fn from(request: UnlockAchievementViaStat) -> Self {
+        SteamCommand::UnlockAchievementViaStat(request.app_id, request.achievement_id)
+    }
+}
+
+impl From<SetIntStat> for SteamCommand {
+    fn from(request: SetIntStat) -> Self {
+        SteamCommand::SetIntStat(request.app_id, request.stat_id, request.value)
+    }
+}
+
+impl From<SetFloatStat> for SteamCommand {
+    fn from(request: SetFloatStat) -> Self {
+        SteamCommand::SetFloatStat(request.app_id, request.stat_id, request.value)
+    }
+}
+
+impl From<ResetStat> for SteamCommand {
+    fn from(request: ResetStat) -> Self {
+        SteamCommand::ResetStat(request.app_id, request.stat_id)
+    }
+}
+
+impl From<ResetStats> for SteamCommand {
+    fn from(request: ResetStats) -> Self {
+        SteamCommand::ResetStats(request.app_id, request.achievements_too)
+    }
+}
+
+impl From<Reconnect> for SteamCommand {
+    fn from(_: Reconnect) -> Self {
+        SteamCommand::Reconnect
+    }
+}
+
+impl From<ListRunningApps> for SteamCommand {
+    fn from(_: ListRunningApps) -> Self {
+        SteamCommand::ListRunningApps
+    }
+}
+
+impl From<GetDiagnostics> for SteamCommand {
+    fn from(_: GetDiagnostics) -> Self {
+        SteamCommand::GetDiagnostics
     }
 }
 
-impl Into<SteamCommand> for GetStats {
-    fn into(self) -> SteamCommand {
-        SteamCommand::GetStats(self.app_id)
+impl From<SetDryRun> for SteamCommand {
+    fn from(request: SetDryRun) -> Self {
+        SteamCommand::SetDryRun(request.enabled)
     }
 }
 
-impl Into<SteamCommand> for SetAchievement {
-    fn into(self) -> SteamCommand {
-        SteamCommand::SetAchievement(self.app_id, self.unlocked, self.achievement_id)
+impl From<CheckSchema> for SteamCommand {
+    fn from(request: CheckSchema) -> Self {
+        SteamCommand::CheckSchema(request.app_id)
     }
 }
 
-impl Into<SteamCommand> for SetIntStat {
-    fn into(self) -> SteamCommand {
-        SteamCommand::SetIntStat(self.app_id, self.stat_id, self.value)
+impl From<GetLastStored> for SteamCommand {
+    fn from(request: GetLastStored) -> Self {
+        SteamCommand::GetLastStored(request.app_id)
     }
 }
 
-impl Into<SteamCommand> for SetFloatStat {
-    fn into(self) -> SteamCommand {
-        SteamCommand::SetFloatStat(self.app_id, self.stat_id, self.value)
+impl From<GetAchievementSummaries> for SteamCommand {
+    fn from(request: GetAchievementSummaries) -> Self {
+        SteamCommand::GetAchievementSummaries(request.app_ids)
     }
 }
 
-impl Into<SteamCommand> for ResetStats {
-    fn into(self) -> SteamCommand {
-        SteamCommand::ResetStats(self.app_id, self.achievements_too)
+impl From<SearchAchievements> for SteamCommand {
+    fn from(request: SearchAchievements) -> Self {
+        SteamCommand::SearchAchievements(request.app_ids, request.query)
     }
 }
@@ -23,62 +23,126 @@ glib::wrapper! {
 }
 
 impl ShimmerImage {
-    /// Create a new ShimmerImage widget.
+    /// Create a new ShimmerImage widget, sized like a Steam small capsule.
     pub fn new() -> Self {
+        Self::with_size(231, 87)
+    }
+
+    /// Create a new ShimmerImage widget with the given size in logical pixels.
+    pub fn with_size(image_width: i32, image_height: i32) -> Self {
         glib::Object::builder()
             .property("url", None::<String>)
+            .property("image_width", image_width)
+            .property("image_height", image_height)
             .build()
     }
 
+    /// Serve images from the cache and local files only, for every ShimmerImage.
+    pub fn set_offline(offline: bool) {
+        imp::OFFLINE.store(offline, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Draw a still placeholder instead of the shimmer while loading, for every ShimmerImage.
+    pub fn set_reduced_motion(reduced: bool) {
+        imp::REDUCED_MOTION.store(reduced, std::sync::atomic::Ordering::Relaxed);
+    }
+
     /// Reset the image state and mark as failed.
     pub fn reset(&self) {
         self.imp().url.borrow_mut().take();
         self.imp().texture.borrow_mut().take();
         self.imp().receiver.borrow_mut().take();
         self.imp().loaded.borrow_mut().take();
+        self.imp().cancel_load();
         self.imp().failed.set(true);
     }
 }
 
 mod imp {
     use crate::dev_println;
+    use crate::utils::app_paths::get_app_cache_dir;
+    use crate::utils::settings::load_settings;
     use glib::Properties;
     use gtk::gdk::{RGBA, Texture};
     use gtk::gio::spawn_blocking;
     use gtk::glib::{self, Bytes, base64_encode};
     use gtk::graphene::{Point, Rect, Size};
-    use gtk::gsk::{ColorStop, LinearGradientNode, RoundedRect};
+    use gtk::gsk::{ColorStop, LinearGradientNode, RoundedRect, ScalingFilter};
     use gtk::prelude::*;
     use gtk::subclass::prelude::*;
-    use reqwest::blocking::Client;
+    use reqwest::{StatusCode, blocking::Client, header::CONTENT_TYPE};
     use std::{
         cell::{Cell, RefCell},
+        collections::HashMap,
         env::temp_dir,
-        fs::{exists, write},
-        sync::mpsc::{Receiver, TryRecvError, sync_channel},
+        fs::{create_dir_all, exists, write},
+        path::PathBuf,
+        future::poll_fn,
+        sync::{
+            Arc, LazyLock, Mutex,
+            atomic::{AtomicBool, Ordering},
+            mpsc::{Receiver, SyncSender, TryRecvError, sync_channel},
+        },
+        task::{Poll, Waker},
     };
 
     const GRADIENT_WIDTH: f32 = 0.8;
     const BASE_COLOR: RGBA = RGBA::new(0.7, 0.7, 0.7, 1.0);
     const HIGHLIGHT_COLOR: RGBA = RGBA::new(0.8, 0.8, 0.8, 1.0);
 
+    const STORE_ASSETS_URL: &str =
+        "https://shared.cloudflare.steamstatic.com/store_item_assets/steam/apps";
+    /// Sharper store capsules, best first. They are not listed in the app data, so each one
+    /// is probed before falling back to the url given by Steam. Portrait art such as
+    /// `library_600x900.jpg` is left out, it would be squashed in a banner.
+    const HIGHER_RES_CAPSULES: [&str; 2] = ["capsule_467x181.jpg", "header.jpg"];
+    /// Width of `capsule_231x87.jpg`, the image listed in the app data.
+    const SMALL_CAPSULE_WIDTH: i32 = 231;
+    /// Extensions of the cached images, see `image_extension`.
+    const IMAGE_EXTENSIONS: [&str; 3] = ["jpg", "png", "webp"];
+    const CAPSULE_CHOICES_FILE_NAME: &str = "capsule_choices.json";
+    /// Folder of the app cache directory the images are kept in, unless set otherwise.
+    const IMAGE_CACHE_FOLDER_NAME: &str = "images";
+
+    /// Set in offline mode, nothing is downloaded then.
+    pub static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+    /// Set when the user asked for less motion, the shimmer is not animated then.
+    pub static REDUCED_MOTION: AtomicBool = AtomicBool::new(false);
+
+    /// Downloads running at once, shared by every ShimmerImage. The others wait their turn.
+    static DOWNLOAD_SLOTS: LazyLock<DownloadSlots> =
+        LazyLock::new(|| DownloadSlots::new(load_settings().max_image_downloads.max(1)));
+
+    /// Capsule picked for each app, so the missing variants are only probed once.
+    static CAPSULE_CHOICES: LazyLock<Mutex<HashMap<u32, String>>> =
+        LazyLock::new(|| Mutex::new(load_capsule_choices()));
+
+    /// Where the downloaded images are kept, see `image_cache_dir`.
+    static IMAGE_CACHE_DIR: LazyLock<PathBuf> = LazyLock::new(image_cache_dir);
+
     /// Internal implementation of ShimmerImage properties and state.
     #[derive(Default, Properties)]
     #[properties(wrapper_type = super::ShimmerImage)]
     pub struct ShimmerImage {
-        #[property(get, set)]
+        /// Size in logical pixels, the texture is picked for the display scale on top of it.
+        #[property(get, set = Self::set_image_width)]
         pub image_width: Cell<i32>,
-        #[property(get, set)]
+        #[property(get, set = Self::set_image_height)]
         pub image_height: Cell<i32>,
         pub start: Cell<i64>,
         pub current: Cell<i64>,
-        #[property(get, set)]
+        #[property(get, set = Self::set_url)]
         pub url: RefCell<Option<String>>,
         #[property(get, set)]
         pub loaded: RefCell<Option<String>>,
         pub failed: Cell<bool>,
         pub receiver: RefCell<Option<Receiver<Texture>>>,
+        /// Set when the image moves on to another url, so the running load gives up.
+        pub cancelled: RefCell<Arc<AtomicBool>>,
         pub texture: RefCell<Option<Texture>>,
+        /// Redraws while an image loads, removed once it is shown or failed.
+        pub tick: RefCell<Option<gtk::TickCallbackId>>,
     }
 
     #[glib::object_subclass]
@@ -95,24 +159,14 @@ mod imp {
             let obj = self.obj();
             obj.reset();
 
-            obj.set_size_request(231, 87);
-            obj.add_tick_callback(|widget, clock| {
-                if let Some(this) = widget.downcast_ref::<super::ShimmerImage>() {
-                    //Enabling this will cause some of the images to retain their old texture
-                    //even if the url property changes, but only if the widget was rendered before
-                    //and then jumps into view while it's contents are still cached.
-                    //if this.imp().texture.borrow().is_none() {
-                    this.queue_draw();
-                    //}
-
-                    let imp = this.imp();
-                    imp.current.set(clock.frame_time());
-                    if imp.start.get() == 0 {
-                        imp.start.set(clock.frame_time());
-                    }
-                }
-                glib::ControlFlow::Continue
+            obj.set_size_request(self.image_width.get(), self.image_height.get());
+            // A sharper image may be needed when moving to a HiDPI display
+            obj.connect_scale_factor_notify(|widget| {
+                widget.imp().loaded.borrow_mut().take();
+                widget.imp().ensure_ticking();
+                widget.queue_draw();
             });
+            self.ensure_ticking();
         }
     }
 
@@ -129,37 +183,26 @@ mod imp {
 
             // Only take the url if it is Some and not already loaded
             let url_opt = self.url.borrow_mut();
-            if let Some(url) = url_opt.as_ref() {
-                if Some(url.as_str()) != self.loaded.borrow().as_deref() {
-                    self.texture.borrow_mut().take();
-                    self.loaded.borrow_mut().take();
-                    self.receiver.borrow_mut().take();
-                    self.load(url.as_str());
-                    self.loaded.borrow_mut().replace(url.clone());
-                }
+            if let Some(url) = url_opt.as_ref()
+                && Some(url.as_str()) != self.loaded.borrow().as_deref()
+            {
+                self.texture.borrow_mut().take();
+                self.loaded.borrow_mut().take();
+                self.receiver.borrow_mut().take();
+                self.load(url.as_str());
+                self.loaded.borrow_mut().replace(url.clone());
             }
 
-            // Only take the receiver if it is Some
-            let mut receiver_opt = self.receiver.borrow_mut();
-            if let Some(receiver) = receiver_opt.as_mut() {
-                match receiver.try_recv() {
-                    Ok(texture) => {
-                        self.texture.borrow_mut().replace(texture);
-                        receiver_opt.take();
-                    }
-                    Err(TryRecvError::Empty) => {}
-                    Err(TryRecvError::Disconnected) => {
-                        self.failed.set(true);
-                        receiver_opt.take();
-                    }
-                }
-            }
+            self.poll_texture();
 
             if self.failed.get() {
                 // TODO: Insert an icon in the middle: insert-image-symbolic
                 snapshot.append_color(&BASE_COLOR, &rect);
             } else if let Some(texture) = &*self.texture.borrow() {
-                snapshot.append_texture(texture, &rect);
+                // Textures are usually larger than the widget on HiDPI displays, scale them smoothly
+                snapshot.append_scaled_texture(texture, ScalingFilter::Trilinear, &rect);
+            } else if !self.animates() {
+                snapshot.append_color(&BASE_COLOR, &rect);
             } else {
                 // convert from continuous microseconds to relative seconds
                 let progress =
@@ -198,7 +241,99 @@ mod imp {
     }
 
     impl ShimmerImage {
+        fn set_url(&self, url: Option<String>) {
+            self.url.replace(url);
+            self.ensure_ticking();
+            self.obj().queue_draw();
+        }
+
+        /// Adds the tick callback unless it is running. It stops by itself once there is
+        /// nothing left to load, so a shown image costs no frame.
+        fn ensure_ticking(&self) {
+            if self.tick.borrow().is_some() {
+                return;
+            }
+            let tick = self.obj().add_tick_callback(|widget, clock| {
+                let imp = widget.imp();
+                imp.current.set(clock.frame_time());
+                if imp.start.get() == 0 {
+                    imp.start.set(clock.frame_time());
+                }
+
+                // Without the animation, only draw when there is something new to show
+                let load_ended = imp.poll_texture();
+                let url_changed = imp.url_changed();
+                let loading = url_changed || imp.receiver.borrow().is_some();
+                if load_ended || url_changed || (loading && imp.animates()) {
+                    widget.queue_draw();
+                }
+                if loading {
+                    glib::ControlFlow::Continue
+                } else {
+                    imp.tick.borrow_mut().take();
+                    glib::ControlFlow::Break
+                }
+            });
+            self.tick.replace(Some(tick));
+        }
+
+        fn set_image_width(&self, image_width: i32) {
+            self.image_width.set(image_width);
+            self.obj()
+                .set_size_request(image_width, self.image_height.get());
+        }
+
+        fn set_image_height(&self, image_height: i32) {
+            self.image_height.set(image_height);
+            self.obj()
+                .set_size_request(self.image_width.get(), image_height);
+        }
+
+        /// Takes the texture of the running load if it arrived. Returns whether the load
+        /// ended, with a texture or not.
+        fn poll_texture(&self) -> bool {
+            let mut receiver_opt = self.receiver.borrow_mut();
+            let Some(receiver) = receiver_opt.as_mut() else {
+                return false;
+            };
+            match receiver.try_recv() {
+                Ok(texture) => {
+                    self.texture.borrow_mut().replace(texture);
+                    receiver_opt.take();
+                    true
+                }
+                Err(TryRecvError::Empty) => false,
+                Err(TryRecvError::Disconnected) => {
+                    self.failed.set(true);
+                    receiver_opt.take();
+                    true
+                }
+            }
+        }
+
+        /// Whether the url changed since the last load started, which the next snapshot does.
+        fn url_changed(&self) -> bool {
+            self.url
+                .borrow()
+                .as_deref()
+                .is_some_and(|url| Some(url) != self.loaded.borrow().as_deref())
+        }
+
+        /// Whether the shimmer moves, unless the user or the system asked for less motion.
+        fn animates(&self) -> bool {
+            !REDUCED_MOTION.load(Ordering::Relaxed)
+                && self.obj().settings().is_gtk_enable_animations()
+        }
+
+        /// Stops the running load from downloading, caching or sending its texture.
+        pub fn cancel_load(&self) {
+            self.cancelled.borrow().store(true, Ordering::Relaxed);
+        }
+
         fn load(&self, url: &str) {
+            self.cancel_load();
+            let cancelled = Arc::new(AtomicBool::new(false));
+            self.cancelled.replace(cancelled.clone());
             self.failed.set(false);
 
             let mut split = url.splitn(2, "://");
@@ -219,68 +354,25 @@ mod imp {
 
             match scheme {
                 "https" => {
-                    let mut path = temp_dir();
                     let url = url.to_string();
-                    path.push(format!("{}.jpg", base64_encode(url.as_bytes())));
-
-                    spawn_blocking(move || {
-                        let exists_result = std::panic::catch_unwind(|| exists(path.as_path()));
-                        let exists: Result<bool, std::io::Error> = match exists_result {
-                            Ok(val) => val,
-                            Err(_) => {
-                                dev_println!("[CLIENT] Failed to check if file exists for {url}");
-                                Ok(false)
-                            }
-                        };
-                        match exists {
-                            Ok(false) => {
-                                dev_println!("[CLIENT] Downloading: {url}");
-                                //Download and store to path
-                                let response = match Client::new()
-                                    .get(url.as_str())
-                                    .send()
-                                    .and_then(|response| response.error_for_status())
-                                    .and_then(|response| response.bytes())
-                                {
-                                    Ok(response) => response,
-                                    Err(error) => {
-                                        failed.set(true);
-                                        return eprintln!("[CLIENT] Failed to download {url}: {error}");
-                                    }
-                                };
-
-                                if let Err(error) = write(path.as_path(), response) {
-                                    failed.set(true);
-                                    eprintln!("[CLIENT] Failed to write {url} to {path:?}: {error}");
-                                    return;
+                    let pixel_width = self.image_width.get() * self.obj().scale_factor();
+                    // A download first waits for a slot here, so that no pool thread is held
+                    // while the downloads before it run
+                    glib::spawn_future_local(async move {
+                        let mut slot = None;
+                        let mut resume = None;
+                        loop {
+                            let (url, sender) = (url.clone(), sender.clone());
+                            let flag = cancelled.clone();
+                            let job = move || {
+                                load_capsule(&url, pixel_width, resume, slot, &flag, &sender)
+                            };
+                            match spawn_blocking(job).await {
+                                Ok(Some(next)) if !cancelled.load(Ordering::Relaxed) => {
+                                    slot = Some(DOWNLOAD_SLOTS.acquire().await);
+                                    resume = Some(next);
                                 }
-                            }
-                            Ok(true) => {
-                                dev_println!("[CLIENT] Cached loading: {url}");
-                            }
-                            Err(e) => {
-                                failed.set(true);
-                                eprintln!("[CLIENT] Error checking if file exists for {url}: {e}");
-                                return;
-                            }
-                        }
-
-                        let data = match std::fs::read(path.as_path()) {
-                            Ok(data) => data,
-                            Err(error) => {
-                                failed.set(true);
-                                eprintln!("[CLIENT] Failed to read {url} from {path:?}: {error}");
-                                return;
-                            }
-                        };
-
-                        match Texture::from_bytes(&Bytes::from(data.as_slice())) {
-                            Ok(texture) => {
-                                sender.send(texture).ok();
-                            }
-                            Err(error) => {
-                                failed.set(true);
-                                eprintln!("[CLIENT] Failed to create {url} from bytes: {error}");
+                                _ => break,
                             }
                         }
                     });
@@ -297,6 +389,9 @@ mod imp {
                             }
                         };
 
+                        if cancelled.load(Ordering::Relaxed) {
+                            return;
+                        }
                         match Texture::from_bytes(&Bytes::from(data.as_slice())) {
                             Ok(texture) => {
                                 sender.send(texture).ok();
@@ -317,4 +412,322 @@ mod imp {
             }
         }
     }
+
+    /// Where `load_capsule` goes on from once a download slot is free.
+    #[derive(Clone, Copy)]
+    struct CapsuleResume {
+        index: usize,
+        /// Whether the sharper candidates before `index` are known not to exist.
+        sharper_missing: bool,
+    }
+
+    /// Loads the first of the capsule candidates for `url` that is cached or downloads, from
+    /// `resume` on, or else from the one picked last time. Returns where to go on from once
+    /// a download slot is free, when `slot` is empty and none is left.
+    fn load_capsule(
+        url: &str,
+        pixel_width: i32,
+        resume: Option<CapsuleResume>,
+        mut slot: Option<DownloadSlot>,
+        cancelled: &AtomicBool,
+        sender: &SyncSender<Texture>,
+    ) -> Option<CapsuleResume> {
+        let candidates = capsule_candidates(url, pixel_width);
+        let app_id = capsule_app_id(url);
+        // Start from the variant picked last time, larger ones are known to be missing
+        let CapsuleResume { index: first, mut sharper_missing } = resume.unwrap_or_else(|| {
+            let index = app_id
+                .and_then(chosen_capsule)
+                .and_then(|chosen| candidates.iter().position(|c| *c == chosen))
+                .unwrap_or(0);
+            CapsuleResume { index, sharper_missing: true }
+        });
+
+        for (index, candidate) in candidates.iter().enumerate().skip(first) {
+            let result = fetch_texture(candidate, &mut slot, cancelled);
+            if cancelled.load(Ordering::Relaxed) {
+                return None;
+            }
+            match result {
+                Ok(texture) => {
+                    // A sharper capsule that failed otherwise, or is only missing from the
+                    // cache while offline, may still load next time
+                    if let Some(app_id) = app_id
+                        && candidates.len() > 1
+                        && sharper_missing
+                        && !OFFLINE.load(Ordering::Relaxed)
+                    {
+                        remember_capsule(app_id, candidate);
+                    }
+                    sender.send(texture).ok();
+                    return None;
+                }
+                Err(FetchError::NeedsSlot) => {
+                    return Some(CapsuleResume { index, sharper_missing });
+                }
+                Err(FetchError::Missing(error)) if index + 1 < candidates.len() => {
+                    log::debug!("[CLIENT] Skipping {candidate}: {error}");
+                }
+                Err(FetchError::Failed(error)) if index + 1 < candidates.len() => {
+                    sharper_missing = false;
+                    log::debug!("[CLIENT] Skipping {candidate}: {error}");
+                }
+                Err(FetchError::Missing(error) | FetchError::Failed(error)) => {
+                    eprintln!("[CLIENT] {error}")
+                }
+            }
+        }
+        None
+    }
+
+    /// App id of a store capsule url, e.g. `.../apps/480/capsule_231x87.jpg`.
+    /// Other images, such as achievement icons, have no capsule variants.
+    fn capsule_app_id(url: &str) -> Option<u32> {
+        url.strip_prefix(STORE_ASSETS_URL)?
+            .strip_prefix('/')?
+            .split('/')
+            .next()?
+            .parse()
+            .ok()
+    }
+
+    /// Urls to try for an image shown `pixel_width` device pixels wide, the higher
+    /// resolution store capsules first when the small capsule would be upscaled.
+    fn capsule_candidates(url: &str, pixel_width: i32) -> Vec<String> {
+        let app_id = capsule_app_id(url).filter(|_| pixel_width > SMALL_CAPSULE_WIDTH);
+        let Some(app_id) = app_id else {
+            return vec![url.to_string()];
+        };
+        let mut candidates: Vec<String> = HIGHER_RES_CAPSULES
+            .iter()
+            .map(|name| format!("{STORE_ASSETS_URL}/{app_id}/{name}"))
+            .filter(|candidate| candidate != url)
+            .collect();
+        candidates.push(url.to_string());
+        candidates
+    }
+
+    fn capsule_choices_path() -> Option<PathBuf> {
+        get_app_cache_dir()
+            .ok()
+            .map(|dir| PathBuf::from(dir).join(CAPSULE_CHOICES_FILE_NAME))
+    }
+
+    fn load_capsule_choices() -> HashMap<u32, String> {
+        capsule_choices_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default()
+    }
+
+    fn chosen_capsule(app_id: u32) -> Option<String> {
+        CAPSULE_CHOICES.lock().ok()?.get(&app_id).cloned()
+    }
+
+    fn remember_capsule(app_id: u32, url: &str) {
+        let Ok(mut choices) = CAPSULE_CHOICES.lock() else {
+            return;
+        };
+        if choices.get(&app_id).map(String::as_str) == Some(url) {
+            return;
+        }
+        choices.insert(app_id, url.to_string());
+        let Some(path) = capsule_choices_path() else {
+            return;
+        };
+        match serde_json::to_string(&*choices) {
+            Ok(contents) => {
+                if let Err(error) = write(&path, contents) {
+                    eprintln!("[CLIENT] Failed to write {path:?}: {error}");
+                }
+            }
+            Err(error) => eprintln!("[CLIENT] Failed to serialize capsule choices: {error}"),
+        }
+    }
+
+    /// Returns the folder set in the settings, or the `images` folder of the app cache
+    /// directory, created if needed. Falls back to the temp dir when it can't be created.
+    fn image_cache_dir() -> PathBuf {
+        let dir = match load_settings().image_cache_dir {
+            Some(dir) if !dir.trim().is_empty() => Some(PathBuf::from(dir)),
+            _ => get_app_cache_dir()
+                .ok()
+                .map(|dir| PathBuf::from(dir).join(IMAGE_CACHE_FOLDER_NAME)),
+        };
+        match dir {
+            Some(dir) => match create_dir_all(&dir) {
+                Ok(()) => dir,
+                Err(error) => {
+                    eprintln!("[CLIENT] Failed to create {dir:?}: {error}, using the temp dir");
+                    temp_dir()
+                }
+            },
+            None => temp_dir(),
+        }
+    }
+
+    /// Why `fetch_texture` did not load an image.
+    enum FetchError {
+        /// The image is not cached, and downloading it takes a download slot.
+        NeedsSlot,
+        /// The server answered that the image does not exist.
+        Missing(String),
+        Failed(String),
+    }
+
+    impl From<String> for FetchError {
+        fn from(error: String) -> Self {
+            FetchError::Failed(error)
+        }
+    }
+
+    /// Loads an image from the image cache, downloading it first if needed with the slot
+    /// taken from `slot`, or else the next free one. Nothing is downloaded or cached once
+    /// `cancelled` is set.
+    fn fetch_texture(
+        url: &str,
+        slot: &mut Option<DownloadSlot>,
+        cancelled: &AtomicBool,
+    ) -> Result<Texture, FetchError> {
+        let cache_stem = base64_encode(url.as_bytes());
+        let cache_path =
+            |extension: &str| IMAGE_CACHE_DIR.join(format!("{cache_stem}.{extension}"));
+
+        let mut cached = None;
+        for extension in IMAGE_EXTENSIONS {
+            let path = cache_path(extension);
+            match exists(path.as_path()) {
+                Ok(true) => {
+                    cached = Some(path);
+                    break;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    return Err(format!("Error checking if file exists for {url}: {e}").into());
+                }
+            }
+        }
+
+        let data = match cached {
+            Some(path) => {
+                dev_println!("[CLIENT] Cached loading: {url}");
+                std::fs::read(path.as_path())
+                    .map_err(|error| format!("Failed to read {url} from {path:?}: {error}"))?
+            }
+            None if OFFLINE.load(Ordering::Relaxed) => {
+                return Err(format!("Offline, {url} is not cached").into());
+            }
+            None => {
+                let Some(_slot) = slot.take().or_else(|| DOWNLOAD_SLOTS.try_acquire()) else {
+                    return Err(FetchError::NeedsSlot);
+                };
+                if cancelled.load(Ordering::Relaxed) {
+                    return Err(format!("Cancelled the download of {url}").into());
+                }
+                dev_println!("[CLIENT] Downloading: {url}");
+                //Download and store to path
+                let response = Client::new()
+                    .get(url)
+                    .send()
+                    .map_err(|error| format!("Failed to download {url}: {error}"))?;
+                if response.status() == StatusCode::NOT_FOUND {
+                    return Err(FetchError::Missing(format!("{url} does not exist")));
+                }
+                let response = response
+                    .error_for_status()
+                    .map_err(|error| format!("Failed to download {url}: {error}"))?;
+                let content_type = response
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_owned);
+                let data = response
+                    .bytes()
+                    .map_err(|error| format!("Failed to download {url}: {error}"))?;
+
+                // Nobody waits for this image anymore, leave the cache alone
+                if cancelled.load(Ordering::Relaxed) {
+                    return Err(format!("Cancelled the download of {url}").into());
+                }
+                let path = cache_path(image_extension(&data, content_type.as_deref()));
+                write(path.as_path(), &data)
+                    .map_err(|error| format!("Failed to write {url} to {path:?}: {error}"))?;
+                data.to_vec()
+            }
+        };
+
+        // Texture::from_bytes reads the format from the data, not from the extension
+        Texture::from_bytes(&Bytes::from(data.as_slice()))
+            .map_err(|error| format!("Failed to create {url} from bytes: {error}").into())
+    }
+
+    /// Cache file extension for a downloaded image, from its magic bytes or else from the
+    /// `Content-Type` header. Steam serves mostly JPEG, so that is the fallback.
+    fn image_extension(data: &[u8], content_type: Option<&str>) -> &'static str {
+        if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+            return "png";
+        }
+        if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+            return "webp";
+        }
+        if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            return "jpg";
+        }
+        match content_type.map(|content_type| content_type.split(';').next().unwrap_or("").trim()) {
+            Some("image/png") => "png",
+            Some("image/webp") => "webp",
+            _ => "jpg",
+        }
+    }
+
+    /// Counting semaphore limiting the concurrent downloads, with the number of free slots
+    /// and the loads waiting for one.
+    struct DownloadSlots {
+        state: Mutex<(usize, Vec<Waker>)>,
+    }
+
+    /// A taken download slot, given back when dropped.
+    struct DownloadSlot(&'static DownloadSlots);
+
+    impl DownloadSlots {
+        fn new(count: usize) -> Self {
+            Self {
+                state: Mutex::new((count, Vec::new())),
+            }
+        }
+
+        fn try_acquire(&'static self) -> Option<DownloadSlot> {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if state.0 == 0 {
+                return None;
+            }
+            state.0 -= 1;
+            Some(DownloadSlot(self))
+        }
+
+        /// Waits until a slot is free, without blocking the thread.
+        async fn acquire(&'static self) -> DownloadSlot {
+            poll_fn(|context| {
+                let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                if state.0 == 0 {
+                    state.1.push(context.waker().clone());
+                    return Poll::Pending;
+                }
+                state.0 -= 1;
+                Poll::Ready(DownloadSlot(self))
+            })
+            .await
+        }
+    }
+
+    impl Drop for DownloadSlot {
+        fn drop(&mut self) {
+            let mut state = self.0.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.0 += 1;
+            // Every waiting load tries again, the first one to run takes the slot
+            let waiting = std::mem::take(&mut state.1);
+            drop(state);
+            waiting.into_iter().for_each(Waker::wake);
+        }
+    }
 }
@@ -14,6 +14,8 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::backend::stat_definitions::StatInfo;
+use crate::tr;
+use crate::utils::format::format_localized_integer;
 use glib::Object;
 use gtk::glib;
 
@@ -32,8 +34,11 @@ impl GStatObject {
                 .property("original-value", info.original_value as f64)
                 .property("current-value", info.float_value as f64)
                 .property("is-increment-only", info.is_increment_only)
-                .property("permission", info.permission)
+                .property("permission", info.permission.bits())
                 .property("is-integer", false)
+                .property("min-value", info.min_value as f64)
+                .property("max-value", info.max_value as f64)
+                .property("max-change", info.max_change as f64)
                 .build(),
             StatInfo::Integer(info) => Object::builder()
                 .property("id", &info.id)
@@ -42,11 +47,60 @@ impl GStatObject {
                 .property("original-value", info.original_value as f64)
                 .property("current-value", info.int_value as f64)
                 .property("is-increment-only", info.is_increment_only)
-                .property("permission", info.permission)
+                .property("permission", info.permission.bits())
                 .property("is-integer", true)
+                .property("min-value", info.min_value as f64)
+                .property("max-value", info.max_value as f64)
+                .property("max-change", info.max_change as f64)
                 .build(),
+            StatInfo::ReadOnly(info) => {
+                let value_text = if info.bits.is_empty() {
+                    format_localized_integer(info.value as i64)
+                } else {
+                    let set_bits = info.set_bits();
+                    if set_bits.is_empty() {
+                        tr!("None")
+                    } else {
+                        set_bits.join(", ")
+                    }
+                };
+                Object::builder()
+                    .property("id", &info.id)
+                    .property("app-id", info.app_id)
+                    .property("display-name", &info.display_name)
+                    .property("original-value", info.value as f64)
+                    .property("current-value", info.value as f64)
+                    .property("permission", info.permission.bits())
+                    .property("is-integer", true)
+                    .property("is-read-only", true)
+                    .property("value-text", value_text)
+                    .property("min-value", info.value as f64)
+                    .property("max-value", info.value as f64)
+                    .build()
+            }
         }
     }
+
+    /// Takes what Steam reported in `fresh`. Refuses when a value or bound differs: on a
+    /// shown row, changing them makes the spin button write the stat back to Steam, so the
+    /// caller swaps in the fresh object instead.
+    pub fn update_from(&self, fresh: &GStatObject) -> bool {
+        let unchanged = self.current_value() == fresh.current_value()
+            && self.original_value() == fresh.original_value()
+            && self.min_value() == fresh.min_value()
+            && self.max_value() == fresh.max_value()
+            && self.is_integer() == fresh.is_integer()
+            && self.is_increment_only() == fresh.is_increment_only()
+            && self.is_read_only() == fresh.is_read_only();
+        if !unchanged {
+            return false;
+        }
+        self.set_display_name(fresh.display_name());
+        self.set_permission(fresh.permission());
+        self.set_value_text(fresh.value_text());
+        self.set_max_change(fresh.max_change());
+        true
+    }
 }
 
 mod imp {
@@ -72,10 +126,22 @@ mod imp {
         is_integer: Cell<bool>,
         #[property(get, set)]
         is_increment_only: Cell<bool>,
+        /// Bit fields and stats of unknown types, shown as text instead of a spin button.
+        #[property(get, set)]
+        is_read_only: Cell<bool>,
+        #[property(get, set)]
+        value_text: RefCell<String>,
         #[property(get, set)]
         app_id: Cell<u32>,
         #[property(get, set)]
         permission: Cell<i32>,
+        #[property(get, set)]
+        min_value: Cell<f64>,
+        #[property(get, set)]
+        max_value: Cell<f64>,
+        /// Largest change Steam accepts in one store, 0 when unlimited.
+        #[property(get, set)]
+        max_change: Cell<f64>,
     }
 
     #[glib::object_subclass]
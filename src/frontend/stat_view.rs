@@ -13,20 +13,26 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use super::request::{Request, SetFloatStat, SetIntStat};
+use super::request::{Request, ResetStat, SetFloatStat, SetIntStat};
 use super::stat::GStatObject;
+use super::toast::show_toast;
+use crate::backend::stat_definitions::{Permission, limit_stat_change};
+use crate::tr;
+use crate::utils::format::{
+    format_localized_decimal, format_localized_integer, parse_localized_number,
+};
+use crate::utils::ipc_types::SamError;
 use glib::prelude::ToValue;
 use gtk::{
-    Adjustment, Align, Box, ClosureExpression, FilterListModel, Frame, Label, ListItem, ListView,
-    NoSelection, Orientation, ScrolledWindow, SignalListItemFactory, SpinButton, StringFilter,
-    StringFilterMatchMode, Widget,
+    Adjustment, Align, Box, Button, ClosureExpression, Entry, FilterListModel, Frame, Label,
+    ListItem, ListView, NoSelection, Orientation, ScrolledWindow, SignalListItemFactory,
+    SpinButton, StringFilter, StringFilterMatchMode, Widget, Window,
     gio::{ListStore, spawn_blocking},
-    glib::{self, SignalHandlerId, object::Cast, translate::FromGlib},
+    glib::{self, SignalHandlerId, clone, object::Cast, translate::FromGlib},
     pango::EllipsizeMode,
     prelude::*,
 };
 use std::{cell::RefCell, ffi::c_ulong, sync::mpsc::channel, time::Duration};
-use log;
 
 /// Create the stats view, including model, filter, and UI.
 pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
@@ -34,7 +40,7 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
     let app_stats_model = ListStore::new::<GStatObject>();
 
     let app_stats_string_filter = StringFilter::builder()
-        .expression(&GStatObject::this_expression("display-name"))
+        .expression(GStatObject::this_expression("display-name"))
         .match_mode(StringFilterMatchMode::Substring)
         .ignore_case(true)
         .build();
@@ -62,11 +68,34 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
             .build();
 
         let spin_button = SpinButton::builder().adjustment(&adjustment).build();
+        // Show and read values with the locale's separators instead of GTK's plain digits
+        spin_button.connect_output(|spin_button| {
+            let digits = spin_button.digits() as usize;
+            let text = if digits == 0 {
+                format_localized_integer(spin_button.value() as i64)
+            } else {
+                format_localized_decimal(spin_button.value(), digits)
+            };
+            spin_button.set_text(&text);
+            glib::Propagation::Stop
+        });
+        spin_button.connect_input(|spin_button| {
+            Some(parse_localized_number(&spin_button.text(), spin_button.digits() == 0).map_err(|_| ()))
+        });
+
+        let value_label = Label::builder()
+            .ellipsize(EllipsizeMode::End)
+            .selectable(true)
+            .tooltip_text(tr!("This statistic can only be viewed."))
+            .build();
+        value_label.add_css_class("dim-label");
 
         let button_box = Box::builder()
             .orientation(Orientation::Vertical)
             .halign(Align::End)
             .build();
+        // The spin button stays last, bind and unbind look it up there
+        button_box.append(&value_label);
         button_box.append(&spin_button);
         let spacer = Box::builder()
             .orientation(Orientation::Horizontal)
@@ -89,18 +118,68 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
 
         let icon_increment_only = gtk::Image::from_icon_name("go-up-symbolic");
         icon_increment_only.set_margin_end(8);
-        icon_increment_only.set_tooltip_text(Some("Increment only"));
+        icon_increment_only.set_tooltip_text(Some(&tr!("Increment only")));
         stat_box.append(&icon_increment_only);
 
         let protected_icon = gtk::Image::from_icon_name("action-unavailable-symbolic");
         protected_icon.set_margin_end(8);
-        protected_icon.set_tooltip_text(Some("This statistic is protected."));
+        protected_icon.set_tooltip_text(Some(&tr!("This statistic is protected.")));
         stat_box.append(&protected_icon);
 
+        let exact_value_button = Button::builder()
+            .icon_name("document-edit-symbolic")
+            .tooltip_text(tr!("Set exact value"))
+            .valign(Align::Center)
+            .margin_end(8)
+            .build();
+        exact_value_button.add_css_class("flat");
+        stat_box.append(&exact_value_button);
+
+        let reset_button = Button::builder()
+            .icon_name("edit-undo-symbolic")
+            .tooltip_text(tr!("Reset to default"))
+            .valign(Align::Center)
+            .margin_end(8)
+            .build();
+        reset_button.add_css_class("flat");
+        stat_box.append(&reset_button);
+
         stat_box.append(&button_box);
         if let Some(list_item) = list_item.downcast_ref::<gtk::ListItem>() {
             list_item.set_child(Some(&stat_box));
 
+            exact_value_button.connect_clicked(clone!(
+                #[weak]
+                list_item,
+                #[weak]
+                spin_button,
+                move |button| {
+                    let Some(stat_object) = list_item
+                        .item()
+                        .and_then(|item| item.downcast::<GStatObject>().ok())
+                    else {
+                        return;
+                    };
+                    show_exact_value_dialog(button, &spin_button, &stat_object);
+                }
+            ));
+
+            reset_button.connect_clicked(clone!(
+                #[weak]
+                list_item,
+                #[weak]
+                spin_button,
+                move |_| {
+                    let Some(stat_object) = list_item
+                        .item()
+                        .and_then(|item| item.downcast::<GStatObject>().ok())
+                    else {
+                        return;
+                    };
+                    reset_stat_to_default(&spin_button, &stat_object);
+                }
+            ));
+
             // Property expressions and bindings
             list_item
                 .property_expression("item")
@@ -117,6 +196,26 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
                 .chain_property::<GStatObject>("is-increment-only")
                 .bind(&icon_increment_only, "visible", Widget::NONE);
 
+            list_item
+                .property_expression("item")
+                .chain_property::<GStatObject>("value-text")
+                .bind(&value_label, "label", Widget::NONE);
+
+            let is_read_only_expr = list_item
+                .property_expression("item")
+                .chain_property::<GStatObject>("is-read-only");
+            is_read_only_expr.bind(&value_label, "visible", Widget::NONE);
+            let is_editable_expr = ClosureExpression::new::<bool>(
+                &[is_read_only_expr],
+                glib::RustClosure::new(|values: &[glib::Value]| {
+                    let is_read_only = values.get(1).and_then(|val| val.get::<bool>().ok()).unwrap_or(false);
+                    Some((!is_read_only).to_value())
+                }),
+            );
+            is_editable_expr.bind(&spin_button, "visible", Widget::NONE);
+            is_editable_expr.bind(&exact_value_button, "visible", Widget::NONE);
+            is_editable_expr.bind(&reset_button, "visible", Widget::NONE);
+
             // Custom expressions
             let is_integer_expr = list_item
                 .property_expression("item")
@@ -158,13 +257,13 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
 
             let permission_sensitive_closure = glib::RustClosure::new(|values: &[glib::Value]| {
                 let permission = values.get(1).and_then(|val| val.get::<i32>().ok()).unwrap_or(0);
-                let is_sensitive = (permission & 2) == 0;
+                let is_sensitive = !Permission(permission).is_protected();
                 Some(is_sensitive.to_value())
             });
 
             let permission_protected_closure = glib::RustClosure::new(|values: &[glib::Value]| {
                 let permission = values.get(1).and_then(|val| val.get::<i32>().ok()).unwrap_or(0);
-                let is_protected = (permission & 2) != 0;
+                let is_protected = Permission(permission).is_protected();
                 Some(is_protected.to_value())
             });
 
@@ -185,6 +284,8 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
             let permission_sensitive_expr =
                 ClosureExpression::new::<bool>(&[permission_expr], permission_sensitive_closure);
             permission_sensitive_expr.bind(&spin_button, "sensitive", Widget::NONE);
+            permission_sensitive_expr.bind(&exact_value_button, "sensitive", Widget::NONE);
+            permission_sensitive_expr.bind(&reset_button, "sensitive", Widget::NONE);
 
             let permission_protected_expr =
                 ClosureExpression::new::<bool>(&[permission_expr_2], permission_protected_closure);
@@ -231,6 +332,9 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
         let handler_id = spin_button.connect_value_changed({
             let stat_object = stat_object.clone();
             move |button| {
+                if stat_object.is_read_only() {
+                    return;
+                }
                 let val = button.value();
                 if sender.borrow_mut().send(val).is_ok() {
                     return;
@@ -242,13 +346,19 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
                 let integer_stat = stat_object.is_integer();
                 let stat_id = stat_object.id().clone();
                 let stat_object_clone = stat_object.clone();
-                let app_id = stat_object.app_id().clone();
+                let app_id = stat_object.app_id();
+                let original_value = stat_object.original_value();
+                let max_change = stat_object.max_change();
+                let spin_button = button.clone();
 
                 glib::spawn_future_local(async move {
                     let join_handle = spawn_blocking(move || {
                         while let Ok(new) = new_receiver.recv_timeout(Duration::from_millis(500)) {
                             value = (new * 100.0).round() / 100.0;
                         }
+                        let requested = value;
+                        value = limit_stat_change(original_value, max_change, value);
+                        let limited = value != requested;
                         let res = if integer_stat {
                             SetIntStat {
                                 app_id,
@@ -264,21 +374,27 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
                             }
                             .request()
                         };
-                        match res {
-                            Ok(success) if success => (true, value),
-                            _ => (false, value),
-                        }
+                        (res, value, limited)
                     });
-                    let (success, debounced_value) =
+                    let (success, debounced_value, limited) =
                         match join_handle.await {
-                            Ok((success, debounced_value)) => (success, debounced_value),
+                            Ok((Ok(success), debounced_value, limited)) => (success, debounced_value, limited),
+                            Ok((Err(SamError::VerificationFailed(_)), debounced_value, _)) => {
+                                show_change_ignored(&spin_button, &stat_object_clone);
+                                (false, debounced_value, false)
+                            }
+                            Ok((Err(_), debounced_value, _)) => (false, debounced_value, false),
                             Err(e) => {
                                 log::error!("spawn_blocking task panicked: {:?}", e);
-                                (false, value)
+                                (false, value, false)
                             }
                         };
                     if success {
                         stat_object_clone.set_original_value(debounced_value);
+                        if limited {
+                            set_value_quietly(&spin_button, &stat_object_clone, debounced_value);
+                            show_change_limited(&spin_button, &stat_object_clone, debounced_value);
+                        }
                     } else {
                         stat_object_clone.set_current_value(stat_object_clone.original_value());
                     }
@@ -327,3 +443,305 @@ pub fn create_stats_view() -> (Frame, ListStore, StringFilter) {
 
     (app_stats_frame, app_stats_model, app_stats_string_filter)
 }
+
+/// Show a small modal window where the user can type an exact value for a stat.
+fn show_exact_value_dialog(parent: &Button, spin_button: &SpinButton, stat_object: &GStatObject) {
+    let is_integer = stat_object.is_integer();
+    let upper = stat_object.max_value();
+    let lower = if stat_object.is_increment_only() {
+        stat_object.original_value().max(stat_object.min_value())
+    } else {
+        stat_object.min_value()
+    };
+    let format_value = |value: f64| {
+        if is_integer {
+            format_localized_integer(value as i64)
+        } else {
+            format_localized_decimal(value, 2)
+        }
+    };
+    let current_text = format_value(stat_object.current_value());
+    let (lower_text, upper_text) = (format_value(lower), format_value(upper));
+
+    let window = Window::builder()
+        .title(tr!("Set exact value"))
+        .modal(true)
+        .resizable(false)
+        .default_width(320)
+        .build();
+    if let Some(root) = parent.root().and_then(|root| root.downcast::<Window>().ok()) {
+        window.set_transient_for(Some(&root));
+    }
+
+    let name_label = Label::builder()
+        .label(stat_object.display_name())
+        .ellipsize(EllipsizeMode::End)
+        .halign(Align::Start)
+        .build();
+    name_label.add_css_class("heading");
+    let range_label = Label::builder()
+        .label(tr!("Allowed range: {lower_text} to {upper_text}", lower_text = lower_text, upper_text = upper_text))
+        .halign(Align::Start)
+        .build();
+    range_label.add_css_class("dim-label");
+    let max_change = stat_object.max_change();
+    let max_change_label = Label::builder()
+        .label(tr!(
+            "Steam accepts changes of up to {max_change} at once, larger ones are reduced.",
+            max_change = format_value(max_change)
+        ))
+        .halign(Align::Start)
+        .wrap(true)
+        .visible(max_change > 0.0)
+        .build();
+    max_change_label.add_css_class("dim-label");
+    let entry = Entry::builder()
+        .text(current_text)
+        .activates_default(false)
+        .build();
+    let error_label = Label::builder()
+        .halign(Align::Start)
+        .wrap(true)
+        .visible(false)
+        .build();
+    error_label.add_css_class("error");
+
+    let cancel_button = Button::builder().label(tr!("Cancel")).build();
+    let set_button = Button::builder().label(tr!("Set")).build();
+    set_button.add_css_class("suggested-action");
+    let buttons_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .halign(Align::End)
+        .margin_top(8)
+        .build();
+    buttons_box.append(&cancel_button);
+    buttons_box.append(&set_button);
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .margin_top(16)
+        .margin_bottom(16)
+        .margin_start(16)
+        .margin_end(16)
+        .build();
+    content.append(&name_label);
+    content.append(&range_label);
+    content.append(&max_change_label);
+    content.append(&entry);
+    content.append(&error_label);
+    content.append(&buttons_box);
+    window.set_child(Some(&content));
+
+    cancel_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        move |_| window.close()
+    ));
+
+    set_button.connect_clicked(clone!(
+        #[weak]
+        window,
+        #[weak]
+        entry,
+        #[weak]
+        error_label,
+        #[weak]
+        spin_button,
+        #[weak]
+        stat_object,
+        move |_| {
+            // The dialog stays open on a value it can't take, with the reason under the entry
+            let error = match parse_localized_number(&entry.text(), is_integer) {
+                Ok(value) if value < lower || value > upper => tr!("The value must be between {lower_text} and {upper_text}", lower_text = lower_text, upper_text = upper_text),
+                Ok(value) => {
+                    window.close();
+                    commit_exact_value(&spin_button, &stat_object, value);
+                    return;
+                }
+                Err(message) => message,
+            };
+            error_label.set_label(&error);
+            error_label.set_visible(true);
+            entry.grab_focus();
+        }
+    ));
+
+    entry.connect_changed(clone!(
+        #[weak]
+        error_label,
+        move |_| error_label.set_visible(false)
+    ));
+
+    entry.connect_activate(clone!(
+        #[weak]
+        set_button,
+        move |_| set_button.emit_clicked()
+    ));
+
+    window.present();
+    entry.grab_focus();
+}
+
+fn format_stat_value(stat_object: &GStatObject, value: f64) -> String {
+    if stat_object.is_integer() {
+        format_localized_integer(value as i64)
+    } else {
+        format_localized_decimal(value, 2)
+    }
+}
+
+/// Shows a stored value on the row without the spinbox sending it to Steam a second time.
+fn set_value_quietly(spin_button: &SpinButton, stat_object: &GStatObject, value: f64) {
+    let handler = unsafe {
+        spin_button
+            .data::<c_ulong>("handler")
+            .map(|handler_id| SignalHandlerId::from_glib(*handler_id.as_ptr()))
+    };
+    if let Some(handler) = &handler {
+        spin_button.block_signal(handler);
+    }
+    stat_object.set_original_value(value);
+    stat_object.set_current_value(value);
+    spin_button.set_value(value);
+    if let Some(handler) = &handler {
+        spin_button.unblock_signal(handler);
+    }
+}
+
+/// Tells the user an edit was reduced to the largest change Steam accepts in one store.
+fn show_change_limited(spin_button: &SpinButton, stat_object: &GStatObject, value: f64) {
+    show_toast(
+        spin_button,
+        &tr!(
+            "Steam accepts changes of up to {max_change} at once, {stat} set to {value}",
+            max_change = format_stat_value(stat_object, stat_object.max_change()),
+            stat = stat_object.display_name(),
+            value = format_stat_value(stat_object, value)
+        ),
+    );
+}
+
+/// Tells the user Steam stored an edit but kept the stat's previous value.
+fn show_change_ignored(spin_button: &SpinButton, stat_object: &GStatObject) {
+    show_toast(
+        spin_button,
+        &tr!("Steam ignored the change to {name}", name = stat_object.display_name()),
+    );
+}
+
+/// Send an exact stat value to Steam, then update the row without re-triggering the spinbox.
+/// Values further than `max_change` from the stored one are reduced first.
+fn commit_exact_value(spin_button: &SpinButton, stat_object: &GStatObject, requested: f64) {
+    let app_id = stat_object.app_id();
+    let stat_id = stat_object.id();
+    let is_integer = stat_object.is_integer();
+    let value = limit_stat_change(stat_object.original_value(), stat_object.max_change(), requested);
+
+    glib::spawn_future_local(clone!(
+        #[weak]
+        spin_button,
+        #[weak]
+        stat_object,
+        async move {
+            let res = spawn_blocking(move || {
+                if is_integer {
+                    SetIntStat {
+                        app_id,
+                        stat_id,
+                        value: value as i32,
+                    }
+                    .request()
+                } else {
+                    SetFloatStat {
+                        app_id,
+                        stat_id,
+                        value: value as f32,
+                    }
+                    .request()
+                }
+            })
+            .await;
+
+            match res {
+                Ok(Ok(true)) => {
+                    // The spinbox handler would otherwise round and send the value a second time.
+                    set_value_quietly(&spin_button, &stat_object, value);
+                    if value != requested {
+                        show_change_limited(&spin_button, &stat_object, value);
+                    } else {
+                        show_toast(
+                            &spin_button,
+                            &tr!(
+                                "{stat} set to {value}",
+                                stat = stat_object.display_name(),
+                                value = format_stat_value(&stat_object, value)
+                            ),
+                        );
+                    }
+                }
+                Ok(Err(SamError::InvalidIdentifier(id))) => {
+                    eprintln!("[CLIENT] Stat has an invalid identifier and cannot be set: {id:?}")
+                }
+                Ok(Err(SamError::VerificationFailed(_))) => {
+                    show_change_ignored(&spin_button, &stat_object)
+                }
+                Ok(_) => eprintln!("[CLIENT] Failed to set exact value for stat {}", stat_object.id()),
+                Err(e) => log::error!("spawn_blocking task panicked: {:?}", e),
+            }
+        }
+    ));
+}
+
+/// Set a stat back to its default in Steam, leaving the other stats alone, then update the
+/// row without re-triggering the spinbox.
+fn reset_stat_to_default(spin_button: &SpinButton, stat_object: &GStatObject) {
+    let app_id = stat_object.app_id();
+    let stat_id = stat_object.id();
+
+    glib::spawn_future_local(clone!(
+        #[weak]
+        spin_button,
+        #[weak]
+        stat_object,
+        async move {
+            let res = spawn_blocking(move || ResetStat { app_id, stat_id }.request()).await;
+
+            match res {
+                Ok(Ok(value)) => {
+                    set_value_quietly(&spin_button, &stat_object, value);
+                    show_toast(
+                        &spin_button,
+                        &tr!(
+                            "{stat} reset to {value}",
+                            stat = stat_object.display_name(),
+                            value = format_stat_value(&stat_object, value)
+                        ),
+                    );
+                }
+                Ok(Err(SamError::VerificationFailed(_))) => {
+                    show_change_ignored(&spin_button, &stat_object)
+                }
+                Ok(Err(e)) => {
+                    eprintln!("[CLIENT] Failed to reset stat {}: {e}", stat_object.id());
+                    let reason = match e {
+                        SamError::ProtectedStat(_) => {
+                            tr!("It is protected, only the game's servers can change it")
+                        }
+                        SamError::StatIncrementOnly(_) => {
+                            tr!("It can only go up, Steam would not store its default")
+                        }
+                        SamError::StatChangeTooLarge(_) => {
+                            tr!("Steam only lets it change a little at a time")
+                        }
+                        SamError::ReadOnlyStat(_) => tr!("It can't be changed"),
+                        _ => tr!("Steam refused the change"),
+                    };
+                    show_toast(&spin_button, &reason);
+                }
+                Err(e) => log::error!("spawn_blocking task panicked: {:?}", e),
+            }
+        }
+    ));
+}
@@ -17,7 +17,8 @@ use crate::backend::app_lister::AppModel;
 use crate::utils::app_paths::get_local_app_banner_file_path;
 use glib::Object;
 use gtk::glib;
-use std::path::Path;
+use gtk::subclass::prelude::*;
+use std::{cmp::Ordering, path::Path};
 
 glib::wrapper! {
     pub struct GSteamAppObject(ObjectSubclass<imp::GSteamAppObject>);
@@ -32,11 +33,12 @@ impl GSteamAppObject {
             _ => app.image_url.clone(),
         };
 
-        Object::builder()
+        let object: Self = Object::builder()
             .property("app_id", app.app_id)
             .property("app_name", app.app_name.clone())
             .property("developer", app.developer.clone())
             .property("image_url", image_url)
+            .property("icon_url", app.icon_url.clone())
             .property(
                 "metacritic_score",
                 match app.metacritic_score {
@@ -47,8 +49,20 @@ impl GSteamAppObject {
                     }
                 },
             )
-            .property("app_type", format!("{:?}", app.app_type))
-            .build()
+            .property("app_type", app.app_type.to_string())
+            .property("is_favorite", false)
+            .property("is_empty", false)
+            .property("is_selected", false)
+            .property("is_running", false)
+            .property("completion", String::new())
+            .build();
+        let _ = object.imp().sort_key.set(glib::CollationKey::from(&app.app_name));
+        object
+    }
+
+    /// Orders by name the way the user's locale does, from keys computed once per app.
+    pub fn cmp_name(&self, other: &Self) -> Ordering {
+        self.imp().sort_key.get().cmp(&other.imp().sort_key.get())
     }
 }
 
@@ -57,7 +71,7 @@ mod imp {
     use gtk::glib;
     use gtk::prelude::*;
     use gtk::subclass::prelude::*;
-    use std::cell::{Cell, RefCell};
+    use std::cell::{Cell, OnceCell, RefCell};
 
     /// Internal implementation of GSteamAppObject properties.
     #[derive(Properties, Default)]
@@ -73,8 +87,29 @@ mod imp {
         metacritic_score: Cell<u8>,
         #[property(get, set)]
         image_url: RefCell<Option<String>>,
+        /// The square icon, shown in the title bar while the app is open.
+        #[property(get, set)]
+        icon_url: RefCell<Option<String>>,
         #[property(get, set)]
         app_type: RefCell<String>,
+        /// Pinned by the user at the top of the library.
+        #[property(get, set)]
+        is_favorite: Cell<bool>,
+        /// Known to have neither achievements nor stats. Unknown apps are not empty.
+        #[property(get, set)]
+        is_empty: Cell<bool>,
+        /// Ticked in the library's selection mode, for batch operations.
+        #[property(get, set)]
+        is_selected: Cell<bool>,
+        /// Has a live app server, some window is managing it.
+        #[property(get, set)]
+        is_running: Cell<bool>,
+        /// Unlocked achievements out of the total, as "12/50", or the number of stats of
+        /// apps without achievements. Empty until known.
+        #[property(get, set)]
+        completion: RefCell<String>,
+        /// The name as a collation key, so sorting the library does not redo it per comparison.
+        pub(super) sort_key: OnceCell<glib::CollationKey>,
     }
 
     // The central trait for subclassing a GObject
@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::tr;
+use crate::utils::settings::{load_settings, save_settings};
+use gtk::{
+    Align, Box, Button, FileDialog, FileFilter, Justification, Label, Orientation, Window,
+    gio::{Cancellable, ListStore},
+    prelude::*,
+};
+
+#[cfg(target_os = "linux")]
+const STEAMCLIENT_FILE_PATTERN: &str = "steamclient*.so";
+#[cfg(target_os = "windows")]
+const STEAMCLIENT_FILE_PATTERN: &str = "steamclient*.dll";
+
+/// Page shown in place of the library when no Steam client library was found. Lists the
+/// probed paths and lets the user pick the library, which is remembered in the settings.
+pub fn create_steamclient_not_found_page(probed_paths: &[String]) -> Box {
+    let title = Label::builder()
+        .label(tr!("SamRewritten could not find the Steam client library."))
+        .css_classes(["title-4"])
+        .wrap(true)
+        .justify(Justification::Center)
+        .build();
+
+    let probed = if probed_paths.is_empty() {
+        tr!("No Steam installation was found.")
+    } else {
+        tr!("Looked in:\n{paths}", paths = probed_paths.join("\n"))
+    };
+    let probed_label = Label::builder()
+        .label(probed)
+        .css_classes(["dim-label"])
+        .wrap(true)
+        .selectable(true)
+        .justify(Justification::Center)
+        .build();
+
+    let locate_button = Button::builder()
+        .label(tr!("Locate the Steam client library..."))
+        .css_classes(["suggested-action"])
+        .halign(Align::Center)
+        .build();
+    locate_button.connect_clicked(pick_steamclient_library);
+
+    let page = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_start(24)
+        .margin_end(24)
+        .valign(Align::Center)
+        .halign(Align::Center)
+        .build();
+    page.append(&title);
+    page.append(&probed_label);
+    page.append(&locate_button);
+    page
+}
+
+/// Asks for the library file, saves it and reloads the app list with it.
+fn pick_steamclient_library(button: &Button) {
+    let filter = FileFilter::new();
+    filter.set_name(Some(&tr!("Steam client library")));
+    filter.add_pattern(STEAMCLIENT_FILE_PATTERN);
+    let filters = ListStore::new::<FileFilter>();
+    filters.append(&filter);
+
+    let dialog = FileDialog::builder()
+        .title(tr!("Locate the Steam client library"))
+        .modal(true)
+        .filters(&filters)
+        .default_filter(&filter)
+        .build();
+
+    let window = button.root().and_downcast::<Window>();
+    let button = button.clone();
+    dialog.open(window.as_ref(), Cancellable::NONE, move |result| {
+        let Some(path) = result.ok().and_then(|file| file.path()) else {
+            return;
+        };
+        let mut settings = load_settings();
+        settings.steamclient_path = Some(path.to_string_lossy().into_owned());
+        save_settings(&settings);
+        if let Err(e) = button.activate_action("win.refresh_app_list", None) {
+            log::error!("[CLIENT] Failed to refresh the app list: {e}");
+        }
+    });
+}
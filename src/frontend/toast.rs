@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Short confirmations shown at the bottom of the window once an operation went through.
+// The Adwaita build uses its toasts, plain GTK gets a lookalike on an overlay.
+
+use gtk::{Widget, prelude::*};
+
+#[cfg(not(feature = "adw"))]
+use gtk::{
+    Align, Box, Label, Orientation, Overlay, Revealer, RevealerTransitionType, glib, glib::clone,
+};
+#[cfg(not(feature = "adw"))]
+use std::time::Duration;
+
+#[cfg(not(feature = "adw"))]
+type ToastOverlay = Overlay;
+#[cfg(feature = "adw")]
+type ToastOverlay = adw::ToastOverlay;
+
+#[cfg(not(feature = "adw"))]
+const TOAST_WIDGET_NAME: &str = "toast";
+#[cfg(not(feature = "adw"))]
+const TOAST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Wraps the content of a window so toasts can show over it. Must be the window's child.
+pub fn create_toast_overlay(content: &impl IsA<Widget>) -> Widget {
+    let overlay = ToastOverlay::new();
+    overlay.set_child(Some(content));
+    overlay.upcast()
+}
+
+fn find_toast_overlay(widget: &impl IsA<Widget>) -> Option<ToastOverlay> {
+    widget
+        .root()
+        .and_downcast::<gtk::Window>()?
+        .child()?
+        .downcast::<ToastOverlay>()
+        .ok()
+}
+
+/// Shows `message` for a few seconds over the window `widget` is in.
+#[cfg(feature = "adw")]
+pub fn show_toast(widget: &impl IsA<Widget>, message: &str) {
+    if let Some(overlay) = find_toast_overlay(widget) {
+        overlay.add_toast(adw::Toast::new(message));
+    }
+}
+
+/// Shows `message` for a few seconds over the window `widget` is in. A newer toast
+/// replaces the one showing.
+#[cfg(not(feature = "adw"))]
+pub fn show_toast(widget: &impl IsA<Widget>, message: &str) {
+    let Some(overlay) = find_toast_overlay(widget) else {
+        return;
+    };
+
+    let mut child = overlay.first_child();
+    while let Some(current) = child {
+        child = current.next_sibling();
+        if current.widget_name() == TOAST_WIDGET_NAME {
+            overlay.remove_overlay(&current);
+        }
+    }
+
+    let label = Label::builder().label(message).wrap(true).build();
+    let toast = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .css_classes(["app-notification"])
+        .build();
+    toast.append(&label);
+    let revealer = Revealer::builder()
+        .name(TOAST_WIDGET_NAME)
+        .transition_type(RevealerTransitionType::SlideUp)
+        .halign(Align::Center)
+        .valign(Align::End)
+        .margin_bottom(24)
+        .child(&toast)
+        .can_target(false)
+        .build();
+    overlay.add_overlay(&revealer);
+    revealer.set_reveal_child(true);
+
+    revealer.connect_child_revealed_notify(clone!(
+        #[weak]
+        overlay,
+        move |revealer| {
+            if !revealer.reveals_child() && !revealer.is_child_revealed() {
+                overlay.remove_overlay(revealer);
+            }
+        }
+    ));
+    glib::timeout_add_local_once(
+        TOAST_TIMEOUT,
+        clone!(
+            #[weak]
+            revealer,
+            move || revealer.set_reveal_child(false)
+        ),
+    );
+}
@@ -13,14 +13,58 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::frontend::MainApplication;
 use crate::frontend::application_actions::set_app_action_enabled;
+use crate::tr;
+use gtk::glib::variant::ToVariant;
 use gtk::{
-    AboutDialog, ApplicationWindow, License, MenuButton, PopoverMenu, PositionType,
+    AboutDialog, ApplicationWindow, CssProvider, License, MenuButton, PopoverMenu, PositionType,
     gdk::Paintable,
     gdk_pixbuf::{Colorspace, Pixbuf},
+    gio::ListStore,
+    glib,
+    prelude::*,
 };
-use std::io::Cursor;
+use std::{
+    collections::{HashMap, HashSet},
+    io::Cursor,
+};
+
+/// Styles for the widgets that need more than the theme's stock classes.
+const APP_CSS: &str = "
+.rarity-badge {
+    border-radius: 999px;
+    padding: 0 8px;
+    font-size: smaller;
+    font-weight: bold;
+}
+.rarity-common { background-color: alpha(#9a9996, 0.3); }
+.rarity-uncommon { background-color: alpha(#2ec27e, 0.3); }
+.rarity-rare { background-color: alpha(#3584e4, 0.35); }
+.rarity-ultra-rare { background-color: alpha(#e5a50a, 0.45); }
+@keyframes recently-unlocked-flash {
+    from { background-color: alpha(#2ec27e, 0.45); }
+    to { background-color: alpha(#2ec27e, 0.12); }
+}
+.recently-unlocked {
+    background-color: alpha(#2ec27e, 0.12);
+    animation: recently-unlocked-flash 1s ease-out 3;
+}
+";
+
+/// Register the application's stylesheet for the default display.
+pub fn load_css() {
+    let Some(display) = gtk::gdk::Display::default() else {
+        eprintln!("[CLIENT] No display to load the stylesheet on");
+        return;
+    };
+    let provider = CssProvider::new();
+    provider.load_from_string(APP_CSS);
+    gtk::style_context_add_provider_for_display(
+        &display,
+        &provider,
+        gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+    );
+}
 
 /// Create the About dialog for the application.
 pub fn create_about_dialog(window: &ApplicationWindow) -> AboutDialog {
@@ -38,7 +82,7 @@ pub fn create_about_dialog(window: &ApplicationWindow) -> AboutDialog {
                 .split(':')
                 .collect::<Vec<_>>(),
         )
-        .comments(env!("CARGO_PKG_DESCRIPTION"))
+        .comments(tr!("Steam Achievement Manager Rewritten"))
         .logo(&logo)
         .build()
 }
@@ -53,7 +97,7 @@ pub fn load_logo() -> Paintable {
         eprintln!("[CLIENT] Failed to load logo. Using a gray square.");
         // Always try to create a 1x1 gray pixbuf as fallback
         let fallback_pixbuf = Pixbuf::new(Colorspace::Rgb, true, 8, 1, 1)
-            .map(|p| { p.fill(0x808080FF); p })
+            .inspect(|p| p.fill(0x808080FF))
             .unwrap_or_else(|| {
                 eprintln!("[CLIENT] Failed to create minimal pixbuf fallback. Returning default Paintable.");
                 let p = Pixbuf::new(Colorspace::Rgb, true, 8, 1, 1).unwrap();
@@ -66,8 +110,17 @@ pub fn load_logo() -> Paintable {
 
 /// Helper to set common menu items.
 fn set_common_menu_items(menu_model: &gtk::gio::Menu) {
-    menu_model.append(Some("About"), Some("app.about"));
-    menu_model.append(Some("Quit"), Some("app.quit"));
+    let language_menu = gtk::gio::Menu::new();
+    for (label, language) in [(tr!("Same as Steam"), ""), (tr!("English"), "english")] {
+        let item = gtk::gio::MenuItem::new(Some(&label), None);
+        item.set_action_and_target_value(Some("win.steam_language"), Some(&language.to_variant()));
+        language_menu.append_item(&item);
+    }
+    menu_model.append_submenu(Some(&tr!("Game data language")), &language_menu);
+    menu_model.append(Some(&tr!("Dry run (simulate changes)")), Some("app.dry_run"));
+    menu_model.append(Some(&tr!("Report a bug")), Some("win.report_bug"));
+    menu_model.append(Some(&tr!("About")), Some("win.about"));
+    menu_model.append(Some(&tr!("Quit")), Some("app.quit"));
 }
 
 /// Create a context menu button with a popover and menu model.
@@ -77,9 +130,22 @@ pub fn create_context_menu_button() -> (MenuButton, PopoverMenu, gtk::gio::Menu)
         .build();
 
     let context_menu_model = gtk::gio::Menu::new();
-    context_menu_model.append(Some("Refresh app list"), Some("app.refresh_app_list"));
-    context_menu_model.append(Some("About"), Some("app.about"));
-    context_menu_model.append(Some("Quit"), Some("app.quit"));
+    context_menu_model.append(Some(&tr!("Refresh app list")), Some("win.refresh_app_list"));
+    context_menu_model.append(Some(&tr!("Reconnect to Steam")), Some("win.reconnect_steam"));
+    context_menu_model.append(Some(&tr!("Close all sessions")), Some("win.close_all_sessions"));
+    context_menu_model.append(
+        Some(&tr!("Hide games without achievements")),
+        Some("win.hide_empty_apps"),
+    );
+    context_menu_model.append(
+        Some(&tr!("Export library completion...")),
+        Some("win.export_library_completion"),
+    );
+    context_menu_model.append(
+        Some(&tr!("Search achievements...")),
+        Some("win.search_achievements"),
+    );
+    set_common_menu_items(&context_menu_model);
 
     let popover = PopoverMenu::builder()
         .position(PositionType::Bottom)
@@ -95,28 +161,113 @@ pub fn create_context_menu_button() -> (MenuButton, PopoverMenu, gtk::gio::Menu)
 /// Set the context popover to the app list context.
 pub fn set_context_popover_to_app_list_context(
     menu_model: &gtk::gio::Menu,
-    application: &MainApplication,
+    window: &ApplicationWindow,
 ) {
     menu_model.remove_all();
-    menu_model.append(Some("Refresh app list"), Some("app.refresh_app_list"));
+    menu_model.append(Some(&tr!("Refresh app list")), Some("win.refresh_app_list"));
+    menu_model.append(Some(&tr!("Reconnect to Steam")), Some("win.reconnect_steam"));
+    menu_model.append(Some(&tr!("Close all sessions")), Some("win.close_all_sessions"));
+    menu_model.append(
+        Some(&tr!("Hide games without achievements")),
+        Some("win.hide_empty_apps"),
+    );
+    menu_model.append(
+        Some(&tr!("Export library completion...")),
+        Some("win.export_library_completion"),
+    );
+    menu_model.append(
+        Some(&tr!("Search achievements...")),
+        Some("win.search_achievements"),
+    );
     set_common_menu_items(menu_model);
-    set_app_action_enabled(&application, "refresh_achievements_list", false);
+    set_app_action_enabled(window, "refresh_achievements_list", false);
+    set_app_action_enabled(window, "lock_all_achievements", false);
 }
 
 /// Set the context popover to the app details context.
 pub fn set_context_popover_to_app_details_context(
     menu_model: &gtk::gio::Menu,
-    application: &MainApplication,
+    window: &ApplicationWindow,
 ) {
     menu_model.remove_all();
     menu_model.append(
-        Some("Refresh achievements & stats"),
-        Some("app.refresh_achievements_list"),
+        Some(&tr!("Refresh achievements & stats")),
+        Some("win.refresh_achievements_list"),
+    );
+    menu_model.append(Some(&tr!("Show sidebar")), Some("win.show_sidebar"));
+    menu_model.append(
+        Some(&tr!("Compare with a profile...")),
+        Some("win.compare_profile"),
+    );
+    menu_model.append(
+        Some(&tr!("Global stats on Steam Community")),
+        Some("win.open_community_stats"),
+    );
+    menu_model.append(
+        Some(&tr!("Lock all achievements...")),
+        Some("win.lock_all_achievements"),
     );
     menu_model.append(
-        Some("Reset everything"),
-        Some("app.clear_all_stats_and_achievements"),
+        Some(&tr!("Reset everything")),
+        Some("win.clear_all_stats_and_achievements"),
     );
     set_common_menu_items(menu_model);
-    set_app_action_enabled(&application, "refresh_app_list", false);
+    set_app_action_enabled(window, "refresh_app_list", false);
+}
+
+/// Makes `model` hold `items` in the same order with as few changes as possible, so the list
+/// keeps its scroll position. An item already in the model under the same key is kept when
+/// `update(existing, new)` could bring it up to date, and replaced by the new one otherwise.
+pub fn sync_list_store<T: IsA<glib::Object>>(
+    model: &ListStore,
+    items: Vec<T>,
+    key: impl Fn(&T) -> String,
+    update: impl Fn(&T, &T) -> bool,
+) {
+    let wanted: HashSet<String> = items.iter().map(&key).collect();
+    // From the end, so the indices left to visit stay valid
+    for index in (0..model.n_items()).rev() {
+        let gone = model
+            .item(index)
+            .and_downcast::<T>()
+            .is_none_or(|item| !wanted.contains(&key(&item)));
+        if gone {
+            model.remove(index);
+        }
+    }
+
+    let key_at = |index: u32| model.item(index).and_downcast::<T>().map(|item| key(&item));
+    let mut existing: HashMap<String, T> = model
+        .iter::<T>()
+        .filter_map(Result::ok)
+        .map(|item| (key(&item), item))
+        .collect();
+    // An item moved up is inserted at its new place, its old copy further down is dropped
+    // once reached, which keeps the whole pass linear
+    let mut placed = HashSet::new();
+    for (index, item) in items.into_iter().enumerate() {
+        let index = index as u32;
+        while key_at(index).is_some_and(|current| placed.contains(&current)) {
+            model.remove(index);
+        }
+
+        let item_key = key(&item);
+        match existing.remove(&item_key) {
+            Some(existing) => {
+                if key_at(index).as_ref() != Some(&item_key) {
+                    model.insert(index, &existing);
+                }
+                if !update(&existing, &item) {
+                    model.splice(index, 1, &[item]);
+                }
+            }
+            None => model.insert(index, &item),
+        }
+        placed.insert(item_key);
+    }
+    // Only old copies of moved items are left past the wanted ones
+    let len = placed.len() as u32;
+    if model.n_items() > len {
+        model.splice(len, model.n_items() - len, &[] as &[glib::Object]);
+    }
 }
@@ -24,7 +24,9 @@ mod steam_client;
 mod utils;
 
 use crate::backend::{app::app, orchestrator::orchestrator};
-use crate::utils::{arguments::parse_cli_arguments, bidir_child::BidirChild};
+use crate::utils::{
+    arguments::parse_cli_arguments, bidir_child::BidirChild, console::attach_console,
+};
 use frontend::main_ui;
 use gtk::glib::{self, ExitCode};
 use std::process::Command;
@@ -37,6 +39,10 @@ const APP_ID: &str = "org.sam_authors.sam_rewritten";
 fn main() -> glib::ExitCode {
     let arguments = parse_cli_arguments();
 
+    if arguments.console {
+        attach_console();
+    }
+
     if arguments.is_orchestrator || arguments.is_app > 0 {
         // Use pattern matching to avoid unnecessary allocations
         let (mut tx, mut rx) = match (arguments.tx, arguments.rx) {
@@ -47,9 +53,16 @@ fn main() -> glib::ExitCode {
             }
         };
         let exit_code = if arguments.is_orchestrator {
-            orchestrator(&mut tx, &mut rx)
+            orchestrator(
+                &mut tx,
+                rx,
+                arguments.dry_run,
+                arguments.offline,
+                arguments.serve,
+                arguments.mock,
+            )
         } else {
-            app(arguments.is_app, &mut tx, &mut rx)
+            app(arguments.is_app, arguments.dry_run, &mut tx, &mut rx)
         };
         return ExitCode::from(exit_code as u8);
     }
@@ -62,7 +75,21 @@ fn main() -> glib::ExitCode {
             return ExitCode::FAILURE;
         }
     };
-    let orchestrator = match BidirChild::new(Command::new(&current_exe).arg("--orchestrator")) {
+    let mut command = Command::new(&current_exe);
+    command.arg("--orchestrator");
+    if arguments.dry_run {
+        command.arg("--dry-run");
+    }
+    if arguments.offline {
+        command.arg("--offline");
+    }
+    if arguments.serve {
+        command.arg("--serve");
+    }
+    if arguments.mock {
+        command.arg("--mock");
+    }
+    let orchestrator = match BidirChild::new(&mut command) {
         Ok(child) => child,
         Err(e) => {
             eprintln!("Failed to spawn orchestrator process: {e}");
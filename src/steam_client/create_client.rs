@@ -52,17 +52,17 @@ pub fn load_steamclient_library() -> Result<Library, Box<dyn std::error::Error>>
     }
 }
 
+/// The client interface and its callback functions, borrowed from the loaded library.
+type SteamClientInterface<'a> = (
+    *mut ISteamClient,
+    Symbol<'a, SteamGetCallbackFn>,
+    Symbol<'a, SteamFreeLastCallbackFn>,
+);
+
 /// Creates a new ISteamClient interface and retrieves callback function pointers.
 pub fn new_steam_client_interface(
     steamclient_so: &Library,
-) -> Result<
-    (
-        *mut ISteamClient,
-        Symbol<'_, SteamGetCallbackFn>,
-        Symbol<'_, SteamFreeLastCallbackFn>,
-    ),
-    Box<dyn std::error::Error>,
-> {
+) -> Result<SteamClientInterface<'_>, Box<dyn std::error::Error>> {
     // Scope unsafe as tightly as possible
     let (create_interface, steam_get_callback, steam_free_last_callback) = unsafe {
         (
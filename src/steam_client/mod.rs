@@ -13,6 +13,10 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+// The wrappers keep the raw interface pointers behind an Arc, which is neither Send nor
+// Sync. They are only ever used from the thread that owns the Steam pipe.
+#![allow(clippy::arc_with_non_send_sync)]
+
 pub mod create_client;
 pub mod steam_app_list_vtable;
 pub mod steam_app_list_wrapper;
@@ -43,5 +43,4 @@ pub struct ISteamAppList {
 }
 
 /// The interface version string for ISteamAppList.
- #[allow(dead_code)]
- pub const STEAMAPPLIST_INTERFACE_VERSION: &str = "STEAMAPPLIST_INTERFACE_VERSION001\0";
+pub const STEAMAPPLIST_INTERFACE_VERSION: &str = "STEAMAPPLIST_INTERFACE_VERSION001\0";
@@ -21,12 +21,15 @@ use std::os::raw::{c_char, c_int};
 use std::sync::Arc;
 
 /// Safe Rust wrapper for the ISteamAppList interface.
-#[allow(dead_code)]
+///
+/// Valve only hands this interface to whitelisted apps. Spacewar (480) is reportedly one of
+/// them, so calls may fail or return nothing for other app ids.
+#[derive(Debug, Clone)]
 pub struct SteamAppList {
     inner: Arc<SteamAppListInner>,
 }
 
-#[allow(dead_code)]
+#[derive(Debug)]
 struct SteamAppListInner {
     ptr: *mut ISteamAppList,
 }
@@ -42,6 +45,34 @@ impl SteamAppList {
         }
     }
 
+    /// Gets the number of apps installed on this machine.
+    pub fn get_num_installed_apps(&self) -> Result<u32, SteamClientError> {
+        unsafe {
+            let vtable = (*self.inner.ptr)
+                .vtable
+                .as_ref()
+                .ok_or(SteamClientError::NullVtable)?;
+
+            Ok((vtable.get_num_installed_apps)(self.inner.ptr))
+        }
+    }
+
+    /// Gets the AppIds of all apps installed on this machine.
+    pub fn get_installed_apps(&self) -> Result<Vec<AppId_t>, SteamClientError> {
+        let count = self.get_num_installed_apps()?;
+        let mut app_ids = vec![0 as AppId_t; count as usize];
+        unsafe {
+            let vtable = (*self.inner.ptr)
+                .vtable
+                .as_ref()
+                .ok_or(SteamClientError::NullVtable)?;
+
+            let written = (vtable.get_installed_apps)(self.inner.ptr, app_ids.as_mut_ptr(), count);
+            app_ids.truncate(written.min(count) as usize);
+        }
+        Ok(app_ids)
+    }
+
     /// Gets the name of an app by its AppId.
     pub fn get_app_name(&self, app_id: AppId_t) -> Result<String, SteamClientError> {
         let mut buffer = vec![0u8; 256];
@@ -17,10 +17,13 @@
 //! This module allows safe access to Steam application data via the Steamworks API.
 use crate::steam_client::steam_apps_001_vtable::ISteamApps001;
 use crate::steam_client::wrapper_types::SteamClientError;
-use std::ffi::CStr;
 use std::os::raw::{c_char, c_int};
 use std::sync::Arc;
 
+/// Buffer first given to `GetAppData`, doubled while values fill it up to the maximum.
+const APP_DATA_INITIAL_LEN: usize = 256;
+const APP_DATA_MAX_LEN: usize = 64 * 1024;
+
 /// Safe wrapper for the `ISteamApps001` interface.
 #[derive(Debug, Clone)]
 pub struct SteamApps001 {
@@ -39,6 +42,8 @@ pub enum SteamApps001AppDataKeys<'a> {
     Name,
     /// The app's logo.
     Logo,
+    /// The app's square icon, as in Steam's library list.
+    Icon,
     /// The app's small capsule image for a given language.
     SmallCapsule(&'a str),
     /// The app's Metacritic score.
@@ -56,6 +61,7 @@ impl<'a> SteamApps001AppDataKeys<'a> {
                 format!("small_capsule/{language}\0")
             }
             SteamApps001AppDataKeys::Logo => "logo\0".to_string(),
+            SteamApps001AppDataKeys::Icon => "icon\0".to_string(),
             SteamApps001AppDataKeys::MetacriticScore => "metacritic_score\0".to_string(),
             SteamApps001AppDataKeys::Developer => "developer\0".to_string(),
         }
@@ -82,30 +88,51 @@ impl SteamApps001 {
     /// # Errors
     /// Returns `SteamClientError` if the vtable is null or the FFI call fails.
     pub fn get_app_data(&self, app_id: &u32, key: &str) -> Result<String, SteamClientError> {
-        let mut buffer = vec![0u8; 256];
+        let mut buffer = vec![0u8; APP_DATA_INITIAL_LEN];
 
-        unsafe {
-            // Get the vtable - return error if null
-            let vtable = (*self.inner.ptr)
-                .vtable
-                .as_ref()
-                .ok_or(SteamClientError::NullVtable)?;
+        loop {
+            let result = unsafe {
+                // Get the vtable - return error if null
+                let vtable = (*self.inner.ptr)
+                    .vtable
+                    .as_ref()
+                    .ok_or(SteamClientError::NullVtable)?;
 
-            // Call through the vtable
-            let result = (vtable.get_app_data)(
-                self.inner.ptr,
-                *app_id,
-                key.as_ptr() as *const c_char,
-                buffer.as_mut_ptr() as *mut c_char,
-                buffer.len() as c_int,
-            );
+                // Call through the vtable
+                (vtable.get_app_data)(
+                    self.inner.ptr,
+                    *app_id,
+                    key.as_ptr() as *const c_char,
+                    buffer.as_mut_ptr() as *mut c_char,
+                    buffer.len() as c_int,
+                )
+            };
 
-            if result == 0 {
+            if result <= 0 {
                 return Err(SteamClientError::UnknownError);
             }
 
-            let c_str = CStr::from_ptr(buffer.as_ptr() as *const c_char);
-            Ok(c_str.to_string_lossy().into_owned())
+            match app_data_value(&buffer, result as usize) {
+                Some(value) => return Ok(value),
+                None if buffer.len() < APP_DATA_MAX_LEN => {
+                    buffer = vec![0u8; buffer.len() * 2];
+                }
+                // Cut off at the maximum, still better than nothing
+                None => {
+                    let end = buffer.iter().position(|byte| *byte == 0).unwrap_or(buffer.len());
+                    return Ok(String::from_utf8_lossy(&buffer[..end]).into_owned());
+                }
+            }
         }
     }
 }
+
+/// Reads the value `GetAppData` wrote to `buffer`, reporting `length`. `None` when the value
+/// filled the buffer and may have been cut off, as long localized names are.
+pub fn app_data_value(buffer: &[u8], length: usize) -> Option<String> {
+    let end = buffer.iter().position(|byte| *byte == 0)?;
+    if end + 1 >= buffer.len() || length >= buffer.len() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&buffer[..end]).into_owned())
+}
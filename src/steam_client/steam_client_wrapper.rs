@@ -14,6 +14,8 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 //! Provides a safe Rust abstraction over the `ISteamClient` FFI interface.
+use crate::steam_client::steam_app_list_vtable::STEAMAPPLIST_INTERFACE_VERSION;
+use crate::steam_client::steam_app_list_wrapper::SteamAppList;
 use crate::steam_client::steam_apps_001_vtable::{ISteamApps001, STEAMAPPS001_INTERFACE_VERSION};
 use crate::steam_client::steam_apps_001_wrapper::SteamApps001;
 use crate::steam_client::steam_apps_vtable::STEAMAPPS_INTERFACE_VERSION;
@@ -188,6 +190,33 @@ impl<'a> SteamClient {
         }
     }
 
+    /// Gets the `ISteamAppList` interface for the given user and pipe.
+    /// Returns `SteamClientError` if the vtable is null or Steam refuses the interface,
+    /// which it does for apps Valve has not whitelisted.
+    pub fn get_isteam_app_list(
+        &self,
+        user: HSteamUser,
+        pipe: HSteamPipe,
+    ) -> Result<SteamAppList, SteamClientError> {
+        unsafe {
+            let version = STEAMAPPLIST_INTERFACE_VERSION.as_ptr() as *const c_char;
+
+            let vtable = (*self.inner.ptr)
+                .vtable
+                .as_ref()
+                .ok_or(SteamClientError::NullVtable)?;
+            let app_list_ptr = (vtable.get_isteam_app_list)(self.inner.ptr, user, pipe, version);
+
+            if app_list_ptr.is_null() {
+                Err(SteamClientError::InterfaceCreationFailed(
+                    "ISteamAppList".to_owned(),
+                ))
+            } else {
+                Ok(SteamAppList::from_raw(app_list_ptr))
+            }
+        }
+    }
+
     /// Gets the `ISteamUtils` interface for the given pipe.
     /// Returns `SteamClientError` if the vtable is null or interface creation fails.
     pub fn get_isteam_utils(&self, pipe: HSteamPipe) -> Result<SteamUtils, SteamClientError> {
@@ -55,7 +55,7 @@ impl SteamUserStats {
             let mut achieved = false;
             let mut unlock_time = 0u32;
             let c_achievement_name = std::ffi::CString::new(achievement_name)
-                .map_err(|_| SteamClientError::UnknownError)?;
+                .map_err(|_| SteamClientError::InteriorNul(achievement_name.to_owned()))?;
 
             let success = (vtable.get_achievement_and_unlock_time)(
                 self.inner.ptr,
@@ -72,6 +72,40 @@ impl SteamUserStats {
         }
     }
 
+    /// Gets whether another user unlocked an achievement and when. Their stats must have
+    /// been received through `request_user_stats` first.
+    pub fn get_user_achievement_and_unlock_time(
+        &self,
+        steam_id: CSteamID,
+        achievement_name: &str,
+    ) -> Result<(bool, u32), SteamClientError> {
+        unsafe {
+            let vtable = (*self.inner.ptr)
+                .vtable
+                .as_ref()
+                .ok_or(SteamClientError::NullVtable)?;
+
+            let mut achieved = false;
+            let mut unlock_time = 0u32;
+            let c_achievement_name = std::ffi::CString::new(achievement_name)
+                .map_err(|_| SteamClientError::InteriorNul(achievement_name.to_owned()))?;
+
+            let success = (vtable.get_user_achievement_and_unlock_time)(
+                self.inner.ptr,
+                steam_id,
+                c_achievement_name.as_ptr(),
+                &mut achieved,
+                &mut unlock_time,
+            );
+
+            if success {
+                Ok((achieved, unlock_time))
+            } else {
+                Err(SteamClientError::UnknownError)
+            }
+        }
+    }
+
     /// Unlocks an achievement.
     pub fn set_achievement(&self, achievement_name: &str) -> Result<(), SteamClientError> {
         unsafe {
@@ -81,7 +115,7 @@ impl SteamUserStats {
                 .ok_or(SteamClientError::NullVtable)?;
 
             let c_achievement_name = std::ffi::CString::new(achievement_name)
-                .map_err(|_| SteamClientError::UnknownError)?;
+                .map_err(|_| SteamClientError::InteriorNul(achievement_name.to_owned()))?;
 
             let success = (vtable.set_achievement)(self.inner.ptr, c_achievement_name.as_ptr());
 
@@ -102,7 +136,7 @@ impl SteamUserStats {
                 .ok_or(SteamClientError::NullVtable)?;
 
             let c_achievement_name = std::ffi::CString::new(achievement_name)
-                .map_err(|_| SteamClientError::UnknownError)?;
+                .map_err(|_| SteamClientError::InteriorNul(achievement_name.to_owned()))?;
 
             let success = (vtable.clear_achievement)(self.inner.ptr, c_achievement_name.as_ptr());
 
@@ -122,8 +156,8 @@ impl SteamUserStats {
                 .as_ref()
                 .ok_or(SteamClientError::NullVtable)?;
 
-            let c_stat_name =
-                std::ffi::CString::new(stat_name).map_err(|_| SteamClientError::UnknownError)?;
+            let c_stat_name = std::ffi::CString::new(stat_name)
+                .map_err(|_| SteamClientError::InteriorNul(stat_name.to_owned()))?;
             let mut stat_value = 0i32;
 
             let success =
@@ -145,8 +179,8 @@ impl SteamUserStats {
                 .as_ref()
                 .ok_or(SteamClientError::NullVtable)?;
 
-            let c_stat_name =
-                std::ffi::CString::new(stat_name).map_err(|_| SteamClientError::UnknownError)?;
+            let c_stat_name = std::ffi::CString::new(stat_name)
+                .map_err(|_| SteamClientError::InteriorNul(stat_name.to_owned()))?;
             let mut stat_value = 0f32;
 
             let success =
@@ -168,8 +202,8 @@ impl SteamUserStats {
                 .as_ref()
                 .ok_or(SteamClientError::NullVtable)?;
 
-            let c_stat_name =
-                std::ffi::CString::new(stat_name).map_err(|_| SteamClientError::UnknownError)?;
+            let c_stat_name = std::ffi::CString::new(stat_name)
+                .map_err(|_| SteamClientError::InteriorNul(stat_name.to_owned()))?;
 
             let success = (vtable.set_stat_int32)(self.inner.ptr, c_stat_name.as_ptr(), stat_value);
 
@@ -193,8 +227,8 @@ impl SteamUserStats {
                 .as_ref()
                 .ok_or(SteamClientError::NullVtable)?;
 
-            let c_stat_name =
-                std::ffi::CString::new(stat_name).map_err(|_| SteamClientError::UnknownError)?;
+            let c_stat_name = std::ffi::CString::new(stat_name)
+                .map_err(|_| SteamClientError::InteriorNul(stat_name.to_owned()))?;
 
             let success = (vtable.set_stat_float)(self.inner.ptr, c_stat_name.as_ptr(), stat_value);
 
@@ -287,7 +321,7 @@ impl SteamUserStats {
                 .ok_or(SteamClientError::NullVtable)?;
 
             let c_achievement_name = std::ffi::CString::new(achievement_name)
-                .map_err(|_| SteamClientError::UnknownError)?;
+                .map_err(|_| SteamClientError::InteriorNul(achievement_name.to_owned()))?;
             let mut achieved_percent = 0f32;
 
             let success = (vtable.get_achievement_achieved_percent)(
@@ -303,4 +337,68 @@ impl SteamUserStats {
             Ok(achieved_percent)
         }
     }
+
+    /// Gets how many achievements the current app defines.
+    pub fn get_num_achievements(&self) -> Result<u32, SteamClientError> {
+        unsafe {
+            let vtable = (*self.inner.ptr)
+                .vtable
+                .as_ref()
+                .ok_or(SteamClientError::NullVtable)?;
+
+            Ok((vtable.get_num_achievements)(self.inner.ptr))
+        }
+    }
+
+    /// Gets the API name of the achievement at `index`, below `get_num_achievements`.
+    pub fn get_achievement_name(&self, index: u32) -> Result<String, SteamClientError> {
+        unsafe {
+            let vtable = (*self.inner.ptr)
+                .vtable
+                .as_ref()
+                .ok_or(SteamClientError::NullVtable)?;
+
+            let name_ptr = (vtable.get_achievement_name)(self.inner.ptr, index);
+            if name_ptr.is_null() {
+                return Err(SteamClientError::UnknownError);
+            }
+
+            Ok(std::ffi::CStr::from_ptr(name_ptr)
+                .to_string_lossy()
+                .into_owned())
+        }
+    }
+
+    /// Gets a display attribute of an achievement, such as `name`, `desc` or `hidden`, in
+    /// the current game language. Empty when the achievement has no such attribute.
+    pub fn get_achievement_display_attribute(
+        &self,
+        achievement_name: &str,
+        key: &str,
+    ) -> Result<String, SteamClientError> {
+        unsafe {
+            let vtable = (*self.inner.ptr)
+                .vtable
+                .as_ref()
+                .ok_or(SteamClientError::NullVtable)?;
+
+            let c_achievement_name = std::ffi::CString::new(achievement_name)
+                .map_err(|_| SteamClientError::InteriorNul(achievement_name.to_owned()))?;
+            let c_key = std::ffi::CString::new(key)
+                .map_err(|_| SteamClientError::InteriorNul(key.to_owned()))?;
+
+            let value_ptr = (vtable.get_achievement_display_attribute)(
+                self.inner.ptr,
+                c_achievement_name.as_ptr(),
+                c_key.as_ptr(),
+            );
+            if value_ptr.is_null() {
+                return Ok(String::new());
+            }
+
+            Ok(std::ffi::CStr::from_ptr(value_ptr)
+                .to_string_lossy()
+                .into_owned())
+        }
+    }
 }
@@ -41,6 +41,18 @@ impl SteamUser {
         }
     }
 
+    /// Whether a user is logged into Steam, which can run without one.
+    pub fn is_logged_on(&self) -> Result<bool, SteamClientError> {
+        unsafe {
+            let vtable = (*self.inner.ptr)
+                .vtable
+                .as_ref()
+                .ok_or(SteamClientError::NullVtable)?;
+
+            Ok((vtable.b_logged_on)(self.inner.ptr))
+        }
+    }
+
     /// Gets the SteamID for the current user (Unix).
     #[cfg(unix)]
     pub fn get_steam_id(&self) -> Result<CSteamID, SteamClientError> {
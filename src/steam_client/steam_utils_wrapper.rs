@@ -22,6 +22,11 @@ use crate::steam_client::steamworks_types::{
 use crate::steam_client::wrapper_types::{SteamCallbackId, SteamClientError};
 use std::ffi::{c_int, c_void};
 use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Time between two checks of a pending API call, about one frame.
+const API_CALL_POLL_INTERVAL: Duration = Duration::from_millis(17);
 /// Safe wrapper for the `ISteamUtils` interface.
 #[derive(Debug, Clone)]
 pub struct SteamUtils {
@@ -117,4 +122,25 @@ impl SteamUtils {
             Ok(result)
         }
     }
+
+    /// Polls an API call until it completes and returns its result. Fails with
+    /// `SteamClientError::Timeout` if it did not complete within `timeout`.
+    pub fn wait_for_api_call<T>(
+        &self,
+        api_call_handle: SteamAPICall_t,
+        callback_id: SteamCallbackId,
+        timeout: Duration,
+    ) -> Result<T, SteamClientError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.is_api_call_completed(api_call_handle)? {
+                return self.get_api_call_result(api_call_handle, callback_id);
+            }
+            if Instant::now() >= deadline {
+                dev_println!("API call {api_call_handle} timed out");
+                return Err(SteamClientError::Timeout);
+            }
+            thread::sleep(API_CALL_POLL_INTERVAL);
+        }
+    }
 }
@@ -30,6 +30,13 @@ pub enum SteamClientError {
     InterfaceCreationFailed(String),
     /// The requested app was not found.
     AppNotFound,
+    /// A name passed to Steam contained an interior NUL byte.
+    InteriorNul(String),
+    /// Steam did not complete an API call in time.
+    Timeout,
+    /// Steam failed a call it should have answered, as it does while busy updating or
+    /// starting a game.
+    Busy,
     /// An unknown error occurred.
     UnknownError,
 }
@@ -47,6 +54,11 @@ impl std::fmt::Display for SteamClientError {
                 write!(f, "Failed to create steam interface: {}", name)
             }
             SteamClientError::AppNotFound => write!(f, "App not found"),
+            SteamClientError::InteriorNul(name) => {
+                write!(f, "Identifier contains a NUL byte: {:?}", name)
+            }
+            SteamClientError::Timeout => write!(f, "Steam did not answer in time"),
+            SteamClientError::Busy => write!(f, "Steam is busy"),
             SteamClientError::UnknownError => write!(f, "Unknown Steam error"),
         }
     }
@@ -54,6 +66,14 @@ impl std::fmt::Display for SteamClientError {
 
 impl std::error::Error for SteamClientError {}
 
+impl SteamClientError {
+    /// Whether the call may succeed if tried again shortly. A bare failure is not, Steam
+    /// answers the same for names it doesn't know.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, SteamClientError::Busy | SteamClientError::Timeout)
+    }
+}
+
 /// Enum of Steam callback IDs for wrapper event handling.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)]
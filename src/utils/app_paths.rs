@@ -13,8 +13,17 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::utils::ipc_types::SamError;
-use std::{env, path::PathBuf};
+use crate::utils::{ipc_types::SamError, settings::load_settings};
+use std::{env, fs, path::PathBuf, sync::OnceLock};
+
+/// Environment variable relocating the app cache directory, settings included.
+const CACHE_DIR_ENV: &str = "SAMREWRITTEN_CACHE_DIR";
+
+/// File name of the Steam client library, and where it sits in a Steam install directory.
+#[cfg(target_os = "linux")]
+const STEAMCLIENT_LIB_NAMES: [&str; 2] = ["steamclient.so", "linux64/steamclient.so"];
+#[cfg(target_os = "windows")]
+const STEAMCLIENT_LIB_NAMES: [&str; 1] = ["steamclient64.dll"];
 
 /// Returns the absolute path to the current executable, resolving symlinks.
 pub fn get_executable_path() -> Result<PathBuf, SamError> {
@@ -22,11 +31,43 @@ pub fn get_executable_path() -> Result<PathBuf, SamError> {
     exe.canonicalize().map_err(|_| SamError::UnknownError)
 }
 
-/// Returns a valid directory for persistent app data (Linux).
+/// Returns the directory named by `SAMREWRITTEN_CACHE_DIR`, created if needed, or `None`
+/// when it is unset or can't be written to. Checked once per process.
+fn get_app_cache_dir_override() -> Option<&'static str> {
+    static OVERRIDE: OnceLock<Option<String>> = OnceLock::new();
+    OVERRIDE
+        .get_or_init(|| {
+            let folder = env::var(CACHE_DIR_ENV)
+                .ok()
+                .filter(|folder| !folder.trim().is_empty())?;
+            let probe = PathBuf::from(&folder).join(".samrewritten-write-test");
+            let writable = fs::create_dir_all(&folder)
+                .and_then(|_| fs::write(&probe, b""))
+                .and_then(|_| fs::remove_file(&probe));
+            match writable {
+                Ok(()) => Some(folder),
+                Err(e) => {
+                    log::error!("Cannot use {CACHE_DIR_ENV}={folder}: {e}, using the default");
+                    None
+                }
+            }
+        })
+        .as_deref()
+}
+
+/// Returns a valid directory for persistent app data: `SAMREWRITTEN_CACHE_DIR` if set and
+/// writable, else the platform's default.
+pub fn get_app_cache_dir() -> Result<String, SamError> {
+    match get_app_cache_dir_override() {
+        Some(folder) => Ok(folder.to_owned()),
+        None => get_default_app_cache_dir(),
+    }
+}
+
+/// Returns the default directory for persistent app data (Linux).
 #[inline]
 #[cfg(target_os = "linux")]
-pub fn get_app_cache_dir() -> Result<String, SamError> {
-    use std::fs;
+fn get_default_app_cache_dir() -> Result<String, SamError> {
     if let Ok(snap_name) = env::var("SNAP_NAME") {
         if snap_name == "samrewritten" {
             let snap_user_common = env::var("SNAP_USER_COMMON").unwrap_or_else(|e| {
@@ -51,66 +92,99 @@ pub fn get_app_cache_dir() -> Result<String, SamError> {
     Ok(folder)
 }
 
-/// Returns a valid directory for persistent app data (Windows).
+/// Returns the default directory for persistent app data (Windows).
 #[inline]
 #[cfg(target_os = "windows")]
-pub fn get_app_cache_dir() -> Result<String, SamError> {
+fn get_default_app_cache_dir() -> Result<String, SamError> {
     let temp = env::temp_dir();
     temp.to_str().map(|s| s.to_owned()).ok_or(SamError::UnknownError)
 }
 
-/// Returns the path to the Steam client library (Linux).
-#[inline]
+/// Returns the places the Steam client library is looked for, in order (Linux).
 #[cfg(target_os = "linux")]
-pub fn get_steamclient_lib_path() -> Result<PathBuf, SamError> {
-    use std::path::Path;
+pub fn get_steamclient_lib_candidates() -> Vec<PathBuf> {
     if let Ok(real_home) = env::var("SNAP_REAL_HOME") {
-        let path_str = format!("{real_home}/snap/steam/common/.local/share/Steam/linux64/steamclient.so");
-        return Ok(Path::new(&path_str).to_owned());
+        return vec![PathBuf::from(format!(
+            "{real_home}/snap/steam/common/.local/share/Steam/linux64/steamclient.so"
+        ))];
     }
-    let home = env::var("HOME").map_err(|_| SamError::UnknownError)?;
-    let lib_paths = [
+    let Ok(home) = env::var("HOME") else {
+        return vec![];
+    };
+    [
         format!("{home}/snap/steam/common/.local/share/Steam/linux64/steamclient.so"),
         format!("{home}/.steam/debian-installation/linux64/steamclient.so"),
         format!("{home}/.steam/sdk64/steamclient.so"),
         format!("{home}/.steam/steam/linux64/steamclient.so"),
         format!("{home}/.steam/root/linux64/steamclient.so"),
-    ];
-    for lib_path in &lib_paths {
-        let path = Path::new(lib_path);
-        if path.exists() {
-            return Ok(path.into());
-        }
-    }
-    Err(SamError::UnknownError)
+        format!("{home}/.var/app/com.valvesoftware.Steam/.local/share/Steam/linux64/steamclient.so"),
+        format!("{home}/.var/app/com.valvesoftware.Steam/data/Steam/linux64/steamclient.so"),
+    ]
+    .into_iter()
+    .map(PathBuf::from)
+    .collect()
 }
 
-/// Returns the path to the Steam client library (Windows).
-#[inline]
+/// Returns the places the Steam client library is looked for, in order (Windows).
 #[cfg(target_os = "windows")]
-pub fn get_steamclient_lib_path() -> Result<PathBuf, SamError> {
-    use std::path::PathBuf;
+pub fn get_steamclient_lib_candidates() -> Vec<PathBuf> {
     use winreg::RegKey;
     use winreg::enums::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
 
     const REG_PATH: &str = "SOFTWARE\\Valve\\Steam";
     const VALUE_NAME: &str = "SteamPath";
 
-    // Try HKEY_CURRENT_USER first
-    if let Ok(subkey) = RegKey::predef(HKEY_CURRENT_USER).open_subkey(REG_PATH) {
-        if let Ok(value) = subkey.get_value::<String, _>(VALUE_NAME) {
-            return Ok(PathBuf::from(value).join("steamclient64.dll"));
-        }
+    // HKEY_CURRENT_USER first, then HKEY_LOCAL_MACHINE
+    [HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE]
+        .into_iter()
+        .filter_map(|hkey| RegKey::predef(hkey).open_subkey(REG_PATH).ok())
+        .filter_map(|subkey| subkey.get_value::<String, _>(VALUE_NAME).ok())
+        .map(|value| PathBuf::from(value).join("steamclient64.dll"))
+        .collect()
+}
+
+/// Resolves a user-provided `steamclient_path`, which may name the library itself or
+/// the Steam directory containing it. `~` stands for the home directory.
+pub fn resolve_steamclient_override(configured: &str) -> Option<PathBuf> {
+    let path = match configured.strip_prefix("~/").or(configured.strip_prefix("~\\")) {
+        Some(rest) => PathBuf::from(env::var("HOME").or(env::var("USERPROFILE")).ok()?).join(rest),
+        None => PathBuf::from(configured),
+    };
+    if path.is_file() {
+        return Some(path);
+    }
+    if path.is_dir() {
+        return STEAMCLIENT_LIB_NAMES
+            .iter()
+            .map(|name| path.join(name))
+            .find(|lib| lib.is_file());
     }
+    None
+}
 
-    // Fallback to HKEY_LOCAL_MACHINE
-    if let Ok(subkey) = RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(REG_PATH) {
-        if let Ok(value) = subkey.get_value::<String, _>(VALUE_NAME) {
-            return Ok(PathBuf::from(value).join("steamclient64.dll"));
+/// Returns the path to the Steam client library: the `steamclient_path` setting if it
+/// points to one, else the first candidate found on disk.
+pub fn get_steamclient_lib_path() -> Result<PathBuf, SamError> {
+    let configured = load_settings()
+        .steamclient_path
+        .filter(|path| !path.trim().is_empty());
+    if let Some(configured) = &configured {
+        if let Some(path) = resolve_steamclient_override(configured.trim()) {
+            return Ok(path);
         }
+        log::warn!("No steamclient library at the configured path {configured}");
     }
 
-    Err(SamError::UnknownError)
+    let candidates = get_steamclient_lib_candidates();
+    if let Some(path) = candidates.iter().find(|path| path.is_file()) {
+        return Ok(path.clone());
+    }
+    // The configured path goes first so the user sees why their setting was not used
+    let probed_paths = configured
+        .into_iter()
+        .chain(candidates.iter().map(|path| path.display().to_string()))
+        .collect();
+    Err(SamError::SteamClientNotFound(probed_paths))
 }
 
 /// Returns the path to the user game stats schema file (Linux).
@@ -129,6 +203,8 @@ pub fn get_user_game_stats_schema_path(app_id: &u32) -> Result<String, SamError>
         format!("{home}/.steam/debian-installation"),
         format!("{home}/.steam/steam"),
         format!("{home}/.steam/root"),
+        format!("{home}/.var/app/com.valvesoftware.Steam/.local/share/Steam"),
+        format!("{home}/.var/app/com.valvesoftware.Steam/data/Steam"),
     ];
     for install_dir in &install_dirs {
         if Path::new(install_dir).exists() {
@@ -156,6 +232,17 @@ pub fn get_user_game_stats_schema_path(app_id: &u32) -> Result<String, SamError>
     Ok(format!("{value}/appcache/stats/UserGameStatsSchema_{app_id}.bin"))
 }
 
+/// Returns the path to `appinfo.vdf`, where Steam caches the metadata of the apps it knows.
+/// It sits in `appcache`, above the stats schemas.
+pub fn get_app_info_path() -> Result<PathBuf, SamError> {
+    let schema_path = PathBuf::from(get_user_game_stats_schema_path(&0)?);
+    schema_path
+        .parent()
+        .and_then(|stats_dir| stats_dir.parent())
+        .map(|appcache_dir| appcache_dir.join("appinfo.vdf"))
+        .ok_or(SamError::UnknownError)
+}
+
 /// Returns the path to the local app banner image (Linux).
 #[inline]
 #[cfg(target_os = "linux")]
@@ -172,6 +259,8 @@ pub fn get_local_app_banner_file_path(app_id: &u32) -> Result<String, SamError>
         format!("{home}/.steam/debian-installation"),
         format!("{home}/.steam/steam"),
         format!("{home}/.steam/root"),
+        format!("{home}/.var/app/com.valvesoftware.Steam/.local/share/Steam"),
+        format!("{home}/.var/app/com.valvesoftware.Steam/data/Steam"),
     ];
     for install_dir in &install_dirs {
         if Path::new(install_dir).exists() {
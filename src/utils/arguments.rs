@@ -14,6 +14,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::dev_println;
+use crate::utils::console::attach_console;
 use gtk::gio::ApplicationCommandLine;
 use gtk::prelude::ApplicationCommandLineExt;
 use interprocess::unnamed_pipe::{Recver, Sender};
@@ -28,6 +29,11 @@ use std::{cell::Cell, env, process::exit, rc::Rc};
 pub struct CliArguments {
     pub is_orchestrator: bool,
     pub is_app: u32,
+    pub console: bool,
+    pub dry_run: bool,
+    pub offline: bool,
+    pub serve: bool,
+    pub mock: bool,
     pub rx: Option<Recver>,
     pub tx: Option<Sender>,
 }
@@ -36,39 +42,143 @@ pub struct CliArguments {
 #[derive(Debug)]
 pub struct GuiArguments {
     pub auto_open: Rc<Cell<u32>>,
+    pub dry_run: bool,
+    pub offline: bool,
 }
 
+/// Checks that an inherited file descriptor is an open pipe before we take ownership of it.
 #[cfg(target_os = "linux")]
-fn parse_sender_fd(value: &str) -> Option<Sender> {
-    value.parse::<i32>().ok().map(|fd| unsafe { Sender::from_raw_fd(fd) })
+fn parse_pipe_fd(value: &str) -> Result<i32, &'static str> {
+    use std::{fs::File, mem::ManuallyDrop, os::unix::fs::FileTypeExt};
+
+    const F_GETFD: i32 = 1;
+
+    unsafe extern "C" {
+        fn fcntl(fd: i32, cmd: i32, ...) -> i32;
+    }
+
+    let fd = value.parse::<i32>().map_err(|_| "not a file descriptor")?;
+    if fd < 0 {
+        return Err("not a file descriptor");
+    }
+
+    // A File may only wrap a descriptor that is open, ask the kernel before building one
+    if unsafe { fcntl(fd, F_GETFD) } == -1 {
+        return Err("the file descriptor is not open");
+    }
+
+    // fstat through a File that must never close the descriptor it borrows
+    let file = ManuallyDrop::new(unsafe { File::from_raw_fd(fd) });
+    match file.metadata() {
+        Ok(metadata) if metadata.file_type().is_fifo() => Ok(fd),
+        Ok(_) => Err("the file descriptor is not a pipe"),
+        Err(_) => Err("the file descriptor is not open"),
+    }
 }
+
+/// Checks that an inherited handle is a pipe before we take ownership of it.
 #[cfg(target_os = "windows")]
-fn parse_sender_fd(value: &str) -> Option<Sender> {
-    value.parse::<usize>().ok().map(|h| unsafe { Sender::from_raw_handle(h as RawHandle) })
+fn parse_pipe_handle(value: &str) -> Result<RawHandle, &'static str> {
+    const FILE_TYPE_PIPE: u32 = 3;
+
+    unsafe extern "system" {
+        fn GetFileType(handle: RawHandle) -> u32;
+    }
+
+    let handle = value.parse::<usize>().map_err(|_| "not a handle")?;
+    if handle == 0 {
+        return Err("not a handle");
+    }
+
+    let handle = handle as RawHandle;
+    if unsafe { GetFileType(handle) } != FILE_TYPE_PIPE {
+        return Err("the handle is not a pipe");
+    }
+    Ok(handle)
 }
+
 #[cfg(target_os = "linux")]
-fn parse_recver_fd(value: &str) -> Option<Recver> {
-    value.parse::<i32>().ok().map(|fd| unsafe { Recver::from_raw_fd(fd) })
+fn parse_sender_fd(value: &str) -> Result<Sender, &'static str> {
+    parse_pipe_fd(value).map(|fd| unsafe { Sender::from_raw_fd(fd) })
 }
 #[cfg(target_os = "windows")]
-fn parse_recver_fd(value: &str) -> Option<Recver> {
-    value.parse::<usize>().ok().map(|h| unsafe { Recver::from_raw_handle(h as RawHandle) })
+fn parse_sender_fd(value: &str) -> Result<Sender, &'static str> {
+    parse_pipe_handle(value).map(|h| unsafe { Sender::from_raw_handle(h) })
+}
+#[cfg(target_os = "linux")]
+fn parse_recver_fd(value: &str) -> Result<Recver, &'static str> {
+    parse_pipe_fd(value).map(|fd| unsafe { Recver::from_raw_fd(fd) })
+}
+#[cfg(target_os = "windows")]
+fn parse_recver_fd(value: &str) -> Result<Recver, &'static str> {
+    parse_pipe_handle(value).map(|h| unsafe { Recver::from_raw_handle(h) })
 }
 
+/// Usage summary printed by `--help`.
+const USAGE: &str = "\
+Usage: samrewritten [OPTIONS]
+
+Options:
+  --auto-open=<APP_ID>  Open the given app as soon as the library is loaded
+  --console             Attach to the parent console on Windows to show logs
+  --dry-run             Log achievement and stat changes instead of sending them to Steam
+  --offline             Only use the cached app list and images, never go online
+  --mock                Show a synthetic development app instead of connecting to Steam
+  --serve               Let other programs drive SamRewritten through a local JSON-RPC socket
+  -h, --help            Print this help and exit
+  -V, --version         Print the version and exit
+
+Internal options, used when SamRewritten launches its own processes:
+  --orchestrator        Run as the orchestrator process
+  --app=<APP_ID>        Run as the app server for the given app
+  --tx=<FD>             File descriptor or handle to send messages on
+  --rx=<FD>             File descriptor or handle to receive messages on";
+
 /// Parses command-line arguments for orchestrator/app mode.
+/// Prints and exits for `--help` and `--version`, before anything else is set up.
 pub fn parse_cli_arguments() -> CliArguments {
     let mut args = CliArguments {
         is_orchestrator: false,
         is_app: 0,
+        console: false,
+        dry_run: false,
+        offline: false,
+        serve: false,
+        mock: false,
         rx: None,
         tx: None,
     };
 
     for (_index, arg) in env::args().enumerate().skip(1) {
         match arg.as_str() {
+            "--help" | "-h" => {
+                attach_console();
+                println!("SamRewritten {}\n\n{USAGE}", env!("CARGO_PKG_VERSION"));
+                exit(0);
+            }
+            "--version" | "-V" => {
+                attach_console();
+                println!("SamRewritten {}", env!("CARGO_PKG_VERSION"));
+                exit(0);
+            }
             "--orchestrator" => {
                 args.is_orchestrator = true;
             }
+            "--console" => {
+                args.console = true;
+            }
+            "--dry-run" => {
+                args.dry_run = true;
+            }
+            "--offline" => {
+                args.offline = true;
+            }
+            "--serve" => {
+                args.serve = true;
+            }
+            "--mock" => {
+                args.mock = true;
+            }
             _ if arg.starts_with("--app=") => {
                 let value = &arg[6..];
                 match value.parse::<u32>() {
@@ -82,9 +192,9 @@ pub fn parse_cli_arguments() -> CliArguments {
             _ if arg.starts_with("--tx=") => {
                 let value = &arg[5..];
                 match parse_sender_fd(value) {
-                    Some(sender) => args.tx = Some(sender),
-                    None => {
-                        eprintln!("Invalid value for --tx: {}", value);
+                    Ok(sender) => args.tx = Some(sender),
+                    Err(reason) => {
+                        eprintln!("Invalid value for --tx: {} ({reason})", value);
                         exit(1);
                     }
                 }
@@ -92,9 +202,9 @@ pub fn parse_cli_arguments() -> CliArguments {
             _ if arg.starts_with("--rx=") => {
                 let value = &arg[5..];
                 match parse_recver_fd(value) {
-                    Some(recver) => args.rx = Some(recver),
-                    None => {
-                        eprintln!("Invalid value for --rx: {}", value);
+                    Ok(recver) => args.rx = Some(recver),
+                    Err(reason) => {
+                        eprintln!("Invalid value for --rx: {} ({reason})", value);
                         exit(1);
                     }
                 }
@@ -115,13 +225,21 @@ pub fn parse_cli_arguments() -> CliArguments {
 /// Parses GUI arguments from a GTK ApplicationCommandLine.
 pub fn parse_gui_arguments(cmd_line: &ApplicationCommandLine) -> GuiArguments {
     let arguments = cmd_line.arguments();
-    let args = GuiArguments {
+    let mut args = GuiArguments {
         auto_open: Rc::new(Cell::new(0)),
+        dry_run: false,
+        offline: false,
     };
 
     for arg in arguments.iter().skip(1) {
         // Skip the first argument (program name)
         if let Some(arg_str) = arg.to_str() {
+            if arg_str == "--dry-run" {
+                args.dry_run = true;
+            }
+            if arg_str == "--offline" {
+                args.offline = true;
+            }
             if let Some(value_str) = arg_str.strip_prefix("--auto-open=") {
                 match value_str.parse::<u32>() {
                     Ok(value) => {
@@ -20,8 +20,87 @@ use interprocess::unnamed_pipe::{Recver, Sender};
 #[cfg(unix)]
 use std::os::fd::IntoRawFd;
 #[cfg(windows)]
-use std::os::windows::io::{AsRawHandle, OwnedHandle};
-use std::process::{Child, Command};
+use std::os::windows::io::{AsRawHandle, FromRawHandle, OwnedHandle, RawHandle};
+use std::{
+    process::{Child, Command},
+    thread,
+    time::{Duration, Instant},
+};
+#[cfg(windows)]
+use std::{ffi::c_void, sync::OnceLock};
+
+/// Job object limits, as laid out by `JOBOBJECT_EXTENDED_LIMIT_INFORMATION`.
+#[cfg(windows)]
+#[repr(C)]
+#[derive(Default)]
+struct JobExtendedLimitInformation {
+    per_process_user_time_limit: i64,
+    per_job_user_time_limit: i64,
+    limit_flags: u32,
+    minimum_working_set_size: usize,
+    maximum_working_set_size: usize,
+    active_process_limit: u32,
+    affinity: usize,
+    priority_class: u32,
+    scheduling_class: u32,
+    io_counters: [u64; 6],
+    process_memory_limit: usize,
+    job_memory_limit: usize,
+    peak_process_memory_used: usize,
+    peak_job_memory_used: usize,
+}
+
+#[cfg(windows)]
+unsafe extern "system" {
+    fn CreateJobObjectW(attributes: *mut c_void, name: *const u16) -> RawHandle;
+    fn SetInformationJobObject(
+        job: RawHandle,
+        information_class: i32,
+        information: *mut c_void,
+        length: u32,
+    ) -> i32;
+    fn AssignProcessToJobObject(job: RawHandle, process: RawHandle) -> i32;
+}
+
+/// Job object holding every child of this process. Its handle is only closed by the OS
+/// when this process ends, however it ends, which kills the children with it.
+#[cfg(windows)]
+fn kill_on_close_job() -> Option<&'static OwnedHandle> {
+    const JOB_OBJECT_EXTENDED_LIMIT_INFORMATION: i32 = 9;
+    const JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE: u32 = 0x2000;
+    static JOB: OnceLock<Option<OwnedHandle>> = OnceLock::new();
+
+    JOB.get_or_init(|| {
+        let handle = unsafe { CreateJobObjectW(std::ptr::null_mut(), std::ptr::null()) };
+        if handle.is_null() {
+            eprintln!("Job object creation failed: {}", std::io::Error::last_os_error());
+            return None;
+        }
+        let job = unsafe { OwnedHandle::from_raw_handle(handle) };
+
+        let mut limits = JobExtendedLimitInformation {
+            limit_flags: JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+            ..Default::default()
+        };
+        let set = unsafe {
+            SetInformationJobObject(
+                job.as_raw_handle(),
+                JOB_OBJECT_EXTENDED_LIMIT_INFORMATION,
+                &mut limits as *mut JobExtendedLimitInformation as *mut c_void,
+                size_of::<JobExtendedLimitInformation>() as u32,
+            )
+        };
+        if set == 0 {
+            eprintln!("Job object setup failed: {}", std::io::Error::last_os_error());
+            return None;
+        }
+        Some(job)
+    })
+    .as_ref()
+}
+
+/// Both ends of one unnamed pipe.
+type PipeEnds = (Sender, Recver);
 
 /// Represents a child process with bidirectional unnamed pipes for IPC.
 #[derive(Debug)]
@@ -39,7 +118,7 @@ impl BidirChild {
     #[cfg(unix)]
     pub fn new(command: &mut Command) -> Result<Self, SamError> {
         // Use a helper to reduce code duplication and improve clarity
-        fn create_pipes() -> Result<((Sender, Recver), (Sender, Recver)), SamError> {
+        fn create_pipes() -> Result<(PipeEnds, PipeEnds), SamError> {
             let (parent_to_child_tx, parent_to_child_rx) = pipe().map_err(|e| {
                 eprintln!("Pipe creation failed: {e}");
                 SamError::UnknownError
@@ -76,7 +155,7 @@ impl BidirChild {
     pub fn new(command: &mut Command) -> Result<Self, SamError> {
         use interprocess::os::windows::unnamed_pipe::CreationOptions;
         // Helper for pipe creation and error logging
-        fn create_pipes() -> Result<((Sender, Recver), (Sender, Recver)), SamError> {
+        fn create_pipes() -> Result<(PipeEnds, PipeEnds), SamError> {
             let (parent_to_child_tx, parent_to_child_rx) = CreationOptions::default()
                 .inheritable(true)
                 .build()
@@ -118,10 +197,56 @@ impl BidirChild {
         drop(parent_to_child_rx_handle);
         drop(child_to_parent_tx_handle);
 
+        // Without the job, a crashed parent would leave its children running
+        if let Some(job) = kill_on_close_job() {
+            let assigned =
+                unsafe { AssignProcessToJobObject(job.as_raw_handle(), child.as_raw_handle()) };
+            if assigned == 0 {
+                eprintln!(
+                    "Unable to attach the child process to the job object: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+        }
+
         Ok(Self {
             child,
             tx: parent_to_child_tx,
             rx: child_to_parent_rx,
         })
     }
+
+    /// Waits for the child to exit, killing it if it is still running after `timeout`,
+    /// as when it's stuck in a call into Steam and can't read the shutdown command.
+    pub fn wait_or_kill(&mut self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.child.try_wait() {
+                Ok(Some(_)) => return,
+                Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(20)),
+                Ok(None) => break,
+                Err(e) => {
+                    eprintln!("Failed to wait child process: {e}");
+                    break;
+                }
+            }
+        }
+
+        eprintln!("Child process {} did not exit in time, killing it", self.child.id());
+        if let Err(e) = self.child.kill() {
+            eprintln!("Failed to kill child process: {e}");
+        }
+        if let Err(e) = self.child.wait() {
+            eprintln!("Failed to wait child process: {e}");
+        }
+    }
+
+    /// Another sender on the pipe to the child, to write to it while `tx` is in use.
+    pub fn try_clone_tx(&self) -> std::io::Result<Sender> {
+        #[cfg(unix)]
+        let handle = std::os::fd::AsFd::as_fd(&self.tx).try_clone_to_owned()?;
+        #[cfg(windows)]
+        let handle = std::os::windows::io::AsHandle::as_handle(&self.tx).try_clone_to_owned()?;
+        Ok(Sender::from(handle))
+    }
 }
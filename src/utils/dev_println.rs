@@ -19,6 +19,12 @@ macro_rules! dev_println {
     ($($arg:tt)*) => {
         #[cfg(debug_assertions)]
         println!($($arg)*);
+        // Release builds still see the arguments, without evaluating them, so a binding
+        // only printed here needs no `_` prefix and does not break the debug build
+        #[cfg(not(debug_assertions))]
+        if false {
+            let _ = format_args!($($arg)*);
+        }
     };
 }
 
@@ -28,5 +34,11 @@ macro_rules! dev_print {
     ($($arg:tt)*) => {
         #[cfg(debug_assertions)]
         print!($($arg)*);
+        // Release builds still see the arguments, without evaluating them, so a binding
+        // only printed here needs no `_` prefix and does not break the debug build
+        #[cfg(not(debug_assertions))]
+        if false {
+            let _ = format_args!($($arg)*);
+        }
     };
 }
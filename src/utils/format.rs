@@ -13,6 +13,90 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use crate::tr;
+use gtk::glib;
+use std::{
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Digit grouping and decimal separators of a locale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberSeparators {
+    pub grouping: char,
+    pub decimal: char,
+}
+
+/// The separators of the first language GLib reports for the user (`LC_ALL`, `LC_NUMERIC`,
+/// `LANG`, or the Windows user locale), looked up once.
+fn number_separators() -> NumberSeparators {
+    static SEPARATORS: OnceLock<NumberSeparators> = OnceLock::new();
+    *SEPARATORS.get_or_init(|| {
+        let locale = glib::language_names()
+            .first()
+            .map(|name| name.to_string())
+            .unwrap_or_default();
+        locale_number_separators(&locale)
+    })
+}
+
+/// The separators of `locale`, such as `de_DE.UTF-8`, falling back to the English ones.
+pub fn locale_number_separators(locale: &str) -> NumberSeparators {
+    let language = locale.split(['_', '.', '@']).next().unwrap_or_default();
+
+    let (grouping, decimal) = match language {
+        _ if locale.starts_with("de_CH") || locale.starts_with("it_CH") => ('\'', '.'),
+        "de" | "nl" | "it" | "es" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl"
+        | "sr" => ('.', ','),
+        "fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "nn" | "no" | "uk" | "hu"
+        | "bg" | "lt" | "lv" | "et" => ('\u{a0}', ','),
+        _ => (',', '.'),
+    };
+    NumberSeparators { grouping, decimal }
+}
+
+fn group_digits(digits: &str, grouping: char) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(grouping);
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Formats a whole number with the locale's digit grouping, e.g. `1,234,567` or `1.234.567`.
+pub fn format_localized_integer(value: i64) -> String {
+    let separators = number_separators();
+    let digits = group_digits(&value.unsigned_abs().to_string(), separators.grouping);
+    if value < 0 { format!("-{digits}") } else { digits }
+}
+
+/// Formats a number with `decimals` fraction digits, using the locale's separators.
+pub fn format_localized_decimal(value: f64, decimals: usize) -> String {
+    let separators = number_separators();
+    let fixed = format!("{:.*}", decimals, value.abs());
+    let (whole, fraction) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let mut formatted = String::with_capacity(fixed.len() + whole.len() / 3 + 1);
+    if value.is_sign_negative() && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        formatted.push('-');
+    }
+    formatted.push_str(&group_digits(whole, separators.grouping));
+    if !fraction.is_empty() {
+        formatted.push(separators.decimal);
+        formatted.push_str(fraction);
+    }
+    formatted
+}
+
+/// Formats an unlock time as a local date and time in the locale's preferred format.
+pub fn format_unlock_time(time: SystemTime) -> Option<String> {
+    let seconds = time.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    let date_time = glib::DateTime::from_unix_local(i64::try_from(seconds).ok()?).ok()?;
+    date_time.format("%x %X").ok().map(|text| text.to_string())
+}
+
 /// Formats a number of seconds as a MM:SS string.
 #[inline]
 pub fn format_seconds_to_mm_ss(total_seconds: usize) -> String {
@@ -20,3 +104,58 @@ pub fn format_seconds_to_mm_ss(total_seconds: usize) -> String {
     let seconds = total_seconds % 60;
     format!("{:02}:{:02}", minutes, seconds)
 }
+
+/// Parses a user-typed number, accepting either `.` or `,` as the decimal separator.
+///
+/// Spaces and thin spaces used as digit grouping are ignored. When both `.` and `,`
+/// appear, the last one is taken as the decimal separator and the other as grouping.
+/// Whole numbers also accept `.` or `,` as grouping when followed by three digits.
+pub fn parse_localized_number(text: &str, is_integer: bool) -> Result<f64, String> {
+    let mut cleaned: String = text
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '\u{a0}' | '\u{202f}' | '\''))
+        .collect();
+
+    if cleaned.is_empty() {
+        return Err(tr!("Please enter a value"));
+    }
+
+    // For whole numbers, "1,000" or "1.000" is digit grouping rather than a fraction.
+    if is_integer {
+        let groups: Vec<&str> = cleaned.split(['.', ',']).collect();
+        if groups.len() > 1
+            && groups[1..]
+                .iter()
+                .all(|group| group.len() == 3 && group.chars().all(|c| c.is_ascii_digit()))
+        {
+            cleaned = groups.concat();
+        }
+    }
+
+    match (cleaned.rfind('.'), cleaned.rfind(',')) {
+        (Some(dot), Some(comma)) if comma > dot => {
+            cleaned = cleaned.replace('.', "").replace(',', ".");
+        }
+        (Some(_), Some(_)) => {
+            cleaned = cleaned.replace(',', "");
+        }
+        (None, Some(_)) => {
+            cleaned = cleaned.replace(',', ".");
+        }
+        _ => {}
+    }
+
+    if is_integer {
+        cleaned
+            .parse::<i64>()
+            .map(|value| value as f64)
+            .map_err(|_| tr!("\"{text}\" is not a whole number", text = text.trim()))
+    } else {
+        cleaned
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| tr!("\"{text}\" is not a number", text = text.trim()))
+    }
+}
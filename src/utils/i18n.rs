@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// User-facing strings go through gettext under the `samrewritten` domain. Translations are
+// compiled `.mo` files in `<locale dir>/<lang>/LC_MESSAGES/samrewritten.mo`, see `po/README.md`.
+
+use gtk::glib;
+use std::{env, path::PathBuf};
+
+/// The gettext domain of SamRewritten's translations.
+pub const GETTEXT_DOMAIN: &str = "samrewritten";
+
+/// Translates `msgid` using the user's locale, or returns it unchanged if there is no translation.
+///
+/// Prefer the `tr!` macro, which also fills in named placeholders.
+pub fn gettext(msgid: &str) -> String {
+    glib::dgettext(Some(GETTEXT_DOMAIN), msgid).to_string()
+}
+
+/// Translates a message with singular and plural forms, picking the one matching `n`.
+///
+/// Prefer the `trn!` macro, which also fills in named placeholders.
+pub fn ngettext(msgid: &str, msgid_plural: &str, n: u64) -> String {
+    glib::dngettext(Some(GETTEXT_DOMAIN), msgid, msgid_plural, n).to_string()
+}
+
+/// Translates a UI string, then substitutes its `{name}` placeholders.
+///
+/// Placeholders are filled after translation so translators can move them around:
+/// `tr!("Resetting {done} of {total}...", done = done, total = total)`.
+#[macro_export]
+macro_rules! tr {
+    ($msgid:literal) => {
+        $crate::utils::i18n::gettext($msgid)
+    };
+    ($msgid:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut text = $crate::utils::i18n::gettext($msgid);
+        $(
+            text = text.replace(concat!("{", stringify!($name), "}"), &$value.to_string());
+        )+
+        text
+    }};
+}
+
+/// Like `tr!`, for messages whose wording depends on a count:
+/// `trn!("{n} achievement selected", "{n} achievements selected", count, n = count)`.
+#[macro_export]
+macro_rules! trn {
+    ($msgid:literal, $msgid_plural:literal, $count:expr $(, $name:ident = $value:expr)* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut text = $crate::utils::i18n::ngettext($msgid, $msgid_plural, $count as u64);
+        $(
+            text = text.replace(concat!("{", stringify!($name), "}"), &$value.to_string());
+        )*
+        text
+    }};
+}
+
+/// Directory holding the compiled translations.
+///
+/// `SAMREWRITTEN_LOCALEDIR` takes precedence, then `share/locale` next to the installed
+/// executable (`/usr/bin/../share/locale`, the snap or AppImage prefix), then a `locale`
+/// folder beside it for portable and Windows builds.
+fn get_locale_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("SAMREWRITTEN_LOCALEDIR") {
+        return Some(PathBuf::from(dir));
+    }
+    let exe_dir = env::current_exe().ok()?.parent()?.to_path_buf();
+    [exe_dir.join("../share/locale"), exe_dir.join("locale")]
+        .into_iter()
+        .find(|dir| dir.is_dir())
+}
+
+#[cfg(target_os = "linux")]
+unsafe extern "C" {
+    fn bindtextdomain(
+        domainname: *const std::ffi::c_char,
+        dirname: *const std::ffi::c_char,
+    ) -> *mut std::ffi::c_char;
+    fn bind_textdomain_codeset(
+        domainname: *const std::ffi::c_char,
+        codeset: *const std::ffi::c_char,
+    ) -> *mut std::ffi::c_char;
+}
+
+/// Points gettext at the translations directory. Call once, before building any UI.
+///
+/// Without a known directory the system default (`/usr/share/locale`) is used.
+#[cfg(target_os = "linux")]
+pub fn init_i18n() {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let Ok(domain) = CString::new(GETTEXT_DOMAIN) else {
+        return;
+    };
+    if let Some(dir) = get_locale_dir()
+        && let Ok(dir) = CString::new(dir.as_os_str().as_bytes())
+    {
+        unsafe { bindtextdomain(domain.as_ptr(), dir.as_ptr()) };
+    }
+    // GTK expects UTF-8 whatever the locale's charset
+    unsafe { bind_textdomain_codeset(domain.as_ptr(), c"UTF-8".as_ptr()) };
+}
+
+/// Windows builds rely on the search path compiled into GTK's bundled libintl, which
+/// does not export `bindtextdomain` under a stable name across toolchains.
+#[cfg(target_os = "windows")]
+pub fn init_i18n() {}
@@ -13,18 +13,55 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use interprocess::unnamed_pipe::Recver;
+use crate::steam_client::wrapper_types::SteamClientError;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
-use std::io::Read;
+use std::{io::Read, time::SystemTime};
+
+/// Largest message accepted from a pipe. The largest ones, app and achievement lists,
+/// stay far below it, a longer length prefix means the stream is corrupt.
+pub const MAX_MESSAGE_LENGTH: usize = 32 * 1024 * 1024;
 
 /// Error types for orchestrator/app IPC.
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum SamError {
     SerializationFailed,
     SteamConnectionFailed,
     AppListRetrievalFailed,
+    OwnershipCheckFailed,
     SocketCommunicationFailed,
     AppMismatchError,
+    /// An achievement or stat id from the schema cannot be passed to Steam.
+    InvalidIdentifier(String),
+    /// The achievement is protected and can only be changed by the game's servers.
+    ProtectedAchievement(String),
+    /// The schema links no editable stat to the achievement.
+    NoProgressStat(String),
+    /// The stat is protected and can only be changed by the game's servers.
+    ProtectedStat(String),
+    /// The change is larger than the stat's `max_change`, Steam would not store it.
+    StatChangeTooLarge(String),
+    /// The stat can only go up, Steam would not store a lower value.
+    StatIncrementOnly(String),
+    /// The stat is shown but can't be changed, like bit fields.
+    ReadOnlyStat(String),
+    /// Steam returned no stats for another user, usually because their profile is private.
+    UserStatsUnavailable,
+    /// No Steam client library was found, with the paths that were probed.
+    SteamClientNotFound(Vec<String>),
+    /// Steam is running, but no user is logged into it.
+    SteamNotLoggedIn,
+    /// The change was made but Steam did not store it.
+    StoreFailed,
+    /// Steam stored the change, but reading the achievement or stat back shows it didn't take.
+    VerificationFailed(String),
+    /// Steam has not downloaded the app's schema yet, so its stats can't be listed.
+    /// Achievements still load, from Steam directly.
+    SchemaUnavailable,
+    /// The user cancelled the command before it was done.
+    Cancelled,
+    /// A message read from a pipe is not valid UTF-8, usually because a length prefix did
+    /// not match its message and the reads are no longer aligned on the messages.
+    FramingError,
     UnknownError,
 }
 
@@ -34,39 +71,172 @@ impl std::fmt::Display for SamError {
             SamError::SerializationFailed => write!(f, "Sam error: Serialization failed"),
             SamError::SteamConnectionFailed => write!(f, "Sam error: Steam connection failed"),
             SamError::AppListRetrievalFailed => write!(f, "Sam error: App list retrieval failed"),
+            SamError::OwnershipCheckFailed => {
+                write!(f, "Sam error: Could not check which apps are owned")
+            }
             SamError::UnknownError => write!(f, "Sam error: Unknown error"),
             SamError::SocketCommunicationFailed => {
                 write!(f, "Sam error: SocketCommunication failed")
             }
             SamError::AppMismatchError => write!(f, "Sam error: App mismatch"),
+            SamError::InvalidIdentifier(id) => {
+                write!(f, "Sam error: Invalid identifier {:?}", id)
+            }
+            SamError::ProtectedAchievement(id) => {
+                write!(f, "Sam error: Achievement {:?} is protected", id)
+            }
+            SamError::NoProgressStat(id) => {
+                write!(f, "Sam error: Achievement {:?} is not unlocked through a stat", id)
+            }
+            SamError::ProtectedStat(id) => write!(f, "Sam error: Stat {:?} is protected", id),
+            SamError::StatChangeTooLarge(id) => {
+                write!(f, "Sam error: Stat {:?} can't change that much at once", id)
+            }
+            SamError::StatIncrementOnly(id) => {
+                write!(f, "Sam error: Stat {:?} can only go up", id)
+            }
+            SamError::ReadOnlyStat(id) => write!(f, "Sam error: Stat {:?} is read-only", id),
+            SamError::UserStatsUnavailable => {
+                write!(f, "Sam error: The user's stats are not available")
+            }
+            SamError::SteamClientNotFound(paths) => {
+                write!(f, "Sam error: Steam client library not found in {}", paths.join(", "))
+            }
+            SamError::SteamNotLoggedIn => write!(f, "Sam error: No user is logged into Steam"),
+            SamError::StoreFailed => write!(f, "Sam error: Steam did not store the change"),
+            SamError::VerificationFailed(id) => {
+                write!(f, "Sam error: Steam ignored the change to {:?}", id)
+            }
+            SamError::SchemaUnavailable => {
+                write!(f, "Sam error: Steam has not downloaded the stats schema")
+            }
+            SamError::Cancelled => write!(f, "Sam error: Cancelled"),
+            SamError::FramingError => {
+                write!(f, "Sam error: Malformed message, the pipe is out of sync")
+            }
         }
     }
 }
 
 impl std::error::Error for SamError {}
 
+/// The result of each achievement of a batch, by achievement id, in request order.
+pub type AchievementResults = Vec<(String, Result<(), SamError>)>;
+
+impl From<SteamClientError> for SamError {
+    fn from(error: SteamClientError) -> Self {
+        match error {
+            SteamClientError::InteriorNul(id) => SamError::InvalidIdentifier(id),
+            _ => SamError::UnknownError,
+        }
+    }
+}
+
 /// Commands sent from frontend to orchestrator/app.
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub enum SteamCommand {
     GetOwnedAppList,
+    GetInstalledAppList,
     LaunchApp(u32),
     StopApp(u32),
     StopApps,
     Shutdown,
     Status, // Ask for status of the process
-    GetAchievements(u32),
+    /// Lists the achievements of an app, named in the given language, or in Steam's when
+    /// `None`. Names missing in that language are in English.
+    GetAchievements(u32, Option<String>),
+    /// Like `GetAchievements`, but asks Steam again even when the achievements were just
+    /// fetched, for when the user asked for a refresh.
+    RefreshAchievements(u32, Option<String>),
+    /// Reads a single achievement, to update it after a change without reloading the list.
+    GetAchievement(u32, String),
     GetStats(u32),
     SetAchievement(u32, bool, String),
+    SetAchievements(u32, bool, Vec<String>),
+    /// Unlocks an achievement by raising the stat the schema links to it.
+    UnlockAchievementViaStat(u32, String),
+    /// Reads another user's achievements for an app, by SteamID64.
+    GetUserAchievements(u32, u64),
     SetIntStat(u32, String, i32),
     SetFloatStat(u32, String, f32),
     ResetStats(u32, bool),
+    /// Sets one stat back to its schema default, or 0, and stores it. Answers the value.
+    ResetStat(u32, String),
+    GetDiagnostics,
+    /// Counts achievements and stats of each app from the schemas on disk.
+    GetAchievementSummaries(Vec<u32>),
+    /// Finds the achievements of each app whose name or id contains a text, from the schemas
+    /// on disk.
+    SearchAchievements(Vec<u32>, String),
+    /// Turns dry-run mode on or off for every app server, current and future.
+    SetDryRun(bool),
+    /// Asks an app server when it last stored stats successfully.
+    GetLastStored(u32),
+    /// Stops every app server and drops the Steam connection. The next command opens a new one.
+    Reconnect,
+    /// Lists the apps with a live app server, which some window is managing.
+    ListRunningApps,
+    /// Compares the achievements of an app's schema with the ones Steam lists.
+    CheckSchema(u32),
+    /// Stops the owned apps being listed, which then fails with `Cancelled`. The GUI sends
+    /// it while waiting for the list, it is acted on as soon as it is read and answers nothing.
+    CancelAppList,
+}
+
+/// Backend state gathered for bug reports.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Diagnostics {
+    /// Whether the orchestrator holds a Steam connection.
+    pub steam_connected: bool,
+    /// Steam client library the orchestrator loads, if one was found.
+    pub steamclient_path: Option<String>,
+    /// Apps with a live app server.
+    pub running_apps: Vec<u32>,
+    /// Apps whose app server is kept running after the user left them.
+    pub parked_apps: Vec<u32>,
+    /// Latest errors the orchestrator answered with, oldest first.
+    pub recent_errors: Vec<RecentError>,
+    /// Running apps whose schema and Steam disagree, with how many achievements differ.
+    pub schema_mismatches: Vec<(u32, usize)>,
+}
+
+/// An error the orchestrator answered a command with.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecentError {
+    pub time: SystemTime,
+    /// The command that failed, as logged.
+    pub command: String,
+    pub error: SamError,
 }
 
 /// Response from orchestrator/app to frontend.
-#[derive(Serialize, Deserialize, Debug)]
+///
+/// Long-running commands may send any number of `Progress(done, total)` messages
+/// before the final `Success` or `Error`. Writes made in dry-run mode answer with
+/// `Simulated` instead of `Success`.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub enum SteamResponse<T> {
     Success(T),
     Error(SamError),
+    Progress(u32, u32),
+    Simulated(T),
+}
+
+/// Tells whether a serialized response payload (without its length prefix) is an
+/// interim `Progress` message, without deserializing the data it might carry.
+pub fn is_progress_message(payload: &[u8]) -> bool {
+    matches!(
+        serde_json::from_slice::<SteamResponse<serde::de::IgnoredAny>>(payload),
+        Ok(SteamResponse::Progress(..))
+    )
+}
+
+/// The error a serialized response payload (without its length prefix) carries, if any.
+pub fn response_error(payload: &[u8]) -> Option<SamError> {
+    match serde_json::from_slice::<SteamResponse<serde::de::IgnoredAny>>(payload) {
+        Ok(SteamResponse::Error(error)) => Some(error),
+        _ => None,
+    }
 }
 
 /// Trait for serializing/deserializing IPC messages with length prefix.
@@ -92,8 +262,8 @@ pub trait SamSerializable {
         result
     }
 
-    /// Reads and deserializes a message from a Recver.
-    fn from_recver(rx: &mut Recver) -> Result<Self, SamError>
+    /// Reads and deserializes a message from a Recver, or any other reader.
+    fn from_recver(rx: &mut impl Read) -> Result<Self, SamError>
     where
         Self: DeserializeOwned,
     {
@@ -104,6 +274,10 @@ pub trait SamSerializable {
         }
 
         let data_length = usize::from_le_bytes(buffer_len);
+        if data_length > MAX_MESSAGE_LENGTH {
+            eprintln!("[IPC] Message length {data_length} is too large, the pipe is corrupt");
+            return Err(SamError::SocketCommunicationFailed);
+        }
         let mut buffer = vec![0u8; data_length];
 
         if let Err(e) = rx.read_exact(&mut buffer) {
@@ -111,8 +285,15 @@ pub trait SamSerializable {
             return Err(SamError::SocketCommunicationFailed);
         }
 
-        // Avoid String conversion, deserialize directly from bytes
-        let message: Self = match serde_json::from_slice(&buffer) {
+        // JSON is UTF-8, anything else was read at the wrong offset rather than badly encoded
+        let message = match std::str::from_utf8(&buffer) {
+            Ok(message) => message,
+            Err(e) => {
+                eprintln!("[IPC] Message is not UTF-8, the pipe is out of sync: {e}");
+                return Err(SamError::FramingError);
+            }
+        };
+        let message: Self = match serde_json::from_str(message) {
             Ok(msg) => msg,
             Err(e) => {
                 eprintln!("[IPC] Failed to deserialize message: {e}");
@@ -126,11 +307,13 @@ pub trait SamSerializable {
 impl<T> SamSerializable for SteamResponse<T> where T: Sized + Serialize {}
 impl SamSerializable for SteamCommand {}
 
-impl<T> Into<Result<T, SamError>> for SteamResponse<T> {
-    fn into(self) -> Result<T, SamError> {
-        match self {
-            SteamResponse::Success(data) => Ok(data),
+impl<T> From<SteamResponse<T>> for Result<T, SamError> {
+    fn from(response: SteamResponse<T>) -> Self {
+        match response {
+            SteamResponse::Success(data) | SteamResponse::Simulated(data) => Ok(data),
             SteamResponse::Error(error) => Err(error),
+            // Interim messages are consumed by the reader, one reaching here is a framing bug
+            SteamResponse::Progress(..) => Err(SamError::SocketCommunicationFailed),
         }
     }
 }
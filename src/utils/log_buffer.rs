@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use once_cell::sync::Lazy;
+use std::{collections::VecDeque, sync::Mutex};
+
+/// Number of log lines kept for bug reports.
+const LOG_BUFFER_CAPACITY: usize = 200;
+
+static LOG_LINES: Lazy<Mutex<VecDeque<String>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(LOG_BUFFER_CAPACITY)));
+
+/// Logger printing `log` records to stderr and keeping the latest ones in memory.
+struct BufferedLogger;
+
+static LOGGER: BufferedLogger = BufferedLogger;
+
+impl log::Log for BufferedLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!("[{}] {}", record.level(), record.args());
+        eprintln!("{line}");
+        if let Ok(mut lines) = LOG_LINES.lock() {
+            if lines.len() == LOG_BUFFER_CAPACITY {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the logger. Warnings and errors are recorded, plus info messages in debug builds.
+pub fn init_logger() {
+    if log::set_logger(&LOGGER).is_ok() {
+        let level = if cfg!(debug_assertions) {
+            log::LevelFilter::Info
+        } else {
+            log::LevelFilter::Warn
+        };
+        log::set_max_level(level);
+    }
+}
+
+/// The last `count` recorded log lines, oldest first.
+pub fn recent_log_lines(count: usize) -> Vec<String> {
+    match LOG_LINES.lock() {
+        Ok(lines) => lines.iter().skip(lines.len().saturating_sub(count)).cloned().collect(),
+        Err(_) => vec![],
+    }
+}
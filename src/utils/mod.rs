@@ -16,6 +16,10 @@
 pub mod app_paths;
 pub mod arguments;
 pub mod bidir_child;
+pub mod console;
 pub mod dev_println;
 pub mod format;
+pub mod i18n;
 pub mod ipc_types;
+pub mod log_buffer;
+pub mod settings;
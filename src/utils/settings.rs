@@ -0,0 +1,125 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::utils::app_paths::get_app_cache_dir;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+/// User preferences, stored as JSON in the app cache directory.
+/// Missing fields take their default value, so older files keep working.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    /// Seconds an app server keeps running after leaving its page, so coming back is instant.
+    /// 0, the default, stops it right away.
+    pub keep_app_running_secs: u64,
+    /// Apps pinned at the top of the library.
+    pub favorite_apps: Vec<u32>,
+    /// Hide library entries without achievements or stats, such as tools and soundtracks.
+    pub hide_empty_apps: bool,
+    /// Steam language code (e.g. `english`) used for app images and achievement names,
+    /// regardless of the language Steam runs in. `None` follows Steam.
+    pub steam_language: Option<String>,
+    /// Steam client library picked by the user, tried before the usual install locations.
+    pub steamclient_path: Option<String>,
+    /// Never go online: the app list and images only come from the cache.
+    pub offline: bool,
+    /// Images downloaded at the same time, the others are queued.
+    pub max_image_downloads: usize,
+    /// Seconds between retries of the changes Steam did not store yet, in case Steam or
+    /// SamRewritten crashes during a long session. 0 turns it off.
+    pub auto_store_secs: u64,
+    /// Apps last opened in the app view, most recent first.
+    pub recent_apps: Vec<RecentApp>,
+    /// Show still placeholders instead of the shimmer while images load. The shimmer also
+    /// stops when the system turns animations off.
+    pub reduce_motion: bool,
+    /// Read achievements and stats back once a change is stored, to report the changes Steam
+    /// accepted but ignored.
+    pub verify_writes: bool,
+    /// Least milliseconds between two achievement changes or stores sent to Steam, to space
+    /// out large batches. 0 turns it off.
+    pub min_write_interval_ms: u64,
+    /// Folder the downloaded game images are kept in. `None` uses an `images` folder in the
+    /// app cache directory.
+    pub image_cache_dir: Option<String>,
+}
+
+/// An app opened recently, with its name so it can be shown before the library loads.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecentApp {
+    pub app_id: u32,
+    pub app_name: String,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            keep_app_running_secs: 0,
+            favorite_apps: vec![],
+            hide_empty_apps: false,
+            steam_language: None,
+            steamclient_path: None,
+            offline: false,
+            max_image_downloads: 6,
+            auto_store_secs: 0,
+            recent_apps: vec![],
+            reduce_motion: false,
+            verify_writes: false,
+            min_write_interval_ms: 0,
+            image_cache_dir: None,
+        }
+    }
+}
+
+fn get_settings_path() -> Option<PathBuf> {
+    get_app_cache_dir()
+        .ok()
+        .map(|dir| PathBuf::from(dir).join(SETTINGS_FILE_NAME))
+}
+
+/// Loads the settings, falling back to the defaults if the file is missing or unreadable.
+pub fn load_settings() -> Settings {
+    let Some(path) = get_settings_path() else {
+        return Settings::default();
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return Settings::default();
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        eprintln!("[SETTINGS] Ignoring invalid {}: {e}", path.display());
+        Settings::default()
+    })
+}
+
+/// Writes the settings back to disk, logging any failure.
+pub fn save_settings(settings: &Settings) {
+    let Some(path) = get_settings_path() else {
+        eprintln!("[SETTINGS] No cache directory to save settings in");
+        return;
+    };
+    let contents = match serde_json::to_string_pretty(settings) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("[SETTINGS] Failed to serialize settings: {e}");
+            return;
+        }
+    };
+    if let Err(e) = fs::write(&path, contents) {
+        eprintln!("[SETTINGS] Failed to write {}: {e}", path.display());
+    }
+}
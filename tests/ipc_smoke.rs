@@ -0,0 +1,111 @@
+// SPDX-License-Identifier: GPL-3.0-only
+// Copyright (C) 2025 Paul <abonnementspaul (at) gmail.com>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+// Starts the real binary as an orchestrator, the way the GUI does, and talks to it about the
+// development app 0. Debug builds answer for that app without Steam, so the whole pipeline
+// (process spawn, pipes, framing, serialization) runs anywhere.
+
+#![cfg(all(unix, debug_assertions))]
+
+use interprocess::unnamed_pipe::{Recver, Sender, pipe};
+use serde_json::{Value, json};
+use std::io::{Read, Write};
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::process::{Child, Command};
+
+struct Orchestrator {
+    child: Child,
+    tx: Sender,
+    rx: Recver,
+}
+
+impl Orchestrator {
+    fn spawn(cache_dir: &std::path::Path) -> Orchestrator {
+        let (tx, child_rx) = pipe().expect("Failed to create the command pipe");
+        let (child_tx, rx) = pipe().expect("Failed to create the response pipe");
+        let (child_tx, child_rx) = (OwnedFd::from(child_tx), OwnedFd::from(child_rx));
+        let child = Command::new(env!("CARGO_BIN_EXE_samrewritten"))
+            .arg("--orchestrator")
+            .arg(format!("--tx={}", child_tx.as_raw_fd()))
+            .arg(format!("--rx={}", child_rx.as_raw_fd()))
+            // Keeps the lock, socket and settings away from a real installation
+            .env("SAMREWRITTEN_CACHE_DIR", cache_dir)
+            .spawn()
+            .expect("Failed to spawn the orchestrator");
+        // Only the child writes responses, so reads fail instead of hanging if it dies
+        drop((child_tx, child_rx));
+        Orchestrator { child, tx, rx }
+    }
+
+    /// Sends a command and returns the response, framed like `SamSerializable` does.
+    fn request(&mut self, command: Value) -> Value {
+        let payload = serde_json::to_vec(&command).expect("Failed to serialize the command");
+        self.tx
+            .write_all(&payload.len().to_le_bytes())
+            .and_then(|_| self.tx.write_all(&payload))
+            .expect("Failed to send the command");
+
+        let mut length = [0u8; size_of::<usize>()];
+        self.rx
+            .read_exact(&mut length)
+            .expect("Failed to read the response length");
+        let mut response = vec![0u8; usize::from_le_bytes(length)];
+        self.rx
+            .read_exact(&mut response)
+            .expect("Failed to read the response");
+        serde_json::from_slice(&response).expect("The response is not JSON")
+    }
+}
+
+#[test]
+fn orchestrator_serves_the_development_app() {
+    let cache_dir = std::env::temp_dir().join(format!("samrewritten-smoke-{}", std::process::id()));
+    let mut orchestrator = Orchestrator::spawn(&cache_dir);
+
+    assert_eq!(
+        orchestrator.request(json!("ListRunningApps")),
+        json!({ "Success": [] })
+    );
+
+    assert_eq!(
+        orchestrator.request(json!({ "LaunchApp": 0 })),
+        json!({ "Success": true })
+    );
+
+    let response = orchestrator.request(json!({ "GetAchievements": [0, null] }));
+    let achievements = response["Success"]
+        .as_array()
+        .unwrap_or_else(|| panic!("Unexpected response: {response}"));
+    assert_eq!(achievements.len(), 999);
+    assert_eq!(achievements[0]["id"], "DEV_ACH_1");
+    assert_eq!(achievements[0]["is_achieved"], false);
+    assert_eq!(achievements[1]["is_achieved"], true);
+
+    assert_eq!(
+        orchestrator.request(json!({ "StopApp": 0 })),
+        json!({ "Success": true })
+    );
+    assert_eq!(
+        orchestrator.request(json!("Shutdown")),
+        json!({ "Success": true })
+    );
+    let status = orchestrator
+        .child
+        .wait()
+        .expect("The orchestrator did not exit");
+    assert!(status.success());
+
+    let _ = std::fs::remove_dir_all(&cache_dir);
+}